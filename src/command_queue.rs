@@ -13,33 +13,64 @@
 // limitations under the License.
 
 //! OpenCL Command Queue API.
+//!
+//! The enqueue functions that take pointer and count pairs, and so require the
+//! caller to uphold the OpenCL C API invariants, are in the [raw] submodule.  
+//! Each raw function is also available at its old path in this module but is
+//! deprecated. Its replacement is either named after the OpenCL function that
+//! it calls, e.g. `enqueue_svm_memcpy`, or has a `_slice` suffix, e.g.
+//! `enqueue_fill_buffer_slice`. The replacements take host memory as slices,
+//! but SVM and mapped pointers are still passed as pointers.
 
 #![allow(non_camel_case_types)]
 
+mod chunked;
+mod clear;
+mod map;
+pub mod raw;
+mod watchdog;
+
+pub use chunked::{
+    default_max_chunk, enqueue_read_buffer_chunked, enqueue_write_buffer_chunked,
+};
+pub use clear::{
+    clear_buffer_region, clear_buffer_region_with, release_clear_kernel, zero_buffer,
+    ClearStrategy,
+};
+pub use map::{
+    enqueue_map_buffer_slice, with_buffer_read, with_buffer_read_using, MappedBuffer,
+    ReadStrategy,
+};
+pub use watchdog::{run_with_watchdog, WatchdogFailure, WatchdogOutcome};
+
 pub use cl_sys::{
     CL_QUEUE_ON_DEVICE, CL_QUEUE_ON_DEVICE_DEFAULT, CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE,
     CL_QUEUE_PROFILING_ENABLE,
 };
 
-use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+use super::context::{get_context_info, ContextInfo};
+use super::device::{get_device_info, DeviceInfo};
+use super::error_codes::{CL_INVALID_VALUE, CL_INVALID_WORK_DIMENSION, CL_SUCCESS};
+use super::event::release_event;
 use super::info_type::InfoType;
+use super::memory::{
+    get_image_info, get_mem_object_info, ImageInfo, MemInfo, CL_MEM_OBJECT_IMAGE1D_ARRAY,
+};
 use super::types::{
     cl_bool, cl_command_queue, cl_command_queue_info, cl_command_queue_properties, cl_context,
-    cl_device_id, cl_event, cl_int, cl_kernel, cl_map_flags, cl_mem, cl_mem_migration_flags,
-    cl_queue_properties, cl_uint, cl_ulong,
+    cl_device_id, cl_event, cl_float, cl_int, cl_kernel, cl_map_flags, cl_mem,
+    cl_mem_migration_flags, cl_queue_properties, cl_uint, cl_ulong, CL_BLOCKING,
 };
 use super::{api_info_size, api_info_value, api_info_vector};
 #[allow(unused_imports)]
 use cl_sys::{
-    clCreateCommandQueue, clCreateCommandQueueWithProperties, clEnqueueBarrierWithWaitList,
-    clEnqueueCopyBuffer, clEnqueueCopyBufferRect, clEnqueueCopyBufferToImage, clEnqueueCopyImage,
-    clEnqueueCopyImageToBuffer, clEnqueueFillBuffer, clEnqueueFillImage, clEnqueueMapBuffer,
-    clEnqueueMapImage, clEnqueueMarkerWithWaitList, clEnqueueMigrateMemObjects,
-    clEnqueueNDRangeKernel, clEnqueueNativeKernel, clEnqueueReadBuffer, clEnqueueReadBufferRect,
-    clEnqueueReadImage, clEnqueueSVMFree, clEnqueueSVMMap, clEnqueueSVMMemFill, clEnqueueSVMMemcpy,
-    clEnqueueSVMMigrateMem, clEnqueueSVMUnmap, clEnqueueTask, clEnqueueUnmapMemObject,
-    clEnqueueWriteBuffer, clEnqueueWriteBufferRect, clEnqueueWriteImage, clFinish, clFlush,
-    clGetCommandQueueInfo, clReleaseCommandQueue, clRetainCommandQueue,
+    clCreateCommandQueue, clCreateCommandQueueWithProperties, clEnqueueCopyBufferToImage,
+    clEnqueueCopyImage, clEnqueueCopyImageToBuffer, clEnqueueMapImage, clEnqueueNDRangeKernel,
+    clEnqueueNativeKernel, clEnqueueReadBuffer, clEnqueueReadImage, clEnqueueSVMFree,
+    clEnqueueSVMMap, clEnqueueSVMMemFill, clEnqueueSVMMemcpy, clEnqueueSVMMigrateMem,
+    clEnqueueSVMUnmap, clEnqueueTask, clEnqueueUnmapMemObject, clEnqueueWriteBuffer,
+    clEnqueueWriteImage, clFinish, clFlush, clGetCommandQueueInfo, clReleaseCommandQueue,
+    clRetainCommandQueue,
 };
 
 use libc::{c_void, intptr_t, size_t};
@@ -74,31 +105,198 @@ pub fn create_command_queue(
     }
 }
 
+#[cfg(feature = "CL_VERSION_2_0")]
+#[deprecated(note = "use the slice-based variant: create_command_queue_with_properties_slice")]
+#[inline]
+pub fn create_command_queue_with_properties(
+    context: cl_context,
+    device: cl_device_id,
+    properties: *const cl_queue_properties,
+) -> Result<cl_command_queue, cl_int> {
+    raw::create_command_queue_with_properties(context, device, properties)
+}
+
 /// Create an OpenCL host or device command-queue on a specific device.  
 /// Calls clCreateCommandQueueWithProperties to create an OpenCL context.  
 /// CL_VERSION_2_0 onwards.
 ///
 /// * `context` - a valid OpenCL context.
 /// * `device` - a device or sub-device associated with context.
-/// * `properties` - a null terminated list of properties for the command-queue, see
+/// * `properties` - a zero terminated list of properties for the command-queue,
+///   e.g. from QueueProperties::to_vec, or an empty slice for the default
+///   properties, see:
 /// [cl_queue_properties](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#queue-properties-table).
 ///
 /// returns a Result containing the new OpenCL command-queue
+/// or CL_INVALID_VALUE if `properties` is not zero terminated
 /// or the error code from the OpenCL C API function.
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
-pub fn create_command_queue_with_properties(
+pub fn create_command_queue_with_properties_slice(
     context: cl_context,
     device: cl_device_id,
-    properties: *const cl_queue_properties,
+    properties: &[cl_queue_properties],
 ) -> Result<cl_command_queue, cl_int> {
-    let mut status: cl_int = CL_INVALID_VALUE;
-    let queue: cl_command_queue =
-        unsafe { clCreateCommandQueueWithProperties(context, device, properties, &mut status) };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(queue)
+    let properties_ptr = match properties.last() {
+        None => ptr::null(),
+        Some(0) => properties.as_ptr(),
+        Some(_) => return Err(CL_INVALID_VALUE),
+    };
+    raw::create_command_queue_with_properties(context, device, properties_ptr)
+}
+
+// The properties common to all of the devices, none if there are no devices.
+fn common_queue_properties<I>(device_properties: I) -> cl_command_queue_properties
+where
+    I: IntoIterator<Item = cl_command_queue_properties>,
+{
+    device_properties
+        .into_iter()
+        .fold(None, |common, properties| {
+            Some(common.map_or(properties, |common| common & properties))
+        })
+        .unwrap_or(0)
+}
+
+/// Get the command-queue properties that are supported by every device in a
+/// context, i.e. the intersection of their CL_DEVICE_QUEUE_ON_HOST_PROPERTIES.  
+/// Calls clGetContextInfo and clGetDeviceInfo for each device.
+///
+/// * `context` - a valid OpenCL context.
+///
+/// returns a Result containing the common cl_command_queue_properties
+/// or the error code from the OpenCL C API function.
+pub fn context_common_queue_properties(
+    context: cl_context,
+) -> Result<cl_command_queue_properties, cl_int> {
+    let devices = get_context_info(context, ContextInfo::CL_CONTEXT_DEVICES)?.to_vec_device_id();
+    let device_properties = devices
+        .iter()
+        .map(|&device| {
+            get_device_info(device, DeviceInfo::CL_DEVICE_QUEUE_ON_HOST_PROPERTIES)
+                .map(|value| value.to_ulong())
+        })
+        .collect::<Result<Vec<_>, cl_int>>()?;
+    Ok(common_queue_properties(device_properties))
+}
+
+/// Remove the command-queue properties that are not supported by every device
+/// in a context, see `context_common_queue_properties`.  
+/// Calls clGetContextInfo and clGetDeviceInfo for each device.
+///
+/// * `context` - a valid OpenCL context.
+/// * `properties` - the requested cl_command_queue_properties.
+///
+/// returns a Result containing the supported properties and the dropped
+/// properties, i.e. `(properties & common, properties & !common)`
+/// or the error code from the OpenCL C API function.
+pub fn clamp_queue_properties(
+    context: cl_context,
+    properties: cl_command_queue_properties,
+) -> Result<(cl_command_queue_properties, cl_command_queue_properties), cl_int> {
+    let common = context_common_queue_properties(context)?;
+    Ok((properties & common, properties & !common))
+}
+
+/// A builder for the properties of a command-queue, for
+/// create_command_queue_with_properties_slice or create_command_queue.  
+/// Properties that are not set take their OpenCL default values:
+/// an in-order queue without profiling.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct QueueProperties {
+    properties: cl_command_queue_properties,
+    size: Option<cl_uint>,
+}
+
+impl QueueProperties {
+    pub fn new() -> QueueProperties {
+        QueueProperties::default()
+    }
+
+    // Set or clear a CL_QUEUE_PROPERTIES bit.
+    fn set(mut self, property: cl_command_queue_properties, enable: bool) -> QueueProperties {
+        if enable {
+            self.properties |= property;
+        } else {
+            self.properties &= !property;
+        }
+        self
+    }
+
+    /// Set CL_QUEUE_PROFILING_ENABLE, for the event profiling functions.
+    pub fn profiling(self, enable: bool) -> QueueProperties {
+        self.set(CL_QUEUE_PROFILING_ENABLE, enable)
+    }
+
+    /// Set CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE.
+    pub fn out_of_order(self, enable: bool) -> QueueProperties {
+        self.set(CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE, enable)
+    }
+
+    /// Set CL_QUEUE_ON_DEVICE, and CL_QUEUE_ON_DEVICE_DEFAULT if `default`.
+    /// A device queue must also be out of order.
+    pub fn on_device(self, enable: bool, default: bool) -> QueueProperties {
+        self.set(CL_QUEUE_ON_DEVICE, enable)
+            .set(CL_QUEUE_ON_DEVICE_DEFAULT, enable && default)
+    }
+
+    /// Set CL_QUEUE_SIZE, the size in bytes of a device queue.
+    pub fn size(mut self, size: cl_uint) -> QueueProperties {
+        self.size = Some(size);
+        self
+    }
+
+    /// The CL_QUEUE_PROPERTIES bit-field, e.g. for create_command_queue.
+    pub fn properties(&self) -> cl_command_queue_properties {
+        self.properties
+    }
+
+    /// Whether all of the `properties` bits are set, e.g. CL_QUEUE_PROFILING_ENABLE.
+    pub fn contains(&self, properties: cl_command_queue_properties) -> bool {
+        properties == self.properties & properties
+    }
+
+    /// Remove the properties that are not supported by every device in a
+    /// context, see `clamp_queue_properties`.  
+    /// CL_QUEUE_SIZE is kept unchanged.
+    ///
+    /// * `context` - a valid OpenCL context.
+    ///
+    /// returns a Result containing the supported QueueProperties and the
+    /// dropped cl_command_queue_properties
+    /// or the error code from the OpenCL C API function.
+    pub fn clamp_to(
+        self,
+        context: cl_context,
+    ) -> Result<(QueueProperties, cl_command_queue_properties), cl_int> {
+        let (properties, dropped) = clamp_queue_properties(context, self.properties)?;
+        Ok((QueueProperties { properties, ..self }, dropped))
+    }
+
+    /// The zero terminated properties list, for
+    /// create_command_queue_with_properties_slice.
+    pub fn to_vec(&self) -> Vec<cl_queue_properties> {
+        let mut properties: Vec<cl_queue_properties> = Vec::with_capacity(5);
+        if 0 != self.properties {
+            properties.push(CommandQueueInfo::CL_QUEUE_PROPERTIES as cl_queue_properties);
+            properties.push(self.properties);
+        }
+        if let Some(size) = self.size {
+            properties.push(CommandQueueInfo::CL_QUEUE_SIZE as cl_queue_properties);
+            properties.push(size as cl_queue_properties);
+        }
+        properties.push(0);
+        properties
+    }
+}
+
+/// Implement the From trait, e.g. for CL_QUEUE_PROPERTIES.
+impl From<cl_command_queue_properties> for QueueProperties {
+    fn from(properties: cl_command_queue_properties) -> Self {
+        QueueProperties {
+            properties,
+            size: None,
+        }
     }
 }
 
@@ -111,6 +309,8 @@ pub fn create_command_queue_with_properties(
 #[inline]
 pub fn retain_command_queue(command_queue: cl_command_queue) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clRetainCommandQueue(command_queue) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::retained(command_queue as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -127,6 +327,8 @@ pub fn retain_command_queue(command_queue: cl_command_queue) -> Result<(), cl_in
 #[inline]
 pub fn release_command_queue(command_queue: cl_command_queue) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clReleaseCommandQueue(command_queue) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::released(command_queue as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -210,6 +412,21 @@ pub fn get_command_queue_info(
     }
 }
 
+/// Get the properties of an OpenCL command-queue.  
+/// Calls clGetCommandQueueInfo to get CL_QUEUE_PROPERTIES.
+///
+/// * `command_queue` - the OpenCL command-queue.
+///
+/// returns a Result containing the QueueProperties of the command-queue
+/// or the error code from the OpenCL C API function.
+pub fn get_command_queue_properties(
+    command_queue: cl_command_queue,
+) -> Result<QueueProperties, cl_int> {
+    let properties =
+        get_command_queue_info(command_queue, CommandQueueInfo::CL_QUEUE_PROPERTIES)?.to_ulong();
+    Ok(QueueProperties::from(properties))
+}
+
 /// Flush commands to a device.  
 /// Calls clFlush to flush an OpenCL command-queue.  
 ///
@@ -244,6 +461,86 @@ pub fn finish(command_queue: cl_command_queue) -> Result<(), cl_int> {
 
 // OpenCL command-queue enqueue commands.
 
+/// The event_wait_list pointer to pass to the OpenCL C API: null if empty.
+#[inline]
+pub(crate) fn wait_list_ptr(event_wait_list: &[cl_event]) -> *const cl_event {
+    if event_wait_list.is_empty() {
+        ptr::null()
+    } else {
+        event_wait_list.as_ptr()
+    }
+}
+
+/// Whether a fill pattern size is one of the sizes allowed by clEnqueueFillBuffer,
+/// i.e. 1, 2, 4, 8, 16, 32, 64 or 128 bytes.
+#[inline]
+fn is_valid_pattern_size(pattern_size: size_t) -> bool {
+    pattern_size.is_power_of_two() && pattern_size <= 128
+}
+
+/// Whether a value is a multiple of a valid pattern size, which is a power of two.
+#[inline]
+fn is_pattern_multiple(value: size_t, pattern_size: size_t) -> bool {
+    0 == value & (pattern_size - 1)
+}
+
+/// The size in bytes of the host memory that a rectangular region ends in,
+/// with zero pitches computed from the region, as by clEnqueueReadBufferRect.
+/// None if a dimension of `region` is zero or the size overflows.
+fn host_rect_size(
+    host_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    host_row_pitch: size_t,
+    host_slice_pitch: size_t,
+) -> Option<size_t> {
+    if region.contains(&0) {
+        return None;
+    }
+    let row_pitch = if 0 == host_row_pitch {
+        region[0]
+    } else {
+        host_row_pitch
+    };
+    let slice_pitch = if 0 == host_slice_pitch {
+        region[1].checked_mul(row_pitch)?
+    } else {
+        host_slice_pitch
+    };
+    let start = host_origin[2]
+        .checked_mul(slice_pitch)?
+        .checked_add(host_origin[1].checked_mul(row_pitch)?)?
+        .checked_add(host_origin[0])?;
+    let extent = (region[2] - 1)
+        .checked_mul(slice_pitch)?
+        .checked_add((region[1] - 1).checked_mul(row_pitch)?)?
+        .checked_add(region[0])?;
+    start.checked_add(extent)
+}
+
+/// The size in bytes of the host memory of a region of an image, as read or
+/// written by clEnqueueReadImage and clEnqueueWriteImage, where the rows of a
+/// 1D image array are its images.
+/// CL_INVALID_VALUE if a dimension of `region` is zero or the size overflows.
+fn host_image_size(
+    image: cl_mem,
+    region: &[size_t; 3],
+    row_pitch: size_t,
+    slice_pitch: size_t,
+) -> Result<size_t, cl_int> {
+    let element_size = get_image_info(image, ImageInfo::CL_IMAGE_ELEMENT_SIZE)?.to_size();
+    let mem_type = get_mem_object_info(image, MemInfo::CL_MEM_TYPE)?.to_uint();
+    let width = region[0]
+        .checked_mul(element_size)
+        .ok_or(CL_INVALID_VALUE)?;
+    let host_region = if CL_MEM_OBJECT_IMAGE1D_ARRAY == mem_type {
+        [width, 1, region[1]]
+    } else {
+        [width, region[1], region[2]]
+    };
+    host_rect_size(&[0; 3], &host_region, row_pitch, slice_pitch).ok_or(CL_INVALID_VALUE)
+}
+
+#[deprecated(note = "use the slice-based variant: enqueue_read_buffer_slice")]
 #[inline]
 pub fn enqueue_read_buffer(
     command_queue: cl_command_queue,
@@ -255,27 +552,59 @@ pub fn enqueue_read_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueReadBuffer(
-            command_queue,
-            buffer,
-            blocking_read,
-            offset,
-            size,
-            ptr,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_read_buffer(
+        command_queue,
+        buffer,
+        blocking_read,
+        offset,
+        size,
+        ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
+/// Enqueue a command to read from a buffer object to host memory.  
+/// Calls clEnqueueReadBuffer to read the buffer.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `blocking_read` - CL_BLOCKING or CL_NON_BLOCKING.
+/// * `offset` - the offset in bytes in the buffer to read from.
+/// * `data` - the host memory to read into, its size is the size of the read.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.  
+/// The event must be released, even when the read is blocking.
+///
+/// # Safety
+///
+/// `T` must be plain old data that is valid for any bit pattern, since the
+/// buffer is read into `data` as bytes. A non-blocking read writes to `data`
+/// until its event completes, so `data` must not be accessed or dropped before then.
+#[inline]
+pub unsafe fn enqueue_read_buffer_slice<T: Copy>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_read: cl_bool,
+    offset: size_t,
+    data: &mut [T],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_read_buffer(
+        command_queue,
+        buffer,
+        blocking_read,
+        offset,
+        mem::size_of_val(data),
+        data.as_mut_ptr() as *mut c_void,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[deprecated(note = "use the slice-based variant: enqueue_read_buffer_rect_slice")]
 #[inline]
 pub fn enqueue_read_buffer_rect(
     command_queue: cl_command_queue,
@@ -292,32 +621,95 @@ pub fn enqueue_read_buffer_rect(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueReadBufferRect(
-            command_queue,
-            buffer,
-            blocking_read,
-            buffer_origin,
-            host_origin,
-            region,
-            buffer_row_pitch,
-            buffer_slice_pitch,
-            host_row_pitch,
-            host_slice_pitch,
-            ptr,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_read_buffer_rect(
+        command_queue,
+        buffer,
+        blocking_read,
+        buffer_origin,
+        host_origin,
+        region,
+        buffer_row_pitch,
+        buffer_slice_pitch,
+        host_row_pitch,
+        host_slice_pitch,
+        ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to read a 2D or 3D rectangular region of a buffer
+/// object to host memory.  
+/// Calls clEnqueueReadBufferRect to read the region.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `blocking_read` - CL_BLOCKING or CL_NON_BLOCKING.
+/// * `buffer_origin` - the (x in bytes, y in rows, z in slices) offset in `buffer`.
+/// * `host_origin` - the (x in bytes, y in rows, z in slices) offset in host memory.
+/// * `region` - the (width in bytes, height in rows, depth in slices) of the
+///   region to read, for a 2D region the depth is 1.
+/// * `buffer_row_pitch` - the length of each row in bytes in `buffer`, or 0
+///   for `region[0]`.
+/// * `buffer_slice_pitch` - the size of each 2D slice in bytes in `buffer`,
+///   or 0 for `region[1] * buffer_row_pitch`.
+/// * `host_row_pitch` - the length of each row in bytes in host memory, or 0
+///   for `region[0]`.
+/// * `host_slice_pitch` - the size of each 2D slice in bytes in host memory,
+///   or 0 for `region[1] * host_row_pitch`.
+/// * `data` - the host memory to read into, `host_origin` is relative to it.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if a dimension of `region` is zero or the region is
+/// not within `data`
+/// or the error code from the OpenCL C API function.  
+/// The event must be released, even when the read is blocking.
+///
+/// # Safety
+///
+/// `T` must be plain old data that is valid for any bit pattern, since the
+/// region is read into `data` as bytes. A non-blocking read writes to `data`
+/// until its event completes, so `data` must not be accessed or dropped before then.
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn enqueue_read_buffer_rect_slice<T: Copy>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_read: cl_bool,
+    buffer_origin: &[size_t; 3],
+    host_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    buffer_row_pitch: size_t,
+    buffer_slice_pitch: size_t,
+    host_row_pitch: size_t,
+    host_slice_pitch: size_t,
+    data: &mut [T],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    match host_rect_size(host_origin, region, host_row_pitch, host_slice_pitch) {
+        Some(size) if size <= mem::size_of_val(data) => {}
+        _ => return Err(CL_INVALID_VALUE),
     }
+
+    raw::enqueue_read_buffer_rect(
+        command_queue,
+        buffer,
+        blocking_read,
+        buffer_origin.as_ptr(),
+        host_origin.as_ptr(),
+        region.as_ptr(),
+        buffer_row_pitch,
+        buffer_slice_pitch,
+        host_row_pitch,
+        host_slice_pitch,
+        data.as_mut_ptr() as *mut c_void,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_write_buffer_slice")]
 #[inline]
 pub fn enqueue_write_buffer(
     command_queue: cl_command_queue,
@@ -329,27 +721,105 @@ pub fn enqueue_write_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueWriteBuffer(
-            command_queue,
-            buffer,
-            blocking_write,
-            offset,
-            size,
-            ptr,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_write_buffer(
+        command_queue,
+        buffer,
+        blocking_write,
+        offset,
+        size,
+        ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to write to a buffer object from host memory.  
+/// Calls clEnqueueWriteBuffer to write the buffer.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `blocking_write` - CL_BLOCKING or CL_NON_BLOCKING.
+/// * `offset` - the offset in bytes in the buffer to write to.
+/// * `data` - the data to write, its size is the size of the write.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.  
+/// The event must be released, even when the write is blocking.
+///
+/// # Safety
+///
+/// A non-blocking write reads from `data` until its event completes, so
+/// `data` must not be modified or dropped before then.
+#[inline]
+pub unsafe fn enqueue_write_buffer_slice<T: Copy>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_write: cl_bool,
+    offset: size_t,
+    data: &[T],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_write_buffer(
+        command_queue,
+        buffer,
+        blocking_write,
+        offset,
+        mem::size_of_val(data),
+        data.as_ptr() as *const c_void,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+/// Read a buffer into a host slice, returning when the read is complete.  
+/// Calls clEnqueueReadBuffer with CL_BLOCKING and releases its event.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `offset` - the offset in bytes in the buffer to read from.
+/// * `data` - the host memory to read into, its size is the size of the read.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+///
+/// # Safety
+///
+/// `T` must be plain old data that is valid for any bit pattern, e.g. a
+/// primitive or an array of them, since the contents of the buffer are
+/// written into `data` as `T`.
+pub unsafe fn read_buffer_blocking<T: Copy>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    offset: size_t,
+    data: &mut [T],
+) -> Result<(), cl_int> {
+    let event = enqueue_read_buffer_slice(command_queue, buffer, CL_BLOCKING, offset, data, &[])?;
+    release_event(event)
+}
+
+/// Write a host slice to a buffer, returning when the write is complete.  
+/// Calls clEnqueueWriteBuffer with CL_BLOCKING and releases its event.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `offset` - the offset in bytes in the buffer to write to.
+/// * `data` - the data to write, its size is the size of the write.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+pub fn write_buffer_blocking<T: Copy>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    offset: size_t,
+    data: &[T],
+) -> Result<(), cl_int> {
+    // The write is blocking, so it has finished reading from data on return.
+    let event = unsafe {
+        enqueue_write_buffer_slice(command_queue, buffer, CL_BLOCKING, offset, data, &[])
+    }?;
+    release_event(event)
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_write_buffer_rect_slice")]
 #[inline]
 pub fn enqueue_write_buffer_rect(
     command_queue: cl_command_queue,
@@ -366,32 +836,94 @@ pub fn enqueue_write_buffer_rect(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueWriteBufferRect(
-            command_queue,
-            buffer,
-            blocking_write,
-            buffer_origin,
-            host_origin,
-            region,
-            buffer_row_pitch,
-            buffer_slice_pitch,
-            host_row_pitch,
-            host_slice_pitch,
-            ptr,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_write_buffer_rect(
+        command_queue,
+        buffer,
+        blocking_write,
+        buffer_origin,
+        host_origin,
+        region,
+        buffer_row_pitch,
+        buffer_slice_pitch,
+        host_row_pitch,
+        host_slice_pitch,
+        ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to write a 2D or 3D rectangular region of a buffer
+/// object from host memory.  
+/// Calls clEnqueueWriteBufferRect to write the region.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `blocking_write` - CL_BLOCKING or CL_NON_BLOCKING.
+/// * `buffer_origin` - the (x in bytes, y in rows, z in slices) offset in `buffer`.
+/// * `host_origin` - the (x in bytes, y in rows, z in slices) offset in host memory.
+/// * `region` - the (width in bytes, height in rows, depth in slices) of the
+///   region to write, for a 2D region the depth is 1.
+/// * `buffer_row_pitch` - the length of each row in bytes in `buffer`, or 0
+///   for `region[0]`.
+/// * `buffer_slice_pitch` - the size of each 2D slice in bytes in `buffer`,
+///   or 0 for `region[1] * buffer_row_pitch`.
+/// * `host_row_pitch` - the length of each row in bytes in host memory, or 0
+///   for `region[0]`.
+/// * `host_slice_pitch` - the size of each 2D slice in bytes in host memory,
+///   or 0 for `region[1] * host_row_pitch`.
+/// * `data` - the host memory to write from, `host_origin` is relative to it.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if a dimension of `region` is zero or the region is
+/// not within `data`
+/// or the error code from the OpenCL C API function.  
+/// The event must be released, even when the write is blocking.
+///
+/// # Safety
+///
+/// A non-blocking write reads from `data` until its event completes, so
+/// `data` must not be modified or dropped before then.
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn enqueue_write_buffer_rect_slice<T: Copy>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_write: cl_bool,
+    buffer_origin: &[size_t; 3],
+    host_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    buffer_row_pitch: size_t,
+    buffer_slice_pitch: size_t,
+    host_row_pitch: size_t,
+    host_slice_pitch: size_t,
+    data: &[T],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    match host_rect_size(host_origin, region, host_row_pitch, host_slice_pitch) {
+        Some(size) if size <= mem::size_of_val(data) => {}
+        _ => return Err(CL_INVALID_VALUE),
     }
+
+    raw::enqueue_write_buffer_rect(
+        command_queue,
+        buffer,
+        blocking_write,
+        buffer_origin.as_ptr(),
+        host_origin.as_ptr(),
+        region.as_ptr(),
+        buffer_row_pitch,
+        buffer_slice_pitch,
+        host_row_pitch,
+        host_slice_pitch,
+        data.as_ptr() as *const c_void,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_fill_buffer_slice")]
 #[inline]
 pub fn enqueue_fill_buffer(
     command_queue: cl_command_queue,
@@ -403,27 +935,63 @@ pub fn enqueue_fill_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueFillBuffer(
-            command_queue,
-            buffer,
-            pattern,
-            pattern_size,
-            offset,
-            size,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_fill_buffer(
+        command_queue,
+        buffer,
+        pattern,
+        pattern_size,
+        offset,
+        size,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to fill a buffer object with a pattern of a given pattern size.  
+/// Calls clEnqueueFillBuffer to fill the buffer.  
+/// CL_VERSION_1_2
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `pattern` - the bytes of the pattern, its length must be 1, 2, 4, 8, 16,
+///   32, 64 or 128 bytes.
+/// * `offset` - the offset in bytes in the buffer, a multiple of the pattern size.
+/// * `size` - the size in bytes of the region to fill, a multiple of the pattern size.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if the pattern, offset or size are invalid
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_fill_buffer_slice(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    pattern: &[u8],
+    offset: size_t,
+    size: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let pattern_size = pattern.len();
+    if !is_valid_pattern_size(pattern_size)
+        || !is_pattern_multiple(offset, pattern_size)
+        || !is_pattern_multiple(size, pattern_size)
+    {
+        return Err(CL_INVALID_VALUE);
     }
+
+    raw::enqueue_fill_buffer(
+        command_queue,
+        buffer,
+        pattern.as_ptr() as *const c_void,
+        pattern_size,
+        offset,
+        size,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_copy_buffer_slice")]
 #[inline]
 pub fn enqueue_copy_buffer(
     command_queue: cl_command_queue,
@@ -435,27 +1003,54 @@ pub fn enqueue_copy_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueCopyBuffer(
-            command_queue,
-            src_buffer,
-            dst_buffer,
-            src_offset,
-            dst_offset,
-            size,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_copy_buffer(
+        command_queue,
+        src_buffer,
+        dst_buffer,
+        src_offset,
+        dst_offset,
+        size,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to copy a buffer object to another buffer object.  
+/// Calls clEnqueueCopyBuffer to copy the buffer on the device.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `src_buffer` - the buffer object to copy from.
+/// * `dst_buffer` - the buffer object to copy to.
+/// * `src_offset` - the offset in bytes in `src_buffer` to copy from.
+/// * `dst_offset` - the offset in bytes in `dst_buffer` to copy to.
+/// * `size` - the size in bytes to copy.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_copy_buffer_slice(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_buffer: cl_mem,
+    src_offset: size_t,
+    dst_offset: size_t,
+    size: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_copy_buffer(
+        command_queue,
+        src_buffer,
+        dst_buffer,
+        src_offset,
+        dst_offset,
+        size,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_copy_buffer_rect_slice")]
 #[inline]
 pub fn enqueue_copy_buffer_rect(
     command_queue: cl_command_queue,
@@ -471,68 +1066,164 @@ pub fn enqueue_copy_buffer_rect(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueCopyBufferRect(
-            command_queue,
-            src_buffer,
-            dst_buffer,
-            src_origin,
-            dst_origin,
-            region,
-            src_row_pitch,
-            src_slice_pitch,
-            dst_row_pitch,
-            dst_slice_pitch,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_copy_buffer_rect(
+        command_queue,
+        src_buffer,
+        dst_buffer,
+        src_origin,
+        dst_origin,
+        region,
+        src_row_pitch,
+        src_slice_pitch,
+        dst_row_pitch,
+        dst_slice_pitch,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
+/// Enqueue a command to copy a 2D or 3D rectangular region of a buffer
+/// object to another buffer object.  
+/// Calls clEnqueueCopyBufferRect to copy the region on the device.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `src_buffer` - the buffer object to copy from.
+/// * `dst_buffer` - the buffer object to copy to.
+/// * `src_origin` - the (x in bytes, y in rows, z in slices) offset in `src_buffer`.
+/// * `dst_origin` - the (x in bytes, y in rows, z in slices) offset in `dst_buffer`.
+/// * `region` - the (width in bytes, height in rows, depth in slices) of the
+///   region to copy, for a 2D region the depth is 1.
+/// * `src_row_pitch` - the length of each row in bytes in `src_buffer`, or 0.
+/// * `src_slice_pitch` - the size of each 2D slice in bytes in `src_buffer`, or 0.
+/// * `dst_row_pitch` - the length of each row in bytes in `dst_buffer`, or 0.
+/// * `dst_slice_pitch` - the size of each 2D slice in bytes in `dst_buffer`, or 0.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if a dimension of `region` is zero
+/// or the error code from the OpenCL C API function.
 #[inline]
-pub fn enqueue_read_image(
+#[allow(clippy::too_many_arguments)]
+pub fn enqueue_copy_buffer_rect_slice(
     command_queue: cl_command_queue,
-    image: cl_mem,
-    blocking_read: cl_bool,
-    origin: *const size_t,
-    region: *const size_t,
+    src_buffer: cl_mem,
+    dst_buffer: cl_mem,
+    src_origin: &[size_t; 3],
+    dst_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    src_row_pitch: size_t,
+    src_slice_pitch: size_t,
+    dst_row_pitch: size_t,
+    dst_slice_pitch: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    if region.contains(&0) {
+        return Err(CL_INVALID_VALUE);
+    }
+
+    raw::enqueue_copy_buffer_rect(
+        command_queue,
+        src_buffer,
+        dst_buffer,
+        src_origin.as_ptr(),
+        dst_origin.as_ptr(),
+        region.as_ptr(),
+        src_row_pitch,
+        src_slice_pitch,
+        dst_row_pitch,
+        dst_slice_pitch,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[deprecated(note = "use the slice-based variant: enqueue_read_image_slice")]
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn enqueue_read_image(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_read: cl_bool,
+    origin: *const size_t,
+    region: *const size_t,
     row_pitch: size_t,
     slice_pitch: size_t,
     ptr: *mut c_void,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueReadImage(
-            command_queue,
-            image,
-            blocking_read,
-            origin,
-            region,
-            row_pitch,
-            slice_pitch,
-            ptr,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_read_image(
+        command_queue,
+        image,
+        blocking_read,
+        origin,
+        region,
+        row_pitch,
+        slice_pitch,
+        ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to read from an image object to host memory.  
+/// Calls clEnqueueReadImage to read the image.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `image` - a valid image object.
+/// * `blocking_read` - CL_BLOCKING or CL_NON_BLOCKING.
+/// * `origin` - the (x, y, z) offset in pixels in the image.
+/// * `region` - the (width, height, depth) in pixels of the region to read.
+/// * `row_pitch` - the length of each row in bytes in host memory, or 0.
+/// * `slice_pitch` - the size of each 2D slice in bytes in host memory, or 0.
+/// * `data` - the host memory to read into.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if a dimension of `region` is zero or the region is
+/// not within `data`
+/// or the error code from the OpenCL C API function.  
+/// The event must be released, even when the read is blocking.
+///
+/// # Safety
+///
+/// `T` must be plain old data that is valid for any bit pattern, since the
+/// image is read into `data` as bytes. A non-blocking read writes to `data`
+/// until its event completes, so `data` must not be accessed or dropped before then.
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn enqueue_read_image_slice<T: Copy>(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_read: cl_bool,
+    origin: &[size_t; 3],
+    region: &[size_t; 3],
+    row_pitch: size_t,
+    slice_pitch: size_t,
+    data: &mut [T],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    if host_image_size(image, region, row_pitch, slice_pitch)? > mem::size_of_val(data) {
+        return Err(CL_INVALID_VALUE);
     }
+
+    raw::enqueue_read_image(
+        command_queue,
+        image,
+        blocking_read,
+        origin.as_ptr(),
+        region.as_ptr(),
+        row_pitch,
+        slice_pitch,
+        data.as_mut_ptr() as *mut c_void,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_write_image_slice")]
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn enqueue_write_image(
     command_queue: cl_command_queue,
     image: cl_mem,
@@ -545,29 +1236,75 @@ pub fn enqueue_write_image(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueWriteImage(
-            command_queue,
-            image,
-            blocking_write,
-            origin,
-            region,
-            row_pitch,
-            slice_pitch,
-            ptr,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_write_image(
+        command_queue,
+        image,
+        blocking_write,
+        origin,
+        region,
+        row_pitch,
+        slice_pitch,
+        ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to write to an image object from host memory.  
+/// Calls clEnqueueWriteImage to write the image.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `image` - a valid image object.
+/// * `blocking_write` - CL_BLOCKING or CL_NON_BLOCKING.
+/// * `origin` - the (x, y, z) offset in pixels in the image.
+/// * `region` - the (width, height, depth) in pixels of the region to write.
+/// * `row_pitch` - the length of each row in bytes in host memory, or 0.
+/// * `slice_pitch` - the size of each 2D slice in bytes in host memory, or 0.
+/// * `data` - the host memory to write from.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if a dimension of `region` is zero or the region is
+/// not within `data`
+/// or the error code from the OpenCL C API function.  
+/// The event must be released, even when the write is blocking.
+///
+/// # Safety
+///
+/// A non-blocking write reads from `data` until its event completes, so
+/// `data` must not be modified or dropped before then.
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn enqueue_write_image_slice<T: Copy>(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_write: cl_bool,
+    origin: &[size_t; 3],
+    region: &[size_t; 3],
+    row_pitch: size_t,
+    slice_pitch: size_t,
+    data: &[T],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    if host_image_size(image, region, row_pitch, slice_pitch)? > mem::size_of_val(data) {
+        return Err(CL_INVALID_VALUE);
     }
+
+    raw::enqueue_write_image(
+        command_queue,
+        image,
+        blocking_write,
+        origin.as_ptr(),
+        region.as_ptr(),
+        row_pitch,
+        slice_pitch,
+        data.as_ptr() as *mut c_void,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_fill_image_slice")]
 #[inline]
 pub fn enqueue_fill_image(
     command_queue: cl_command_queue,
@@ -578,26 +1315,74 @@ pub fn enqueue_fill_image(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueFillImage(
-            command_queue,
-            image,
-            fill_color,
-            origin,
-            region,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_fill_image(
+        command_queue,
+        image,
+        fill_color,
+        origin,
+        region,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// The fill color of enqueue_fill_image_slice: a four component RGBA value
+/// of the type that matches the image channel data type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFillColor {
+    /// For images with a normalized or floating-point channel data type.
+    Float([cl_float; 4]),
+    /// For images with an unnormalized signed integer channel data type.
+    Int([cl_int; 4]),
+    /// For images with an unnormalized unsigned integer channel data type.
+    Uint([cl_uint; 4]),
+}
+
+impl ImageFillColor {
+    fn as_ptr(&self) -> *const c_void {
+        match self {
+            ImageFillColor::Float(color) => color.as_ptr() as *const c_void,
+            ImageFillColor::Int(color) => color.as_ptr() as *const c_void,
+            ImageFillColor::Uint(color) => color.as_ptr() as *const c_void,
+        }
     }
 }
 
+/// Enqueue a command to fill an image object with a specified color.  
+/// Calls clEnqueueFillImage to fill the image.  
+/// CL_VERSION_1_2
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `image` - a valid image object.
+/// * `fill_color` - the RGBA color value, of the type that matches the image
+///   channel data type.
+/// * `origin` - the (x, y, z) offset in pixels in the image.
+/// * `region` - the (width, height, depth) in pixels of the region to fill.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_fill_image_slice(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    fill_color: &ImageFillColor,
+    origin: &[size_t; 3],
+    region: &[size_t; 3],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_fill_image(
+        command_queue,
+        image,
+        fill_color.as_ptr(),
+        origin.as_ptr(),
+        region.as_ptr(),
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[deprecated(note = "use the slice-based variant: enqueue_copy_image_slice")]
 #[inline]
 pub fn enqueue_copy_image(
     command_queue: cl_command_queue,
@@ -609,27 +1394,55 @@ pub fn enqueue_copy_image(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueCopyImage(
-            command_queue,
-            src_image,
-            dst_image,
-            src_origin,
-            dst_origin,
-            region,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_copy_image(
+        command_queue,
+        src_image,
+        dst_image,
+        src_origin,
+        dst_origin,
+        region,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to copy a region of an image object to another image object.  
+/// Calls clEnqueueCopyImage to copy the image.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `src_image` - the image object to copy from.
+/// * `dst_image` - the image object to copy to, with the same image format
+///   as `src_image`.
+/// * `src_origin` - the (x, y, z) offset in pixels in `src_image`.
+/// * `dst_origin` - the (x, y, z) offset in pixels in `dst_image`.
+/// * `region` - the (width, height, depth) in pixels of the region to copy.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_copy_image_slice(
+    command_queue: cl_command_queue,
+    src_image: cl_mem,
+    dst_image: cl_mem,
+    src_origin: &[size_t; 3],
+    dst_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_copy_image(
+        command_queue,
+        src_image,
+        dst_image,
+        src_origin.as_ptr(),
+        dst_origin.as_ptr(),
+        region.as_ptr(),
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_copy_image_to_buffer_slice")]
 #[inline]
 pub fn enqueue_copy_image_to_buffer(
     command_queue: cl_command_queue,
@@ -641,27 +1454,54 @@ pub fn enqueue_copy_image_to_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueCopyImageToBuffer(
-            command_queue,
-            src_image,
-            dst_buffer,
-            src_origin,
-            region,
-            dst_offset,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_copy_image_to_buffer(
+        command_queue,
+        src_image,
+        dst_buffer,
+        src_origin,
+        region,
+        dst_offset,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to copy a region of an image object to a buffer object.  
+/// Calls clEnqueueCopyImageToBuffer to copy the image.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `src_image` - the image object to copy from.
+/// * `dst_buffer` - the buffer object to copy to.
+/// * `src_origin` - the (x, y, z) offset in pixels in `src_image`.
+/// * `region` - the (width, height, depth) in pixels of the region to copy.
+/// * `dst_offset` - the offset in bytes in `dst_buffer`.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_copy_image_to_buffer_slice(
+    command_queue: cl_command_queue,
+    src_image: cl_mem,
+    dst_buffer: cl_mem,
+    src_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    dst_offset: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_copy_image_to_buffer(
+        command_queue,
+        src_image,
+        dst_buffer,
+        src_origin.as_ptr(),
+        region.as_ptr(),
+        dst_offset,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_copy_buffer_to_image_slice")]
 #[inline]
 pub fn enqueue_copy_buffer_to_image(
     command_queue: cl_command_queue,
@@ -673,29 +1513,54 @@ pub fn enqueue_copy_buffer_to_image(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueCopyBufferToImage(
-            command_queue,
-            src_buffer,
-            dst_image,
-            src_offset,
-            dst_origin,
-            region,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_copy_buffer_to_image(
+        command_queue,
+        src_buffer,
+        dst_image,
+        src_offset,
+        dst_origin,
+        region,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
-/// Note: returns event NOT pointer for consistency with other enqueue functions.  
-/// The buffer pointer is returned in the buffer_ptr mutable reference.
+/// Enqueue a command to copy a buffer object to a region of an image object.  
+/// Calls clEnqueueCopyBufferToImage to copy the buffer.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `src_buffer` - the buffer object to copy from.
+/// * `dst_image` - the image object to copy to.
+/// * `src_offset` - the offset in bytes in `src_buffer`.
+/// * `dst_origin` - the (x, y, z) offset in pixels in `dst_image`.
+/// * `region` - the (width, height, depth) in pixels of the region to copy.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_copy_buffer_to_image_slice(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_image: cl_mem,
+    src_offset: size_t,
+    dst_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_copy_buffer_to_image(
+        command_queue,
+        src_buffer,
+        dst_image,
+        src_offset,
+        dst_origin.as_ptr(),
+        region.as_ptr(),
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[deprecated(note = "use the slice-based variant: enqueue_map_buffer_slice")]
 #[inline]
 pub fn enqueue_map_buffer(
     command_queue: cl_command_queue,
@@ -708,31 +1573,20 @@ pub fn enqueue_map_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let mut status: cl_int = CL_INVALID_VALUE;
-    *buffer_ptr = unsafe {
-        clEnqueueMapBuffer(
-            command_queue,
-            buffer,
-            blocking_map,
-            map_flags,
-            offset,
-            size,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-            &mut status,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_map_buffer(
+        command_queue,
+        buffer,
+        blocking_map,
+        map_flags,
+        offset,
+        size,
+        buffer_ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
-/// Note: returns event NOT pointer for consistency with other enqueue functions.  
-/// The image pointer is returned in the image_ptr mutable reference.
+#[deprecated(note = "use the slice-based variant: enqueue_map_image_slice")]
 #[inline]
 pub fn enqueue_map_image(
     command_queue: cl_command_queue,
@@ -747,31 +1601,71 @@ pub fn enqueue_map_image(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<*mut c_void, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let mut status: cl_int = CL_INVALID_VALUE;
-    *image_ptr = unsafe {
-        clEnqueueMapImage(
-            command_queue,
-            image,
-            blocking_map,
-            map_flags,
-            origin,
-            region,
-            image_row_pitch,
-            image_slice_pitch,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-            &mut status,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_map_image(
+        command_queue,
+        image,
+        blocking_map,
+        map_flags,
+        origin,
+        region,
+        image_row_pitch,
+        image_slice_pitch,
+        image_ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
+/// Enqueue a command to map a region of an image object into host memory.  
+/// Calls clEnqueueMapImage to map the region, which must be unmapped with
+/// enqueue_unmap_mem_object_slice.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `image` - a valid image object.
+/// * `blocking_map` - CL_BLOCKING or CL_NON_BLOCKING.
+/// * `map_flags` - CL_MAP_READ, CL_MAP_WRITE or CL_MAP_WRITE_INVALIDATE_REGION.
+/// * `origin` - the (x, y, z) offset in pixels in the image.
+/// * `region` - the (width, height, depth) in pixels of the region to map.
+/// * `image_row_pitch` - set to the length of each row in bytes of the
+///   mapped region.
+/// * `image_slice_pitch` - set to the size of each 2D slice in bytes of the
+///   mapped region, or 0 for a 1D or 2D image.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the pointer to the mapped region and the new
+/// OpenCL event, which must be released
+/// or the error code from the OpenCL C API function.
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn enqueue_map_image_slice(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_map: cl_bool,
+    map_flags: cl_map_flags,
+    origin: &[size_t; 3],
+    region: &[size_t; 3],
+    image_row_pitch: &mut size_t,
+    image_slice_pitch: &mut size_t,
+    event_wait_list: &[cl_event],
+) -> Result<(*mut c_void, cl_event), cl_int> {
+    let mut mapped_ptr: *mut c_void = ptr::null_mut();
+    let event = raw::enqueue_map_image(
+        command_queue,
+        image,
+        blocking_map,
+        map_flags,
+        origin.as_ptr(),
+        region.as_ptr(),
+        image_row_pitch,
+        image_slice_pitch,
+        &mut mapped_ptr,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )?;
+    Ok((mapped_ptr, event))
+}
+
+#[deprecated(note = "use the slice-based variant: enqueue_unmap_mem_object_slice")]
 #[inline]
 pub fn enqueue_unmap_mem_object(
     command_queue: cl_command_queue,
@@ -780,24 +1674,43 @@ pub fn enqueue_unmap_mem_object(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueUnmapMemObject(
-            command_queue,
-            memobj,
-            mapped_ptr,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_unmap_mem_object(
+        command_queue,
+        memobj,
+        mapped_ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
+/// Enqueue a command to unmap a region of a memory object mapped by
+/// enqueue_map_image_slice or enqueue_map_buffer.  
+/// Calls clEnqueueUnmapMemObject to unmap the region.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `memobj` - the mapped memory object.
+/// * `mapped_ptr` - the pointer returned by the map.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_unmap_mem_object_slice(
+    command_queue: cl_command_queue,
+    memobj: cl_mem,
+    mapped_ptr: *mut c_void,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_unmap_mem_object(
+        command_queue,
+        memobj,
+        mapped_ptr,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[deprecated(note = "use the slice-based variant: enqueue_migrate_mem_objects")]
 #[inline]
 pub fn enqueue_migrate_mem_object(
     command_queue: cl_command_queue,
@@ -807,25 +1720,52 @@ pub fn enqueue_migrate_mem_object(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueMigrateMemObjects(
-            command_queue,
-            num_mem_objects,
-            mem_objects,
-            flags,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_migrate_mem_object(
+        command_queue,
+        num_mem_objects,
+        mem_objects,
+        flags,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to indicate which device a set of memory objects should
+/// be associated with.  
+/// Calls clEnqueueMigrateMemObjects to migrate the memory objects.  
+/// CL_VERSION_1_2
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `mem_objects` - the memory objects to migrate, must not be empty.
+/// * `flags` - a bit-field of CL_MIGRATE_MEM_OBJECT_HOST and
+///   CL_MIGRATE_MEM_OBJECT_CONTENT_UNDEFINED, or 0 to migrate to the device
+///   associated with `command_queue`.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_migrate_mem_objects(
+    command_queue: cl_command_queue,
+    mem_objects: &[cl_mem],
+    flags: cl_mem_migration_flags,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    if mem_objects.is_empty() {
+        return Err(CL_INVALID_VALUE);
     }
+
+    raw::enqueue_migrate_mem_object(
+        command_queue,
+        mem_objects.len() as cl_uint,
+        mem_objects.as_ptr(),
+        flags,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_nd_range_kernel_slice")]
 #[inline]
 pub fn enqueue_nd_range_kernel(
     command_queue: cl_command_queue,
@@ -837,29 +1777,69 @@ pub fn enqueue_nd_range_kernel(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueNDRangeKernel(
-            command_queue,
-            kernel,
-            work_dim,
-            global_work_offset,
-            global_work_dims,
-            local_work_dims,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_nd_range_kernel(
+        command_queue,
+        kernel,
+        work_dim,
+        global_work_offset,
+        global_work_dims,
+        local_work_dims,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to execute a kernel on a device.  
+/// Calls clEnqueueNDRangeKernel to execute the kernel.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `kernel` - a valid kernel object, with all of its arguments set.
+/// * `global_work_offset` - the offsets of the global IDs in each dimension,
+///   or None for zero offsets.
+/// * `global_work_size` - the number of global work-items in each dimension,
+///   its length, 1, 2 or 3, is the number of dimensions.
+/// * `local_work_size` - the number of work-items in a work-group in each
+///   dimension, or None for the OpenCL implementation to choose.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_WORK_DIMENSION if `global_work_size` does not have 1, 2 or 3
+/// dimensions
+/// or CL_INVALID_VALUE if `global_work_offset` or `local_work_size` has a
+/// different number of dimensions
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_nd_range_kernel_slice(
+    command_queue: cl_command_queue,
+    kernel: cl_kernel,
+    global_work_offset: Option<&[size_t]>,
+    global_work_size: &[size_t],
+    local_work_size: Option<&[size_t]>,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let work_dim = global_work_size.len();
+    if !(1..=3).contains(&work_dim) {
+        return Err(CL_INVALID_WORK_DIMENSION);
     }
+    let has_work_dim = |sizes: Option<&[size_t]>| sizes.iter().all(|s| s.len() == work_dim);
+    if !has_work_dim(global_work_offset) || !has_work_dim(local_work_size) {
+        return Err(CL_INVALID_VALUE);
+    }
+
+    raw::enqueue_nd_range_kernel(
+        command_queue,
+        kernel,
+        work_dim as cl_uint,
+        global_work_offset.map_or(ptr::null(), |offset| offset.as_ptr()),
+        global_work_size.as_ptr(),
+        local_work_size.map_or(ptr::null(), |size| size.as_ptr()),
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
-// Deprecated in CL_VERSION_2_0
 #[cfg(feature = "CL_VERSION_1_2")]
+#[deprecated(note = "use the slice-based variant: enqueue_task_slice")]
 #[inline]
 pub fn enqueue_task(
     command_queue: cl_command_queue,
@@ -867,23 +1847,40 @@ pub fn enqueue_task(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueTask(
-            command_queue,
-            kernel,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_task(
+        command_queue,
+        kernel,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
+/// Enqueue a command to execute a kernel with a single work-item.  
+/// Calls clEnqueueTask to execute the kernel.  
+/// CL_VERSION_1_2, deprecated in CL_VERSION_2_0
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `kernel` - a valid kernel object, with all of its arguments set.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_1_2")]
+#[inline]
+pub fn enqueue_task_slice(
+    command_queue: cl_command_queue,
+    kernel: cl_kernel,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_task(
+        command_queue,
+        kernel,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[deprecated(note = "use the slice-based variant: enqueue_native_kernel_slice")]
 #[inline]
 pub fn enqueue_native_kernel(
     command_queue: cl_command_queue,
@@ -896,72 +1893,151 @@ pub fn enqueue_native_kernel(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueNativeKernel(
-            command_queue,
-            user_func,
-            args,
-            cb_args,
-            num_mem_objects,
-            mem_list,
-            args_mem_loc,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
+    raw::enqueue_native_kernel(
+        command_queue,
+        user_func,
+        args,
+        cb_args,
+        num_mem_objects,
+        mem_list,
+        args_mem_loc,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to execute a native C/C++ function.  
+/// Calls clEnqueueNativeKernel to execute the function, which is called with
+/// a pointer to a copy of `args`.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `user_func` - the function to execute.
+/// * `args` - the arguments of the function, copied by OpenCL.
+/// * `mem_list` - memory objects whose global memory pointers replace the
+///   pointers at `args_mem_loc` in the copy of `args`.
+/// * `args_mem_loc` - pointers to the locations in `args` of the memory
+///   object handles, the same length as `mem_list`.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if `mem_list` and `args_mem_loc` have different
+/// lengths or a location is not within `args`
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_native_kernel_slice(
+    command_queue: cl_command_queue,
+    user_func: Option<extern "C" fn(*mut c_void)>,
+    args: &[u8],
+    mem_list: &[cl_mem],
+    args_mem_loc: &[*const c_void],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let args_start = args.as_ptr() as usize;
+    let args_end = args_start + args.len();
+    let is_in_args = |loc: &*const c_void| {
+        let loc = *loc as usize;
+        args_start <= loc && loc < args_end && mem::size_of::<cl_mem>() <= args_end - loc
     };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    if mem_list.len() != args_mem_loc.len() || !args_mem_loc.iter().all(is_in_args) {
+        return Err(CL_INVALID_VALUE);
     }
+
+    raw::enqueue_native_kernel(
+        command_queue,
+        user_func,
+        if args.is_empty() {
+            ptr::null_mut()
+        } else {
+            args.as_ptr() as *mut c_void
+        },
+        args.len(),
+        mem_list.len() as cl_uint,
+        if mem_list.is_empty() {
+            ptr::null()
+        } else {
+            mem_list.as_ptr()
+        },
+        if args_mem_loc.is_empty() {
+            ptr::null()
+        } else {
+            args_mem_loc.as_ptr()
+        },
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[deprecated(note = "use the slice-based variant: enqueue_marker_with_wait_list_slice")]
 #[inline]
 pub fn enqueue_marker_with_wait_list(
     command_queue: cl_command_queue,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueMarkerWithWaitList(
-            command_queue,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_marker_with_wait_list(command_queue, num_events_in_wait_list, event_wait_list)
 }
 
+/// Enqueue a marker command which waits for events, or for all previously
+/// enqueued commands if the wait list is empty, to complete.  
+/// Calls clEnqueueMarkerWithWaitList.  
+/// CL_VERSION_1_2
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `event_wait_list` - events that must complete before the marker
+///   completes, or empty to wait for all previously enqueued commands.
+///
+/// returns a Result containing the new OpenCL event, which later commands
+/// may wait for
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_marker_with_wait_list_slice(
+    command_queue: cl_command_queue,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_marker_with_wait_list(
+        command_queue,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[deprecated(note = "use the slice-based variant: enqueue_barrier_with_wait_list_slice")]
 #[inline]
 pub fn enqueue_barrier_with_wait_list(
     command_queue: cl_command_queue,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueBarrierWithWaitList(
-            command_queue,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_barrier_with_wait_list(command_queue, num_events_in_wait_list, event_wait_list)
 }
 
+/// Enqueue a barrier command which waits for events, or for all previously
+/// enqueued commands if the wait list is empty, to complete. Commands
+/// enqueued after the barrier do not start until it has completed, even on an
+/// out-of-order command-queue.  
+/// Calls clEnqueueBarrierWithWaitList.  
+/// CL_VERSION_1_2
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `event_wait_list` - events that must complete before the barrier
+///   completes, or empty to wait for all previously enqueued commands.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn enqueue_barrier_with_wait_list_slice(
+    command_queue: cl_command_queue,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_barrier_with_wait_list(
+        command_queue,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[cfg(feature = "CL_VERSION_2_0")]
+#[deprecated(note = "use the slice-based variant: enqueue_svm_free_slice")]
 #[inline]
 pub fn enqueue_svm_free(
     command_queue: cl_command_queue,
@@ -979,26 +2055,64 @@ pub fn enqueue_svm_free(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueSVMFree(
-            command_queue,
-            num_svm_pointers,
-            svm_pointers,
-            pfn_free_func,
-            user_data,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_svm_free(
+        command_queue,
+        num_svm_pointers,
+        svm_pointers,
+        pfn_free_func,
+        user_data,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to free SVM allocations.  
+/// Calls clEnqueueSVMFree to free the SVM.  
+/// CL_VERSION_2_0
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `svm_pointers` - the SVM pointers to free, must not be empty.
+/// * `pfn_free_func` - a function to free the SVM pointers, called with
+///   `user_data`, or None for clSVMFree.
+/// * `user_data` - passed to `pfn_free_func`.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if `svm_pointers` is empty
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_0")]
+#[inline]
+pub fn enqueue_svm_free_slice(
+    command_queue: cl_command_queue,
+    svm_pointers: &[*const c_void],
+    pfn_free_func: Option<
+        extern "C" fn(
+            queue: cl_command_queue,
+            num_svm_pointers: cl_uint,
+            svm_pointers: *const *const c_void,
+            user_data: *mut c_void,
+        ),
+    >,
+    user_data: *mut c_void,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    if svm_pointers.is_empty() {
+        return Err(CL_INVALID_VALUE);
     }
+
+    raw::enqueue_svm_free(
+        command_queue,
+        svm_pointers.len() as cl_uint,
+        svm_pointers.as_ptr(),
+        pfn_free_func,
+        user_data,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[cfg(feature = "CL_VERSION_2_0")]
+#[deprecated(note = "use the slice-based variant: enqueue_svm_memcpy")]
 #[inline]
 pub fn enqueue_svm_mem_cpy(
     command_queue: cl_command_queue,
@@ -1009,26 +2123,53 @@ pub fn enqueue_svm_mem_cpy(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueSVMMemcpy(
-            command_queue,
-            blocking_copy,
-            dst_ptr,
-            src_ptr,
-            size,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_svm_mem_cpy(
+        command_queue,
+        blocking_copy,
+        dst_ptr,
+        src_ptr,
+        size,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
+/// Enqueue a command to copy between host or SVM memory.  
+/// Calls clEnqueueSVMMemcpy to copy the memory.  
+/// CL_VERSION_2_0
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `blocking_copy` - CL_BLOCKING or CL_NON_BLOCKING.
+/// * `dst_ptr` - the destination host or SVM pointer.
+/// * `src_ptr` - the source host or SVM pointer.
+/// * `size` - the size in bytes to copy.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_0")]
+#[inline]
+pub fn enqueue_svm_memcpy(
+    command_queue: cl_command_queue,
+    blocking_copy: cl_bool,
+    dst_ptr: *mut c_void,
+    src_ptr: *const c_void,
+    size: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_svm_mem_cpy(
+        command_queue,
+        blocking_copy,
+        dst_ptr,
+        src_ptr,
+        size,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[cfg(feature = "CL_VERSION_2_0")]
+#[deprecated(note = "use the slice-based variant: enqueue_svm_mem_fill_slice")]
 #[inline]
 pub fn enqueue_svm_mem_fill(
     command_queue: cl_command_queue,
@@ -1039,26 +2180,61 @@ pub fn enqueue_svm_mem_fill(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueSVMMemFill(
-            command_queue,
-            svm_ptr,
-            pattern,
-            pattern_size,
-            size,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_svm_mem_fill(
+        command_queue,
+        svm_ptr,
+        pattern,
+        pattern_size,
+        size,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to fill a region of SVM with a pattern of a given pattern size.  
+/// Calls clEnqueueSVMMemFill to fill the SVM.  
+/// CL_VERSION_2_0
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `svm_ptr` - a pointer to the SVM region to fill, aligned to the pattern size.
+/// * `pattern` - the bytes of the pattern, its length must be 1, 2, 4, 8, 16,
+///   32, 64 or 128 bytes.
+/// * `size` - the size in bytes of the region to fill, a multiple of the pattern size.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if the pattern, pointer or size are invalid
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_0")]
+#[inline]
+pub fn enqueue_svm_mem_fill_slice(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    pattern: &[u8],
+    size: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let pattern_size = pattern.len();
+    if !is_valid_pattern_size(pattern_size)
+        || !is_pattern_multiple(svm_ptr as usize, pattern_size)
+        || !is_pattern_multiple(size, pattern_size)
+    {
+        return Err(CL_INVALID_VALUE);
     }
+
+    raw::enqueue_svm_mem_fill(
+        command_queue,
+        svm_ptr,
+        pattern.as_ptr() as *const c_void,
+        pattern_size,
+        size,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
+#[cfg(feature = "CL_VERSION_2_0")]
+#[deprecated(note = "use the slice-based variant: enqueue_svm_map_slice")]
 #[inline]
 pub fn enqueue_svm_map(
     command_queue: cl_command_queue,
@@ -1069,26 +2245,54 @@ pub fn enqueue_svm_map(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueSVMMap(
-            command_queue,
-            blocking_map,
-            flags,
-            svm_ptr,
-            size,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_svm_map(
+        command_queue,
+        blocking_map,
+        flags,
+        svm_ptr,
+        size,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
+/// Enqueue a command to map a region of coarse-grained SVM for host access.  
+/// Calls clEnqueueSVMMap to map the SVM.  
+/// CL_VERSION_2_0
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `blocking_map` - CL_BLOCKING or CL_NON_BLOCKING.
+/// * `flags` - a bit-field of CL_MAP_READ, CL_MAP_WRITE or
+///   CL_MAP_WRITE_INVALIDATE_REGION.
+/// * `svm_ptr` - a pointer to the SVM region to map.
+/// * `size` - the size in bytes of the region to map.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_0")]
+#[inline]
+pub fn enqueue_svm_map_slice(
+    command_queue: cl_command_queue,
+    blocking_map: cl_bool,
+    flags: cl_map_flags,
+    svm_ptr: *mut c_void,
+    size: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_svm_map(
+        command_queue,
+        blocking_map,
+        flags,
+        svm_ptr,
+        size,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[cfg(feature = "CL_VERSION_2_0")]
+#[deprecated(note = "use the slice-based variant: enqueue_svm_unmap_slice")]
 #[inline]
 pub fn enqueue_svm_unmap(
     command_queue: cl_command_queue,
@@ -1096,24 +2300,41 @@ pub fn enqueue_svm_unmap(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueSVMUnmap(
-            command_queue,
-            svm_ptr,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_svm_unmap(
+        command_queue,
+        svm_ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to unmap a region of SVM mapped by enqueue_svm_map_slice.  
+/// Calls clEnqueueSVMUnmap to unmap the SVM.  
+/// CL_VERSION_2_0
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `svm_ptr` - the pointer passed to enqueue_svm_map_slice.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_0")]
+#[inline]
+pub fn enqueue_svm_unmap_slice(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_svm_unmap(
+        command_queue,
+        svm_ptr,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
 #[cfg(feature = "CL_VERSION_2_1")]
+#[deprecated(note = "use the slice-based variant: enqueue_svm_migrate_mem_slice")]
 #[inline]
 pub fn enqueue_svm_migrate_mem(
     command_queue: cl_command_queue,
@@ -1124,24 +2345,58 @@ pub fn enqueue_svm_migrate_mem(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueSVMMigrateMem(
-            command_queue,
-            num_svm_pointers,
-            svm_pointers,
-            sizes,
-            flags,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
+    raw::enqueue_svm_migrate_mem(
+        command_queue,
+        num_svm_pointers,
+        svm_pointers,
+        sizes,
+        flags,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to indicate which device a set of ranges of SVM
+/// allocations should be associated with.  
+/// Calls clEnqueueSVMMigrateMem to migrate the SVM.  
+/// CL_VERSION_2_1
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `svm_pointers` - pointers to the SVM ranges to migrate, must not be empty.
+/// * `sizes` - the sizes in bytes of the ranges, the same length as
+/// `svm_pointers`, or None to migrate the whole allocations.
+/// A size of zero also migrates the whole allocation.
+/// * `flags` - a bit-field of CL_MIGRATE_MEM_OBJECT_HOST and
+/// CL_MIGRATE_MEM_OBJECT_CONTENT_UNDEFINED, or 0 to migrate to the device
+/// associated with `command_queue`.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if `svm_pointers` is empty or `sizes` has a different length
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_1")]
+#[inline]
+pub fn enqueue_svm_migrate_mem_slice(
+    command_queue: cl_command_queue,
+    svm_pointers: &[*const c_void],
+    sizes: Option<&[size_t]>,
+    flags: cl_mem_migration_flags,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    if svm_pointers.is_empty() || sizes.map_or(false, |sizes| sizes.len() != svm_pointers.len())
+    {
+        return Err(CL_INVALID_VALUE);
     }
+
+    raw::enqueue_svm_migrate_mem(
+        command_queue,
+        svm_pointers.len() as cl_uint,
+        svm_pointers.as_ptr(),
+        sizes.map_or(ptr::null(), |sizes| sizes.as_ptr()),
+        flags,
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
 
@@ -1149,9 +2404,13 @@ pub fn enqueue_svm_migrate_mem(
 mod tests {
     use super::*;
     use crate::context::{create_context, release_context};
-    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::device::{get_device_ids, select_device, CL_DEVICE_TYPE_ALL, CL_DEVICE_TYPE_GPU};
     use crate::platform::get_platform_ids;
     use crate::error_codes::error_text;
+    use crate::event::{
+        create_user_event, get_event_command_execution_status,
+        set_user_event_status, wait_for_events, CommandExecutionStatus, CL_COMPLETE,
+    };
 
     #[test]
     fn test_command_queue() {
@@ -1190,6 +2449,10 @@ mod tests {
         let value = value.to_ulong();
         println!("CL_QUEUE_PROPERTIES: {}", value);
 
+        let properties = get_command_queue_properties(queue).unwrap();
+        assert_eq!(value, properties.properties());
+        assert!(properties.contains(CL_QUEUE_PROFILING_ENABLE));
+
         // CL_VERSION_2_0 value
         match get_command_queue_info(queue, CommandQueueInfo::CL_QUEUE_SIZE) {
             Ok(value) => {
@@ -1221,4 +2484,837 @@ mod tests {
 
         release_context(context).unwrap();
     }
+
+    #[test]
+    fn test_queue_properties() {
+        assert_eq!(vec![0], QueueProperties::new().to_vec());
+
+        let properties = QueueProperties::new().profiling(true).out_of_order(true);
+        assert_eq!(
+            CL_QUEUE_PROFILING_ENABLE | CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE,
+            properties.properties()
+        );
+        assert_eq!(
+            vec![
+                CommandQueueInfo::CL_QUEUE_PROPERTIES as cl_queue_properties,
+                CL_QUEUE_PROFILING_ENABLE | CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE,
+                0
+            ],
+            properties.to_vec()
+        );
+        assert_eq!(
+            CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE,
+            properties.profiling(false).properties()
+        );
+
+        let properties = QueueProperties::from(CL_QUEUE_PROFILING_ENABLE);
+        assert!(properties.contains(CL_QUEUE_PROFILING_ENABLE));
+        assert!(!properties.contains(CL_QUEUE_PROFILING_ENABLE | CL_QUEUE_ON_DEVICE));
+        assert!(properties.contains(0));
+
+        let properties = QueueProperties::new()
+            .out_of_order(true)
+            .on_device(true, true)
+            .size(16384);
+        assert_eq!(
+            vec![
+                CommandQueueInfo::CL_QUEUE_PROPERTIES as cl_queue_properties,
+                CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE
+                    | CL_QUEUE_ON_DEVICE
+                    | CL_QUEUE_ON_DEVICE_DEFAULT,
+                CommandQueueInfo::CL_QUEUE_SIZE as cl_queue_properties,
+                16384,
+                0
+            ],
+            properties.to_vec()
+        );
+        assert_eq!(
+            CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE,
+            properties.on_device(false, true).properties()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn test_create_command_queue_with_properties() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let properties = QueueProperties::new().profiling(true).to_vec();
+        let queue =
+            create_command_queue_with_properties_slice(context, device_id, &properties).unwrap();
+
+        let value = get_command_queue_info(queue, CommandQueueInfo::CL_QUEUE_PROPERTIES).unwrap();
+        let value = value.to_ulong();
+        println!("CL_QUEUE_PROPERTIES: {}", value);
+        assert_eq!(CL_QUEUE_PROFILING_ENABLE, value);
+
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_common_queue_properties() {
+        // Devices with differing capabilities
+        let devices = [
+            CL_QUEUE_PROFILING_ENABLE | CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE,
+            CL_QUEUE_PROFILING_ENABLE,
+        ];
+        let common = common_queue_properties(devices.iter().copied());
+        assert_eq!(CL_QUEUE_PROFILING_ENABLE, common);
+        assert_eq!(
+            devices[0],
+            common_queue_properties(devices[..1].iter().copied())
+        );
+        assert_eq!(0, common_queue_properties(Vec::new()));
+    }
+
+    #[test]
+    fn test_context_common_queue_properties() {
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let device_ids = [device_id];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let common = context_common_queue_properties(context).unwrap();
+        println!("common queue properties: {:#x}", common);
+        let requested = CL_QUEUE_PROFILING_ENABLE | CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE;
+        let (supported, dropped) = clamp_queue_properties(context, requested).unwrap();
+        assert_eq!(requested, supported | dropped);
+        assert_eq!(supported, requested & common);
+        assert_eq!(0, supported & dropped);
+
+        // Every device in the context accepts the supported properties
+        for &device_id in device_ids.iter() {
+            let queue = create_command_queue(context, device_id, supported).unwrap();
+            release_command_queue(queue).unwrap();
+        }
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_queue_properties_clamp_to() {
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let device_ids = [device_id];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let requested = QueueProperties::new()
+            .profiling(true)
+            .out_of_order(true)
+            .size(1024);
+        let (clamped, dropped) = requested.clamp_to(context).unwrap();
+        assert_eq!(requested.properties(), clamped.properties() | dropped);
+        assert_eq!(0, clamped.properties() & dropped);
+        assert!(requested.contains(clamped.properties()));
+        let size = CommandQueueInfo::CL_QUEUE_SIZE as cl_queue_properties;
+        assert!(clamped.to_vec().ends_with(&[size, 1024, 0]));
+
+        // Clamping is idempotent
+        assert_eq!((clamped, 0), clamped.clamp_to(context).unwrap());
+        release_context(context).unwrap();
+
+        assert!(QueueProperties::new().clamp_to(ptr::null_mut()).is_err());
+    }
+
+    #[test]
+    fn test_enqueue_marker_and_barrier() {
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let device_ids = [device_id];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let (properties, _) =
+            clamp_queue_properties(context, CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE).unwrap();
+        let queue = create_command_queue(context, device_id, properties).unwrap();
+
+        // The marker waits for the user event
+        let user_event = create_user_event(context).unwrap();
+        let marker = enqueue_marker_with_wait_list_slice(queue, &[user_event]).unwrap();
+        let barrier = enqueue_barrier_with_wait_list_slice(queue, &[marker]).unwrap();
+        flush(queue).unwrap();
+        assert_ne!(
+            CommandExecutionStatus::Complete,
+            get_event_command_execution_status(marker).unwrap()
+        );
+
+        set_user_event_status(user_event, CL_COMPLETE).unwrap();
+        wait_for_events(&[barrier]).unwrap();
+        assert_eq!(
+            CommandExecutionStatus::Complete,
+            get_event_command_execution_status(marker).unwrap()
+        );
+
+        // An empty wait list waits for all previously enqueued commands
+        let marker_all = enqueue_marker_with_wait_list_slice(queue, &[]).unwrap();
+        let barrier_all = enqueue_barrier_with_wait_list_slice(queue, &[]).unwrap();
+        finish(queue).unwrap();
+
+        for event in [user_event, marker, barrier, marker_all, barrier_all].iter() {
+            release_event(*event).unwrap();
+        }
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_read_write_buffer_blocking() {
+        use crate::memory::{create_buffer_of_size, release_mem_object, CL_MEM_READ_WRITE};
+
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let device_ids = [device_id];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_ids[0], 0).unwrap();
+
+        let input: Vec<cl_uint> = (0..1024).collect();
+        let size = mem::size_of_val(input.as_slice());
+        let buffer = create_buffer_of_size(context, CL_MEM_READ_WRITE, size).unwrap();
+
+        write_buffer_blocking(queue, buffer, 0, &input).unwrap();
+        let mut output = vec![0 as cl_uint; input.len()];
+        unsafe { read_buffer_blocking(queue, buffer, 0, &mut output) }.unwrap();
+        assert_eq!(input, output);
+
+        // Read the second half, the offset is in bytes
+        let mut half = vec![0 as cl_uint; input.len() / 2];
+        unsafe { read_buffer_blocking(queue, buffer, size / 2, &mut half) }.unwrap();
+        assert_eq!(&input[input.len() / 2..], half.as_slice());
+
+        release_mem_object(buffer).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_enqueue_copy_buffer() {
+        use crate::memory::{create_buffer_of_size, release_mem_object, CL_MEM_READ_WRITE};
+
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let device_ids = [device_id];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_ids[0], 0).unwrap();
+
+        // A 16 x 16 matrix of cl_uint
+        const WIDTH: usize = 16;
+        let input: Vec<cl_uint> = (0..(WIDTH * WIDTH) as cl_uint).collect();
+        let size = mem::size_of_val(input.as_slice());
+        let src = create_buffer_of_size(context, CL_MEM_READ_WRITE, size).unwrap();
+        let dst = create_buffer_of_size(context, CL_MEM_READ_WRITE, size).unwrap();
+        write_buffer_blocking(queue, src, 0, &input).unwrap();
+        write_buffer_blocking(queue, dst, 0, &vec![0 as cl_uint; input.len()]).unwrap();
+
+        let event = enqueue_copy_buffer_slice(queue, src, dst, 0, 0, size, &[]).unwrap();
+        let mut output = vec![0 as cl_uint; input.len()];
+        unsafe { read_buffer_blocking(queue, dst, 0, &mut output) }.unwrap();
+        assert_eq!(input, output);
+
+        // Copy the top left 4 x 4 block of src to the bottom right of dst,
+        // after the previous copy
+        let row_pitch = WIDTH * mem::size_of::<cl_uint>();
+        let block = 4 * mem::size_of::<cl_uint>();
+        let rect_event = enqueue_copy_buffer_rect_slice(
+            queue,
+            src,
+            dst,
+            &[0, 0, 0],
+            &[row_pitch - block, WIDTH - 4, 0],
+            &[block, 4, 1],
+            row_pitch,
+            0,
+            row_pitch,
+            0,
+            &[event],
+        )
+        .unwrap();
+        unsafe { read_buffer_blocking(queue, dst, 0, &mut output) }.unwrap();
+        for y in 0..4 {
+            let row = (WIDTH - 4 + y) * WIDTH + WIDTH - 4;
+            assert_eq!(&input[y * WIDTH..y * WIDTH + 4], &output[row..row + 4]);
+        }
+
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            enqueue_copy_buffer_rect_slice(
+                queue,
+                src,
+                dst,
+                &[0, 0, 0],
+                &[0, 0, 0],
+                &[block, 4, 0],
+                row_pitch,
+                0,
+                row_pitch,
+                0,
+                &[],
+            )
+        );
+
+        release_event(rect_event).unwrap();
+        release_event(event).unwrap();
+        release_mem_object(dst).unwrap();
+        release_mem_object(src).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_read_write_buffer_rect() {
+        use crate::memory::{create_buffer_of_size, release_mem_object, CL_MEM_READ_WRITE};
+
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let device_ids = [device_id];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_ids[0], 0).unwrap();
+
+        // A 16 x 16 buffer of cl_uint, initially zero
+        const WIDTH: usize = 16;
+        let row_pitch = WIDTH * mem::size_of::<cl_uint>();
+        let size = WIDTH * row_pitch;
+        let buffer = create_buffer_of_size(context, CL_MEM_READ_WRITE, size).unwrap();
+        write_buffer_blocking(queue, buffer, 0, &vec![0 as cl_uint; WIDTH * WIDTH]).unwrap();
+
+        // Write a 4 x 2 tile from the middle of an 8 x 4 host array to
+        // column 2, row 3 of the buffer
+        let host: Vec<cl_uint> = (1..=32).collect();
+        let host_row_pitch = 8 * mem::size_of::<cl_uint>();
+        let tile_width = 4 * mem::size_of::<cl_uint>();
+        let event = unsafe {
+            enqueue_write_buffer_rect_slice(
+                queue,
+                buffer,
+                CL_BLOCKING,
+                &[2 * mem::size_of::<cl_uint>(), 3, 0],
+                &[2 * mem::size_of::<cl_uint>(), 1, 0],
+                &[tile_width, 2, 1],
+                row_pitch,
+                0,
+                host_row_pitch,
+                0,
+                &host,
+                &[],
+            )
+        }
+        .unwrap();
+        release_event(event).unwrap();
+
+        let mut output = vec![0 as cl_uint; WIDTH * WIDTH];
+        unsafe { read_buffer_blocking(queue, buffer, 0, &mut output) }.unwrap();
+        assert_eq!(&host[10..14], &output[3 * WIDTH + 2..3 * WIDTH + 6]);
+        assert_eq!(&host[18..22], &output[4 * WIDTH + 2..4 * WIDTH + 6]);
+        assert_eq!(output.iter().filter(|&&x| 0 != x).count(), 8);
+
+        // Read the tile back into a packed host array, with zero pitches
+        let mut tile = vec![0 as cl_uint; 8];
+        let event = unsafe {
+            enqueue_read_buffer_rect_slice(
+                queue,
+                buffer,
+                CL_BLOCKING,
+                &[2 * mem::size_of::<cl_uint>(), 3, 0],
+                &[0, 0, 0],
+                &[tile_width, 2, 1],
+                row_pitch,
+                0,
+                0,
+                0,
+                &mut tile,
+                &[],
+            )
+        }
+        .unwrap();
+        release_event(event).unwrap();
+        assert_eq!(&host[10..14], &tile[..4]);
+        assert_eq!(&host[18..22], &tile[4..]);
+
+        // A region that is not within the host slice is rejected
+        let mut short = vec![0 as cl_uint; 7];
+        let result = unsafe {
+            enqueue_read_buffer_rect_slice(
+                queue,
+                buffer,
+                CL_BLOCKING,
+                &[0, 0, 0],
+                &[0, 0, 0],
+                &[tile_width, 2, 1],
+                row_pitch,
+                0,
+                0,
+                0,
+                &mut short,
+                &[],
+            )
+        };
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+        let result = unsafe {
+            enqueue_write_buffer_rect_slice(
+                queue,
+                buffer,
+                CL_BLOCKING,
+                &[0, 0, 0],
+                &[2 * mem::size_of::<cl_uint>(), 3, 0],
+                &[tile_width, 2, 1],
+                row_pitch,
+                0,
+                host_row_pitch,
+                0,
+                &host,
+                &[],
+            )
+        };
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+
+        release_mem_object(buffer).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_raw_and_deprecated_paths() {
+        // The deprecated path must still resolve to the raw function signature.
+        let _old: fn(
+            cl_command_queue,
+            cl_uint,
+            *const cl_mem,
+            cl_mem_migration_flags,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_migrate_mem_object;
+        let _new: fn(
+            cl_command_queue,
+            cl_uint,
+            *const cl_mem,
+            cl_mem_migration_flags,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = raw::enqueue_migrate_mem_object;
+
+        #[cfg(feature = "CL_VERSION_2_0")]
+        {
+            let _with_properties: fn(
+                cl_context,
+                cl_device_id,
+                *const cl_queue_properties,
+            ) -> Result<cl_command_queue, cl_int> = create_command_queue_with_properties;
+            let _old: fn(
+                cl_command_queue,
+                cl_bool,
+                *mut c_void,
+                *const c_void,
+                size_t,
+                cl_uint,
+                *const cl_event,
+            ) -> Result<cl_event, cl_int> = enqueue_svm_mem_cpy;
+            let _new: fn(
+                cl_command_queue,
+                cl_bool,
+                *mut c_void,
+                *const c_void,
+                size_t,
+                cl_uint,
+                *const cl_event,
+            ) -> Result<cl_event, cl_int> = raw::enqueue_svm_mem_cpy;
+            let _svm_mem_fill: fn(
+                cl_command_queue,
+                *mut c_void,
+                *const c_void,
+                size_t,
+                size_t,
+                cl_uint,
+                *const cl_event,
+            ) -> Result<cl_event, cl_int> = enqueue_svm_mem_fill;
+        }
+
+        #[cfg(feature = "CL_VERSION_2_1")]
+        {
+            let _svm_migrate_mem: fn(
+                cl_command_queue,
+                cl_uint,
+                *const *const c_void,
+                *const size_t,
+                cl_mem_migration_flags,
+                cl_uint,
+                *const cl_event,
+            ) -> Result<cl_event, cl_int> = enqueue_svm_migrate_mem;
+        }
+
+        // Functions replaced in place keep their pointer-based signatures.
+        let _fill_buffer: fn(
+            cl_command_queue,
+            cl_mem,
+            *const c_void,
+            size_t,
+            size_t,
+            size_t,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_fill_buffer;
+        let _fill_image: fn(
+            cl_command_queue,
+            cl_mem,
+            *const c_void,
+            *const size_t,
+            *const size_t,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_fill_image;
+        let _read_image: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_bool,
+            *const size_t,
+            *const size_t,
+            size_t,
+            size_t,
+            *mut c_void,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_read_image;
+        let _write_image: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_bool,
+            *const size_t,
+            *const size_t,
+            size_t,
+            size_t,
+            *mut c_void,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_write_image;
+        let _copy_image: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_mem,
+            *const size_t,
+            *const size_t,
+            *const size_t,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_copy_image;
+        let _copy_image_to_buffer: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_mem,
+            *const size_t,
+            *const size_t,
+            size_t,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_copy_image_to_buffer;
+        let _copy_buffer_to_image: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_mem,
+            size_t,
+            *const size_t,
+            *const size_t,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_copy_buffer_to_image;
+        let _marker_with_wait_list: fn(
+            cl_command_queue,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_marker_with_wait_list;
+        let _barrier_with_wait_list: fn(
+            cl_command_queue,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_barrier_with_wait_list;
+        let _map_buffer: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_bool,
+            cl_map_flags,
+            size_t,
+            size_t,
+            &mut cl_mem,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_map_buffer;
+        let _copy_buffer: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_mem,
+            size_t,
+            size_t,
+            size_t,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_copy_buffer;
+        let _copy_buffer_rect: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_mem,
+            *const size_t,
+            *const size_t,
+            *const size_t,
+            size_t,
+            size_t,
+            size_t,
+            size_t,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_copy_buffer_rect;
+        let _read_buffer_rect: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_bool,
+            *const size_t,
+            *const size_t,
+            *const size_t,
+            size_t,
+            size_t,
+            size_t,
+            size_t,
+            *mut c_void,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_read_buffer_rect;
+        let _write_buffer_rect: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_bool,
+            *const size_t,
+            *const size_t,
+            *const size_t,
+            size_t,
+            size_t,
+            size_t,
+            size_t,
+            *const c_void,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_write_buffer_rect;
+        let _read_buffer: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_bool,
+            size_t,
+            size_t,
+            *mut c_void,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_read_buffer;
+        let _write_buffer: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_bool,
+            size_t,
+            size_t,
+            *const c_void,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_write_buffer;
+        let _map_image: fn(
+            cl_command_queue,
+            cl_mem,
+            cl_bool,
+            cl_map_flags,
+            *const size_t,
+            *const size_t,
+            *mut size_t,
+            *mut size_t,
+            &mut cl_mem,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<*mut c_void, cl_int> = enqueue_map_image;
+        let _unmap_mem_object: fn(
+            cl_command_queue,
+            cl_mem,
+            *mut c_void,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_unmap_mem_object;
+        let _nd_range_kernel: fn(
+            cl_command_queue,
+            cl_kernel,
+            cl_uint,
+            *const size_t,
+            *const size_t,
+            *const size_t,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_nd_range_kernel;
+        let _native_kernel: fn(
+            cl_command_queue,
+            Option<extern "C" fn(*mut c_void)>,
+            *mut c_void,
+            size_t,
+            cl_uint,
+            *const cl_mem,
+            *const *const c_void,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_native_kernel;
+
+        #[cfg(feature = "CL_VERSION_1_2")]
+        {
+            let _task: fn(
+                cl_command_queue,
+                cl_kernel,
+                cl_uint,
+                *const cl_event,
+            ) -> Result<cl_event, cl_int> = enqueue_task;
+        }
+
+        #[cfg(feature = "CL_VERSION_2_0")]
+        {
+            let _svm_map: fn(
+                cl_command_queue,
+                cl_bool,
+                cl_map_flags,
+                *mut c_void,
+                size_t,
+                cl_uint,
+                *const cl_event,
+            ) -> Result<cl_event, cl_int> = enqueue_svm_map;
+            let _svm_unmap: fn(
+                cl_command_queue,
+                *mut c_void,
+                cl_uint,
+                *const cl_event,
+            ) -> Result<cl_event, cl_int> = enqueue_svm_unmap;
+            let _svm_free: fn(
+                cl_command_queue,
+                cl_uint,
+                *const *const c_void,
+                Option<extern "C" fn(cl_command_queue, cl_uint, *const *const c_void, *mut c_void)>,
+                *mut c_void,
+                cl_uint,
+                *const cl_event,
+            ) -> Result<cl_event, cl_int> = enqueue_svm_free;
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_paths_match() {
+        // The deprecated and slice-based paths must call the same OpenCL function.
+        let queue: cl_command_queue = ptr::null_mut();
+        let buffer: cl_mem = ptr::null_mut();
+        let old = enqueue_migrate_mem_object(queue, 1, &buffer, 0, 0, ptr::null());
+        let new = enqueue_migrate_mem_objects(queue, &[buffer], 0, &[]);
+        assert!(old.is_err());
+        assert_eq!(old, new);
+
+        let pattern = [0u8; 4];
+        let old = enqueue_fill_buffer(
+            queue,
+            buffer,
+            pattern.as_ptr() as *const c_void,
+            pattern.len(),
+            0,
+            16,
+            0,
+            ptr::null(),
+        );
+        let new = enqueue_fill_buffer_slice(queue, buffer, &pattern, 0, 16, &[]);
+        assert!(old.is_err());
+        assert_eq!(old, new);
+    }
+
+    #[test]
+    fn test_kernel_slice_checks() {
+        // Invalid slices are rejected before calling OpenCL.
+        let queue: cl_command_queue = ptr::null_mut();
+        let kernel: cl_kernel = ptr::null_mut();
+        for global in [&[][..], &[1, 1, 1, 1][..]].iter() {
+            let result = enqueue_nd_range_kernel_slice(queue, kernel, None, global, None, &[]);
+            assert_eq!(Err(CL_INVALID_WORK_DIMENSION), result);
+        }
+        let result = enqueue_nd_range_kernel_slice(queue, kernel, Some(&[0]), &[8, 8], None, &[]);
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+        let result = enqueue_nd_range_kernel_slice(queue, kernel, None, &[8, 8], Some(&[4]), &[]);
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+
+        let args = [0u8; 16];
+        let buffer: cl_mem = ptr::null_mut();
+        let inside = args[8..].as_ptr() as *const c_void;
+        let past_end = args[12..].as_ptr() as *const c_void;
+        let result = enqueue_native_kernel_slice(queue, None, &args, &[buffer], &[], &[]);
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+        let result = enqueue_native_kernel_slice(queue, None, &args, &[buffer], &[past_end], &[]);
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+        let result = enqueue_native_kernel_slice(queue, None, &args, &[buffer], &[inside], &[]);
+        assert_ne!(Err(CL_INVALID_VALUE), result);
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    #[test]
+    fn test_queue_properties_not_terminated() {
+        // A properties list must be zero terminated
+        let context: cl_context = ptr::null_mut();
+        let device: cl_device_id = ptr::null_mut();
+        let properties = QueueProperties::new().profiling(true).to_vec();
+        let unterminated = &properties[..properties.len() - 1];
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            create_command_queue_with_properties_slice(context, device, unterminated)
+        );
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    #[test]
+    fn test_svm_mem_fill_invalid_pattern() {
+        // Invalid arguments are rejected before calling OpenCL
+        let queue: cl_command_queue = ptr::null_mut();
+        let svm_ptr = 0x1000 as *mut c_void;
+        let pattern = [0u8; 3];
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            enqueue_svm_mem_fill_slice(queue, svm_ptr, &pattern, 12, &[])
+        );
+        let pattern = [0u8; 8];
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            enqueue_svm_mem_fill_slice(queue, svm_ptr, &pattern, 12, &[])
+        );
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            enqueue_svm_mem_fill_slice(queue, 0x1004 as *mut c_void, &pattern, 16, &[])
+        );
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            enqueue_svm_mem_fill_slice(queue, svm_ptr, &[], 16, &[])
+        );
+    }
+
+    #[cfg(feature = "CL_VERSION_2_1")]
+    #[test]
+    fn test_svm_migrate_mem_invalid_sizes() {
+        // Invalid arguments are rejected before calling OpenCL
+        let queue: cl_command_queue = ptr::null_mut();
+        let svm_pointers = [0x1000 as *const c_void, 0x2000 as *const c_void];
+        let sizes: [size_t; 1] = [64];
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            enqueue_svm_migrate_mem_slice(queue, &svm_pointers, Some(&sizes), 0, &[])
+        );
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            enqueue_svm_migrate_mem_slice(queue, &[], None, 0, &[])
+        );
+    }
+
+    #[test]
+    fn test_is_valid_pattern_size() {
+        for size in [1, 2, 4, 8, 16, 32, 64, 128].iter() {
+            assert!(is_valid_pattern_size(*size));
+        }
+        for size in [0, 3, 6, 12, 256].iter() {
+            assert!(!is_valid_pattern_size(*size));
+        }
+    }
+
+    #[test]
+    fn test_host_rect_size() {
+        // Packed, with zero pitches
+        assert_eq!(Some(32), host_rect_size(&[0, 0, 0], &[16, 2, 1], 0, 0));
+        assert_eq!(Some(96), host_rect_size(&[0, 0, 0], &[16, 2, 3], 0, 0));
+        // The last row ends at the width of the region, not the row pitch
+        assert_eq!(Some(120), host_rect_size(&[8, 1, 0], &[16, 3, 1], 32, 0));
+        assert_eq!(Some(88), host_rect_size(&[8, 0, 1], &[16, 1, 1], 32, 64));
+        assert_eq!(None, host_rect_size(&[0, 0, 0], &[16, 0, 1], 0, 0));
+        assert_eq!(None, host_rect_size(&[0, 0, 0], &[size_t::MAX, 2, 1], 0, 0));
+    }
 }
\ No newline at end of file
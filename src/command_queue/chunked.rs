@@ -0,0 +1,313 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Buffer transfers split into chunks.
+//!
+//! Some drivers mis-handle a single transfer larger than 4 GiB, e.g. where an
+//! ICD shim truncates the size to 32 bits. These functions split a transfer
+//! into chunks of at most `max_chunk` bytes, where each chunk waits for the
+//! event of the previous chunk.
+
+use super::raw::{enqueue_read_buffer, enqueue_write_buffer};
+use super::CommandQueueInfo;
+use super::{finish, get_command_queue_info, wait_list_ptr};
+use crate::device::device_info_cache;
+use crate::error_codes::CL_INVALID_VALUE;
+use crate::event::{release_event, wait_for_events};
+use crate::types::{
+    cl_bool, cl_command_queue, cl_device_id, cl_event, cl_int, cl_mem, cl_uint, cl_ulong,
+    CL_BLOCKING, CL_NON_BLOCKING,
+};
+
+use libc::{c_void, size_t};
+use std::cmp;
+
+/// Get a conservative maximum chunk size for buffer transfers on a device.
+/// The smaller of CL_DEVICE_MAX_MEM_ALLOC_SIZE and half of the address
+/// space of the device, limited to 2 GiB.
+/// Calls clGetDeviceInfo, unless the values are in the DeviceInfoCache of
+/// the device helpers.
+///
+/// * `device` - a valid OpenCL device.
+///
+/// returns a Result containing the maximum chunk size in bytes
+/// or the error code from the OpenCL C API function.
+pub fn default_max_chunk(device: cl_device_id) -> Result<size_t, cl_int> {
+    let cache = device_info_cache();
+    let max_alloc_size = cache.max_mem_alloc_size(device)?;
+    let address_bits = cache.address_bits(device)?;
+    let address_limit: cl_ulong = 1 << (address_bits.clamp(2, 32) - 1);
+    let max_chunk = cmp::min(max_alloc_size, address_limit);
+    Ok(cmp::max(cmp::min(max_chunk, size_t::MAX as cl_ulong), 1) as size_t)
+}
+
+/// The (start, size) of each chunk of a transfer of `size` bytes.
+fn chunk_ranges(size: size_t, max_chunk: size_t) -> impl Iterator<Item = (size_t, size_t)> {
+    (0..size)
+        .step_by(max_chunk)
+        .map(move |start| (start, cmp::min(max_chunk, size - start)))
+}
+
+// Wait for an enqueued chunk and release its event, after an error.
+// The host memory of the transfer must not be released while the chunk may still use it.
+fn abandon_chunk(command_queue: cl_command_queue, event: cl_event) {
+    if wait_for_events(&[event]).is_err() {
+        finish(command_queue).unwrap_or(());
+    }
+    release_event(event).unwrap_or(());
+}
+
+// Enqueue a transfer in chunks, where the first chunk waits for
+// event_wait_list and each following chunk waits for the previous chunk.
+// The last chunk is blocking, so the whole transfer is complete on return.
+// On an error, the chunks that were enqueued complete before it is returned.
+fn enqueue_chunked<F>(
+    command_queue: cl_command_queue,
+    size: size_t,
+    max_chunk: size_t,
+    event_wait_list: &[cl_event],
+    mut enqueue: F,
+) -> Result<cl_event, cl_int>
+where
+    F: FnMut(size_t, size_t, cl_bool, &[cl_event]) -> Result<cl_event, cl_int>,
+{
+    if 0 == size {
+        return Err(CL_INVALID_VALUE);
+    }
+    let max_chunk = if 0 == max_chunk {
+        let device =
+            get_command_queue_info(command_queue, CommandQueueInfo::CL_QUEUE_DEVICE)?.to_ptr();
+        default_max_chunk(device as cl_device_id)?
+    } else {
+        max_chunk
+    };
+
+    let mut previous: Option<cl_event> = None;
+    for (start, chunk_size) in chunk_ranges(size, max_chunk) {
+        let blocking = if size == start + chunk_size {
+            CL_BLOCKING
+        } else {
+            CL_NON_BLOCKING
+        };
+        let result = match previous {
+            Some(event) => enqueue(start, chunk_size, blocking, &[event]),
+            None => enqueue(start, chunk_size, blocking, event_wait_list),
+        };
+        let event = match result {
+            Ok(event) => event,
+            Err(e) => {
+                if let Some(event) = previous {
+                    abandon_chunk(command_queue, event);
+                }
+                return Err(e);
+            }
+        };
+        if let Some(previous) = previous.replace(event) {
+            if let Err(e) = release_event(previous) {
+                abandon_chunk(command_queue, event);
+                return Err(e);
+            }
+        }
+    }
+    Ok(previous.unwrap())
+}
+
+/// Enqueue commands to write host memory to a buffer in chunks.
+/// Calls clEnqueueWriteBuffer for each chunk, each chunk waiting for the
+/// previous one. The last chunk is blocking, so `data` may be reused when
+/// this function returns.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `offset` - the offset in bytes in the buffer to write to.
+/// * `data` - the data to write, must not be empty.
+/// * `max_chunk` - the maximum size in bytes of a chunk, or zero for
+///   `default_max_chunk` of the command-queue device.
+/// * `event_wait_list` - events that must complete before the first chunk is written.
+///
+/// returns a Result containing the event of the last chunk
+/// or the error code from the OpenCL C API function.
+pub fn enqueue_write_buffer_chunked(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    offset: size_t,
+    data: &[u8],
+    max_chunk: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    enqueue_chunked(
+        command_queue,
+        data.len(),
+        max_chunk,
+        event_wait_list,
+        |start, size, blocking, wait_list| {
+            enqueue_write_buffer(
+                command_queue,
+                buffer,
+                blocking,
+                offset + start,
+                size,
+                data[start..].as_ptr() as *const c_void,
+                wait_list.len() as cl_uint,
+                wait_list_ptr(wait_list),
+            )
+        },
+    )
+}
+
+/// Enqueue commands to read a buffer to host memory in chunks.
+/// Calls clEnqueueReadBuffer for each chunk, each chunk waiting for the
+/// previous one. The last chunk is blocking, so `data` contains the buffer
+/// contents when this function returns.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `offset` - the offset in bytes in the buffer to read from.
+/// * `data` - the host memory to read into, must not be empty.
+/// * `max_chunk` - the maximum size in bytes of a chunk, or zero for
+///   `default_max_chunk` of the command-queue device.
+/// * `event_wait_list` - events that must complete before the first chunk is read.
+///
+/// returns a Result containing the event of the last chunk
+/// or the error code from the OpenCL C API function.
+pub fn enqueue_read_buffer_chunked(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    offset: size_t,
+    data: &mut [u8],
+    max_chunk: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let size = data.len();
+    let data_ptr = data.as_mut_ptr();
+    enqueue_chunked(
+        command_queue,
+        size,
+        max_chunk,
+        event_wait_list,
+        |start, size, blocking, wait_list| {
+            enqueue_read_buffer(
+                command_queue,
+                buffer,
+                blocking,
+                offset + start,
+                size,
+                unsafe { data_ptr.add(start) } as *mut c_void,
+                wait_list.len() as cl_uint,
+                wait_list_ptr(wait_list),
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::{create_command_queue, release_command_queue};
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
+    use crate::error_codes::CL_OUT_OF_RESOURCES;
+    use crate::event::{create_user_event, set_user_event_status, CL_COMPLETE};
+    use crate::macros::refcount_hooks::balance;
+    use crate::memory::{create_buffer_of_size, release_mem_object, CL_MEM_READ_WRITE};
+    use crate::platform::get_platform_ids;
+    use std::ptr;
+
+    #[test]
+    fn test_chunk_ranges() {
+        let chunks: Vec<(size_t, size_t)> = chunk_ranges(1 << 20, 1 << 16).collect();
+        assert_eq!(16, chunks.len());
+        assert_eq!((0, 1 << 16), chunks[0]);
+        assert_eq!((15 << 16, 1 << 16), chunks[15]);
+
+        let chunks: Vec<(size_t, size_t)> = chunk_ranges(10, 4).collect();
+        assert_eq!(vec![(0, 4), (4, 4), (8, 2)], chunks);
+
+        let chunks: Vec<(size_t, size_t)> = chunk_ranges(3, 4).collect();
+        assert_eq!(vec![(0, 3)], chunks);
+    }
+
+    #[test]
+    fn test_chunked_middle_chunk_error() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids[..1], ptr::null(), None, ptr::null_mut()).unwrap();
+
+        // The first chunk is enqueued, the second chunk fails
+        let mut events: Vec<cl_event> = Vec::new();
+        let result = enqueue_chunked(ptr::null_mut(), 12, 4, &[], |start, _, _, _| {
+            if 0 == start {
+                let event = create_user_event(context)?;
+                set_user_event_status(event, CL_COMPLETE)?;
+                events.push(event);
+                Ok(event)
+            } else {
+                Err(CL_OUT_OF_RESOURCES)
+            }
+        });
+        assert_eq!(Err(CL_OUT_OF_RESOURCES), result);
+
+        // The event of the first chunk was released
+        assert_eq!(1, events.len());
+        assert_eq!(-1, balance(events[0] as usize));
+
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_chunked_transfers() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+
+        let max_chunk = default_max_chunk(device_id).unwrap();
+        println!("default_max_chunk: {}", max_chunk);
+        assert!(0 < max_chunk && max_chunk <= 1 << 31);
+
+        const SIZE: usize = 1 << 20;
+        const MAX_CHUNK: usize = 1 << 16;
+        let buffer = create_buffer_of_size(context, CL_MEM_READ_WRITE, SIZE).unwrap();
+
+        let data: Vec<u8> = (0..SIZE).map(|i| (i % 251) as u8).collect();
+        let event = enqueue_write_buffer_chunked(queue, buffer, 0, &data, MAX_CHUNK, &[]).unwrap();
+
+        // The first read chunk waits for the last write chunk
+        let mut result = vec![0u8; SIZE];
+        let read_event =
+            enqueue_read_buffer_chunked(queue, buffer, 0, &mut result, MAX_CHUNK, &[event])
+                .unwrap();
+        assert!(data == result);
+
+        // Read part of the buffer, with an offset
+        let mut part = vec![0u8; 3 * MAX_CHUNK / 2];
+        let part_event =
+            enqueue_read_buffer_chunked(queue, buffer, MAX_CHUNK, &mut part, 0, &[]).unwrap();
+        assert!(data[MAX_CHUNK..MAX_CHUNK + part.len()] == part[..]);
+
+        let mut empty: [u8; 0] = [];
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            enqueue_read_buffer_chunked(queue, buffer, 0, &mut empty, MAX_CHUNK, &[])
+        );
+
+        release_event(part_event).unwrap();
+        release_event(read_event).unwrap();
+        release_event(event).unwrap();
+        release_mem_object(buffer).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+}
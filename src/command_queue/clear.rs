@@ -0,0 +1,311 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Buffer clearing helpers.
+//!
+//! Buffers are cleared with clEnqueueFillBuffer by default. Since filling
+//! with a small pattern is slow on some drivers, a buffer may instead be
+//! cleared by a small kernel, which is built the first time that it is used
+//! in a context and then cached until `release_clear_kernel` is called.
+
+use super::CommandQueueInfo;
+use super::{
+    enqueue_fill_buffer_slice, enqueue_marker_with_wait_list_slice, enqueue_nd_range_kernel_slice,
+    get_command_queue_info,
+};
+use crate::context::{get_context_info, ContextInfo};
+use crate::kernel::{create_kernel, release_kernel, KernelArg};
+use crate::memory::{get_mem_object_info, MemInfo};
+use crate::program::{build_program, create_program_with_source, release_program};
+use crate::types::{cl_command_queue, cl_context, cl_event, cl_int, cl_kernel, cl_mem, cl_program};
+
+use libc::size_t;
+use std::ffi::CString;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// How to clear a buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClearStrategy {
+    /// Fill the buffer with a single zero byte pattern, see enqueue_fill_buffer_slice.
+    #[default]
+    Fill,
+    /// Run a kernel that zeroes each byte of the buffer.
+    Kernel,
+}
+
+static CLEAR_SOURCE: &str = r#"
+kernel void cl3_clear_buffer(global uchar* buffer)
+{
+    buffer[get_global_id(0)] = 0;
+}"#;
+
+static CLEAR_KERNEL_NAME: &str = "cl3_clear_buffer";
+
+// A clearing program and kernel for each context, keyed by the context.
+// The handles are stored as usize since raw pointers are not Send.
+struct ClearKernel {
+    context: usize,
+    program: usize,
+    kernel: usize,
+}
+
+static CLEAR_KERNELS: Mutex<Vec<ClearKernel>> = Mutex::new(Vec::new());
+
+// The number of clearing programs built, to verify that kernels are cached.
+static CLEAR_KERNEL_BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+fn build_clear_kernel(context: cl_context) -> Result<ClearKernel, cl_int> {
+    let devices = get_context_info(context, ContextInfo::CL_CONTEXT_DEVICES)?.to_vec_device_id();
+    let program = create_program_with_source(context, &[CLEAR_SOURCE])?;
+    let options = CString::default();
+    let kernel_name = CString::new(CLEAR_KERNEL_NAME).unwrap();
+    let kernel = build_program(program, &devices, &options, None, ptr::null_mut())
+        .and_then(|_| create_kernel(program, &kernel_name));
+    match kernel {
+        Ok(kernel) => {
+            CLEAR_KERNEL_BUILDS.fetch_add(1, Ordering::SeqCst);
+            Ok(ClearKernel {
+                context: context as usize,
+                program: program as usize,
+                kernel: kernel as usize,
+            })
+        }
+        Err(e) => {
+            release_program(program).unwrap_or(());
+            Err(e)
+        }
+    }
+}
+
+// Release a clearing kernel and its program, returning the first error.
+fn release_clear_kernel_objects(k: ClearKernel) -> Result<(), cl_int> {
+    let kernel_result = release_kernel(k.kernel as cl_kernel);
+    let program_result = release_program(k.program as cl_program);
+    kernel_result.and(program_result)
+}
+
+fn enqueue_clear_kernel(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    offset: size_t,
+    size: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let context =
+        get_command_queue_info(command_queue, CommandQueueInfo::CL_QUEUE_CONTEXT)?.to_ptr();
+
+    loop {
+        {
+            // Hold the lock while the kernel argument is set and the kernel
+            // enqueued, since kernel arguments are shared by every user of the kernel.
+            let kernels = CLEAR_KERNELS.lock().unwrap();
+            if let Some(k) = kernels.iter().find(|k| k.context == context as usize) {
+                let kernel = k.kernel as cl_kernel;
+                buffer.set_arg(kernel, 0)?;
+                return enqueue_nd_range_kernel_slice(
+                    command_queue,
+                    kernel,
+                    Some(&[offset]),
+                    &[size],
+                    None,
+                    event_wait_list,
+                );
+            }
+        }
+
+        // Build the kernel without holding the lock, so that building for one
+        // context does not block clearing buffers in other contexts.
+        let built = build_clear_kernel(context as cl_context)?;
+        let mut kernels = CLEAR_KERNELS.lock().unwrap();
+        if kernels.iter().any(|k| k.context == context as usize) {
+            // Another thread built a kernel for the context first
+            drop(kernels);
+            release_clear_kernel_objects(built)?;
+        } else {
+            kernels.push(built);
+        }
+    }
+}
+
+/// Enqueue a command to set a region of a buffer to zero.  
+/// Calls clEnqueueFillBuffer or clEnqueueNDRangeKernel depending on `strategy`.  
+/// CL_VERSION_1_2
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `offset` - the offset in bytes of the region in the buffer.
+/// * `size` - the size in bytes of the region to clear.
+/// * `strategy` - whether to fill the region or run the clearing kernel.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.  
+/// Clearing an empty region does nothing: the event is a marker that
+/// completes when the events in `event_wait_list` have completed.
+pub fn clear_buffer_region_with(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    offset: size_t,
+    size: size_t,
+    strategy: ClearStrategy,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    if 0 == size {
+        return enqueue_marker_with_wait_list_slice(command_queue, event_wait_list);
+    }
+
+    match strategy {
+        ClearStrategy::Fill => {
+            enqueue_fill_buffer_slice(command_queue, buffer, &[0u8], offset, size, event_wait_list)
+        }
+        ClearStrategy::Kernel => {
+            enqueue_clear_kernel(command_queue, buffer, offset, size, event_wait_list)
+        }
+    }
+}
+
+/// Enqueue a command to set a region of a buffer to zero.  
+/// Calls clEnqueueFillBuffer with a single zero byte pattern.  
+/// CL_VERSION_1_2
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `offset` - the offset in bytes of the region in the buffer.
+/// * `size` - the size in bytes of the region to clear.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn clear_buffer_region(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    offset: size_t,
+    size: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    clear_buffer_region_with(
+        command_queue,
+        buffer,
+        offset,
+        size,
+        ClearStrategy::Fill,
+        event_wait_list,
+    )
+}
+
+/// Enqueue a command to set the whole of a buffer to zero.  
+/// Calls clGetMemObjectInfo for CL_MEM_SIZE and clEnqueueFillBuffer with a
+/// single zero byte pattern.  
+/// CL_VERSION_1_2
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn zero_buffer(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let size = get_mem_object_info(buffer, MemInfo::CL_MEM_SIZE)?.to_size();
+    clear_buffer_region(command_queue, buffer, 0, size, event_wait_list)
+}
+
+/// Release the clearing kernel built for a context by ClearStrategy::Kernel.  
+/// The cached program holds a reference to its context, so this must be
+/// called before the context can be destroyed. It does nothing if no
+/// clearing kernel has been built for the context.
+///
+/// * `context` - the OpenCL context.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+pub fn release_clear_kernel(context: cl_context) -> Result<(), cl_int> {
+    let mut kernels = CLEAR_KERNELS.lock().unwrap();
+    if let Some(index) = kernels.iter().position(|k| k.context == context as usize) {
+        let k = kernels.swap_remove(index);
+        drop(kernels);
+        release_clear_kernel_objects(k)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::read_buffer_blocking;
+    use crate::command_queue::{create_command_queue, finish, release_command_queue};
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
+    use crate::event::release_event;
+    use crate::memory::{create_buffer_from_slice, release_mem_object, CL_MEM_READ_WRITE};
+    use crate::platform::get_platform_ids;
+
+    #[test]
+    fn test_clear_buffer_strategies() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+
+        const SIZE: usize = 1000;
+        let mut data = [0xffu8; SIZE];
+        let buffer = create_buffer_from_slice(context, CL_MEM_READ_WRITE, &data).unwrap();
+
+        let builds = CLEAR_KERNEL_BUILDS.load(Ordering::SeqCst);
+        let read_back = |data: &mut [u8]| {
+            unsafe { read_buffer_blocking(queue, buffer, 0, data) }.unwrap();
+        };
+
+        // Clear the middle of the buffer with the kernel, twice
+        for _ in 0..2 {
+            let event =
+                clear_buffer_region_with(queue, buffer, 100, 800, ClearStrategy::Kernel, &[])
+                    .unwrap();
+            release_event(event).unwrap();
+        }
+        finish(queue).unwrap();
+        assert_eq!(builds + 1, CLEAR_KERNEL_BUILDS.load(Ordering::SeqCst));
+
+        read_back(&mut data);
+        assert!(data[..100].iter().all(|&x| 0xff == x));
+        assert!(data[100..900].iter().all(|&x| 0 == x));
+        assert!(data[900..].iter().all(|&x| 0xff == x));
+
+        // Clear the whole buffer with a fill
+        let event = zero_buffer(queue, buffer, &[]).unwrap();
+        release_event(event).unwrap();
+        finish(queue).unwrap();
+
+        read_back(&mut data);
+        assert!(data.iter().all(|&x| 0 == x));
+
+        // Clearing an empty region does nothing
+        for strategy in [ClearStrategy::Fill, ClearStrategy::Kernel] {
+            let event = clear_buffer_region_with(queue, buffer, SIZE, 0, strategy, &[]).unwrap();
+            release_event(event).unwrap();
+        }
+
+        release_clear_kernel(context).unwrap();
+        release_mem_object(buffer).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+}
@@ -0,0 +1,565 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed host access to the contents of buffers.
+//!
+//! A buffer that is allocated in host memory, i.e. created with
+//! CL_MEM_USE_HOST_PTR or CL_MEM_ALLOC_HOST_PTR or on a device with unified
+//! memory, can be read without a copy by mapping it.
+//! Other buffers are read more efficiently by copying them to host memory.
+
+use super::raw::enqueue_read_buffer;
+use super::CommandQueueInfo;
+use super::{
+    enqueue_unmap_mem_object_slice, get_command_queue_info, release_command_queue,
+    retain_command_queue,
+};
+use crate::device::device_has_unified_memory;
+use crate::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+use crate::event::{release_event, retain_event, wait_for_events};
+use crate::memory::{get_mem_object_info, release_mem_object, retain_mem_object, MemInfo};
+use crate::memory::{CL_MAP_READ, CL_MEM_ALLOC_HOST_PTR, CL_MEM_USE_HOST_PTR};
+use crate::types::{
+    cl_bool, cl_command_queue, cl_event, cl_int, cl_map_flags, cl_mem, cl_mem_flags, CL_BLOCKING,
+    CL_NON_BLOCKING,
+};
+use cl_sys::clEnqueueMapBuffer;
+
+use libc::{c_void, size_t};
+use std::cell::Cell;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::slice;
+
+/// How to read the contents of a buffer into host memory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReadStrategy {
+    /// Map the buffer if it is in host memory, otherwise copy it.
+    #[default]
+    Auto,
+    /// Map the buffer for reading with clEnqueueMapBuffer.
+    Map,
+    /// Copy the buffer to host memory with clEnqueueReadBuffer.
+    Copy,
+}
+
+/// Choose between mapping and copying a buffer for ReadStrategy::Auto.
+fn select_read_strategy(mem_flags: cl_mem_flags, unified_memory: bool) -> ReadStrategy {
+    if unified_memory || 0 != mem_flags & (CL_MEM_USE_HOST_PTR | CL_MEM_ALLOC_HOST_PTR) {
+        ReadStrategy::Map
+    } else {
+        ReadStrategy::Copy
+    }
+}
+
+// Unmaps a mapped buffer when dropped, including when the closure panics.
+struct Unmap {
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    mapped_ptr: *mut c_void,
+}
+
+impl Drop for Unmap {
+    fn drop(&mut self) {
+        if let Ok(event) =
+            enqueue_unmap_mem_object_slice(self.command_queue, self.buffer, self.mapped_ptr, &[])
+        {
+            wait_for_events(&[event]).unwrap_or(());
+            release_event(event).unwrap_or(());
+        }
+    }
+}
+
+// Map a region of a buffer, returning the mapped pointer and the event of the map.
+fn map_buffer(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_map: cl_bool,
+    map_flags: cl_map_flags,
+    offset: size_t,
+    size: size_t,
+) -> Result<(*mut c_void, cl_event), cl_int> {
+    let mut event: cl_event = ptr::null_mut();
+    let mut status: cl_int = CL_INVALID_VALUE;
+    let mapped_ptr: *mut c_void = unsafe {
+        clEnqueueMapBuffer(
+            command_queue,
+            buffer,
+            blocking_map,
+            map_flags,
+            offset,
+            size,
+            0,
+            ptr::null(),
+            &mut event,
+            &mut status,
+        )
+    };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok((mapped_ptr, event))
+    }
+}
+
+fn map_and_read<T: Copy, R, F: FnOnce(&[T]) -> R>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    size: size_t,
+    f: F,
+) -> Result<R, cl_int> {
+    let (mapped_ptr, event) = map_buffer(command_queue, buffer, CL_BLOCKING, CL_MAP_READ, 0, size)?;
+    // Unmap the buffer even if the event cannot be released.
+    let guard = Unmap {
+        command_queue,
+        buffer,
+        mapped_ptr,
+    };
+    release_event(event)?;
+
+    let count = size / mem::size_of::<T>();
+    if 0 == guard.mapped_ptr as usize & (mem::align_of::<T>() - 1) {
+        let data = unsafe { slice::from_raw_parts(guard.mapped_ptr as *const T, count) };
+        Ok(f(data))
+    } else {
+        // Copy a misaligned mapping, rather than create a misaligned slice.
+        let mut data: Vec<T> = Vec::with_capacity(count);
+        unsafe {
+            ptr::copy_nonoverlapping(
+                guard.mapped_ptr as *const u8,
+                data.as_mut_ptr() as *mut u8,
+                count * mem::size_of::<T>(),
+            );
+            data.set_len(count);
+        }
+        Ok(f(&data))
+    }
+}
+
+fn copy_and_read<T: Copy, R, F: FnOnce(&[T]) -> R>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    size: size_t,
+    f: F,
+) -> Result<R, cl_int> {
+    let count = size / mem::size_of::<T>();
+    let mut data: Vec<T> = Vec::with_capacity(count);
+    let event = enqueue_read_buffer(
+        command_queue,
+        buffer,
+        CL_BLOCKING,
+        0,
+        count * mem::size_of::<T>(),
+        data.as_mut_ptr() as *mut c_void,
+        0,
+        ptr::null(),
+    )?;
+    release_event(event)?;
+    unsafe { data.set_len(count) };
+    Ok(f(&data))
+}
+
+/// Read the contents of a buffer as a slice of `T` using a given strategy.
+/// Calls clEnqueueMapBuffer and clEnqueueUnmapMemObject or clEnqueueReadBuffer,
+/// waiting for the commands to complete.
+/// A mapped buffer is unmapped after `f` returns, or if `f` panics.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `strategy` - whether to map or copy the buffer, see ReadStrategy.
+/// * `f` - a function of the buffer contents. If the buffer size is not a
+///   multiple of the size of `T`, the remaining bytes are not in the slice.
+///
+/// returns a Result containing the result of `f`
+/// or CL_INVALID_VALUE if `T` is a zero sized type
+/// or the error code from the OpenCL C API function.
+///
+/// # Safety
+///
+/// `T` must be plain old data that is valid for any bit pattern, e.g. a
+/// primitive or an array of them, since the contents of the buffer are
+/// read as `T`.
+pub unsafe fn with_buffer_read_using<T: Copy, R, F: FnOnce(&[T]) -> R>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    strategy: ReadStrategy,
+    f: F,
+) -> Result<R, cl_int> {
+    if 0 == mem::size_of::<T>() {
+        return Err(CL_INVALID_VALUE);
+    }
+    let size = get_mem_object_info(buffer, MemInfo::CL_MEM_SIZE)?.to_size();
+    let strategy = match strategy {
+        ReadStrategy::Auto => {
+            let flags = get_mem_object_info(buffer, MemInfo::CL_MEM_FLAGS)?.to_ulong();
+            let device =
+                get_command_queue_info(command_queue, CommandQueueInfo::CL_QUEUE_DEVICE)?.to_ptr();
+            select_read_strategy(flags, device_has_unified_memory(device as _))
+        }
+        strategy => strategy,
+    };
+
+    match strategy {
+        ReadStrategy::Map => map_and_read(command_queue, buffer, size, f),
+        _ => copy_and_read(command_queue, buffer, size, f),
+    }
+}
+
+/// Read the contents of a buffer as a slice of `T`.
+/// The buffer is mapped if it is in host memory, otherwise it is copied,
+/// see `with_buffer_read_using`.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `f` - a function of the buffer contents.
+///
+/// returns a Result containing the result of `f`
+/// or the error code from the OpenCL C API function.
+///
+/// # Safety
+///
+/// `T` must be plain old data that is valid for any bit pattern, see
+/// `with_buffer_read_using`.
+#[inline]
+pub unsafe fn with_buffer_read<T: Copy, R, F: FnOnce(&[T]) -> R>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    f: F,
+) -> Result<R, cl_int> {
+    with_buffer_read_using(command_queue, buffer, ReadStrategy::Auto, f)
+}
+
+/// A region of a buffer mapped into host memory as a slice of `T`,
+/// see `enqueue_map_buffer_slice`.
+///
+/// The MappedBuffer holds a reference to its command-queue and buffer, so
+/// they remain valid until the region is unmapped on the command-queue when
+/// the MappedBuffer is dropped. By default, dropping blocks until the unmap
+/// has completed, after `set_blocking_unmap(false)` dropping only enqueues
+/// the unmap, so the buffer must not be used by the host until it completes.
+pub struct MappedBuffer<T> {
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    mapped_ptr: *mut T,
+    len: usize,
+    // The event of a non-blocking map, waited for on first access.
+    map_event: Cell<cl_event>,
+    blocking_unmap: bool,
+}
+
+impl<T> MappedBuffer<T> {
+    /// The command-queue that mapped the buffer.
+    pub fn command_queue(&self) -> cl_command_queue {
+        self.command_queue
+    }
+
+    /// The mapped buffer.
+    pub fn buffer(&self) -> cl_mem {
+        self.buffer
+    }
+
+    /// Whether dropping waits for the unmap to complete, or only enqueues it.
+    pub fn set_blocking_unmap(&mut self, blocking: bool) {
+        self.blocking_unmap = blocking;
+    }
+
+    // Wait for a non-blocking map to complete before the region is accessed.
+    fn wait_for_map(&self) {
+        let event = self.map_event.replace(ptr::null_mut());
+        if !event.is_null() {
+            let result = wait_for_events(&[event]);
+            release_event(event).unwrap_or(());
+            if let Err(e) = result {
+                panic!("MappedBuffer: clEnqueueMapBuffer failed: {}", e);
+            }
+        }
+    }
+}
+
+impl<T> Deref for MappedBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.wait_for_map();
+        unsafe { slice::from_raw_parts(self.mapped_ptr, self.len) }
+    }
+}
+
+impl<T> DerefMut for MappedBuffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.wait_for_map();
+        unsafe { slice::from_raw_parts_mut(self.mapped_ptr, self.len) }
+    }
+}
+
+impl<T> Drop for MappedBuffer<T> {
+    fn drop(&mut self) {
+        // The unmap waits for a non-blocking map that was never accessed.
+        let map_event = self.map_event.replace(ptr::null_mut());
+        let wait_list: &[cl_event] = if map_event.is_null() {
+            &[]
+        } else {
+            slice::from_ref(&map_event)
+        };
+        if let Ok(event) = enqueue_unmap_mem_object_slice(
+            self.command_queue,
+            self.buffer,
+            self.mapped_ptr as *mut c_void,
+            wait_list,
+        ) {
+            if self.blocking_unmap {
+                wait_for_events(&[event]).unwrap_or(());
+            }
+            release_event(event).unwrap_or(());
+        }
+        if !map_event.is_null() {
+            release_event(map_event).unwrap_or(());
+        }
+        release_mem_object(self.buffer).unwrap_or(());
+        release_command_queue(self.command_queue).unwrap_or(());
+    }
+}
+
+// Retain a command-queue and a buffer for a MappedBuffer.
+fn retain_queue_and_buffer(command_queue: cl_command_queue, buffer: cl_mem) -> Result<(), cl_int> {
+    retain_command_queue(command_queue)?;
+    let result = retain_mem_object(buffer);
+    if result.is_err() {
+        release_command_queue(command_queue).unwrap_or(());
+    }
+    result
+}
+
+/// Enqueue a command to map a region of a buffer into host memory as a
+/// slice of `T`.  
+/// Calls clEnqueueMapBuffer to map the region, which is unmapped with
+/// clEnqueueUnmapMemObject when the returned MappedBuffer is dropped.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `buffer` - a valid buffer object.
+/// * `blocking_map` - CL_BLOCKING or CL_NON_BLOCKING. The MappedBuffer of a
+///   non-blocking map waits for the map to complete when it is first accessed.
+/// * `map_flags` - CL_MAP_READ, CL_MAP_WRITE or CL_MAP_WRITE_INVALIDATE_REGION.
+/// * `offset` - the offset in bytes of the region in the buffer.
+/// * `len` - the number of `T` in the region.
+///
+/// returns a Result containing the MappedBuffer and the event of the map,
+/// which must be released
+/// or CL_INVALID_VALUE if `T` is a zero sized type, `len` is zero or the
+/// mapped region is not aligned for `T`
+/// or the error code from the OpenCL C API function.
+///
+/// # Safety
+///
+/// `T` must be plain old data that is valid for any bit pattern, e.g. a
+/// primitive or an array of them, since the contents of the buffer are
+/// accessed as `T`.
+pub unsafe fn enqueue_map_buffer_slice<T: Copy>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_map: cl_bool,
+    map_flags: cl_map_flags,
+    offset: size_t,
+    len: size_t,
+) -> Result<(MappedBuffer<T>, cl_event), cl_int> {
+    let size = len
+        .checked_mul(mem::size_of::<T>())
+        .filter(|&size| 0 < size)
+        .ok_or(CL_INVALID_VALUE)?;
+
+    retain_queue_and_buffer(command_queue, buffer)?;
+    let (mapped_ptr, event) =
+        match map_buffer(command_queue, buffer, blocking_map, map_flags, offset, size) {
+            Ok(mapping) => mapping,
+            Err(e) => {
+                release_mem_object(buffer).unwrap_or(());
+                release_command_queue(command_queue).unwrap_or(());
+                return Err(e);
+            }
+        };
+    let mapped = MappedBuffer {
+        command_queue,
+        buffer,
+        mapped_ptr: mapped_ptr as *mut T,
+        len,
+        map_event: Cell::new(ptr::null_mut()),
+        blocking_unmap: true,
+    };
+
+    let result = if CL_NON_BLOCKING == blocking_map {
+        retain_event(event).map(|_| mapped.map_event.set(event))
+    } else {
+        Ok(())
+    };
+    let result = result.and_then(|_| {
+        if 0 == mapped_ptr as usize & (mem::align_of::<T>() - 1) {
+            Ok(())
+        } else {
+            Err(CL_INVALID_VALUE)
+        }
+    });
+    match result {
+        Ok(()) => Ok((mapped, event)),
+        Err(e) => {
+            drop(mapped);
+            release_event(event)?;
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::read_buffer_blocking;
+    use crate::command_queue::{create_command_queue, release_command_queue};
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
+    use crate::memory::{
+        create_buffer_from_slice, release_mem_object, CL_MAP_WRITE, CL_MEM_READ_ONLY,
+        CL_MEM_READ_WRITE,
+    };
+    use crate::platform::get_platform_ids;
+    use std::panic;
+
+    #[test]
+    fn test_select_read_strategy() {
+        assert_eq!(
+            ReadStrategy::Copy,
+            select_read_strategy(CL_MEM_READ_ONLY, false)
+        );
+        assert_eq!(
+            ReadStrategy::Map,
+            select_read_strategy(CL_MEM_READ_ONLY, true)
+        );
+        assert_eq!(
+            ReadStrategy::Map,
+            select_read_strategy(CL_MEM_READ_ONLY | CL_MEM_USE_HOST_PTR, false)
+        );
+        assert_eq!(
+            ReadStrategy::Map,
+            select_read_strategy(CL_MEM_ALLOC_HOST_PTR, false)
+        );
+    }
+
+    #[test]
+    fn test_with_buffer_read() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+
+        const COUNT: usize = 256;
+        let values: Vec<u32> = (0..COUNT as u32).collect();
+        let buffer = create_buffer_from_slice(context, CL_MEM_READ_ONLY, &values).unwrap();
+        let expected: u32 = values.iter().sum();
+
+        for &strategy in [ReadStrategy::Auto, ReadStrategy::Map, ReadStrategy::Copy].iter() {
+            let sum = unsafe {
+                with_buffer_read_using(queue, buffer, strategy, |data: &[u32]| {
+                    assert_eq!(COUNT, data.len());
+                    data.iter().sum::<u32>()
+                })
+            }
+            .unwrap();
+            assert_eq!(expected, sum);
+        }
+        assert_eq!(
+            0,
+            unsafe { with_buffer_read(queue, buffer, |data: &[u32]| data[0]) }.unwrap()
+        );
+        assert_eq!(Err(CL_INVALID_VALUE), unsafe {
+            with_buffer_read(queue, buffer, |data: &[()]| data.len())
+        });
+
+        // The buffer is unmapped when the closure panics
+        let result = panic::catch_unwind(|| unsafe {
+            with_buffer_read_using(queue, buffer, ReadStrategy::Map, |_: &[u32]| {
+                panic!("panic while mapped")
+            })
+        });
+        assert!(result.is_err());
+        let map_count = get_mem_object_info(buffer, MemInfo::CL_MEM_MAP_COUNT)
+            .unwrap()
+            .to_uint();
+        assert_eq!(0, map_count);
+
+        release_mem_object(buffer).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_enqueue_map_buffer() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+
+        const COUNT: usize = 256;
+        let mut values: Vec<u32> = (0..COUNT as u32).collect();
+        let buffer = create_buffer_from_slice(context, CL_MEM_READ_WRITE, &values).unwrap();
+
+        // Read the second half of the buffer with a blocking map
+        let (mapped, event) = unsafe {
+            enqueue_map_buffer_slice::<u32>(queue, buffer, CL_BLOCKING, CL_MAP_READ, 512, COUNT / 2)
+        }
+        .unwrap();
+        release_event(event).unwrap();
+        assert_eq!(&values[COUNT / 2..], &mapped[..]);
+
+        // The MappedBuffer holds a reference to the buffer until it is dropped
+        let reference_count = || {
+            get_mem_object_info(buffer, MemInfo::CL_MEM_REFERENCE_COUNT)
+                .unwrap()
+                .to_uint()
+        };
+        assert_eq!(2, reference_count());
+        drop(mapped);
+        assert_eq!(1, reference_count());
+
+        // Write the buffer with a non-blocking map
+        let (mut mapped, event) = unsafe {
+            enqueue_map_buffer_slice::<u32>(queue, buffer, CL_NON_BLOCKING, CL_MAP_WRITE, 0, COUNT)
+        }
+        .unwrap();
+        release_event(event).unwrap();
+        mapped.iter_mut().for_each(|value| *value *= 2);
+        drop(mapped);
+        let map_count = get_mem_object_info(buffer, MemInfo::CL_MEM_MAP_COUNT)
+            .unwrap()
+            .to_uint();
+        assert_eq!(0, map_count);
+
+        unsafe { read_buffer_blocking(queue, buffer, 0, &mut values) }.unwrap();
+        assert!(values.iter().enumerate().all(|(i, &v)| 2 * i as u32 == v));
+
+        let empty = unsafe {
+            enqueue_map_buffer_slice::<u32>(queue, buffer, CL_BLOCKING, CL_MAP_READ, 0, 0)
+        };
+        assert!(empty.is_err());
+        let zero_sized = unsafe {
+            enqueue_map_buffer_slice::<()>(queue, buffer, CL_BLOCKING, CL_MAP_READ, 0, 1)
+        };
+        assert!(zero_sized.is_err());
+        assert_eq!(1, reference_count());
+
+        release_mem_object(buffer).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+}
@@ -0,0 +1,273 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A watchdog for long-running commands.
+//!
+//! Desktop operating systems reset a GPU that runs a kernel for longer than
+//! their watchdog timeout, typically about 2 seconds, and the kernel then
+//! terminates abnormally, usually with CL_OUT_OF_RESOURCES.
+//! `run_with_watchdog` flushes a command and polls its event, so that the
+//! caller can act when the command runs for longer than it expected.
+
+use super::{flush, get_command_queue_info, CommandQueueInfo};
+use crate::device::{get_device_info, DeviceInfo};
+use crate::error_codes::{error_text, CL_OUT_OF_RESOURCES};
+use crate::event::{get_event_command_execution_status, release_event, CommandExecutionStatus};
+use crate::types::{cl_command_queue, cl_device_id, cl_event, cl_int};
+
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The interval between polls of the event status.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A command that terminated abnormally, see `run_with_watchdog`.
+#[derive(Debug)]
+pub struct WatchdogFailure {
+    /// The event of the command, which must be released.
+    pub event: cl_event,
+    /// The negative execution status of the command.
+    pub status: cl_int,
+    /// The time from enqueuing the command to observing the failure.
+    pub elapsed: Duration,
+    /// CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV of the device, or None if the
+    /// device does not support cl_nv_device_attribute_query.
+    pub kernel_exec_timeout: Option<bool>,
+}
+
+impl WatchdogFailure {
+    /// Whether the failure looks like an OS watchdog reset: a command that
+    /// ran out of resources on a device that does not report that it has
+    /// no kernel execution timeout.
+    pub fn is_likely_os_watchdog(&self) -> bool {
+        CL_OUT_OF_RESOURCES == self.status && Some(false) != self.kernel_exec_timeout
+    }
+
+    /// A hint about the failure, if it looks like an OS watchdog reset.
+    pub fn hint(&self) -> Option<&'static str> {
+        if !self.is_likely_os_watchdog() {
+            None
+        } else if Some(true) == self.kernel_exec_timeout {
+            Some(
+                "the device has a kernel execution timeout (CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV), \
+                 split the kernel into shorter launches or use a device without a display",
+            )
+        } else {
+            Some(
+                "the kernel may have exceeded the OS GPU watchdog timeout, e.g. Windows TDR, \
+                 split the kernel into shorter launches",
+            )
+        }
+    }
+}
+
+impl fmt::Display for WatchdogFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "command failed with {} after {:?}",
+            error_text(self.status),
+            self.elapsed
+        )?;
+        if let Some(hint) = self.hint() {
+            write!(f, ", {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of `run_with_watchdog`.
+#[derive(Debug)]
+pub enum WatchdogOutcome {
+    /// The command completed successfully, its event must be released.
+    Completed(cl_event),
+    /// The command had not finished when the soft timeout passed, its event
+    /// may be waited for again or released.
+    StillRunning(cl_event),
+    /// The command terminated abnormally.
+    Failed(WatchdogFailure),
+}
+
+// The CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV of the device of a command-queue.
+fn kernel_exec_timeout(command_queue: cl_command_queue) -> Option<bool> {
+    let device = get_command_queue_info(command_queue, CommandQueueInfo::CL_QUEUE_DEVICE)
+        .ok()?
+        .to_ptr() as cl_device_id;
+    get_device_info(device, DeviceInfo::CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV)
+        .ok()
+        .map(|value| value.to_bool())
+}
+
+// Poll the event of a command until it finishes or the soft timeout passes.
+fn poll_event(
+    command_queue: cl_command_queue,
+    event: cl_event,
+    start: Instant,
+    soft_timeout: Duration,
+) -> Result<WatchdogOutcome, cl_int> {
+    loop {
+        match get_event_command_execution_status(event)? {
+            CommandExecutionStatus::Complete => return Ok(WatchdogOutcome::Completed(event)),
+            CommandExecutionStatus::Error(status) => {
+                return Ok(WatchdogOutcome::Failed(WatchdogFailure {
+                    event,
+                    status,
+                    elapsed: start.elapsed(),
+                    kernel_exec_timeout: kernel_exec_timeout(command_queue),
+                }))
+            }
+            _ => {}
+        }
+
+        let elapsed = start.elapsed();
+        if soft_timeout <= elapsed {
+            return Ok(WatchdogOutcome::StillRunning(event));
+        }
+        thread::sleep(POLL_INTERVAL.min(soft_timeout - elapsed));
+    }
+}
+
+/// Enqueue a command, flush it and poll its event until it finishes or
+/// `soft_timeout` passes.
+/// Calls clFlush and clGetEventInfo to get CL_EVENT_COMMAND_EXECUTION_STATUS.
+///
+/// * `command_queue` - the OpenCL command-queue that `enqueue` enqueues to.
+/// * `enqueue` - enqueues the command and returns its event,
+///   e.g. a call to enqueue_nd_range_kernel.
+/// * `soft_timeout` - the time to wait for the command to finish.
+///
+/// returns a Result containing the WatchdogOutcome
+/// or the error code from the OpenCL C API function.
+pub fn run_with_watchdog<F>(
+    command_queue: cl_command_queue,
+    enqueue: F,
+    soft_timeout: Duration,
+) -> Result<WatchdogOutcome, cl_int>
+where
+    F: FnOnce() -> Result<cl_event, cl_int>,
+{
+    let start = Instant::now();
+    let event = enqueue()?;
+    let outcome =
+        flush(command_queue).and_then(|_| poll_event(command_queue, event, start, soft_timeout));
+    if outcome.is_err() {
+        release_event(event)?;
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::{
+        create_command_queue, enqueue_nd_range_kernel_slice, release_command_queue,
+    };
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
+    use crate::event::wait_for_events;
+    use crate::kernel::{create_kernel, release_kernel, KernelArg};
+    use crate::memory::{create_buffer_of_size, release_mem_object, CL_MEM_WRITE_ONLY};
+    use crate::platform::get_platform_ids;
+    use crate::program::{build_program, create_program_with_source, release_program};
+    use crate::types::cl_uint;
+    use libc::size_t;
+    use std::ffi::CString;
+    use std::mem;
+    use std::ptr;
+
+    #[test]
+    fn test_watchdog_failure() {
+        let mut failure = WatchdogFailure {
+            event: ptr::null_mut(),
+            status: CL_OUT_OF_RESOURCES,
+            elapsed: Duration::from_secs(2),
+            kernel_exec_timeout: Some(true),
+        };
+        assert!(failure.is_likely_os_watchdog());
+        assert!(failure
+            .to_string()
+            .contains("CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV"));
+
+        failure.kernel_exec_timeout = None;
+        assert!(failure.is_likely_os_watchdog());
+        assert!(failure.to_string().contains("watchdog"));
+
+        failure.kernel_exec_timeout = Some(false);
+        assert!(!failure.is_likely_os_watchdog());
+        assert_eq!(None, failure.hint());
+    }
+
+    #[test]
+    fn test_run_with_watchdog() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+
+        let source = "kernel void spin(global uint* out, uint count)
+            { uint x = get_global_id(0);
+              for (uint i = 0; i < count; ++i) { x = x * 1664525u + 1013904223u; }
+              out[get_global_id(0)] = x; }";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::default();
+        build_program(program, &[device_id], &options, None, ptr::null_mut()).unwrap();
+        let kernel = create_kernel(program, &CString::new("spin").unwrap()).unwrap();
+
+        const COUNT: size_t = 1024;
+        let out = create_buffer_of_size(
+            context,
+            CL_MEM_WRITE_ONLY,
+            COUNT * mem::size_of::<cl_uint>(),
+        )
+        .unwrap();
+        out.set_arg(kernel, 0).unwrap();
+
+        let run = |count: cl_uint, soft_timeout| {
+            count.set_arg(kernel, 1).unwrap();
+            run_with_watchdog(
+                queue,
+                || enqueue_nd_range_kernel_slice(queue, kernel, None, &[COUNT], None, &[]),
+                soft_timeout,
+            )
+            .unwrap()
+        };
+
+        // A short kernel completes
+        match run(1, Duration::from_secs(10)) {
+            WatchdogOutcome::Completed(event) => release_event(event).unwrap(),
+            outcome => panic!("short kernel: {:?}", outcome),
+        }
+
+        // A long kernel is still running after a tiny soft timeout
+        match run(1 << 24, Duration::from_micros(1)) {
+            WatchdogOutcome::StillRunning(event) => {
+                wait_for_events(&[event]).unwrap();
+                release_event(event).unwrap();
+            }
+            WatchdogOutcome::Failed(failure) => {
+                println!("long kernel: {}", failure);
+                release_event(failure.event).unwrap();
+            }
+            outcome => panic!("long kernel: {:?}", outcome),
+        }
+
+        release_mem_object(out).unwrap();
+        release_kernel(kernel).unwrap();
+        release_program(program).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+}
@@ -0,0 +1,401 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Legacy OpenCL 1.x entry points, to ease migration from older bindings.
+//!
+//! The functions take the arguments of the OpenCL 1.x API functions that
+//! were deprecated in later OpenCL versions. They call the replacement
+//! function when it is enabled by the crate features and supported by the
+//! OpenCL devices, and the deprecated function otherwise.
+//! Every function is deprecated in favour of its replacement.
+
+#![allow(deprecated)]
+
+#[allow(unused_imports)]
+use super::command_queue::{enqueue_nd_range_kernel, wait_list_ptr, CommandQueueInfo};
+use super::context::{get_context_info, ContextInfo};
+use super::device::{device_opencl_version, Version};
+use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+use super::memory::image_desc_2d;
+#[allow(unused_imports)]
+use super::types::{
+    cl_addressing_mode, cl_bool, cl_command_queue, cl_command_queue_properties, cl_context,
+    cl_device_id, cl_event, cl_filter_mode, cl_image_format, cl_int, cl_kernel, cl_mem,
+    cl_mem_flags, cl_queue_properties, cl_sampler, cl_sampler_properties, cl_uint,
+};
+
+use libc::{c_void, size_t};
+use std::ptr;
+
+// clCreateImage2D, declared with the cl_image_format of the types module,
+// like clCreateImage in the memory module.
+#[cfg_attr(not(target_os = "macos"), link(name = "OpenCL"))]
+#[cfg_attr(target_os = "macos", link(name = "OpenCL", kind = "framework"))]
+extern "system" {
+    fn clCreateImage2D(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_format: *const cl_image_format,
+        image_width: size_t,
+        image_height: size_t,
+        image_row_pitch: size_t,
+        host_ptr: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem;
+}
+
+// The lowest OpenCL version of the devices in a context.
+fn context_opencl_version(context: cl_context) -> Result<Version, cl_int> {
+    let devices = get_context_info(context, ContextInfo::CL_CONTEXT_DEVICES)?.to_vec_device_id();
+    let mut lowest: Option<Version> = None;
+    for device in devices {
+        let version = device_opencl_version(device)?;
+        lowest = Some(lowest.map_or(version, |lowest| lowest.min(version)));
+    }
+    lowest.ok_or(CL_INVALID_VALUE)
+}
+
+/// Create a command-queue with the arguments of clCreateCommandQueue.
+/// Calls clCreateCommandQueueWithProperties if CL_VERSION_2_0 is enabled and
+/// the device supports OpenCL 2.0, otherwise clCreateCommandQueue.
+///
+/// * `context` - a valid OpenCL context.
+/// * `device` - a device or sub-device associated with context.
+/// * `properties` - a bit-field of cl_command_queue_properties.
+///
+/// returns a Result containing the new OpenCL command-queue
+/// or the error code from the OpenCL C API function.
+#[deprecated(note = "use command_queue::create_command_queue_with_properties_slice")]
+#[cfg_attr(not(feature = "CL_VERSION_1_2"), allow(unused_variables))]
+pub fn create_command_queue(
+    context: cl_context,
+    device: cl_device_id,
+    properties: cl_command_queue_properties,
+) -> Result<cl_command_queue, cl_int> {
+    #[cfg(feature = "CL_VERSION_2_0")]
+    {
+        if Version::new(2, 0, 0) <= device_opencl_version(device)? {
+            let properties: [cl_queue_properties; 3] = [
+                CommandQueueInfo::CL_QUEUE_PROPERTIES as cl_queue_properties,
+                properties,
+                0,
+            ];
+            return super::command_queue::create_command_queue_with_properties_slice(
+                context,
+                device,
+                &properties,
+            );
+        }
+    }
+
+    #[cfg(feature = "CL_VERSION_1_2")]
+    {
+        super::command_queue::create_command_queue(context, device, properties)
+    }
+    #[cfg(not(feature = "CL_VERSION_1_2"))]
+    {
+        Err(super::error_codes::CL_INVALID_OPERATION)
+    }
+}
+
+/// Create a sampler with the arguments of clCreateSampler.
+/// Calls clCreateSamplerWithProperties if CL_VERSION_2_0 is enabled and all
+/// the devices in the context support OpenCL 2.0, otherwise clCreateSampler.
+///
+/// * `context` - a valid OpenCL context.
+/// * `normalized_coords` - same interpretation as CL_SAMPLER_NORMALIZED_COORDS.
+/// * `addressing_mode` - same interpretation as CL_SAMPLER_ADDRESSING_MODE.
+/// * `filter_mode` - same interpretation as CL_SAMPLER_FILTER_MODE.
+///
+/// returns a Result containing the new OpenCL sampler object
+/// or the error code from the OpenCL C API function.
+#[deprecated(note = "use sampler::create_sampler_with_properties")]
+#[cfg_attr(not(feature = "CL_VERSION_1_2"), allow(unused_variables))]
+pub fn create_sampler(
+    context: cl_context,
+    normalized_coords: cl_bool,
+    addressing_mode: cl_addressing_mode,
+    filter_mode: cl_filter_mode,
+) -> Result<cl_sampler, cl_int> {
+    #[cfg(feature = "CL_VERSION_2_0")]
+    {
+        use super::sampler::SamplerInfo;
+
+        if Version::new(2, 0, 0) <= context_opencl_version(context)? {
+            let properties: [cl_sampler_properties; 7] = [
+                SamplerInfo::CL_SAMPLER_NORMALIZED_COORDS as cl_sampler_properties,
+                normalized_coords as cl_sampler_properties,
+                SamplerInfo::CL_SAMPLER_ADDRESSING_MODE as cl_sampler_properties,
+                addressing_mode as cl_sampler_properties,
+                SamplerInfo::CL_SAMPLER_FILTER_MODE as cl_sampler_properties,
+                filter_mode as cl_sampler_properties,
+                0,
+            ];
+            return super::sampler::create_sampler_with_properties(context, &properties);
+        }
+    }
+
+    #[cfg(feature = "CL_VERSION_1_2")]
+    {
+        super::sampler::create_sampler(context, normalized_coords, addressing_mode, filter_mode)
+    }
+    #[cfg(not(feature = "CL_VERSION_1_2"))]
+    {
+        Err(super::error_codes::CL_INVALID_OPERATION)
+    }
+}
+
+/// Create a 2D image with the arguments of clCreateImage2D.
+/// Calls clCreateImage if all the devices in the context support OpenCL 1.2,
+/// otherwise clCreateImage2D.
+///
+/// * `context` - a valid OpenCL context.
+/// * `flags` - a bit-field used to specify allocation and usage information
+///   about the image memory object being created.
+/// * `image_format` - the image format properties.
+/// * `image_width` - the width of the image in pixels.
+/// * `image_height` - the height of the image in pixels.
+/// * `image_row_pitch` - the scan-line pitch in bytes, or 0.
+/// * `host_ptr` - a pointer to the image data that may already be allocated
+///   by the application.
+///
+/// returns a Result containing the new OpenCL image object
+/// or the error code from the OpenCL C API function.
+#[deprecated(note = "use memory::create_image_from_desc with memory::image_desc_2d")]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn create_image_2d(
+    context: cl_context,
+    flags: cl_mem_flags,
+    image_format: &cl_image_format,
+    image_width: size_t,
+    image_height: size_t,
+    image_row_pitch: size_t,
+    host_ptr: *mut c_void,
+) -> Result<cl_mem, cl_int> {
+    if Version::new(1, 2, 0) <= context_opencl_version(context)? {
+        let desc = image_desc_2d(image_width, image_height, image_row_pitch);
+        return super::memory::raw::create_image(context, flags, image_format, &desc, host_ptr);
+    }
+
+    let mut status: cl_int = CL_INVALID_VALUE;
+    let image: cl_mem = unsafe {
+        clCreateImage2D(
+            context,
+            flags,
+            image_format,
+            image_width,
+            image_height,
+            image_row_pitch,
+            host_ptr,
+            &mut status,
+        )
+    };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(image)
+    }
+}
+
+/// Enqueue a kernel to execute as a single work-item, like clEnqueueTask.
+/// Calls clEnqueueNDRangeKernel with a global and local work size of 1,
+/// which is equivalent to clEnqueueTask in every OpenCL version.
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `kernel` - a valid OpenCL kernel.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[deprecated(note = "use command_queue::enqueue_nd_range_kernel")]
+pub fn enqueue_task(
+    command_queue: cl_command_queue,
+    kernel: cl_kernel,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let work_size: [size_t; 1] = [1];
+    enqueue_nd_range_kernel(
+        command_queue,
+        kernel,
+        1,
+        ptr::null(),
+        work_size.as_ptr(),
+        work_size.as_ptr(),
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::{
+        enqueue_read_buffer, get_command_queue_info, release_command_queue,
+    };
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
+    use crate::event::release_event;
+    use crate::kernel::{create_kernel, release_kernel, KernelArg};
+    use crate::memory::{
+        create_buffer_of_size, get_image_info, image_format_rgba_u8, release_mem_object, ImageInfo,
+        CL_MEM_READ_WRITE, CL_MEM_WRITE_ONLY,
+    };
+    use crate::platform::get_platform_ids;
+    use crate::program::{build_program, create_program_with_source, release_program};
+    use crate::sampler::{get_sampler_info, release_sampler, SamplerInfo};
+    use crate::types::{CL_BLOCKING, CL_FALSE};
+    use cl_sys::{CL_ADDRESS_CLAMP_TO_EDGE, CL_FILTER_NEAREST, CL_QUEUE_PROFILING_ENABLE};
+    use std::ffi::CString;
+    use std::mem;
+
+    #[test]
+    fn test_compat() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        println!(
+            "Context OpenCL version: {}",
+            context_opencl_version(context).unwrap()
+        );
+
+        let queue = create_command_queue(context, device_id, CL_QUEUE_PROFILING_ENABLE).unwrap();
+        let value = get_command_queue_info(queue, CommandQueueInfo::CL_QUEUE_PROPERTIES).unwrap();
+        assert_eq!(CL_QUEUE_PROFILING_ENABLE, value.to_ulong());
+
+        let sampler = create_sampler(
+            context,
+            CL_FALSE,
+            CL_ADDRESS_CLAMP_TO_EDGE,
+            CL_FILTER_NEAREST,
+        )
+        .unwrap();
+        let value = get_sampler_info(sampler, SamplerInfo::CL_SAMPLER_ADDRESSING_MODE).unwrap();
+        assert_eq!(CL_ADDRESS_CLAMP_TO_EDGE, value.to_uint());
+        let value = get_sampler_info(sampler, SamplerInfo::CL_SAMPLER_NORMALIZED_COORDS).unwrap();
+        assert!(!value.to_bool());
+        release_sampler(sampler).unwrap();
+
+        let format = image_format_rgba_u8();
+        let image = create_image_2d(
+            context,
+            CL_MEM_READ_WRITE,
+            &format,
+            16,
+            8,
+            0,
+            ptr::null_mut(),
+        )
+        .unwrap();
+        let value = get_image_info(image, ImageInfo::CL_IMAGE_WIDTH).unwrap();
+        assert_eq!(16, value.to_size());
+        let value = get_image_info(image, ImageInfo::CL_IMAGE_HEIGHT).unwrap();
+        assert_eq!(8, value.to_size());
+        release_mem_object(image).unwrap();
+
+        // A task runs as a single work-item
+        let source = "kernel void task(global int* value) { *value = get_global_size(0); }";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::default();
+        build_program(program, &[device_id], &options, None, ptr::null_mut()).unwrap();
+        let kernel_name = CString::new("task").unwrap();
+        let kernel = create_kernel(program, &kernel_name).unwrap();
+        let buffer =
+            create_buffer_of_size(context, CL_MEM_WRITE_ONLY, mem::size_of::<cl_int>()).unwrap();
+        buffer.set_arg(kernel, 0).unwrap();
+        let task_event = enqueue_task(queue, kernel, &[]).unwrap();
+
+        let mut value: cl_int = 0;
+        let read_event = enqueue_read_buffer(
+            queue,
+            buffer,
+            CL_BLOCKING,
+            0,
+            mem::size_of::<cl_int>(),
+            &mut value as *mut cl_int as *mut c_void,
+            1,
+            &task_event,
+        )
+        .unwrap();
+        assert_eq!(1, value);
+
+        release_event(read_event).unwrap();
+        release_event(task_event).unwrap();
+        release_mem_object(buffer).unwrap();
+        release_kernel(kernel).unwrap();
+        release_program(program).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_compat_matches_modern() {
+        // Without a valid command-queue, both paths fail with the same error
+        let work_size: [size_t; 1] = [1];
+        let modern = enqueue_nd_range_kernel(
+            ptr::null_mut(),
+            ptr::null_mut(),
+            1,
+            ptr::null(),
+            work_size.as_ptr(),
+            work_size.as_ptr(),
+            0,
+            ptr::null(),
+        );
+        let compat = enqueue_task(ptr::null_mut(), ptr::null_mut(), &[]);
+        assert!(compat.is_err());
+        assert_eq!(modern, compat);
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    #[test]
+    fn test_compat_sampler_matches_modern() {
+        use crate::sampler::{
+            create_sampler_with_properties, AddressingMode, FilterMode, SamplerProperties,
+        };
+
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids[..1], ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let compat = create_sampler(
+            context,
+            CL_FALSE,
+            CL_ADDRESS_CLAMP_TO_EDGE,
+            CL_FILTER_NEAREST,
+        )
+        .unwrap();
+        let properties = SamplerProperties::new()
+            .normalized_coords(false)
+            .addressing_mode(AddressingMode::ClampToEdge)
+            .filter_mode(FilterMode::Nearest)
+            .to_vec();
+        let modern = create_sampler_with_properties(context, &properties).unwrap();
+
+        for &param_name in &[
+            SamplerInfo::CL_SAMPLER_NORMALIZED_COORDS,
+            SamplerInfo::CL_SAMPLER_ADDRESSING_MODE,
+            SamplerInfo::CL_SAMPLER_FILTER_MODE,
+        ] {
+            assert_eq!(
+                get_sampler_info(modern, param_name).unwrap().to_uint(),
+                get_sampler_info(compat, param_name).unwrap().to_uint()
+            );
+        }
+
+        release_sampler(modern).unwrap();
+        release_sampler(compat).unwrap();
+        release_context(context).unwrap();
+    }
+}
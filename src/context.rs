@@ -16,9 +16,17 @@
 
 #![allow(non_camel_case_types)]
 
+#[cfg(feature = "cl_khr_terminate_context")]
+pub use super::ffi::cl_ext::CL_CONTEXT_TERMINATE_KHR;
 pub use cl_sys::{CL_CONTEXT_INTEROP_USER_SYNC, CL_CONTEXT_PLATFORM};
 
-use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+use super::device::devices_share_platform;
+use super::error_codes::{CL_INVALID_DEVICE, CL_INVALID_VALUE, CL_SUCCESS};
+use super::ffi::cl_d3d10::CL_CONTEXT_D3D10_DEVICE_KHR;
+use super::ffi::cl_d3d11::CL_CONTEXT_D3D11_DEVICE_KHR;
+use super::ffi::cl_dx9_media_sharing::{
+    CL_CONTEXT_ADAPTER_D3D9EX_KHR, CL_CONTEXT_ADAPTER_D3D9_KHR, CL_CONTEXT_ADAPTER_DXVA_KHR,
+};
 use super::info_type::InfoType;
 use super::types::{
     cl_context, cl_context_info, cl_context_properties, cl_device_id, cl_device_type, cl_int,
@@ -27,11 +35,16 @@ use super::types::{
 use super::{api_info_size, api_info_value, api_info_vector};
 use cl_sys::{
     clCreateContext, clCreateContextFromType, clGetContextInfo, clReleaseContext, clRetainContext,
+    CL_CGL_SHAREGROUP_KHR, CL_EGL_DISPLAY_KHR, CL_GL_CONTEXT_KHR,
 };
 
 use libc::{c_char, c_void, intptr_t, size_t};
+use std::borrow::Cow;
+use std::ffi::CStr;
 use std::mem;
+use std::panic;
 use std::ptr;
+use std::slice;
 
 // clSetContextDestructorCallback is CL_VERSION_3_0, not in cl_sys yet
 #[cfg_attr(not(target_os = "macos"), link(name = "OpenCL"))]
@@ -54,6 +67,7 @@ extern "system" {
 /// * `user_data` - passed as the user_data argument when pfn_notify is called.
 ///
 /// returns a Result containing the new OpenCL context
+/// or CL_INVALID_DEVICE if the devices belong to different platforms
 /// or the error code from the OpenCL C API function.
 #[inline]
 pub fn create_context(
@@ -62,6 +76,10 @@ pub fn create_context(
     pfn_notify: Option<extern "C" fn(*const c_char, *const c_void, size_t, *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<cl_context, cl_int> {
+    // A context cannot span platforms, but the ICD loader may not detect it.
+    if !devices_share_platform(devices)? {
+        return Err(CL_INVALID_DEVICE);
+    }
     let mut status: cl_int = CL_INVALID_VALUE;
     let context = unsafe {
         clCreateContext(
@@ -80,10 +98,73 @@ pub fn create_context(
     }
 }
 
+/// The type of a closure registered by create_context_with_callback.
+pub type ContextCallback = Box<dyn Fn(&str, &[u8]) + Send + Sync>;
+
+// Trampoline for create_context_with_callback: converts the error information
+// and private information for the closure in user_data.
+extern "C" fn context_callback_trampoline(
+    errinfo: *const c_char,
+    private_info: *const c_void,
+    cb: size_t,
+    user_data: *mut c_void,
+) {
+    let callback = unsafe { &*(user_data as *const ContextCallback) };
+    let errinfo = if errinfo.is_null() {
+        Cow::Borrowed("")
+    } else {
+        unsafe { CStr::from_ptr(errinfo) }.to_string_lossy()
+    };
+    let private_info = if private_info.is_null() {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(private_info as *const u8, cb) }
+    };
+    // A panic must not unwind into the OpenCL implementation.
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| callback(&errinfo, private_info)));
+}
+
+/// Create an OpenCL context with a Rust closure to report errors, see
+/// `create_context`.  
+/// Calls clCreateContext with a callback function that calls the closure with
+/// the error information string and the implementation's private information.
+///
+/// Note: OpenCL may call the closure at any time while the context exists and
+/// from any thread, so it must be `Send` and `Sync`. Since OpenCL does not
+/// report when the context is destroyed before CL_VERSION_3_0, the closure is
+/// intentionally leaked if the context is created.
+///
+/// * `devices` - a slice of unique devices for an OpenCL platform.
+/// * `properties` - a null terminated list of cl_context_properties, see
+///   [Context Properties](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#context-properties-table).
+/// * `callback` - the closure to call with the error information.
+///
+/// returns a Result containing the new OpenCL context
+/// or CL_INVALID_DEVICE if the devices belong to different platforms
+/// or the error code from the OpenCL C API function.
+pub fn create_context_with_callback(
+    devices: &[cl_device_id],
+    properties: *const cl_context_properties,
+    callback: ContextCallback,
+) -> Result<cl_context, cl_int> {
+    let user_data = Box::into_raw(Box::new(callback));
+    let result = create_context(
+        devices,
+        properties,
+        Some(context_callback_trampoline),
+        user_data as *mut c_void,
+    );
+    if result.is_err() {
+        // The callback is not called after clCreateContext fails.
+        unsafe { drop(Box::from_raw(user_data)) };
+    }
+    result
+}
+
 /// Create an OpenCL context from a specific device type.  
 /// Calls clCreateContextFromType to create an OpenCL context.
 ///
-/// * `device_type` - the type of OpenCL device, see:
+/// * `device_type` - the type of OpenCL device, a DeviceType or a cl_device_type, see:
 /// [Device Types](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#device-types-table).
 /// * `properties` - a null terminated list of cl_context_properties, see:
 /// [Context Properties](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#context-properties-table).
@@ -93,12 +174,13 @@ pub fn create_context(
 /// returns a Result containing the new OpenCL context
 /// or the error code from the OpenCL C API function.
 #[inline]
-pub fn create_context_from_type(
-    device_type: cl_device_type,
+pub fn create_context_from_type<T: Into<cl_device_type>>(
+    device_type: T,
     properties: *const cl_context_properties,
     pfn_notify: Option<extern "C" fn(*const c_char, *const c_void, size_t, *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<cl_context, cl_int> {
+    let device_type: cl_device_type = device_type.into();
     let mut status: cl_int = CL_INVALID_VALUE;
     let context = unsafe {
         clCreateContextFromType(properties, device_type, pfn_notify, user_data, &mut status)
@@ -119,6 +201,8 @@ pub fn create_context_from_type(
 #[inline]
 pub fn retain_context(context: cl_context) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clRetainContext(context) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::retained(context as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -135,6 +219,8 @@ pub fn retain_context(context: cl_context) -> Result<(), cl_int> {
 #[inline]
 pub fn release_context(context: cl_context) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clReleaseContext(context) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::released(context as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -190,6 +276,80 @@ pub fn get_context_info(context: cl_context, param_name: ContextInfo) -> Result<
     }
 }
 
+/// Get the devices of an OpenCL context, e.g. of a context created elsewhere.  
+/// Calls clGetContextInfo to get CL_CONTEXT_DEVICES.
+///
+/// * `context` - the cl_context of the OpenCL context.
+///
+/// returns a Result containing the device ids of the context
+/// or the error code from the OpenCL C API function.
+pub fn get_context_devices(context: cl_context) -> Result<Vec<cl_device_id>, cl_int> {
+    Ok(get_context_info(context, ContextInfo::CL_CONTEXT_DEVICES)?.to_vec_device_id())
+}
+
+/// The graphics API interoperability of an OpenCL context.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteropKind {
+    None,
+    /// cl_khr_gl_sharing: created with CL_GL_CONTEXT_KHR or CL_CGL_SHAREGROUP_KHR.
+    Gl,
+    /// cl_khr_egl_image: created with CL_EGL_DISPLAY_KHR but no OpenGL context.
+    Egl,
+    /// cl_khr_d3d10_sharing: created with CL_CONTEXT_D3D10_DEVICE_KHR.
+    D3D10,
+    /// cl_khr_d3d11_sharing: created with CL_CONTEXT_D3D11_DEVICE_KHR.
+    D3D11,
+    /// cl_khr_dx9_media_sharing: created with a CL_CONTEXT_ADAPTER_*_KHR property.
+    Dx9MediaSharing,
+}
+
+/// Find the graphics API interoperability in a list of context properties.  
+///
+/// * `properties` - a list of cl_context_properties name, value pairs,
+///   optionally null terminated, e.g. from CL_CONTEXT_PROPERTIES.
+///
+/// returns the InteropKind of the first interop property found, OpenGL taking
+/// precedence over an EGL display, or InteropKind::None.
+pub fn interop_kind(properties: &[cl_context_properties]) -> InteropKind {
+    let mut kind = InteropKind::None;
+    for pair in properties.chunks(2) {
+        let key = pair[0];
+        if 0 == key {
+            break;
+        }
+
+        match key {
+            CL_GL_CONTEXT_KHR | CL_CGL_SHAREGROUP_KHR => return InteropKind::Gl,
+            CL_EGL_DISPLAY_KHR => kind = InteropKind::Egl,
+            _ => {
+                if InteropKind::None == kind {
+                    kind = match key as cl_context_info {
+                        CL_CONTEXT_D3D10_DEVICE_KHR => InteropKind::D3D10,
+                        CL_CONTEXT_D3D11_DEVICE_KHR => InteropKind::D3D11,
+                        CL_CONTEXT_ADAPTER_D3D9_KHR
+                        | CL_CONTEXT_ADAPTER_D3D9EX_KHR
+                        | CL_CONTEXT_ADAPTER_DXVA_KHR => InteropKind::Dx9MediaSharing,
+                        _ => InteropKind::None,
+                    };
+                }
+            }
+        }
+    }
+    kind
+}
+
+/// Get the graphics API interoperability that an OpenCL context was created with.  
+/// Calls clGetContextInfo to get CL_CONTEXT_PROPERTIES.
+///
+/// * `context` - the cl_context of the OpenCL context.
+///
+/// returns a Result containing the InteropKind of the context
+/// or the error code from the OpenCL C API function.
+pub fn context_interop_kind(context: cl_context) -> Result<InteropKind, cl_int> {
+    let properties = get_context_info(context, ContextInfo::CL_CONTEXT_PROPERTIES)?;
+    Ok(interop_kind(&properties.to_vec_intptr()))
+}
+
 // #ifdef CL_VERSION_3_0
 /// Register a callback function with a context that is called when the context is destroyed.  
 /// Calls clSetContextDestructorCallback.  
@@ -219,9 +379,82 @@ pub fn set_context_destructor_callback(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::macros::refcount_hooks::balance;
+    use crate::refcount_invariant;
+    use cl_sys::CL_GLX_DISPLAY_KHR;
+    use std::ffi::CString;
+    use std::sync::{mpsc, Mutex};
+    use std::thread;
+    use crate::device::{
+        get_device_ids, get_device_info, select_device, DeviceInfo, DeviceType,
+        CL_DEVICE_TYPE_ALL, CL_DEVICE_TYPE_GPU,
+    };
+    use crate::event::{create_user_event, release_event, retain_event};
     use crate::platform::get_platform_ids;
 
+    #[test]
+    fn test_context_callback_trampoline() {
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let callback: ContextCallback = Box::new(move |errinfo, private_info| {
+            let message = (errinfo.to_owned(), private_info.to_vec());
+            sender.lock().unwrap().send(message).unwrap();
+        });
+        let user_data = &callback as *const ContextCallback as *mut c_void;
+
+        let errinfo = CString::new("out of resources").unwrap();
+        let private_info = [1u8, 2, 3];
+        context_callback_trampoline(
+            errinfo.as_ptr(),
+            private_info.as_ptr() as *const c_void,
+            private_info.len(),
+            user_data,
+        );
+        context_callback_trampoline(ptr::null(), ptr::null(), 0, user_data);
+
+        assert_eq!(
+            ("out of resources".to_owned(), vec![1, 2, 3]),
+            receiver.recv().unwrap()
+        );
+        assert_eq!((String::new(), Vec::new()), receiver.recv().unwrap());
+    }
+
+    #[test]
+    fn test_create_context_with_callback() {
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let context = create_context_with_callback(
+            &[device_id],
+            ptr::null(),
+            Box::new(|errinfo, _| println!("OpenCL context error: {}", errinfo)),
+        )
+        .unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_create_context_from_type() {
+        let (platform_id, device_id) =
+            select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let device_type = get_device_info(device_id, DeviceInfo::CL_DEVICE_TYPE)
+            .unwrap()
+            .to_ulong();
+        let device_type = DeviceType::from_bits(device_type).unwrap();
+        let device_ids = get_device_ids(platform_id, device_type).unwrap();
+
+        let properties: [cl_context_properties; 3] = [
+            CL_CONTEXT_PLATFORM as cl_context_properties,
+            platform_id as cl_context_properties,
+            0,
+        ];
+        let context =
+            create_context_from_type(device_type, properties.as_ptr(), None, ptr::null_mut())
+                .unwrap();
+        let value = get_context_info(context, ContextInfo::CL_CONTEXT_DEVICES).unwrap();
+        assert_eq!(device_ids, value.to_vec_device_id());
+        assert!(device_ids.contains(&device_id));
+        release_context(context).unwrap();
+    }
+
     #[test]
     fn test_context() {
         let platform_ids = get_platform_ids().unwrap();
@@ -241,10 +474,10 @@ mod tests {
         assert!(0 < value);
 
         let value = get_context_info(context, ContextInfo::CL_CONTEXT_DEVICES).unwrap();
-        let value = value.to_vec_intptr();
+        let value = value.to_vec_device_id();
         println!("CL_CONTEXT_DEVICES: {}", value.len());
         println!("CL_CONTEXT_DEVICES: {:?}", value);
-        assert!(0 < value.len());
+        assert_eq!(device_ids, value);
 
         let value = get_context_info(context, ContextInfo::CL_CONTEXT_PROPERTIES).unwrap();
         let value = value.to_vec_intptr();
@@ -256,6 +489,109 @@ mod tests {
         let value = value.to_uint();
         println!("CL_CONTEXT_NUM_DEVICES: {}", value);
         assert!(0 < value);
+        assert_eq!(device_ids.len(), value as usize);
+        assert_eq!(device_ids, get_context_devices(context).unwrap());
+
+        let value = context_interop_kind(context).unwrap();
+        println!("context interop kind: {:?}", value);
+        assert_eq!(InteropKind::None, value);
+
+        let count = || {
+            get_context_info(context, ContextInfo::CL_CONTEXT_REFERENCE_COUNT)
+                .unwrap()
+                .to_uint()
+        };
+        refcount_invariant!(count(), {
+            retain_context(context).unwrap();
+            release_context(context).unwrap();
+        })
+        .unwrap();
+
+        // Release before panicking, the count must still return to baseline
+        let result = refcount_invariant!(count(), {
+            retain_context(context).unwrap();
+            release_context(context).unwrap();
+            panic!("panic in scope");
+        });
+        assert!(result.is_err());
+
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_interop_kind() {
+        assert_eq!(InteropKind::None, interop_kind(&[]));
+        assert_eq!(InteropKind::None, interop_kind(&[0]));
+
+        let platform = CL_CONTEXT_PLATFORM as cl_context_properties;
+        assert_eq!(InteropKind::None, interop_kind(&[platform, 0x1234, 0]));
+
+        let properties = [CL_GL_CONTEXT_KHR, 0x1234, CL_GLX_DISPLAY_KHR, 0x5678, 0];
+        assert_eq!(InteropKind::Gl, interop_kind(&properties));
+
+        // An EGL display with an OpenGL context is OpenGL sharing
+        let properties = [CL_EGL_DISPLAY_KHR, 0x1234, CL_GL_CONTEXT_KHR, 0x5678, 0];
+        assert_eq!(InteropKind::Gl, interop_kind(&properties));
+
+        let properties = [platform, 0x1234, CL_EGL_DISPLAY_KHR, 0x5678, 0];
+        assert_eq!(InteropKind::Egl, interop_kind(&properties));
+
+        let d3d11 = CL_CONTEXT_D3D11_DEVICE_KHR as cl_context_properties;
+        assert_eq!(InteropKind::D3D11, interop_kind(&[d3d11, 0x1234]));
+
+        let dxva = CL_CONTEXT_ADAPTER_DXVA_KHR as cl_context_properties;
+        assert_eq!(InteropKind::Dx9MediaSharing, interop_kind(&[dxva, 0x1234, 0]));
+
+        // Properties after the terminating null are ignored
+        assert_eq!(InteropKind::None, interop_kind(&[0, 0, CL_GL_CONTEXT_KHR, 0x1234]));
+    }
+
+    #[test]
+    fn test_refcount_stress() {
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let count = || {
+            get_context_info(context, ContextInfo::CL_CONTEXT_REFERENCE_COUNT)
+                .unwrap()
+                .to_uint()
+        };
+        let before = count();
+
+        // The handle is passed as usize since raw pointers are not Send
+        let handle = context as usize;
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                thread::spawn(move || {
+                    let context = handle as cl_context;
+                    for _ in 0..100 {
+                        refcount_invariant!(balance(handle), {
+                            retain_context(context).unwrap();
+                            let event = create_user_event(context).unwrap();
+                            refcount_invariant!(balance(event as usize), {
+                                retain_event(event).unwrap();
+                                release_event(event).unwrap();
+                            })
+                            .unwrap();
+                            release_event(event).unwrap();
+                            release_context(context).unwrap();
+                        })
+                        .unwrap();
+                    }
+
+                    // Release before panicking, the count must still return to baseline
+                    let result = refcount_invariant!(balance(handle), {
+                        retain_context(context).unwrap();
+                        release_context(context).unwrap();
+                        panic!("panic in scope");
+                    });
+                    assert!(result.is_err());
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(before, count());
 
         release_context(context).unwrap();
     }
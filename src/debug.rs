@@ -0,0 +1,138 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functions for debugging OpenCL ICD loader issues.
+//!
+//! When OpenCL is provided by an ICD loader, e.g. the Khronos OpenCL ICD
+//! loader, each platform is implemented by a vendor library. The first field
+//! of a platform object is a pointer to the vendor's dispatch table of OpenCL
+//! functions, which is used to find the library that implements the platform.
+
+use super::device::has_extension;
+use super::platform::{get_platform_info, PlatformInfo};
+use super::types::{cl_int, cl_platform_id};
+
+#[allow(unused_imports)]
+use libc::c_void;
+#[allow(unused_imports)]
+use std::ffi::CStr;
+
+/// The platform names and the library that implements a platform,
+/// see `platform_library_info`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlatformLibraryInfo {
+    pub name: String,
+    pub vendor: String,
+    pub version: String,
+    /// CL_PLATFORM_ICD_SUFFIX_KHR, if the platform supports cl_khr_icd.
+    pub icd_suffix: Option<String>,
+    /// The path of the vendor library that implements the platform, if the
+    /// platform supports cl_khr_icd and the path can be found.
+    pub library_path: Option<String>,
+}
+
+// The index of clGetPlatformInfo in the ICD dispatch table.
+#[allow(dead_code)]
+const DISPATCH_GET_PLATFORM_INFO: usize = 1;
+
+/// Find the path of the library containing the clGetPlatformInfo function
+/// in the ICD dispatch table of a platform.
+///
+/// # Safety
+///
+/// `platform` must be a valid platform that supports cl_khr_icd.
+#[cfg(target_os = "linux")]
+unsafe fn dispatch_library_path(platform: cl_platform_id) -> Option<String> {
+    let dispatch = *(platform as *const *const *const c_void);
+    if dispatch.is_null() {
+        return None;
+    }
+    let function = *dispatch.add(DISPATCH_GET_PLATFORM_INFO);
+    if function.is_null() {
+        return None;
+    }
+
+    let mut info: libc::Dl_info = std::mem::zeroed();
+    if 0 == libc::dladdr(function, &mut info) || info.dli_fname.is_null() {
+        None
+    } else {
+        Some(
+            CStr::from_ptr(info.dli_fname)
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+// The library path is only found with dladdr on Linux.
+#[cfg(not(target_os = "linux"))]
+unsafe fn dispatch_library_path(_platform: cl_platform_id) -> Option<String> {
+    None
+}
+
+/// Get the names of a platform and the library that implements it.
+/// Calls clGetPlatformInfo and, on Linux, dladdr for the platform's
+/// clGetPlatformInfo function in its ICD dispatch table.
+///
+/// * `platform` - the cl_platform_id of the OpenCL platform.
+///
+/// returns a Result containing the PlatformLibraryInfo
+/// or the error code from the OpenCL C API function.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn platform_library_info(platform: cl_platform_id) -> Result<PlatformLibraryInfo, cl_int> {
+    let extensions = get_platform_info(platform, PlatformInfo::CL_PLATFORM_EXTENSIONS)?.to_string();
+    let (icd_suffix, library_path) = if has_extension(&extensions, "cl_khr_icd") {
+        (
+            get_platform_info(platform, PlatformInfo::CL_PLATFORM_ICD_SUFFIX_KHR)
+                .map(|value| value.to_string())
+                .ok(),
+            unsafe { dispatch_library_path(platform) },
+        )
+    } else {
+        (None, None)
+    };
+
+    Ok(PlatformLibraryInfo {
+        name: get_platform_info(platform, PlatformInfo::CL_PLATFORM_NAME)?.to_string(),
+        vendor: get_platform_info(platform, PlatformInfo::CL_PLATFORM_VENDOR)?.to_string(),
+        version: get_platform_info(platform, PlatformInfo::CL_PLATFORM_VERSION)?.to_string(),
+        icd_suffix,
+        library_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::get_platform_ids;
+
+    #[test]
+    fn test_platform_library_info() {
+        let platform_ids = get_platform_ids().unwrap();
+        assert!(0 < platform_ids.len());
+
+        for platform_id in platform_ids {
+            let info = platform_library_info(platform_id).unwrap();
+            println!("{:?}", info);
+            assert!(!info.name.is_empty());
+            assert!(!info.vendor.is_empty());
+            assert!(!info.version.is_empty());
+
+            if cfg!(target_os = "linux") && info.icd_suffix.is_some() {
+                let library_path = info.library_path.unwrap();
+                assert!(!library_path.is_empty());
+            }
+        }
+    }
+}
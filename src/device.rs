@@ -16,7 +16,14 @@
 
 #![allow(non_camel_case_types)]
 
-use super::error_codes::{CL_DEVICE_NOT_FOUND, CL_SUCCESS};
+mod cache;
+
+pub use cache::DeviceInfoCache;
+pub(crate) use cache::device_info_cache;
+
+use super::error_codes::{
+    error_text, CL_DEVICE_NOT_FOUND, CL_INVALID_DEVICE_TYPE, CL_INVALID_VALUE, CL_SUCCESS,
+};
 
 pub use cl_sys::{
     CL_DEVICE_TYPE_DEFAULT, CL_DEVICE_TYPE_CPU,
@@ -38,10 +45,11 @@ use super::types::{
     cl_command_queue, cl_context, cl_device_affinity_domain, cl_device_exec_capabilities,
     cl_device_fp_config, cl_device_id, cl_device_info, cl_device_local_mem_type,
     cl_device_mem_cache_type, cl_device_partition_property, cl_device_svm_capabilities,
-    cl_device_type, cl_int, cl_name_version, cl_platform_id, cl_uint, cl_ulong,
-    cl_device_atomic_capabilities, cl_device_device_enqueue_capabilities, cl_version
+    cl_device_type, cl_int, cl_kernel, cl_name_version, cl_platform_id, cl_uint, cl_ulong,
+    cl_device_atomic_capabilities, cl_device_device_enqueue_capabilities, cl_version, cl_uchar,
 };
 use super::ffi::cl_ext::{cl_amd_device_topology, cl_device_pci_bus_info_khr,
+    CL_DEVICE_TERMINATE_CAPABILITY_KHR,
     CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV, CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV,
     CL_DEVICE_REGISTERS_PER_BLOCK_NV, CL_DEVICE_WARP_SIZE_NV, CL_DEVICE_GPU_OVERLAP_NV,
     CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV, CL_DEVICE_INTEGRATED_MEMORY_NV,
@@ -59,8 +67,24 @@ use super::ffi::cl_ext::{cl_amd_device_topology, cl_device_pci_bus_info_khr,
     CL_DEVICE_PCIE_ID_AMD, CL_DEVICE_PCI_BUS_INFO_KHR, CL_DEVICE_INTEGER_DOT_PRODUCT_CAPABILITIES_KHR,
     CL_DEVICE_UUID_KHR, CL_DRIVER_UUID_KHR, CL_DEVICE_LUID_VALID_KHR,
     CL_DEVICE_LUID_KHR, CL_DEVICE_NODE_MASK_KHR,
+    CL_DEVICE_IP_VERSION_INTEL, CL_DEVICE_ID_INTEL, CL_DEVICE_NUM_SLICES_INTEL,
+    CL_DEVICE_NUM_SUB_SLICES_PER_SLICE_INTEL, CL_DEVICE_NUM_EUS_PER_SUB_SLICE_INTEL,
+    CL_DEVICE_NUM_THREADS_PER_EU_INTEL, CL_DEVICE_FEATURE_CAPABILITIES_INTEL,
+    CL_DEVICE_SUB_GROUP_SIZES_INTEL,
+};
+#[cfg(any(test, feature = "cl_khr_device_uuid"))]
+use super::ffi::cl_ext::{CL_LUID_SIZE_KHR, CL_UUID_SIZE_KHR};
+#[cfg(feature = "cl_khr_terminate_context")]
+use super::ffi::cl_ext::{
+    cl_device_terminate_capability_khr, CL_DEVICE_TERMINATE_CAPABILITY_CONTEXT_KHR,
+};
+#[cfg(feature = "CL_VERSION_2_1")]
+use super::kernel::{get_kernel_sub_group_info_slice, KernelSubGroupInfo};
+use super::platform::get_platform_ids;
+use super::selection::{env_device, SelectionError, CL3_DEVICE};
+use super::{
+    api_info_size, api_info_value, api_info_vector, create_object_list, get_object_list,
 };
-use super::{api_info_size, api_info_value, api_info_vector};
 #[allow(unused_imports)]
 use cl_sys::{
     clCreateSubDevices, clGetDeviceIDs, clGetDeviceInfo, clReleaseDevice, clRetainDevice, 
@@ -81,7 +105,11 @@ extern "system" {
 }
 
 use libc::{c_void, intptr_t, size_t};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
 use std::mem;
+use std::ops;
 use std::ptr;
 
 // cl_device_atomic_capabilities
@@ -124,6 +152,448 @@ pub fn make_version(major: cl_version, minor: cl_version, patch: cl_version) ->
     (patch & CL_VERSION_PATCH_MASK)
 }
 
+/// An OpenCL version, e.g. of a platform, device or OpenCL C compiler.  
+/// Versions are ordered by major, minor and then patch version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Version {
+    pub major: cl_version,
+    pub minor: cl_version,
+    pub patch: cl_version,
+}
+
+impl Version {
+    pub fn new(major: cl_version, minor: cl_version, patch: cl_version) -> Version {
+        Version {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// The numeric cl_version encoding of the version, see `make_version`.
+    pub fn to_cl_version(self) -> cl_version {
+        make_version(self.major, self.minor, self.patch)
+    }
+}
+
+impl From<cl_version> for Version {
+    fn from(version: cl_version) -> Self {
+        Version::new(
+            version_major(version),
+            version_minor(version),
+            version_patch(version),
+        )
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Parse an OpenCL version string, e.g. from CL_PLATFORM_VERSION or
+/// CL_DEVICE_VERSION: "OpenCL <major>.<minor> <vendor-specific information>"
+/// or CL_DEVICE_OPENCL_C_VERSION: "OpenCL C <major>.<minor> <vendor-specific information>".
+///
+/// * `text` - the version string.
+///
+/// returns the version, with a patch version of 0 unless one is given,
+/// or None if the string is not an OpenCL version string.
+pub fn parse_opencl_version(text: &str) -> Option<Version> {
+    let mut words = text.split_whitespace();
+    if "OpenCL" != words.next()? {
+        return None;
+    }
+    let mut word = words.next()?;
+    if "C" == word {
+        word = words.next()?;
+    }
+
+    let mut numbers = word.splitn(3, '.');
+    let major = numbers.next()?.parse().ok()?;
+    let minor = numbers.next()?.parse().ok()?;
+    let patch = match numbers.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    Some(Version::new(major, minor, patch))
+}
+
+/// Get the OpenCL version of a device.  
+/// Calls clGetDeviceInfo for CL_DEVICE_NUMERIC_VERSION and, if it is not
+/// supported, for CL_DEVICE_VERSION.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the OpenCL version of the device
+/// or CL_INVALID_VALUE if the version string cannot be parsed
+/// or the error code from the OpenCL C API function.
+pub fn device_opencl_version(device: cl_device_id) -> Result<Version, cl_int> {
+    // CL_DEVICE_NUMERIC_VERSION is not supported before OpenCL 3.0
+    if let Ok(value) = get_device_info(device, DeviceInfo::CL_DEVICE_NUMERIC_VERSION) {
+        let version = value.to_uint();
+        if 0 != version {
+            return Ok(Version::from(version));
+        }
+    }
+    let text = get_device_info(device, DeviceInfo::CL_DEVICE_VERSION)?.to_string();
+    parse_opencl_version(&text).ok_or(CL_INVALID_VALUE)
+}
+
+/// The information about an OpenCL device that is commonly needed for
+/// diagnostics and device selection, see `get_device_summary`.  
+/// The optional fields are None if the device does not support the query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceSummary {
+    pub name: String,
+    pub vendor: String,
+    pub profile: String,
+    pub version_string: String,
+    /// The parsed OpenCL version, None if CL_DEVICE_VERSION cannot be parsed.
+    pub version: Option<Version>,
+    pub driver_version: String,
+    /// CL_VERSION_1_1
+    pub opencl_c_version: Option<String>,
+    pub device_type: DeviceType,
+    pub max_compute_units: cl_uint,
+    pub max_clock_frequency: cl_uint,
+    pub global_mem_size: cl_ulong,
+    pub local_mem_size: cl_ulong,
+    pub max_work_group_size: size_t,
+    pub max_work_item_sizes: Vec<size_t>,
+    pub extensions: Vec<String>,
+    pub image_support: bool,
+}
+
+/// Get a summary of the information about an OpenCL device.  
+/// Calls clGetDeviceInfo for each field of DeviceSummary.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the DeviceSummary
+/// or CL_INVALID_DEVICE_TYPE if CL_DEVICE_TYPE is not a DeviceType
+/// or the error code from the OpenCL C API function.
+pub fn get_device_summary(device: cl_device_id) -> Result<DeviceSummary, cl_int> {
+    let extensions = get_device_info(device, DeviceInfo::CL_DEVICE_EXTENSIONS)?
+        .to_string()
+        .split_whitespace()
+        .map(String::from)
+        .collect();
+
+    Ok(DeviceSummary {
+        name: get_device_info(device, DeviceInfo::CL_DEVICE_NAME)?.to_string(),
+        vendor: get_device_info(device, DeviceInfo::CL_DEVICE_VENDOR)?.to_string(),
+        profile: get_device_info(device, DeviceInfo::CL_DEVICE_PROFILE)?.to_string(),
+        version_string: get_device_info(device, DeviceInfo::CL_DEVICE_VERSION)?.to_string(),
+        version: device_opencl_version(device).ok(),
+        driver_version: get_device_info(device, DeviceInfo::CL_DRIVER_VERSION)?.to_string(),
+        opencl_c_version: get_device_info(device, DeviceInfo::CL_DEVICE_OPENCL_C_VERSION)
+            .ok()
+            .map(|value| value.to_string()),
+        device_type: DeviceType::try_from(
+            get_device_info(device, DeviceInfo::CL_DEVICE_TYPE)?.to_ulong(),
+        )?,
+        max_compute_units: get_device_info(device, DeviceInfo::CL_DEVICE_MAX_COMPUTE_UNITS)?
+            .to_uint(),
+        max_clock_frequency: get_device_info(device, DeviceInfo::CL_DEVICE_MAX_CLOCK_FREQUENCY)?
+            .to_uint(),
+        global_mem_size: get_device_info(device, DeviceInfo::CL_DEVICE_GLOBAL_MEM_SIZE)?
+            .to_ulong(),
+        local_mem_size: get_device_info(device, DeviceInfo::CL_DEVICE_LOCAL_MEM_SIZE)?.to_ulong(),
+        max_work_group_size: get_device_info(device, DeviceInfo::CL_DEVICE_MAX_WORK_GROUP_SIZE)?
+            .to_size(),
+        max_work_item_sizes: get_device_info(device, DeviceInfo::CL_DEVICE_MAX_WORK_ITEM_SIZES)?
+            .to_vec_size(),
+        extensions,
+        image_support: get_device_info(device, DeviceInfo::CL_DEVICE_IMAGE_SUPPORT)?.to_bool(),
+    })
+}
+
+// A typed OpenCL bit-field: flag constants, the set operations and a Display
+// that lists the flag names, e.g. "GPU | ACCELERATOR", or "NONE" if empty.
+// `: From` also converts from the bit-field without validating it.
+macro_rules! device_bitflags {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident($bits:ty) $(: $from:ident)? {
+            $($flag:ident = $value:expr),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        pub struct $name($bits);
+
+        impl $name {
+            $(pub const $flag: $name = $name($value);)*
+
+            /// The OpenCL bit-field value.
+            pub fn bits(self) -> $bits {
+                self.0
+            }
+
+            /// Whether all of the flags in `other` are in this set.
+            pub fn contains(self, other: $name) -> bool {
+                other.0 == self.0 & other.0
+            }
+
+            /// Whether any of the flags in `other` are in this set.
+            pub fn intersects(self, other: $name) -> bool {
+                0 != self.0 & other.0
+            }
+
+            /// Whether no flags are set.
+            pub fn is_empty(self) -> bool {
+                0 == self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                if self.is_empty() {
+                    return write!(f, "NONE");
+                }
+                let names = [$(($name::$flag, stringify!($flag))),*];
+                // A flag that names the whole set, e.g. DeviceType::ALL
+                if let Some((_, name)) = names.iter().find(|(flag, _)| flag == self) {
+                    return write!(f, "{}", name);
+                }
+                let names: Vec<&str> = names
+                    .iter()
+                    .filter(|(flag, _)| self.contains(*flag))
+                    .map(|(_, name)| *name)
+                    .collect();
+                write!(f, "{}", names.join(" | "))
+            }
+        }
+
+        // Debug lists the flags, as Display does, for logs.
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(self, f)
+            }
+        }
+
+        $(
+            impl $from<$bits> for $name {
+                fn from(bits: $bits) -> Self {
+                    $name(bits)
+                }
+            }
+        )?
+
+        impl From<$name> for $bits {
+            fn from(flags: $name) -> Self {
+                flags.0
+            }
+        }
+
+        impl ops::BitOr for $name {
+            type Output = $name;
+
+            fn bitor(self, other: $name) -> $name {
+                $name(self.0 | other.0)
+            }
+        }
+
+        impl ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, other: $name) {
+                self.0 |= other.0;
+            }
+        }
+
+        impl ops::BitAnd for $name {
+            type Output = $name;
+
+            fn bitand(self, other: $name) -> $name {
+                $name(self.0 & other.0)
+            }
+        }
+
+        impl ops::BitAndAssign for $name {
+            fn bitand_assign(&mut self, other: $name) {
+                self.0 &= other.0;
+            }
+        }
+    };
+}
+
+device_bitflags! {
+    /// A set of OpenCL device types, a typed cl_device_type.  
+    /// Device types are combined with `|`, e.g. `DeviceType::GPU | DeviceType::ACCELERATOR`.
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(try_from = "cl_device_type", into = "cl_device_type")
+    )]
+    pub struct DeviceType(cl_device_type) {
+        DEFAULT = CL_DEVICE_TYPE_DEFAULT,
+        CPU = CL_DEVICE_TYPE_CPU,
+        GPU = CL_DEVICE_TYPE_GPU,
+        ACCELERATOR = CL_DEVICE_TYPE_ACCELERATOR,
+        CUSTOM = CL_DEVICE_TYPE_CUSTOM,
+        ALL = CL_DEVICE_TYPE_ALL,
+    }
+}
+
+impl DeviceType {
+    /// Convert a cl_device_type, e.g. from CL_DEVICE_TYPE.
+    ///
+    /// returns None if `bits` contains bits that are not device types.
+    pub fn from_bits(bits: cl_device_type) -> Option<DeviceType> {
+        const KNOWN: cl_device_type = CL_DEVICE_TYPE_DEFAULT
+            | CL_DEVICE_TYPE_CPU
+            | CL_DEVICE_TYPE_GPU
+            | CL_DEVICE_TYPE_ACCELERATOR
+            | CL_DEVICE_TYPE_CUSTOM;
+        if CL_DEVICE_TYPE_ALL == bits || 0 == bits & !KNOWN {
+            Some(DeviceType(bits))
+        } else {
+            None
+        }
+    }
+}
+
+impl TryFrom<cl_device_type> for DeviceType {
+    type Error = cl_int;
+
+    /// returns CL_INVALID_DEVICE_TYPE if `bits` contains bits that are not
+    /// device types, see `from_bits`.
+    fn try_from(bits: cl_device_type) -> Result<Self, Self::Error> {
+        DeviceType::from_bits(bits).ok_or(CL_INVALID_DEVICE_TYPE)
+    }
+}
+
+device_bitflags! {
+    /// The floating-point capabilities of a device, a typed
+    /// CL_DEVICE_SINGLE_FP_CONFIG, CL_DEVICE_DOUBLE_FP_CONFIG or
+    /// CL_DEVICE_HALF_FP_CONFIG value.
+    #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct FpConfig(cl_device_fp_config): From {
+        DENORM = CL_FP_DENORM,
+        INF_NAN = CL_FP_INF_NAN,
+        ROUND_TO_NEAREST = CL_FP_ROUND_TO_NEAREST,
+        ROUND_TO_ZERO = CL_FP_ROUND_TO_ZERO,
+        ROUND_TO_INF = CL_FP_ROUND_TO_INF,
+        FMA = CL_FP_FMA,
+        SOFT_FLOAT = CL_FP_SOFT_FLOAT,
+        CORRECTLY_ROUNDED_DIVIDE_SQRT = CL_FP_CORRECTLY_ROUNDED_DIVIDE_SQRT,
+    }
+}
+
+/// Get the floating-point capabilities of a device for a floating-point type.  
+/// Calls clGetDeviceInfo for CL_DEVICE_SINGLE_FP_CONFIG,
+/// CL_DEVICE_DOUBLE_FP_CONFIG or CL_DEVICE_HALF_FP_CONFIG.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+/// * `param_name` - CL_DEVICE_SINGLE_FP_CONFIG, CL_DEVICE_DOUBLE_FP_CONFIG
+///   or CL_DEVICE_HALF_FP_CONFIG, which requires the cl_khr_fp16 extension.
+///
+/// returns a Result containing the FpConfig
+/// or CL_INVALID_VALUE if `param_name` is not a floating-point config
+/// or the error code from the OpenCL C API function, e.g. for
+/// CL_DEVICE_HALF_FP_CONFIG on a device without cl_khr_fp16.
+pub fn fp_config(device: cl_device_id, param_name: DeviceInfo) -> Result<FpConfig, cl_int> {
+    match param_name {
+        DeviceInfo::CL_DEVICE_SINGLE_FP_CONFIG
+        | DeviceInfo::CL_DEVICE_DOUBLE_FP_CONFIG
+        | DeviceInfo::CL_DEVICE_HALF_FP_CONFIG => {
+            Ok(FpConfig::from(get_device_info(device, param_name)?.to_ulong()))
+        }
+        _ => Err(CL_INVALID_VALUE),
+    }
+}
+
+device_bitflags! {
+    /// The shared virtual memory capabilities of a device, a typed
+    /// CL_DEVICE_SVM_CAPABILITIES value.
+    #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct SvmCapabilities(cl_device_svm_capabilities): From {
+        COARSE_GRAIN_BUFFER = CL_DEVICE_SVM_COARSE_GRAIN_BUFFER,
+        FINE_GRAIN_BUFFER = CL_DEVICE_SVM_FINE_GRAIN_BUFFER,
+        FINE_GRAIN_SYSTEM = CL_DEVICE_SVM_FINE_GRAIN_SYSTEM,
+        ATOMICS = CL_DEVICE_SVM_ATOMICS,
+    }
+}
+
+impl SvmCapabilities {
+    /// Whether fine-grained SVM buffers or system allocations are supported.
+    pub fn has_fine_grain(self) -> bool {
+        0 != self.0 & (CL_DEVICE_SVM_FINE_GRAIN_BUFFER | CL_DEVICE_SVM_FINE_GRAIN_SYSTEM)
+    }
+
+    /// Whether coarse-grained SVM buffers are supported.
+    pub fn supports_coarse_grain_buffer(self) -> bool {
+        self.contains(SvmCapabilities::COARSE_GRAIN_BUFFER)
+    }
+
+    /// Whether fine-grained SVM buffers are supported.
+    pub fn supports_fine_grain_buffer(self) -> bool {
+        self.contains(SvmCapabilities::FINE_GRAIN_BUFFER)
+    }
+
+    /// Whether fine-grained system allocations, e.g. by malloc, can be used as SVM.
+    pub fn supports_fine_grain_system(self) -> bool {
+        self.contains(SvmCapabilities::FINE_GRAIN_SYSTEM)
+    }
+
+    /// Whether SVM atomics are supported.
+    pub fn supports_atomics(self) -> bool {
+        self.contains(SvmCapabilities::ATOMICS)
+    }
+}
+
+#[cfg(feature = "cl_khr_terminate_context")]
+device_bitflags! {
+    /// The controlled termination capabilities of a device, a typed
+    /// CL_DEVICE_TERMINATE_CAPABILITY_KHR value, see cl_khr_terminate_context.
+    #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct TerminateCapability(cl_device_terminate_capability_khr): From {
+        CONTEXT = CL_DEVICE_TERMINATE_CAPABILITY_CONTEXT_KHR,
+    }
+}
+
+#[cfg(feature = "cl_khr_terminate_context")]
+impl TerminateCapability {
+    /// Whether contexts on the device may be created with CL_CONTEXT_TERMINATE_KHR
+    /// and terminated with clTerminateContextKHR.
+    pub fn supports_context(self) -> bool {
+        self.contains(TerminateCapability::CONTEXT)
+    }
+}
+
+/// Get the controlled termination capabilities of a device.  
+/// Calls clGetDeviceInfo to get CL_DEVICE_TERMINATE_CAPABILITY_KHR.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the TerminateCapability
+/// or the error code from the OpenCL C API function, e.g. CL_INVALID_VALUE
+/// if the device does not support cl_khr_terminate_context.
+#[cfg(feature = "cl_khr_terminate_context")]
+pub fn get_device_terminate_capability(
+    device: cl_device_id,
+) -> Result<TerminateCapability, cl_int> {
+    let value =
+        get_device_info(device, DeviceInfo::CL_DEVICE_TERMINATE_CAPABILITY_KHR)?.to_ulong();
+    Ok(TerminateCapability::from(value))
+}
+
+/// Get the shared virtual memory capabilities of a device.  
+/// Calls clGetDeviceInfo for CL_DEVICE_SVM_CAPABILITIES.  
+/// CL_VERSION_2_0
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the SvmCapabilities
+/// or the error code from the OpenCL C API function.
+pub fn device_svm_capabilities(device: cl_device_id) -> Result<SvmCapabilities, cl_int> {
+    let value = get_device_info(device, DeviceInfo::CL_DEVICE_SVM_CAPABILITIES)?.to_ulong();
+    Ok(SvmCapabilities::from(value))
+}
+
 /// Get the list of available devices of the given type on a platform.  
 /// Calls clGetDeviceIDs to get the available device ids on the platform.
 ///  # Examples
@@ -142,47 +612,87 @@ pub fn make_version(major: cl_version, minor: cl_version, patch: cl_version) ->
 /// assert!(0 < device_ids.len());
 /// ```
 /// * `platform` - the cl_platform_id of the OpenCL platform.
-/// * `device_type` - the type of device, see
+/// * `device_type` - the type of device, a DeviceType or a cl_device_type, see
 /// [Device Types](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#device-types-table).
 ///
 /// returns a Result containing a vector of available device ids
 /// or the error code from the OpenCL C API function.
-pub fn get_device_ids(
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn get_device_ids<T: Into<cl_device_type>>(
     platform: cl_platform_id,
-    device_type: cl_device_type,
+    device_type: T,
 ) -> Result<Vec<cl_device_id>, cl_int> {
-    // Get the number of devices of device_type
-    let mut count: cl_uint = 0;
-    let mut status =
-        unsafe { clGetDeviceIDs(platform, device_type, 0, ptr::null_mut(), &mut count) };
+    #[cfg(test)]
+    crate::macros::call_hooks::called("clGetDeviceIDs");
+    let device_type: cl_device_type = device_type.into();
+    get_object_list(
+        Some(CL_DEVICE_NOT_FOUND),
+        |num_entries, devices, num_devices| unsafe {
+            clGetDeviceIDs(platform, device_type, num_entries, devices, num_devices)
+        },
+    )
+}
 
-    if (CL_SUCCESS != status) && (CL_DEVICE_NOT_FOUND != status) {
-        Err(status)
-    } else {
-        if 0 < count {
-            // Get the device ids.
-            let len = count as usize;
-            let mut ids: Vec<cl_device_id> = Vec::with_capacity(len);
-            unsafe {
-                ids.set_len(len);
-                status = clGetDeviceIDs(
-                    platform,
-                    device_type,
-                    count,
-                    ids.as_mut_ptr(),
-                    ptr::null_mut(),
-                );
-            };
+/// Get the available devices of the given type on all platforms.  
+/// Calls clGetPlatformIDs and clGetDeviceIDs for each platform.
+///  # Examples
+/// ```no_run
+/// use cl3::device::{get_all_devices, CL_DEVICE_TYPE_GPU};
+///
+/// for (platform_id, device_id) in get_all_devices(CL_DEVICE_TYPE_GPU).unwrap() {
+///     println!("platform: {:?}, GPU: {:?}", platform_id, device_id);
+/// }
+/// ```
+/// * `device_type` - the type of device, a DeviceType or a cl_device_type.
+///
+/// returns a Result containing a vector of the available devices, with
+/// their platforms, which is empty if there are no devices of the type
+/// or the error code from the OpenCL C API function.
+pub fn get_all_devices<T: Into<cl_device_type>>(
+    device_type: T,
+) -> Result<Vec<(cl_platform_id, cl_device_id)>, cl_int> {
+    let device_type: cl_device_type = device_type.into();
+    let mut devices = Vec::new();
+    for platform in get_platform_ids()? {
+        // get_device_ids returns no devices, rather than CL_DEVICE_NOT_FOUND
+        let device_ids = get_device_ids(platform, device_type)?;
+        devices.extend(device_ids.into_iter().map(|device| (platform, device)));
+    }
+    Ok(devices)
+}
 
-            if CL_SUCCESS != status {
-                Err(status)
-            } else {
-                Ok(ids)
-            }
-        } else {
-            Ok(Vec::default())
+/// Get the platform of a device.  
+/// Calls clGetDeviceInfo to get CL_DEVICE_PLATFORM.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the cl_platform_id of the device
+/// or the error code from the OpenCL C API function.
+pub fn get_device_platform(device: cl_device_id) -> Result<cl_platform_id, cl_int> {
+    let platform = get_device_info(device, DeviceInfo::CL_DEVICE_PLATFORM)?.to_ptr();
+    Ok(platform as cl_platform_id)
+}
+
+/// Whether devices all belong to the same platform, as the devices of a
+/// context must.  
+/// Calls clGetDeviceInfo to get CL_DEVICE_PLATFORM for each device.
+///
+/// * `devices` - the OpenCL devices.
+///
+/// returns a Result containing true if the devices share a platform or there
+/// are fewer than two devices
+/// or the error code from the OpenCL C API function.
+pub fn devices_share_platform(devices: &[cl_device_id]) -> Result<bool, cl_int> {
+    if devices.len() < 2 {
+        return Ok(true);
+    }
+    let platform = get_device_platform(devices[0])?;
+    for &device in devices[1..].iter() {
+        if platform != get_device_platform(device)? {
+            return Ok(false);
         }
     }
+    Ok(true)
 }
 
 /// Get data about an OpenCL device.
@@ -333,6 +843,9 @@ pub enum DeviceInfo {
     // cl_khr_integer_dot_product extension
     CL_DEVICE_INTEGER_DOT_PRODUCT_CAPABILITIES_KHR = CL_DEVICE_INTEGER_DOT_PRODUCT_CAPABILITIES_KHR as isize,
 
+    // cl_khr_terminate_context extension
+    CL_DEVICE_TERMINATE_CAPABILITY_KHR = CL_DEVICE_TERMINATE_CAPABILITY_KHR as isize,
+
     // cl_nv_device_attribute_query extension
     CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV = CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV as isize,
     CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV = CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV as isize,
@@ -369,8 +882,26 @@ pub enum DeviceInfo {
     CL_DEVICE_PREFERRED_CONSTANT_BUFFER_SIZE_AMD = CL_DEVICE_PREFERRED_CONSTANT_BUFFER_SIZE_AMD as isize,
     CL_DEVICE_PCIE_ID_AMD = CL_DEVICE_PCIE_ID_AMD as isize,
     CL_DEVICE_PCI_BUS_INFO_KHR = CL_DEVICE_PCI_BUS_INFO_KHR as isize,
+
+    // cl_intel_device_attribute_query
+    CL_DEVICE_IP_VERSION_INTEL = CL_DEVICE_IP_VERSION_INTEL as isize,
+    CL_DEVICE_ID_INTEL = CL_DEVICE_ID_INTEL as isize,
+    CL_DEVICE_NUM_SLICES_INTEL = CL_DEVICE_NUM_SLICES_INTEL as isize,
+    CL_DEVICE_NUM_SUB_SLICES_PER_SLICE_INTEL = CL_DEVICE_NUM_SUB_SLICES_PER_SLICE_INTEL as isize,
+    CL_DEVICE_NUM_EUS_PER_SUB_SLICE_INTEL = CL_DEVICE_NUM_EUS_PER_SUB_SLICE_INTEL as isize,
+    CL_DEVICE_NUM_THREADS_PER_EU_INTEL = CL_DEVICE_NUM_THREADS_PER_EU_INTEL as isize,
+    CL_DEVICE_FEATURE_CAPABILITIES_INTEL = CL_DEVICE_FEATURE_CAPABILITIES_INTEL as isize,
+
+    // cl_intel_required_subgroup_size
+    CL_DEVICE_SUB_GROUP_SIZES_INTEL = CL_DEVICE_SUB_GROUP_SIZES_INTEL as isize,
 }
 
+// cl_khr_device_uuid array types
+#[cfg(feature = "cl_khr_device_uuid")]
+type uuid_array = [cl_uchar; CL_UUID_SIZE_KHR];
+#[cfg(feature = "cl_khr_device_uuid")]
+type luid_array = [cl_uchar; CL_LUID_SIZE_KHR];
+
 /// Get specific information about an OpenCL device.  
 /// Calls clGetDeviceInfo to get the desired information about the device.
 ///  # Examples
@@ -413,6 +944,8 @@ pub enum DeviceInfo {
 /// returns a Result containing the desired information in an InfoType enum
 /// or the error code from the OpenCL C API function.
 pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<InfoType, cl_int> {
+    #[cfg(test)]
+    crate::macros::call_hooks::called("clGetDeviceInfo");
     api_info_size!(get_size, clGetDeviceInfo);
 
     let param_id = param_name as cl_device_info;
@@ -426,9 +959,6 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
         | DeviceInfo::CL_DEVICE_OPENCL_C_VERSION
         | DeviceInfo::CL_DEVICE_BUILT_IN_KERNELS
         | DeviceInfo::CL_DEVICE_IL_VERSION
-        | DeviceInfo::CL_DEVICE_UUID_KHR // cl_khr_device_uuid
-        | DeviceInfo::CL_DRIVER_UUID_KHR // cl_khr_device_uuid
-        | DeviceInfo::CL_DEVICE_LUID_KHR // cl_khr_device_uuid
         | DeviceInfo::CL_DEVICE_LATEST_CONFORMANCE_VERSION_PASSED // CL_VERSION_3_0
         | DeviceInfo::CL_DEVICE_TOPOLOGY_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_BOARD_NAME_AMD // cl_amd_device_attribute_query
@@ -437,6 +967,52 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
             Ok(InfoType::VecUchar(get_device_data(device, param_id)?))
         }
 
+        #[cfg(feature = "cl_khr_device_uuid")]
+        DeviceInfo::CL_DEVICE_UUID_KHR // cl_khr_device_uuid
+        | DeviceInfo::CL_DRIVER_UUID_KHR // cl_khr_device_uuid
+        => {
+            api_info_value!(get_value, uuid_array, clGetDeviceInfo);
+            Ok(InfoType::Uuid(get_value(device, param_id)?))
+        }
+
+        #[cfg(feature = "cl_khr_device_uuid")]
+        DeviceInfo::CL_DEVICE_LUID_KHR // cl_khr_device_uuid
+        => {
+            api_info_value!(get_value, luid_array, clGetDeviceInfo);
+            Ok(InfoType::Luid(get_value(device, param_id)?))
+        }
+
+        #[cfg(not(feature = "cl_khr_device_uuid"))]
+        DeviceInfo::CL_DEVICE_UUID_KHR // cl_khr_device_uuid
+        | DeviceInfo::CL_DRIVER_UUID_KHR // cl_khr_device_uuid
+        | DeviceInfo::CL_DEVICE_LUID_KHR // cl_khr_device_uuid
+        => {
+            Ok(InfoType::VecUchar(get_device_data(device, param_id)?))
+        }
+
+        DeviceInfo::CL_DEVICE_IMAGE_SUPPORT
+        | DeviceInfo::CL_DEVICE_ERROR_CORRECTION_SUPPORT
+        | DeviceInfo::CL_DEVICE_ENDIAN_LITTLE
+        | DeviceInfo::CL_DEVICE_AVAILABLE
+        | DeviceInfo::CL_DEVICE_COMPILER_AVAILABLE
+        | DeviceInfo::CL_DEVICE_HOST_UNIFIED_MEMORY
+        | DeviceInfo::CL_DEVICE_LINKER_AVAILABLE
+        | DeviceInfo::CL_DEVICE_PREFERRED_INTEROP_USER_SYNC
+        | DeviceInfo::CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS
+        | DeviceInfo::CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT // CL_VERSION_3_0
+        | DeviceInfo::CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT // CL_VERSION_3_0
+        | DeviceInfo::CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT // CL_VERSION_3_0
+        | DeviceInfo::CL_DEVICE_PIPE_SUPPORT // CL_VERSION_3_0
+        | DeviceInfo::CL_DEVICE_LUID_VALID_KHR // cl_khr_device_uuid
+        | DeviceInfo::CL_DEVICE_GPU_OVERLAP_NV // cl_nv_device_attribute_query
+        | DeviceInfo::CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV // cl_nv_device_attribute_query
+        | DeviceInfo::CL_DEVICE_INTEGRATED_MEMORY_NV // cl_nv_device_attribute_query
+        | DeviceInfo::CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD // cl_amd_device_attribute_query
+        => {
+            api_info_value!(get_value, cl_uint, clGetDeviceInfo);
+            Ok(InfoType::Bool(0 != get_value(device, param_id)?))
+        }
+
         DeviceInfo::CL_DEVICE_VENDOR_ID
         | DeviceInfo::CL_DEVICE_MAX_COMPUTE_UNITS
         | DeviceInfo::CL_DEVICE_MAX_WORK_ITEM_DIMENSIONS
@@ -450,7 +1026,6 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
         | DeviceInfo::CL_DEVICE_ADDRESS_BITS
         | DeviceInfo::CL_DEVICE_MAX_READ_IMAGE_ARGS
         | DeviceInfo::CL_DEVICE_MAX_WRITE_IMAGE_ARGS
-        | DeviceInfo::CL_DEVICE_IMAGE_SUPPORT
         | DeviceInfo::CL_DEVICE_MAX_SAMPLERS
         | DeviceInfo::CL_DEVICE_MEM_BASE_ADDR_ALIGN
         | DeviceInfo::CL_DEVICE_MIN_DATA_TYPE_ALIGN_SIZE
@@ -458,12 +1033,7 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
         | DeviceInfo::CL_DEVICE_GLOBAL_MEM_CACHELINE_SIZE
         | DeviceInfo::CL_DEVICE_MAX_CONSTANT_ARGS
         | DeviceInfo::CL_DEVICE_LOCAL_MEM_TYPE
-        | DeviceInfo::CL_DEVICE_ERROR_CORRECTION_SUPPORT
-        | DeviceInfo::CL_DEVICE_ENDIAN_LITTLE
-        | DeviceInfo::CL_DEVICE_AVAILABLE
-        | DeviceInfo::CL_DEVICE_COMPILER_AVAILABLE
         | DeviceInfo::CL_DEVICE_PREFERRED_VECTOR_WIDTH_HALF
-        | DeviceInfo::CL_DEVICE_HOST_UNIFIED_MEMORY
         | DeviceInfo::CL_DEVICE_NATIVE_VECTOR_WIDTH_CHAR
         | DeviceInfo::CL_DEVICE_NATIVE_VECTOR_WIDTH_SHORT
         | DeviceInfo::CL_DEVICE_NATIVE_VECTOR_WIDTH_INT
@@ -471,10 +1041,8 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
         | DeviceInfo::CL_DEVICE_NATIVE_VECTOR_WIDTH_FLOAT
         | DeviceInfo::CL_DEVICE_NATIVE_VECTOR_WIDTH_DOUBLE
         | DeviceInfo::CL_DEVICE_NATIVE_VECTOR_WIDTH_HALF
-        | DeviceInfo::CL_DEVICE_LINKER_AVAILABLE
         | DeviceInfo::CL_DEVICE_PARTITION_MAX_SUB_DEVICES
         | DeviceInfo::CL_DEVICE_REFERENCE_COUNT
-        | DeviceInfo::CL_DEVICE_PREFERRED_INTEROP_USER_SYNC
         | DeviceInfo::CL_DEVICE_IMAGE_PITCH_ALIGNMENT
         | DeviceInfo::CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT
         | DeviceInfo::CL_DEVICE_MAX_READ_WRITE_IMAGE_ARGS
@@ -487,24 +1055,15 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
         | DeviceInfo::CL_DEVICE_PREFERRED_GLOBAL_ATOMIC_ALIGNMENT
         | DeviceInfo::CL_DEVICE_PREFERRED_LOCAL_ATOMIC_ALIGNMENT
         | DeviceInfo::CL_DEVICE_MAX_NUM_SUB_GROUPS
-        | DeviceInfo::CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS
 
         | DeviceInfo::CL_DEVICE_NUMERIC_VERSION // CL_VERSION_3_0
-        | DeviceInfo::CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT // CL_VERSION_3_0
-        | DeviceInfo::CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT // CL_VERSION_3_0
-        | DeviceInfo::CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT // CL_VERSION_3_0
-        | DeviceInfo::CL_DEVICE_PIPE_SUPPORT // CL_VERSION_3_0
 
-        | DeviceInfo::CL_DEVICE_LUID_VALID_KHR // cl_khr_device_uuid
         | DeviceInfo::CL_DEVICE_NODE_MASK_KHR // cl_khr_device_uuid
 
         | DeviceInfo::CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV // cl_nv_device_attribute_query
         | DeviceInfo::CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV // cl_nv_device_attribute_query
         | DeviceInfo::CL_DEVICE_REGISTERS_PER_BLOCK_NV // cl_nv_device_attribute_query
         | DeviceInfo::CL_DEVICE_WARP_SIZE_NV // cl_nv_device_attribute_query
-        | DeviceInfo::CL_DEVICE_GPU_OVERLAP_NV // cl_nv_device_attribute_query
-        | DeviceInfo::CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV // cl_nv_device_attribute_query
-        | DeviceInfo::CL_DEVICE_INTEGRATED_MEMORY_NV // cl_nv_device_attribute_query
        
         | DeviceInfo::CL_DEVICE_PCI_BUS_ID_NV // cl_nv_device_attribute_query, undocumented
         | DeviceInfo::CL_DEVICE_PCI_SLOT_ID_NV // cl_nv_device_attribute_query, undocumented
@@ -518,11 +1077,17 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
         | DeviceInfo::CL_DEVICE_GLOBAL_MEM_CHANNEL_BANK_WIDTH_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_LOCAL_MEM_SIZE_PER_COMPUTE_UNIT_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_LOCAL_MEM_BANKS_AMD // cl_amd_device_attribute_query
-        | DeviceInfo::CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_GFXIP_MAJOR_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_GFXIP_MINOR_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_AVAILABLE_ASYNC_QUEUES_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_PCIE_ID_AMD // cl_amd_device_attribute_query
+
+        | DeviceInfo::CL_DEVICE_IP_VERSION_INTEL // cl_intel_device_attribute_query
+        | DeviceInfo::CL_DEVICE_ID_INTEL // cl_intel_device_attribute_query
+        | DeviceInfo::CL_DEVICE_NUM_SLICES_INTEL // cl_intel_device_attribute_query
+        | DeviceInfo::CL_DEVICE_NUM_SUB_SLICES_PER_SLICE_INTEL // cl_intel_device_attribute_query
+        | DeviceInfo::CL_DEVICE_NUM_EUS_PER_SUB_SLICE_INTEL // cl_intel_device_attribute_query
+        | DeviceInfo::CL_DEVICE_NUM_THREADS_PER_EU_INTEL // cl_intel_device_attribute_query
         => {
             api_info_value!(get_value, cl_uint, clGetDeviceInfo);
             Ok(InfoType::Uint(get_value(device, param_id)?))
@@ -544,6 +1109,8 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
         | DeviceInfo::CL_DEVICE_ATOMIC_FENCE_CAPABILITIES // CL_VERSION_3_0
         | DeviceInfo::CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES // CL_VERSION_3_0
         | DeviceInfo::CL_DEVICE_INTEGER_DOT_PRODUCT_CAPABILITIES_KHR
+        | DeviceInfo::CL_DEVICE_TERMINATE_CAPABILITY_KHR // cl_khr_terminate_context
+        | DeviceInfo::CL_DEVICE_FEATURE_CAPABILITIES_INTEL // cl_intel_device_attribute_query
          => {
             api_info_value!(get_value, cl_ulong, clGetDeviceInfo);
             Ok(InfoType::Ulong(get_value(device, param_id)?))
@@ -566,7 +1133,6 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
         | DeviceInfo::CL_DEVICE_GLOBAL_VARIABLE_PREFERRED_TOTAL_SIZE 
         | DeviceInfo::CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_MULTIPLE // CL_VERSION_3_0
         | DeviceInfo::CL_DEVICE_PROFILING_TIMER_OFFSET_AMD // cl_amd_device_attribute_query
-        | DeviceInfo::CL_DEVICE_GLOBAL_FREE_MEMORY_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_MAX_WORK_GROUP_SIZE_AMD // cl_amd_device_attribute_query
         | DeviceInfo::CL_DEVICE_PREFERRED_CONSTANT_BUFFER_SIZE_AMD // cl_amd_device_attribute_query
@@ -586,15 +1152,24 @@ pub fn get_device_info(device: cl_device_id, param_name: DeviceInfo) -> Result<I
             Ok(InfoType::VecUlong(get_vec(device, param_id, size)?))
         }
 
-        DeviceInfo::CL_DEVICE_MAX_WORK_ITEM_SIZES => {
+        DeviceInfo::CL_DEVICE_MAX_WORK_ITEM_SIZES
+        | DeviceInfo::CL_DEVICE_GLOBAL_FREE_MEMORY_AMD // cl_amd_device_attribute_query
+        | DeviceInfo::CL_DEVICE_SUB_GROUP_SIZES_INTEL // cl_intel_required_subgroup_size
+        => {
             api_info_vector!(get_vec, size_t, clGetDeviceInfo);
             let size = get_size(device, param_id)?;
             Ok(InfoType::VecSize(get_vec(device, param_id, size)?))
         }
 
-        DeviceInfo::CL_DEVICE_PARTITION_PROPERTIES
-        | DeviceInfo::CL_DEVICE_PARTITION_TYPE
-        | DeviceInfo::CL_DEVICE_QUEUE_ON_DEVICE_PROPERTIES => {
+        DeviceInfo::CL_DEVICE_PARTITION_PROPERTIES => {
+            api_info_vector!(get_vec, intptr_t, clGetDeviceInfo);
+            let size = get_size(device, param_id)?;
+            Ok(InfoType::VecPartitionProperty(decode_partition_properties(
+                &get_vec(device, param_id, size)?,
+            )))
+        }
+
+        DeviceInfo::CL_DEVICE_PARTITION_TYPE | DeviceInfo::CL_DEVICE_QUEUE_ON_DEVICE_PROPERTIES => {
             api_info_vector!(get_vec, intptr_t, clGetDeviceInfo);
             let size = get_size(device, param_id)?;
             Ok(InfoType::VecIntPtr(get_vec(device, param_id, size)?))
@@ -639,70 +1214,918 @@ pub fn get_device_pci_bus_info_khr(bytes: &[u8]) -> cl_device_pci_bus_info_khr {
     pci_bus_info
 }
 
-// cl_device_partition_property:
-pub const CL_DEVICE_PARTITION_EQUALLY: cl_device_partition_property = 0x1086;
-pub const CL_DEVICE_PARTITION_BY_COUNTS: cl_device_partition_property = 0x1087;
-pub const CL_DEVICE_PARTITION_BY_COUNTS_LIST_END: cl_device_partition_property = 0x0;
-pub const CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN: cl_device_partition_property = 0x1088;
+/// Get the PCI bus address of a device, see cl_khr_pci_bus_info.  
+/// Calls clGetDeviceInfo to get CL_DEVICE_PCI_BUS_INFO_KHR.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the cl_device_pci_bus_info_khr
+/// or the error code from the OpenCL C API function, e.g. CL_INVALID_VALUE
+/// if the device does not support cl_khr_pci_bus_info.
+pub fn get_device_pci_bus_info(device: cl_device_id) -> Result<cl_device_pci_bus_info_khr, cl_int> {
+    api_info_value!(get_value, cl_device_pci_bus_info_khr, clGetDeviceInfo);
+    get_value(device, CL_DEVICE_PCI_BUS_INFO_KHR)
+}
 
-// helper function for create_sub_devices
-#[inline]
-fn count_sub_devices(
-    in_device: cl_device_id,
-    properties: &[cl_device_partition_property],
-) -> Result<cl_uint, cl_int> {
-    let mut count: cl_uint = 0;
-    let status: cl_int = unsafe {
-        clCreateSubDevices(
-            in_device,
-            properties.as_ptr(),
-            0,
-            ptr::null_mut(),
-            &mut count,
+/// Implement the Display trait, as a PCI domain:bus:device.function address,
+/// e.g. 0000:65:00.0
+impl fmt::Display for cl_device_pci_bus_info_khr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:04x}:{:02x}:{:02x}.{:x}",
+            self.pci_domain, self.pci_bus, self.pci_device, self.pci_function
         )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(count)
     }
 }
 
-/// Create sub-devices by partitioning an OpenCL device.
-/// Calls clCreateSubDevices to get the partitioned sub-devices.
-///
-/// * `in_device` - the cl_device_id of the OpenCL device to partition.
-/// * `properties` - the slice of cl_device_partition_property, see
-/// [Subdevice Partition](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#subdevice-partition-table).
-///
-/// returns a Result containing a vector of available sub-device ids
-/// or the error code from the OpenCL C API function.
+/// The graphics API interoperability extensions supported by a device.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InteropSupport {
+    /// cl_khr_gl_sharing or cl_APPLE_gl_sharing
+    pub gl_sharing: bool,
+    /// cl_khr_gl_event
+    pub gl_event: bool,
+    /// cl_khr_egl_image
+    pub egl_image: bool,
+    /// cl_khr_egl_event
+    pub egl_event: bool,
+    /// cl_khr_d3d10_sharing
+    pub d3d10_sharing: bool,
+    /// cl_khr_d3d11_sharing
+    pub d3d11_sharing: bool,
+    /// cl_khr_dx9_media_sharing
+    pub dx9_media_sharing: bool,
+    /// CL_DEVICE_PREFERRED_INTEROP_USER_SYNC
+    pub preferred_interop_user_sync: bool,
+}
+
+impl InteropSupport {
+    /// Find the interoperability extensions in a space separated list of
+    /// extension names, e.g. from CL_DEVICE_EXTENSIONS.
+    pub fn from_extensions(extensions: &str) -> Self {
+        let mut support = InteropSupport::default();
+        for extension in extensions.split_whitespace() {
+            match extension {
+                "cl_khr_gl_sharing" | "cl_APPLE_gl_sharing" => support.gl_sharing = true,
+                "cl_khr_gl_event" => support.gl_event = true,
+                "cl_khr_egl_image" => support.egl_image = true,
+                "cl_khr_egl_event" => support.egl_event = true,
+                "cl_khr_d3d10_sharing" => support.d3d10_sharing = true,
+                "cl_khr_d3d11_sharing" => support.d3d11_sharing = true,
+                "cl_khr_dx9_media_sharing" => support.dx9_media_sharing = true,
+                _ => {}
+            }
+        }
+        support
+    }
+
+    /// Whether the device supports any graphics API interoperability.
+    pub fn any(&self) -> bool {
+        self.gl_sharing
+            || self.egl_image
+            || self.d3d10_sharing
+            || self.d3d11_sharing
+            || self.dx9_media_sharing
+    }
+}
+
+/// Get the graphics API interoperability supported by a device.  
+/// Calls clGetDeviceInfo to get CL_DEVICE_EXTENSIONS and
+/// CL_DEVICE_PREFERRED_INTEROP_USER_SYNC.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing an InteropSupport summary
+/// or the error code from the OpenCL C API function.
+pub fn device_interop_support(device: cl_device_id) -> Result<InteropSupport, cl_int> {
+    let extensions = get_device_info(device, DeviceInfo::CL_DEVICE_EXTENSIONS)?.to_string();
+    let mut support = InteropSupport::from_extensions(&extensions);
+    support.preferred_interop_user_sync =
+        get_device_info(device, DeviceInfo::CL_DEVICE_PREFERRED_INTEROP_USER_SYNC)?.to_bool();
+    Ok(support)
+}
+
+/// The maximum image dimensions of a device, in pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ImageLimits {
+    /// CL_DEVICE_IMAGE2D_MAX_WIDTH
+    pub image2d_max_width: size_t,
+    /// CL_DEVICE_IMAGE2D_MAX_HEIGHT
+    pub image2d_max_height: size_t,
+    /// CL_DEVICE_IMAGE3D_MAX_WIDTH
+    pub image3d_max_width: size_t,
+    /// CL_DEVICE_IMAGE3D_MAX_HEIGHT
+    pub image3d_max_height: size_t,
+    /// CL_DEVICE_IMAGE3D_MAX_DEPTH
+    pub image3d_max_depth: size_t,
+}
+
+/// Get the maximum image dimensions of a device.  
+/// Calls clGetDeviceInfo for each dimension.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the ImageLimits
+/// or the error code from the OpenCL C API function.
+pub fn device_image_limits(device: cl_device_id) -> Result<ImageLimits, cl_int> {
+    Ok(ImageLimits {
+        image2d_max_width: get_device_info(device, DeviceInfo::CL_DEVICE_IMAGE2D_MAX_WIDTH)?
+            .to_size(),
+        image2d_max_height: get_device_info(device, DeviceInfo::CL_DEVICE_IMAGE2D_MAX_HEIGHT)?
+            .to_size(),
+        image3d_max_width: get_device_info(device, DeviceInfo::CL_DEVICE_IMAGE3D_MAX_WIDTH)?
+            .to_size(),
+        image3d_max_height: get_device_info(device, DeviceInfo::CL_DEVICE_IMAGE3D_MAX_HEIGHT)?
+            .to_size(),
+        image3d_max_depth: get_device_info(device, DeviceInfo::CL_DEVICE_IMAGE3D_MAX_DEPTH)?
+            .to_size(),
+    })
+}
+
+/// The sub-group limits of a device and, optionally, a kernel.  
+/// The limits are zero, false or None for devices before OpenCL 2.1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SubgroupLimits {
+    /// CL_DEVICE_MAX_NUM_SUB_GROUPS, zero if sub-groups are not supported.
+    pub max_num_sub_groups: cl_uint,
+    /// CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS
+    pub independent_forward_progress: bool,
+    /// CL_KERNEL_MAX_NUM_SUB_GROUPS of the kernel, if a kernel was given,
+    /// requires the CL_VERSION_2_1 feature.
+    pub kernel_max_num_sub_groups: Option<size_t>,
+}
+
+/// Get the sub-group limits of a device and, optionally, of a kernel on the device.  
+/// Calls clGetDeviceInfo and, if a kernel is given, clGetKernelSubGroupInfo.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+/// * `kernel` - an optional kernel built for the device.
+///
+/// returns a Result containing the SubgroupLimits
+/// or the error code from the OpenCL C API function.
+pub fn get_device_subgroup_limits(
+    device: cl_device_id,
+    kernel: Option<cl_kernel>,
+) -> Result<SubgroupLimits, cl_int> {
+    // The sub-group queries are not supported before OpenCL 2.1
+    if device_opencl_version(device)? < Version::new(2, 1, 0) {
+        return Ok(SubgroupLimits::default());
+    }
+
+    let max_num_sub_groups =
+        get_device_info(device, DeviceInfo::CL_DEVICE_MAX_NUM_SUB_GROUPS)?.to_uint();
+    let independent_forward_progress = get_device_info(
+        device,
+        DeviceInfo::CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS,
+    )?
+    .to_bool();
+    let kernel_max_num_sub_groups = match kernel {
+        Some(kernel) if 0 < max_num_sub_groups => kernel_max_num_sub_groups(kernel, device)?,
+        _ => None,
+    };
+
+    Ok(SubgroupLimits {
+        max_num_sub_groups,
+        independent_forward_progress,
+        kernel_max_num_sub_groups,
+    })
+}
+
+#[cfg(feature = "CL_VERSION_2_1")]
+fn kernel_max_num_sub_groups(
+    kernel: cl_kernel,
+    device: cl_device_id,
+) -> Result<Option<size_t>, cl_int> {
+    let value = get_kernel_sub_group_info_slice(
+        kernel,
+        device,
+        KernelSubGroupInfo::CL_KERNEL_MAX_NUM_SUB_GROUPS,
+        &[],
+    )?;
+    Ok(Some(value.to_size()))
+}
+
+#[cfg(not(feature = "CL_VERSION_2_1"))]
+fn kernel_max_num_sub_groups(
+    _kernel: cl_kernel,
+    _device: cl_device_id,
+) -> Result<Option<size_t>, cl_int> {
+    Ok(None)
+}
+
+/// The NVIDIA device attributes of a device, see cl_nv_device_attribute_query.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NvDeviceAttributes {
+    /// CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV and CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV
+    pub compute_capability: (cl_uint, cl_uint),
+    /// CL_DEVICE_REGISTERS_PER_BLOCK_NV
+    pub registers_per_block: cl_uint,
+    /// CL_DEVICE_WARP_SIZE_NV
+    pub warp_size: cl_uint,
+    /// CL_DEVICE_GPU_OVERLAP_NV
+    pub gpu_overlap: bool,
+    /// CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV
+    pub kernel_exec_timeout: bool,
+    /// CL_DEVICE_INTEGRATED_MEMORY_NV
+    pub integrated_memory: bool,
+}
+
+/// Get the NVIDIA device attributes of a device.  
+/// Calls clGetDeviceInfo for each cl_nv_device_attribute_query attribute.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the NvDeviceAttributes
+/// or the error code from the OpenCL C API function, e.g. CL_INVALID_VALUE
+/// if the device does not support cl_nv_device_attribute_query.
+pub fn get_nv_device_attributes(device: cl_device_id) -> Result<NvDeviceAttributes, cl_int> {
+    let major = get_device_info(device, DeviceInfo::CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV)?;
+    let minor = get_device_info(device, DeviceInfo::CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV)?;
+    Ok(NvDeviceAttributes {
+        compute_capability: (major.to_uint(), minor.to_uint()),
+        registers_per_block: get_device_info(device, DeviceInfo::CL_DEVICE_REGISTERS_PER_BLOCK_NV)?
+            .to_uint(),
+        warp_size: get_device_info(device, DeviceInfo::CL_DEVICE_WARP_SIZE_NV)?.to_uint(),
+        gpu_overlap: get_device_info(device, DeviceInfo::CL_DEVICE_GPU_OVERLAP_NV)?.to_bool(),
+        kernel_exec_timeout: get_device_info(device, DeviceInfo::CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV)?
+            .to_bool(),
+        integrated_memory: get_device_info(device, DeviceInfo::CL_DEVICE_INTEGRATED_MEMORY_NV)?
+            .to_bool(),
+    })
+}
+
+/// The AMD device attributes of a device, see cl_amd_device_attribute_query.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AmdDeviceAttributes {
+    /// CL_DEVICE_BOARD_NAME_AMD, without surrounding whitespace.
+    pub board_name: String,
+    /// CL_DEVICE_GLOBAL_FREE_MEMORY_AMD: the total free global memory in KiB.
+    pub global_free_memory: size_t,
+    /// CL_DEVICE_GLOBAL_FREE_MEMORY_AMD: the largest free block of global
+    /// memory in KiB, if the device returns it.
+    pub largest_free_block: Option<size_t>,
+    /// CL_DEVICE_SIMD_PER_COMPUTE_UNIT_AMD
+    pub simd_per_compute_unit: cl_uint,
+    /// CL_DEVICE_WAVEFRONT_WIDTH_AMD
+    pub wavefront_width: cl_uint,
+    /// CL_DEVICE_GLOBAL_MEM_CHANNELS_AMD
+    pub global_mem_channels: cl_uint,
+    /// CL_DEVICE_LOCAL_MEM_SIZE_PER_COMPUTE_UNIT_AMD
+    pub local_mem_size_per_compute_unit: cl_uint,
+}
+
+/// Get the AMD device attributes of a device.  
+/// Calls clGetDeviceInfo for each cl_amd_device_attribute_query attribute.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the AmdDeviceAttributes
+/// or the error code from the OpenCL C API function, e.g. CL_INVALID_VALUE
+/// if the device does not support cl_amd_device_attribute_query.
+pub fn get_amd_device_attributes(device: cl_device_id) -> Result<AmdDeviceAttributes, cl_int> {
+    let board_name = get_device_info(device, DeviceInfo::CL_DEVICE_BOARD_NAME_AMD)?.to_string();
+    let free_memory =
+        get_device_info(device, DeviceInfo::CL_DEVICE_GLOBAL_FREE_MEMORY_AMD)?.to_vec_size();
+    let uint_value = |param_name| -> Result<cl_uint, cl_int> {
+        Ok(get_device_info(device, param_name)?.to_uint())
+    };
+    Ok(AmdDeviceAttributes {
+        board_name: board_name.trim().to_string(),
+        global_free_memory: free_memory.first().copied().unwrap_or(0),
+        largest_free_block: free_memory.get(1).copied(),
+        simd_per_compute_unit: uint_value(DeviceInfo::CL_DEVICE_SIMD_PER_COMPUTE_UNIT_AMD)?,
+        wavefront_width: uint_value(DeviceInfo::CL_DEVICE_WAVEFRONT_WIDTH_AMD)?,
+        global_mem_channels: uint_value(DeviceInfo::CL_DEVICE_GLOBAL_MEM_CHANNELS_AMD)?,
+        local_mem_size_per_compute_unit: uint_value(
+            DeviceInfo::CL_DEVICE_LOCAL_MEM_SIZE_PER_COMPUTE_UNIT_AMD,
+        )?,
+    })
+}
+
+// The name of a cl_name_version, up to its nul terminator.
+pub(crate) fn name_version_str(name_version: &cl_name_version) -> Cow<'_, str> {
+    let len = name_version
+        .name
+        .iter()
+        .position(|&c| 0 == c)
+        .unwrap_or(name_version.name.len());
+    String::from_utf8_lossy(&name_version.name[..len])
+}
+
+// Whether a space separated list of extension names contains an extension.
+pub(crate) fn has_extension(extensions: &str, name: &str) -> bool {
+    extensions
+        .split_whitespace()
+        .any(|extension| extension == name)
+}
+
+/// The identifiers of a device from the cl_khr_device_uuid extension, e.g.
+/// to match an OpenCL device to a Vulkan physical device.
+#[cfg(feature = "cl_khr_device_uuid")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeviceUuids {
+    /// CL_DEVICE_UUID_KHR
+    pub device_uuid: [cl_uchar; CL_UUID_SIZE_KHR],
+    /// CL_DRIVER_UUID_KHR
+    pub driver_uuid: [cl_uchar; CL_UUID_SIZE_KHR],
+    /// CL_DEVICE_LUID_KHR, or None if CL_DEVICE_LUID_VALID_KHR is false.
+    pub luid: Option<[cl_uchar; CL_LUID_SIZE_KHR]>,
+    /// CL_DEVICE_NODE_MASK_KHR, only meaningful if `luid` is valid.
+    pub node_mask: cl_uint,
+}
+
+/// Get the cl_khr_device_uuid identifiers of a device.  
+/// Calls clGetDeviceInfo for CL_DEVICE_UUID_KHR, CL_DRIVER_UUID_KHR,
+/// CL_DEVICE_LUID_VALID_KHR, CL_DEVICE_LUID_KHR and CL_DEVICE_NODE_MASK_KHR.  
+/// cl_khr_device_uuid
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the DeviceUuids
+/// or the error code from the OpenCL C API function, e.g. for a device
+/// without the cl_khr_device_uuid extension.
+#[cfg(feature = "cl_khr_device_uuid")]
+pub fn get_device_uuids(device: cl_device_id) -> Result<DeviceUuids, cl_int> {
+    let luid_valid = get_device_info(device, DeviceInfo::CL_DEVICE_LUID_VALID_KHR)?.to_bool();
+    let luid = if luid_valid {
+        Some(get_device_info(device, DeviceInfo::CL_DEVICE_LUID_KHR)?.to_luid())
+    } else {
+        None
+    };
+    Ok(DeviceUuids {
+        device_uuid: get_device_info(device, DeviceInfo::CL_DEVICE_UUID_KHR)?.to_uuid(),
+        driver_uuid: get_device_info(device, DeviceInfo::CL_DRIVER_UUID_KHR)?.to_uuid(),
+        luid,
+        node_mask: get_device_info(device, DeviceInfo::CL_DEVICE_NODE_MASK_KHR)?.to_uint(),
+    })
+}
+
+/// Whether kernels on a device can write to 3D images.  
+/// Calls clGetDeviceInfo for CL_DEVICE_EXTENSIONS to find the
+/// cl_khr_3d_image_writes extension and, if it is not found,
+/// CL_DEVICE_OPENCL_C_FEATURES to find the OpenCL 3.0
+/// __opencl_c_3d_image_writes feature.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing true if the device supports 3D image writes
+/// or the error code from the OpenCL C API function.
+pub fn device_supports_3d_image_writes(device: cl_device_id) -> Result<bool, cl_int> {
+    let extensions = get_device_info(device, DeviceInfo::CL_DEVICE_EXTENSIONS)?.to_string();
+    if has_extension(&extensions, "cl_khr_3d_image_writes") {
+        return Ok(true);
+    }
+
+    // CL_DEVICE_OPENCL_C_FEATURES is not supported before OpenCL 3.0
+    Ok(
+        match get_device_info(device, DeviceInfo::CL_DEVICE_OPENCL_C_FEATURES) {
+            Ok(features) => features
+                .to_vec_name_version()
+                .iter()
+                .any(|feature| "__opencl_c_3d_image_writes" == name_version_str(feature)),
+            Err(_) => false,
+        },
+    )
+}
+
+// Whether a list of intermediate languages contains an IL with a name and a
+// major and minor version.
+#[cfg(feature = "CL_VERSION_3_0")]
+fn has_il(ils: &[cl_name_version], name: &str, version: Version) -> bool {
+    ils.iter().any(|il| {
+        let il_version = Version::from(il.version);
+        name == name_version_str(il)
+            && il_version.major == version.major
+            && il_version.minor == version.minor
+    })
+}
+
+/// Whether a device accepts programs in an intermediate language version,
+/// e.g. SPIR-V 1.2, for create_program_with_il.  
+/// Calls clGetDeviceInfo to get CL_DEVICE_ILS_WITH_VERSION.  
+/// CL_VERSION_3_0
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+/// * `name` - the name of the intermediate language, e.g. "SPIR-V".
+/// * `version` - the version of the intermediate language, the patch
+///   version is ignored.
+///
+/// returns a Result containing true if the device accepts the IL version
+/// or the error code from the OpenCL C API function, e.g. CL_INVALID_VALUE
+/// for a device before OpenCL 3.0.
+#[cfg(feature = "CL_VERSION_3_0")]
+pub fn device_supports_il(
+    device: cl_device_id,
+    name: &str,
+    version: Version,
+) -> Result<bool, cl_int> {
+    let ils = get_device_info(device, DeviceInfo::CL_DEVICE_ILS_WITH_VERSION)?;
+    Ok(has_il(&ils.to_vec_name_version(), name, version))
+}
+
+// Split a semi-colon separated list of built-in kernel names.
+fn parse_built_in_kernel_names(text: &str) -> Vec<String> {
+    text.split(';')
+        .map(|name| name.trim_matches(|c: char| c.is_whitespace() || '\0' == c))
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Get the names of the built-in kernels of a device, e.g. to select the
+/// kernel names for create_program_with_builtin_kernels.  
+/// Calls clGetDeviceInfo to get CL_DEVICE_BUILT_IN_KERNELS.  
+/// CL_VERSION_1_2
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the kernel names, empty if the device has no
+/// built-in kernels
+/// or the error code from the OpenCL C API function.
+pub fn built_in_kernel_names(device: cl_device_id) -> Result<Vec<String>, cl_int> {
+    let names = get_device_info(device, DeviceInfo::CL_DEVICE_BUILT_IN_KERNELS)?.to_string();
+    Ok(parse_built_in_kernel_names(&names))
+}
+
+/// Whether a device shares a unified memory subsystem with the host,
+/// e.g. an integrated GPU or a CPU device.  
+/// Calls clGetDeviceInfo for CL_DEVICE_HOST_UNIFIED_MEMORY, which is
+/// deprecated in OpenCL 2.0 and may not be supported by later devices,
+/// unless the value is in the DeviceInfoCache of the device helpers.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns true if the device has unified memory, false otherwise
+/// or if CL_DEVICE_HOST_UNIFIED_MEMORY is not supported.
+pub fn device_has_unified_memory(device: cl_device_id) -> bool {
+    device_info_cache()
+        .host_unified_memory(device)
+        .unwrap_or(false)
+}
+
+/// A rough estimate of the peak single precision floating point performance
+/// of a device, for ranking devices rather than predicting performance.  
+/// The estimate is: compute units * clock frequency * float vector width * 2,
+/// where 2 counts a fused multiply-add as two operations.  
+/// Caveats: the clock frequency is the maximum, not the sustained, frequency;
+/// the width of a compute unit is only approximated by the native float vector
+/// width, so the estimate is very low for GPUs with many SIMD lanes per
+/// compute unit; memory bandwidth is ignored.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PeakEstimate {
+    /// CL_DEVICE_MAX_COMPUTE_UNITS
+    pub max_compute_units: cl_uint,
+    /// CL_DEVICE_MAX_CLOCK_FREQUENCY in MHz
+    pub max_clock_frequency: cl_uint,
+    /// CL_DEVICE_PREFERRED_VECTOR_WIDTH_FLOAT
+    pub preferred_vector_width_float: cl_uint,
+    /// CL_DEVICE_NATIVE_VECTOR_WIDTH_FLOAT
+    pub native_vector_width_float: cl_uint,
+    /// The estimated peak GFLOPS.
+    pub gflops: f64,
+}
+
+impl PeakEstimate {
+    /// Estimate the peak GFLOPS from the device parameters.  
+    /// The native vector width is used if it is set, otherwise the preferred
+    /// vector width, otherwise a width of 1.
+    pub fn new(
+        max_compute_units: cl_uint,
+        max_clock_frequency: cl_uint,
+        preferred_vector_width_float: cl_uint,
+        native_vector_width_float: cl_uint,
+    ) -> PeakEstimate {
+        let width = if 0 < native_vector_width_float {
+            native_vector_width_float
+        } else if 0 < preferred_vector_width_float {
+            preferred_vector_width_float
+        } else {
+            1
+        };
+        let gflops =
+            max_compute_units as f64 * max_clock_frequency as f64 * width as f64 * 2.0 / 1000.0;
+        PeakEstimate {
+            max_compute_units,
+            max_clock_frequency,
+            preferred_vector_width_float,
+            native_vector_width_float,
+            gflops,
+        }
+    }
+}
+
+/// Estimate the peak single precision GFLOPS of a device, see PeakEstimate.  
+/// Calls clGetDeviceInfo for the parameters of the estimate.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the PeakEstimate
+/// or the error code from the OpenCL C API function.
+pub fn estimate_peak_gflops(device: cl_device_id) -> Result<PeakEstimate, cl_int> {
+    Ok(PeakEstimate::new(
+        get_device_info(device, DeviceInfo::CL_DEVICE_MAX_COMPUTE_UNITS)?.to_uint(),
+        get_device_info(device, DeviceInfo::CL_DEVICE_MAX_CLOCK_FREQUENCY)?.to_uint(),
+        get_device_info(device, DeviceInfo::CL_DEVICE_PREFERRED_VECTOR_WIDTH_FLOAT)?.to_uint(),
+        get_device_info(device, DeviceInfo::CL_DEVICE_NATIVE_VECTOR_WIDTH_FLOAT)?.to_uint(),
+    ))
+}
+
+// Sort devices by decreasing estimate, devices with equal estimates keep
+// their original order so that the ranking is deterministic.
+fn sort_ranked_devices(ranked: &mut [(cl_device_id, f64)]) {
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+}
+
+/// Rank devices by their estimated peak GFLOPS, see PeakEstimate.  
+/// Devices that cannot be queried are given an estimate of zero.
+///
+/// * `devices` - the OpenCL devices to rank.
+///
+/// returns the devices and their estimates in decreasing order of estimate,
+/// devices with equal estimates remain in the order of `devices`.
+pub fn rank_devices(devices: &[cl_device_id]) -> Vec<(cl_device_id, f64)> {
+    let mut ranked: Vec<(cl_device_id, f64)> = devices
+        .iter()
+        .map(|&device| {
+            let gflops = estimate_peak_gflops(device).map_or(0.0, |estimate| estimate.gflops);
+            (device, gflops)
+        })
+        .collect();
+    sort_ranked_devices(&mut ranked);
+    ranked
+}
+
+/// How select_device_by chooses between several devices of the same type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceScore {
+    /// The first device found, on the first platform with a device of the type.
+    First,
+    /// The device with the most CL_DEVICE_MAX_COMPUTE_UNITS.
+    ComputeUnits,
+    /// The device with the most CL_DEVICE_GLOBAL_MEM_SIZE.
+    GlobalMemSize,
+}
+
+impl DeviceScore {
+    // The score of a device, devices that cannot be queried score zero.
+    fn score(self, device: cl_device_id) -> cl_ulong {
+        match self {
+            DeviceScore::First => 0,
+            DeviceScore::ComputeUnits => device_info_cache()
+                .max_compute_units(device)
+                .map_or(0, cl_ulong::from),
+            DeviceScore::GlobalMemSize => device_info_cache().global_mem_size(device).unwrap_or(0),
+        }
+    }
+}
+
+// The first device with the highest score, or None if there are no devices.
+fn best_device<T: Copy>(devices: &[T], score: impl Fn(&T) -> cl_ulong) -> Option<T> {
+    let mut best: Option<(T, cl_ulong)> = None;
+    for device in devices {
+        let device_score = score(device);
+        match best {
+            Some((_, best_score)) if device_score <= best_score => {}
+            _ => best = Some((*device, device_score)),
+        }
+    }
+    best.map(|(device, _)| device)
+}
+
+/// Why select_device or select_device_by could not select a device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SelectDeviceError {
+    /// There is no device of any of the preferred types on any platform.
+    NotFound,
+    /// The CL3_DEVICE environment variable is invalid.
+    Selection(SelectionError),
+    /// The device selected by CL3_DEVICE, of the given cl_device_type, is not
+    /// of any of the preferred types.
+    NotPreferred(cl_device_type),
+    /// The error code from the OpenCL C API function.
+    Api(cl_int),
+}
+
+impl SelectDeviceError {
+    /// The OpenCL error code of the error, for functions that return error codes:
+    /// CL_DEVICE_NOT_FOUND if there is no suitable device
+    /// or CL_INVALID_VALUE if CL3_DEVICE is invalid.
+    pub fn status(&self) -> cl_int {
+        match self {
+            SelectDeviceError::NotFound | SelectDeviceError::NotPreferred(_) => CL_DEVICE_NOT_FOUND,
+            SelectDeviceError::Selection(_) => CL_INVALID_VALUE,
+            SelectDeviceError::Api(status) => *status,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for SelectDeviceError {
+    fn from(status: cl_int) -> Self {
+        SelectDeviceError::Api(status)
+    }
+}
+
+/// Implement the From trait
+impl From<SelectionError> for SelectDeviceError {
+    fn from(error: SelectionError) -> Self {
+        SelectDeviceError::Selection(error)
+    }
+}
+
+/// Implement the Display trait
+impl fmt::Display for SelectDeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SelectDeviceError::NotFound => write!(f, "no device of the preferred types found"),
+            SelectDeviceError::Selection(error) => write!(f, "{}", error),
+            SelectDeviceError::NotPreferred(device_type) => write!(
+                f,
+                "the {} device selected by {} is not of a preferred type",
+                DeviceType(*device_type),
+                CL3_DEVICE
+            ),
+            SelectDeviceError::Api(status) => write!(f, "{}", error_text(*status)),
+        }
+    }
+}
+
+impl std::error::Error for SelectDeviceError {}
+
+/// Select a device of the first preferred type that is available, on any
+/// platform, choosing between several devices of that type by `score`.  
+/// Calls clGetPlatformIDs and clGetDeviceIDs for each platform and, to score
+/// devices, clGetDeviceInfo.  
+/// If the CL3_DEVICE environment variable is set, the device that it selects
+/// is returned instead, provided that it is of one of the preferred types,
+/// see the selection module.
+///
+/// * `preferences` - the device types in order of preference,
+///   e.g. `[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]`.
+/// * `score` - how to choose between several devices of the same type.
+///
+/// returns a Result containing the platform and device
+/// or SelectDeviceError::NotFound if there is no device of any of the
+/// preferred types on any platform
+/// or SelectDeviceError::Selection if CL3_DEVICE is invalid
+/// or SelectDeviceError::NotPreferred if the device selected by CL3_DEVICE
+/// is not of any of the preferred types
+/// or the error code from the OpenCL C API function.
+pub fn select_device_by(
+    preferences: &[cl_device_type],
+    score: DeviceScore,
+) -> Result<(cl_platform_id, cl_device_id), SelectDeviceError> {
+    if let Some((platform, device)) = env_device()? {
+        let device_type = get_device_info(device, DeviceInfo::CL_DEVICE_TYPE)?.to_ulong();
+        let preferred = preferences
+            .iter()
+            .any(|&preference| CL_DEVICE_TYPE_ALL == preference || 0 != preference & device_type);
+        return if preferred {
+            Ok((platform, device))
+        } else {
+            Err(SelectDeviceError::NotPreferred(device_type))
+        };
+    }
+    for &device_type in preferences {
+        let devices = get_all_devices(device_type)?;
+        if let Some(device) = best_device(&devices, |&(_, device)| score.score(device)) {
+            return Ok(device);
+        }
+    }
+    Err(SelectDeviceError::NotFound)
+}
+
+/// Select the first device of the first preferred type that is available,
+/// on any platform, or the device selected by CL3_DEVICE, see `select_device_by`.
+///  # Examples
+/// ```no_run
+/// use cl3::device::{select_device, CL_DEVICE_TYPE_ALL, CL_DEVICE_TYPE_GPU};
+///
+/// // A GPU if there is one, otherwise any device
+/// let (platform_id, device_id) =
+///     select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+/// ```
+/// * `preferences` - the device types in order of preference.
+///
+/// returns a Result containing the platform and device
+/// or a SelectDeviceError, see `select_device_by`.
+pub fn select_device(
+    preferences: &[cl_device_type],
+) -> Result<(cl_platform_id, cl_device_id), SelectDeviceError> {
+    select_device_by(preferences, DeviceScore::First)
+}
+
+// cl_device_partition_property:
+pub const CL_DEVICE_PARTITION_EQUALLY: cl_device_partition_property = 0x1086;
+pub const CL_DEVICE_PARTITION_BY_COUNTS: cl_device_partition_property = 0x1087;
+pub const CL_DEVICE_PARTITION_BY_COUNTS_LIST_END: cl_device_partition_property = 0x0;
+pub const CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN: cl_device_partition_property = 0x1088;
+
+/// The partition types that a device may support, from
+/// CL_DEVICE_PARTITION_PROPERTIES.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DevicePartitionProperty {
+    Equally,
+    ByCounts,
+    ByAffinityDomain,
+    Unknown(cl_device_partition_property),
+}
+
+impl From<cl_device_partition_property> for DevicePartitionProperty {
+    fn from(value: cl_device_partition_property) -> Self {
+        match value {
+            CL_DEVICE_PARTITION_EQUALLY => DevicePartitionProperty::Equally,
+            CL_DEVICE_PARTITION_BY_COUNTS => DevicePartitionProperty::ByCounts,
+            CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN => DevicePartitionProperty::ByAffinityDomain,
+            _ => DevicePartitionProperty::Unknown(value),
+        }
+    }
+}
+
+impl From<DevicePartitionProperty> for cl_device_partition_property {
+    fn from(value: DevicePartitionProperty) -> Self {
+        match value {
+            DevicePartitionProperty::Equally => CL_DEVICE_PARTITION_EQUALLY,
+            DevicePartitionProperty::ByCounts => CL_DEVICE_PARTITION_BY_COUNTS,
+            DevicePartitionProperty::ByAffinityDomain => CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN,
+            DevicePartitionProperty::Unknown(value) => value,
+        }
+    }
+}
+
+/// Decode a CL_DEVICE_PARTITION_PROPERTIES list.  
+/// The list ends at the first zero, a device that cannot be partitioned
+/// returns a list containing only zero, i.e. an empty Vec.
+///
+/// * `properties` - the raw cl_device_partition_property values.
+///
+/// returns the partition types in the list.
+pub fn decode_partition_properties(
+    properties: &[cl_device_partition_property],
+) -> Vec<DevicePartitionProperty> {
+    properties
+        .iter()
+        .take_while(|&&value| 0 != value)
+        .map(|&value| DevicePartitionProperty::from(value))
+        .collect()
+}
+
+device_bitflags! {
+    /// The cache hierarchy levels that a device may be partitioned by, a typed
+    /// CL_DEVICE_PARTITION_AFFINITY_DOMAIN value.
+    #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct AffinityDomain(cl_device_affinity_domain): From {
+        NUMA = CL_DEVICE_AFFINITY_DOMAIN_NUMA,
+        L4_CACHE = CL_DEVICE_AFFINITY_DOMAIN_L4_CACHE,
+        L3_CACHE = CL_DEVICE_AFFINITY_DOMAIN_L3_CACHE,
+        L2_CACHE = CL_DEVICE_AFFINITY_DOMAIN_L2_CACHE,
+        L1_CACHE = CL_DEVICE_AFFINITY_DOMAIN_L1_CACHE,
+        NEXT_PARTITIONABLE = CL_DEVICE_AFFINITY_DOMAIN_NEXT_PARTITIONABLE,
+    }
+}
+
+/// Get the cache hierarchy levels that a device may be partitioned by.  
+/// Calls clGetDeviceInfo for CL_DEVICE_PARTITION_AFFINITY_DOMAIN.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the AffinityDomain, empty if the device
+/// cannot be partitioned by affinity domain
+/// or the error code from the OpenCL C API function.
+pub fn device_partition_affinity_domain(device: cl_device_id) -> Result<AffinityDomain, cl_int> {
+    let value = get_device_info(device, DeviceInfo::CL_DEVICE_PARTITION_AFFINITY_DOMAIN)?
+        .to_vec_ulong()
+        .first()
+        .copied()
+        .unwrap_or(0);
+    Ok(AffinityDomain::from(value))
+}
+
+/// How a sub-device was partitioned, from CL_DEVICE_PARTITION_TYPE.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DevicePartitionType {
+    /// The device is not a sub-device.
+    None,
+    /// Partitioned equally, with the number of compute units of each sub-device.
+    Equally(cl_uint),
+    /// Partitioned by counts, with the number of compute units of each sub-device.
+    ByCounts(Vec<cl_uint>),
+    /// Partitioned by the affinity domain.
+    ByAffinityDomain(AffinityDomain),
+    /// An unknown partition type, with the raw property list.
+    Unknown(Vec<cl_device_partition_property>),
+}
+
+/// Decode a CL_DEVICE_PARTITION_TYPE property list.  
+/// The list is the one passed to clCreateSubDevices, a device that is not a
+/// sub-device returns an empty list or a list containing only zero.
+///
+/// * `properties` - the raw cl_device_partition_property values.
+///
+/// returns the partition type and its value.
+pub fn decode_partition_type(properties: &[cl_device_partition_property]) -> DevicePartitionType {
+    match properties {
+        [] | [0, ..] => DevicePartitionType::None,
+        [CL_DEVICE_PARTITION_EQUALLY, count, ..] => DevicePartitionType::Equally(*count as cl_uint),
+        [CL_DEVICE_PARTITION_BY_COUNTS, counts @ ..] => DevicePartitionType::ByCounts(
+            counts
+                .iter()
+                .take_while(|&&count| CL_DEVICE_PARTITION_BY_COUNTS_LIST_END != count)
+                .map(|&count| count as cl_uint)
+                .collect(),
+        ),
+        [CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN, domain, ..] => {
+            DevicePartitionType::ByAffinityDomain(AffinityDomain::from(
+                *domain as cl_device_affinity_domain,
+            ))
+        }
+        _ => DevicePartitionType::Unknown(properties.to_vec()),
+    }
+}
+
+/// Get how a sub-device was partitioned.  
+/// Calls clGetDeviceInfo for CL_DEVICE_PARTITION_TYPE.
+///
+/// * `device` - the cl_device_id of the OpenCL device.
+///
+/// returns a Result containing the DevicePartitionType
+/// or the error code from the OpenCL C API function.
+pub fn device_partition_type(device: cl_device_id) -> Result<DevicePartitionType, cl_int> {
+    let value = get_device_info(device, DeviceInfo::CL_DEVICE_PARTITION_TYPE)?.to_vec_intptr();
+    Ok(decode_partition_type(&value))
+}
+
+/// The properties to partition a device into as many sub-devices as possible,
+/// each with `compute_units` compute units, for create_sub_devices.
+pub fn partition_equally(compute_units: cl_uint) -> Vec<cl_device_partition_property> {
+    vec![
+        CL_DEVICE_PARTITION_EQUALLY,
+        compute_units as cl_device_partition_property,
+        0,
+    ]
+}
+
+/// The properties to partition a device into a sub-device for each count,
+/// with that number of compute units, for create_sub_devices.
+pub fn partition_by_counts(counts: &[cl_uint]) -> Vec<cl_device_partition_property> {
+    let mut properties: Vec<cl_device_partition_property> = Vec::with_capacity(counts.len() + 3);
+    properties.push(CL_DEVICE_PARTITION_BY_COUNTS);
+    properties.extend(
+        counts
+            .iter()
+            .map(|&count| count as cl_device_partition_property),
+    );
+    properties.push(CL_DEVICE_PARTITION_BY_COUNTS_LIST_END);
+    properties.push(0);
+    properties
+}
+
+/// The properties to partition a device into sub-devices that share the
+/// given level of the cache hierarchy, an AffinityDomain or a
+/// cl_device_affinity_domain, for create_sub_devices.
+pub fn partition_by_affinity_domain<T: Into<cl_device_affinity_domain>>(
+    affinity_domain: T,
+) -> Vec<cl_device_partition_property> {
+    let affinity_domain: cl_device_affinity_domain = affinity_domain.into();
+    vec![
+        CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN,
+        affinity_domain as cl_device_partition_property,
+        0,
+    ]
+}
+
+/// Create sub-devices by partitioning an OpenCL device.
+/// Calls clCreateSubDevices to get the partitioned sub-devices.
+///
+/// * `in_device` - the cl_device_id of the OpenCL device to partition.
+/// * `properties` - the zero terminated slice of cl_device_partition_property,
+///   e.g. from partition_equally, partition_by_counts or partition_by_affinity_domain, see
+///   [Subdevice Partition](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#subdevice-partition-table).
+///
+/// returns a Result containing a vector of sub-device ids, which must be
+/// released with release_device
+/// or CL_INVALID_VALUE if `properties` is not zero terminated
+/// or the error code from the OpenCL C API function.
 #[inline]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn create_sub_devices(
     in_device: cl_device_id,
     properties: &[cl_device_partition_property],
 ) -> Result<Vec<cl_device_id>, cl_int> {
-    // get the number of partitions
-    let num_devices: cl_uint = count_sub_devices(in_device, properties)?;
+    if Some(&0) != properties.last() {
+        return Err(CL_INVALID_VALUE);
+    }
 
-    // partition in_device
-    let mut ids: Vec<cl_device_id> = Vec::with_capacity(num_devices as usize);
-    let status: cl_int = unsafe {
-        ids.set_len(num_devices as usize);
+    create_object_list(|num_entries, devices, num_devices| unsafe {
         clCreateSubDevices(
             in_device,
             properties.as_ptr(),
-            num_devices * mem::size_of::<cl_device_id>() as cl_uint,
-            ids.as_mut_ptr(),
-            ptr::null_mut(),
+            num_entries,
+            devices,
+            num_devices,
         )
-    };
-
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(ids)
-    }
+    })
 }
 
 /// Retain an OpenCL device.  
@@ -739,7 +2162,6 @@ pub fn release_device(device: cl_device_id) -> Result<(), cl_int> {
     }
 }
 
-// #ifdef CL_VERSION_2_1
 /// Replace the default command queue on an OpenCL device.  
 /// Calls clSetDefaultDeviceCommandQueue to replace the default command queue  
 /// CL_VERSION_2_1
@@ -747,7 +2169,7 @@ pub fn release_device(device: cl_device_id) -> Result<(), cl_int> {
 /// * `context` - the OpenCL context used to create command_queue.
 /// * `device` - a valid OpenCL device associated with context.
 /// * `command_queue` - a command queue object which replaces the default
-/// device command queue.
+///   device command queue.
 ///
 /// returns an empty Result or the error code from the OpenCL C API function.
 #[cfg(feature = "CL_VERSION_2_1")]
@@ -763,56 +2185,337 @@ pub fn set_default_device_command_queue(
     } else {
         Ok(())
     }
-}
+}
+
+/// Query device and host timestamps.  
+/// Calls clGetDeviceAndHostTimer  
+/// CL_VERSION_2_1
+///
+/// * `device` - a valid OpenCL device.
+///
+/// returns a Result containing a tuple of device_timestamp and host_timestamp
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_1")]
+#[inline]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn get_device_and_host_timer(
+    device: cl_device_id,
+) -> Result<(cl_ulong, cl_ulong), cl_int> {
+    let mut device_timestamp: cl_ulong = 0;
+    let mut host_timestamp: cl_ulong = 0;
+    let status: cl_int =
+        unsafe { clGetDeviceAndHostTimer(device, &mut device_timestamp, &mut host_timestamp) };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok((device_timestamp, host_timestamp))
+    }
+}
+
+/// The offset of the device clock from the host clock, in nanoseconds.  
+/// Add the offset to a host timestamp to get the equivalent device timestamp,
+/// e.g. to compare host times with OpenCL profiling timestamps.
+///
+/// * `timestamps` - device and host timestamps sampled together, from
+///   get_device_and_host_timer.
+///
+/// returns the device timestamp minus the host timestamp.
+#[inline]
+pub fn device_host_timer_offset(timestamps: (cl_ulong, cl_ulong)) -> i64 {
+    let (device_timestamp, host_timestamp) = timestamps;
+    device_timestamp.wrapping_sub(host_timestamp) as i64
+}
+
+/// The current value of the host clock as seen by device.  
+/// Calls clGetHostTimer  
+/// CL_VERSION_2_1
+///
+/// * `device` - a valid OpenCL device.
+///
+/// returns a Result containing host_timestamp
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_1")]
+#[inline]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn get_host_timer(device: cl_device_id) -> Result<cl_ulong, cl_int> {
+    let mut host_timestamp: cl_ulong = 0;
+    let status: cl_int = unsafe { clGetHostTimer(device, &mut host_timestamp) };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(host_timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::get_platform_ids;
+    use crate::error_codes::{ClError};
+    use crate::types::CL_NAME_VERSION_MAX_NAME_SIZE;
+    use std::env;
+
+    #[test]
+    fn test_interop_support_from_extensions() {
+        let support = InteropSupport::from_extensions("");
+        assert_eq!(InteropSupport::default(), support);
+        assert!(!support.any());
+
+        let support = InteropSupport::from_extensions(
+            "cl_khr_fp64 cl_khr_gl_sharing cl_khr_gl_event cl_khr_d3d11_sharing ",
+        );
+        assert!(support.gl_sharing);
+        assert!(support.gl_event);
+        assert!(support.d3d11_sharing);
+        assert!(!support.egl_image);
+        assert!(!support.dx9_media_sharing);
+        assert!(support.any());
+
+        // Extension names must match exactly
+        let support = InteropSupport::from_extensions("cl_khr_gl_sharing_ext cl_khr_egl_event");
+        assert!(!support.gl_sharing);
+        assert!(support.egl_event);
+        assert!(!support.any());
+    }
+
+    #[test]
+    fn test_parse_built_in_kernel_names() {
+        assert!(parse_built_in_kernel_names("").is_empty());
+        assert!(parse_built_in_kernel_names(" ;\0").is_empty());
+        assert_eq!(
+            vec!["block_motion_estimate_intel"],
+            parse_built_in_kernel_names("block_motion_estimate_intel")
+        );
+        assert_eq!(
+            vec!["block_motion_estimate_intel", "block_advanced_motion_estimate_check_intel"],
+            parse_built_in_kernel_names(
+                "block_motion_estimate_intel; block_advanced_motion_estimate_check_intel;\0"
+            )
+        );
+        assert_eq!(
+            vec!["a", "b"],
+            parse_built_in_kernel_names(" a ;;\n b ; ")
+        );
+    }
+
+    #[test]
+    fn test_name_version_str() {
+        let mut feature = cl_name_version {
+            version: make_version(3, 0, 0),
+            name: [0; CL_NAME_VERSION_MAX_NAME_SIZE],
+        };
+        assert_eq!("", name_version_str(&feature));
+        feature.name[..6].copy_from_slice(b"SPIR-V");
+        assert_eq!("SPIR-V", name_version_str(&feature));
+        feature.name = [b'a'; CL_NAME_VERSION_MAX_NAME_SIZE];
+        let name = name_version_str(&feature);
+        assert_eq!(CL_NAME_VERSION_MAX_NAME_SIZE, name.len());
+    }
+
+    #[test]
+    #[cfg(feature = "CL_VERSION_3_0")]
+    fn test_has_il() {
+        let mut spirv = cl_name_version {
+            version: make_version(1, 2, 0),
+            name: [0; CL_NAME_VERSION_MAX_NAME_SIZE],
+        };
+        spirv.name[..6].copy_from_slice(b"SPIR-V");
+        let ils = [spirv];
+        assert!(has_il(&ils, "SPIR-V", Version::new(1, 2, 0)));
+        assert!(has_il(&ils, "SPIR-V", Version::new(1, 2, 1)));
+        assert!(!has_il(&ils, "SPIR-V", Version::new(1, 3, 0)));
+        assert!(!has_il(&ils, "SPIR", Version::new(1, 2, 0)));
+        assert!(!has_il(&[], "SPIR-V", Version::new(1, 2, 0)));
+    }
+
+    #[test]
+    fn test_decode_partition_properties() {
+        // A device that cannot be partitioned
+        assert!(decode_partition_properties(&[0]).is_empty());
+        assert!(decode_partition_properties(&[]).is_empty());
+
+        let raw = [
+            CL_DEVICE_PARTITION_EQUALLY,
+            CL_DEVICE_PARTITION_BY_COUNTS,
+            CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN,
+            0x4242,
+            0,
+            CL_DEVICE_PARTITION_EQUALLY,
+        ];
+        let properties = decode_partition_properties(&raw);
+        assert_eq!(
+            vec![
+                DevicePartitionProperty::Equally,
+                DevicePartitionProperty::ByCounts,
+                DevicePartitionProperty::ByAffinityDomain,
+                DevicePartitionProperty::Unknown(0x4242),
+            ],
+            properties
+        );
+
+        let raw_again: Vec<cl_device_partition_property> =
+            properties.into_iter().map(|p| p.into()).collect();
+        assert_eq!(&raw[..4], &raw_again[..]);
+    }
+
+    #[test]
+    fn test_decode_partition_type() {
+        // A device that is not a sub-device
+        assert_eq!(DevicePartitionType::None, decode_partition_type(&[]));
+        assert_eq!(DevicePartitionType::None, decode_partition_type(&[0]));
+
+        assert_eq!(
+            DevicePartitionType::Equally(4),
+            decode_partition_type(&partition_equally(4))
+        );
+        assert_eq!(
+            DevicePartitionType::ByCounts(vec![3, 1]),
+            decode_partition_type(&partition_by_counts(&[3, 1]))
+        );
+        assert_eq!(
+            DevicePartitionType::ByAffinityDomain(AffinityDomain::L2_CACHE),
+            decode_partition_type(&partition_by_affinity_domain(AffinityDomain::L2_CACHE))
+        );
+        assert_eq!(
+            DevicePartitionType::Unknown(vec![0x4242, 1, 0]),
+            decode_partition_type(&[0x4242, 1, 0])
+        );
+        // A property without its value
+        assert_eq!(
+            DevicePartitionType::Unknown(vec![CL_DEVICE_PARTITION_EQUALLY]),
+            decode_partition_type(&[CL_DEVICE_PARTITION_EQUALLY])
+        );
+    }
+
+    #[test]
+    fn test_affinity_domain() {
+        let domain = AffinityDomain::from(
+            CL_DEVICE_AFFINITY_DOMAIN_L1_CACHE | CL_DEVICE_AFFINITY_DOMAIN_NEXT_PARTITIONABLE,
+        );
+        assert!(domain.contains(AffinityDomain::L1_CACHE));
+        assert!(!domain.contains(AffinityDomain::NUMA));
+        assert_eq!("L1_CACHE | NEXT_PARTITIONABLE", domain.to_string());
+        assert_eq!("NONE", AffinityDomain::default().to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "cl_khr_device_uuid")]
+    fn test_get_device_uuids() {
+        let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+        for &(_, device_id) in devices.iter() {
+            let extensions = get_device_info(device_id, DeviceInfo::CL_DEVICE_EXTENSIONS)
+                .unwrap()
+                .to_string();
+            if !has_extension(&extensions, "cl_khr_device_uuid") {
+                continue;
+            }
+
+            let uuids = get_device_uuids(device_id).unwrap();
+            let device_uuid = InfoType::Uuid(uuids.device_uuid).to_string();
+            println!("CL_DEVICE_UUID_KHR: {}", device_uuid);
+            println!("CL_DRIVER_UUID_KHR: {}", InfoType::Uuid(uuids.driver_uuid));
+            if let Some(luid) = uuids.luid {
+                println!("CL_DEVICE_LUID_KHR: {}", InfoType::Luid(luid));
+                println!("CL_DEVICE_NODE_MASK_KHR: {:#x}", uuids.node_mask);
+            }
+
+            // The canonical 8-4-4-4-12 hex form
+            let groups: Vec<usize> = device_uuid.split('-').map(str::len).collect();
+            assert_eq!(vec![8, 4, 4, 4, 12], groups);
+        }
+    }
+
+    #[test]
+    fn test_pci_bus_info_display() {
+        let pci_bus_info = cl_device_pci_bus_info_khr {
+            pci_domain: 0,
+            pci_bus: 0x65,
+            pci_device: 0,
+            pci_function: 0,
+        };
+        assert_eq!("0000:65:00.0", pci_bus_info.to_string());
+
+        let pci_bus_info = cl_device_pci_bus_info_khr {
+            pci_domain: 1,
+            pci_bus: 0xa,
+            pci_device: 0x1f,
+            pci_function: 7,
+        };
+        assert_eq!("0001:0a:1f.7", pci_bus_info.to_string());
+    }
 
-/// Query device and host timestamps.  
-/// Calls clGetDeviceAndHostTimer  
-/// CL_VERSION_2_1
-///
-/// * `device` - a valid OpenCL device.
-///
-/// returns a Result containing device_timestamp and host_timestamp in a 2D array
-/// or the error code from the OpenCL C API function.
-#[cfg(feature = "CL_VERSION_2_1")]
-#[inline]
-pub fn get_device_and_host_timer(device: cl_device_id) -> Result<[cl_ulong; 2], cl_int> {
-    let mut device_timestamp: cl_ulong = 0;
-    let mut host_timestamp: cl_ulong = 0;
-    let status: cl_int =
-        unsafe { clGetDeviceAndHostTimer(device, &mut device_timestamp, &mut host_timestamp) };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok([device_timestamp, host_timestamp])
+    #[test]
+    fn test_has_extension() {
+        let extensions = "cl_khr_fp64 cl_khr_3d_image_writes_ext  cl_khr_fp16\n";
+        assert!(has_extension(extensions, "cl_khr_fp64"));
+        assert!(has_extension(extensions, "cl_khr_fp16"));
+        assert!(!has_extension(extensions, "cl_khr_3d_image_writes"));
+        assert!(!has_extension("", "cl_khr_fp64"));
     }
-}
 
-/// The current value of the host clock as seen by device.  
-/// Calls clGetHostTimer  
-/// CL_VERSION_2_1
-///
-/// * `device` - a valid OpenCL device.
-///
-/// returns a Result containing host_timestamp
-/// or the error code from the OpenCL C API function.
-#[cfg(feature = "CL_VERSION_2_1")]
-#[inline]
-pub fn get_host_timer(device: cl_device_id) -> Result<cl_ulong, cl_int> {
-    let mut host_timestamp: cl_ulong = 0;
-    let status: cl_int = unsafe { clGetHostTimer(device, &mut host_timestamp) };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(host_timestamp)
+    #[test]
+    fn test_device_image_limits() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+
+        let limits = device_image_limits(device_id).unwrap();
+        println!("ImageLimits: {:?}", limits);
+
+        let value = device_supports_3d_image_writes(device_id).unwrap();
+        println!("device_supports_3d_image_writes: {}", value);
     }
-}
-// #endif
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::platform::get_platform_ids;
-    use crate::error_codes::{ClError};
+    #[test]
+    fn test_peak_estimate() {
+        // 20 compute units at 1500 MHz, native float width 4
+        let estimate = PeakEstimate::new(20, 1500, 8, 4);
+        assert_eq!(240.0, estimate.gflops);
+        assert_eq!(8, estimate.preferred_vector_width_float);
+
+        // Falls back to the preferred width, then to a width of 1
+        assert_eq!(480.0, PeakEstimate::new(20, 1500, 8, 0).gflops);
+        assert_eq!(60.0, PeakEstimate::new(20, 1500, 0, 0).gflops);
+        assert_eq!(0.0, PeakEstimate::new(0, 1500, 4, 4).gflops);
+    }
+
+    #[test]
+    fn test_sort_ranked_devices() {
+        let a = 0x1 as cl_device_id;
+        let b = 0x2 as cl_device_id;
+        let c = 0x3 as cl_device_id;
+        let d = 0x4 as cl_device_id;
+        let mut ranked = vec![(a, 10.0), (b, 240.0), (c, 10.0), (d, 0.0)];
+        sort_ranked_devices(&mut ranked);
+        assert_eq!(vec![(b, 240.0), (a, 10.0), (c, 10.0), (d, 0.0)], ranked);
+    }
+
+    #[test]
+    fn test_best_device() {
+        let scores = [(1, 4), (2, 8), (3, 8), (4, 2)];
+        assert_eq!(Some((2, 8)), best_device(&scores, |&(_, score)| score));
+        assert_eq!(Some((1, 4)), best_device(&scores, |_| 0));
+        let none: [(u32, cl_ulong); 0] = [];
+        assert_eq!(None, best_device(&none, |&(_, score)| score));
+    }
+
+    #[test]
+    fn test_select_device() {
+        // The environment of the tests must not select the device
+        env::remove_var(CL3_DEVICE);
+
+        // A GPU if there is one, otherwise any device
+        let (platform_id, device_id) =
+            select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        assert_eq!(platform_id, get_device_platform(device_id).unwrap());
+
+        let (_, device_id) =
+            select_device_by(&[CL_DEVICE_TYPE_ALL], DeviceScore::GlobalMemSize).unwrap();
+        let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_GLOBAL_MEM_SIZE).unwrap();
+        println!("CL_DEVICE_GLOBAL_MEM_SIZE: {}", value.to_ulong());
+
+        assert_eq!(Err(SelectDeviceError::NotFound), select_device(&[]));
+    }
 
     #[test]
     fn test_get_platform_devices() {
@@ -967,9 +2670,9 @@ mod tests {
         assert!(0 < value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_IMAGE_SUPPORT).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_IMAGE_SUPPORT: {}", value);
-        assert!(0 < value);
+        assert!(value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_MAX_PARAMETER_SIZE).unwrap();
         let value = value.to_size();
@@ -1037,7 +2740,7 @@ mod tests {
         assert!(0 < value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_ERROR_CORRECTION_SUPPORT).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_ERROR_CORRECTION_SUPPORT: {}", value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_PROFILING_TIMER_RESOLUTION).unwrap();
@@ -1046,19 +2749,19 @@ mod tests {
         assert!(0 < value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_ENDIAN_LITTLE).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_ENDIAN_LITTLE: {}", value);
-        assert!(0 < value);
+        assert!(value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_AVAILABLE).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_AVAILABLE: {}", value);
-        assert!(0 < value);
+        assert!(value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_COMPILER_AVAILABLE).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_COMPILER_AVAILABLE: {}", value);
-        assert!(0 < value);
+        assert!(value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_EXECUTION_CAPABILITIES).unwrap();
         let value = value.to_ulong();
@@ -1164,9 +2867,9 @@ mod tests {
         assert!(!value.is_empty());
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_LINKER_AVAILABLE).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_LINKER_AVAILABLE: {}", value);
-        assert!(0 < value);
+        assert!(value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_BUILT_IN_KERNELS).unwrap();
         let value = value.to_string();
@@ -1192,10 +2895,10 @@ mod tests {
         println!("CL_DEVICE_PARTITION_MAX_SUB_DEVICES: {}", value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_PARTITION_PROPERTIES).unwrap();
-        let value = value.to_vec_intptr();
+        let value = value.to_vec_partition_property();
         println!("CL_DEVICE_PARTITION_PROPERTIES: {}", value.len());
         println!("CL_DEVICE_PARTITION_PROPERTIES: {:?}", value);
-        assert!(0 < value.len());
+        // Empty if the device cannot be partitioned
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_PARTITION_AFFINITY_DOMAIN).unwrap();
         let value = value.to_vec_ulong();
@@ -1203,18 +2906,26 @@ mod tests {
         println!("CL_DEVICE_PARTITION_AFFINITY_DOMAIN: {:?}", value);
         assert!(0 < value.len());
 
+        let value = device_partition_affinity_domain(device_id).unwrap();
+        println!("CL_DEVICE_PARTITION_AFFINITY_DOMAIN: {}", value);
+
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_PARTITION_TYPE).unwrap();
         let value = value.to_vec_intptr();
         println!("CL_DEVICE_PARTITION_TYPE: {}", value.len());
         println!("CL_DEVICE_PARTITION_TYPE: {:?}", value);
 
+        let value = device_partition_type(device_id).unwrap();
+        println!("CL_DEVICE_PARTITION_TYPE: {:?}", value);
+        // The root device is not a sub-device
+        assert_eq!(DevicePartitionType::None, value);
+
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_REFERENCE_COUNT).unwrap();
         let value = value.to_uint();
         println!("CL_DEVICE_REFERENCE_COUNT: {}", value);
         assert!(0 < value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_PREFERRED_INTEROP_USER_SYNC).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_PREFERRED_INTEROP_USER_SYNC: {}", value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_PRINTF_BUFFER_SIZE).unwrap();
@@ -1225,8 +2936,12 @@ mod tests {
         // cl_khr_device_uuid extension
         match get_device_info(device_id, DeviceInfo::CL_DEVICE_UUID_KHR) {
             Ok(value) => {
+                println!("CL_DEVICE_UUID_KHR: {}", value);
+                #[cfg(feature = "cl_khr_device_uuid")]
+                let value = value.to_uuid().to_vec();
+                #[cfg(not(feature = "cl_khr_device_uuid"))]
                 let value = value.to_vec_uchar();
-                println!("CL_DEVICE_UUID_KHR: {:?}", value);
+                assert_eq!(CL_UUID_SIZE_KHR, value.len());
             }
             Err(e) => println!("OpenCL error, CL_DEVICE_UUID_KHR: {}", ClError(e))
         };
@@ -1234,8 +2949,12 @@ mod tests {
         // cl_khr_device_uuid extension
         match get_device_info(device_id, DeviceInfo::CL_DRIVER_UUID_KHR) {
             Ok(value) => {
+                println!("CL_DRIVER_UUID_KHR: {}", value);
+                #[cfg(feature = "cl_khr_device_uuid")]
+                let value = value.to_uuid().to_vec();
+                #[cfg(not(feature = "cl_khr_device_uuid"))]
                 let value = value.to_vec_uchar();
-                println!("CL_DRIVER_UUID_KHR: {:?}", value);
+                assert_eq!(CL_UUID_SIZE_KHR, value.len());
             }
             Err(e) => println!("OpenCL error, CL_DRIVER_UUID_KHR: {}", ClError(e))
         };
@@ -1243,7 +2962,7 @@ mod tests {
         // cl_khr_device_uuid extension
         match get_device_info(device_id, DeviceInfo::CL_DEVICE_LUID_VALID_KHR) {
             Ok(value) => {
-                let value = value.to_uint();
+                let value = value.to_bool();
                 println!("CL_DEVICE_LUID_VALID_KHR: {:?}", value);
             }
             Err(e) => println!("OpenCL error, CL_DEVICE_LUID_VALID_KHR: {}", ClError(e))
@@ -1252,8 +2971,12 @@ mod tests {
         // cl_khr_device_uuid extension
         match get_device_info(device_id, DeviceInfo::CL_DEVICE_LUID_KHR) {
             Ok(value) => {
+                println!("CL_DEVICE_LUID_KHR: {}", value);
+                #[cfg(feature = "cl_khr_device_uuid")]
+                let value = value.to_luid().to_vec();
+                #[cfg(not(feature = "cl_khr_device_uuid"))]
                 let value = value.to_vec_uchar();
-                println!("CL_DEVICE_LUID_KHR: {:?}", value);
+                assert_eq!(CL_LUID_SIZE_KHR, value.len());
             }
             Err(e) => println!("OpenCL error, CL_DEVICE_LUID_KHR: {}", ClError(e))
         };
@@ -1306,7 +3029,7 @@ mod tests {
         // Nvidia specific extension
         match get_device_info(device_id, DeviceInfo::CL_DEVICE_GPU_OVERLAP_NV) {
             Ok(value) => {
-                let value = value.to_uint();
+                let value = value.to_bool();
                 println!("CL_DEVICE_GPU_OVERLAP_NV: {}", value)
             }
             Err(e) => println!("OpenCL error, CL_DEVICE_GPU_OVERLAP_NV: {}", ClError(e))
@@ -1315,7 +3038,7 @@ mod tests {
         // Nvidia specific extension
         match get_device_info(device_id, DeviceInfo::CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV) {
             Ok(value) => {
-                let value = value.to_uint();
+                let value = value.to_bool();
                 println!("CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV: {}", value)
             }
             Err(e) => println!("OpenCL error, CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV: {}", ClError(e))
@@ -1324,7 +3047,7 @@ mod tests {
         // Nvidia specific extension
         match get_device_info(device_id, DeviceInfo::CL_DEVICE_INTEGRATED_MEMORY_NV) {
             Ok(value) => {
-                let value = value.to_uint();
+                let value = value.to_bool();
                 println!("CL_DEVICE_INTEGRATED_MEMORY_NV: {}", value)
             }
             Err(e) => println!("OpenCL error, CL_DEVICE_INTEGRATED_MEMORY_NV: {}", ClError(e))
@@ -1379,8 +3102,8 @@ mod tests {
 
         match get_device_info(device_id, DeviceInfo::CL_DEVICE_GLOBAL_FREE_MEMORY_AMD) {
             Ok(value) => {
-                let value = value.to_size();
-                println!("CL_DEVICE_GLOBAL_FREE_MEMORY_AMD: {}", value)
+                let value = value.to_vec_size();
+                println!("CL_DEVICE_GLOBAL_FREE_MEMORY_AMD: {:?}", value)
             }
             Err(e) => println!("OpenCL error, CL_DEVICE_GLOBAL_FREE_MEMORY_AMD: {}", ClError(e))
         };
@@ -1459,7 +3182,7 @@ mod tests {
         
         match get_device_info(device_id, DeviceInfo::CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD) {
             Ok(value) => {
-                let value = value.to_uint();
+                let value = value.to_bool();
                 println!("CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD: {}", value)
             }
             Err(e) => println!("OpenCL error, CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD: {}", ClError(e))
@@ -1531,6 +3254,8 @@ mod tests {
                 println!("CL_DEVICE_PCI_BUS_INFO_KHR pci_bus: {}", pci_bus_info.pci_bus);
                 println!("CL_DEVICE_PCI_BUS_INFO_KHR pci_device: {}", pci_bus_info.pci_device);
                 println!("CL_DEVICE_PCI_BUS_INFO_KHR pci_function: {}", pci_bus_info.pci_function);
+                println!("CL_DEVICE_PCI_BUS_INFO_KHR: {}", pci_bus_info);
+                assert_eq!(pci_bus_info, get_device_pci_bus_info(device_id).unwrap());
             }
             Err(e) => println!("OpenCL error, CL_DEVICE_PCI_BUS_INFO_KHR: {}", ClError(e))
         };
@@ -1587,6 +3312,9 @@ mod tests {
             let value = value.to_ulong();
             println!("CL_DEVICE_SVM_CAPABILITIES: {}", value);
             assert!(0 < value);
+            let capabilities = device_svm_capabilities(device_id).unwrap();
+            assert_eq!(value, capabilities.bits());
+            assert!(capabilities.contains(SvmCapabilities::COARSE_GRAIN_BUFFER));
 
             let value =
                 get_device_info(device_id, DeviceInfo::CL_DEVICE_GLOBAL_VARIABLE_PREFERRED_TOTAL_SIZE).unwrap();
@@ -1641,12 +3369,12 @@ mod tests {
 
                 let value =
                     get_device_info(device_id, DeviceInfo::CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS).unwrap();
-                let value = value.to_uint();
+                let value = value.to_bool();
                 println!(
                     "CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS: {}",
                     value
                 );
-                assert!(0 < value);
+                assert!(value);
             }
         }
     }
@@ -1654,16 +3382,18 @@ mod tests {
     #[test]
     #[cfg(feature = "CL_VERSION_3_0")]
     fn test_get_device_info_3_0() {
-        let platform_ids = get_platform_ids().unwrap();
-
-        // Choose the platform with the most compliant GPU
-        let platform_id = platform_ids[1];
-
-        let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_GPU).unwrap();
-        println!("CL_DEVICE_TYPE_GPU count: {}", device_ids.len());
-        assert!(0 < device_ids.len());
-
-        let device_id = device_ids[0];
+        // Choose the first OpenCL 3.0 device
+        let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = devices.iter().map(|&(_, device)| device).find(|&device| {
+            device_opencl_version(device).map_or(false, |version| Version::new(3, 0, 0) <= version)
+        });
+        let device_id = match device_id {
+            Some(device) => device,
+            None => {
+                println!("No OpenCL 3.0 device");
+                return;
+            }
+        };
 
         // CL_VERSION_3_0
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_NUMERIC_VERSION).unwrap();
@@ -1694,15 +3424,15 @@ mod tests {
         println!("CL_DEVICE_ATOMIC_MEMORY_CAPABILITIES: {}", value);
         assert!(0 < value);
 
-        let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_ATOMIC_MEMORY_CAPABILITIES).unwrap();
+        let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_ATOMIC_FENCE_CAPABILITIES).unwrap();
         let value = value.to_ulong();
         println!("CL_DEVICE_ATOMIC_FENCE_CAPABILITIES: {}", value);
         assert!(0 < value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT: {}", value);
-        // assert!(0 < value);
+        // assert!(value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_OPENCL_C_ALL_VERSIONS).unwrap();
         let value = value.to_vec_name_version();
@@ -1718,17 +3448,17 @@ mod tests {
 
         let value =
             get_device_info(device_id, DeviceInfo::CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!(
             "CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT: {}",
             value
         );
-        // assert!(0 < value);
+        // assert!(value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT: {}", value);
-        // assert!(0 < value);
+        // assert!(value);
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_OPENCL_C_FEATURES).unwrap();
         let value = value.to_vec_name_version();
@@ -1739,12 +3469,12 @@ mod tests {
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES).unwrap();
         let value = value.to_ulong();
         println!("CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES: {}", value);
-        assert!(0 < value);
+        // Device-side enqueue is optional in OpenCL 3.0
 
         let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_PIPE_SUPPORT).unwrap();
-        let value = value.to_uint();
+        let value = value.to_bool();
         println!("CL_DEVICE_PIPE_SUPPORT: {}", value);
-        // assert!(0 < value);
+        // assert!(value);
 
         let value =
             get_device_info(device_id, DeviceInfo::CL_DEVICE_LATEST_CONFORMANCE_VERSION_PASSED).unwrap();
@@ -1753,6 +3483,74 @@ mod tests {
         assert!(!value.is_empty());
     }
 
+    #[test]
+    #[cfg(feature = "CL_VERSION_3_0")]
+    fn test_device_supports_il() {
+        // SPIR-V should be accepted by at least one OpenCL 3.0 device that
+        // accepts any intermediate language
+        let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+        let mut any_il = false;
+        let mut spirv = false;
+        for &(_, device) in devices.iter() {
+            let is_opencl_3 = device_opencl_version(device)
+                .map_or(false, |version| Version::new(3, 0, 0) <= version);
+            if !is_opencl_3 {
+                continue;
+            }
+
+            let ils = get_device_info(device, DeviceInfo::CL_DEVICE_ILS_WITH_VERSION)
+                .unwrap()
+                .to_vec_name_version();
+            println!("CL_DEVICE_ILS_WITH_VERSION: {:?}", ils);
+            any_il |= !ils.is_empty();
+            for minor in 0..=5 {
+                let version = Version::new(1, minor, 0);
+                if device_supports_il(device, "SPIR-V", version).unwrap() {
+                    println!("SPIR-V {}", version);
+                    spirv = true;
+                }
+            }
+        }
+        if any_il {
+            assert!(spirv);
+        } else {
+            println!("No OpenCL 3.0 device accepts an intermediate language");
+        }
+    }
+
+    #[test]
+    fn test_partition_properties() {
+        assert_eq!(
+            vec![CL_DEVICE_PARTITION_EQUALLY, 4, 0],
+            partition_equally(4)
+        );
+        assert_eq!(
+            vec![
+                CL_DEVICE_PARTITION_BY_COUNTS,
+                3,
+                1,
+                CL_DEVICE_PARTITION_BY_COUNTS_LIST_END,
+                0
+            ],
+            partition_by_counts(&[3, 1])
+        );
+        assert_eq!(
+            vec![
+                CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN,
+                CL_DEVICE_AFFINITY_DOMAIN_NUMA as cl_device_partition_property,
+                0
+            ],
+            partition_by_affinity_domain(CL_DEVICE_AFFINITY_DOMAIN_NUMA)
+        );
+
+        // A properties list must be zero terminated
+        let properties = [CL_DEVICE_PARTITION_EQUALLY, 2];
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            create_sub_devices(ptr::null_mut(), &properties)
+        );
+    }
+
     #[test]
     fn test_get_sub_devices() {
         let platform_ids = get_platform_ids().unwrap();
@@ -1779,8 +3577,7 @@ mod tests {
         }
 
         if has_sub_devices {
-            let properties: [cl_device_partition_property; 3] =
-                [CL_DEVICE_PARTITION_EQUALLY, 2, 0];
+            let properties = partition_equally(2);
             let sub_devices = create_sub_devices(device_id, &properties).unwrap();
 
             println!("CL_DEVICE_TYPE_CPU count: {}", sub_devices.len());
@@ -1789,8 +3586,413 @@ mod tests {
             for device in sub_devices {
                 release_device(device).unwrap();
             }
+
+            let properties = partition_by_counts(&[1, 1]);
+            let sub_devices = create_sub_devices(device_id, &properties).unwrap();
+            assert_eq!(2, sub_devices.len());
+            for device in sub_devices {
+                let value =
+                    get_device_info(device, DeviceInfo::CL_DEVICE_MAX_COMPUTE_UNITS).unwrap();
+                assert_eq!(1, value.to_uint());
+                release_device(device).unwrap();
+            }
         } else {
             println!("OpenCL device capable of sub division not found");
         }
     }
+
+    #[cfg(feature = "CL_VERSION_2_1")]
+    #[test]
+    fn test_get_device_and_host_timer() {
+        use crate::error_codes::error_text;
+
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+
+        match get_device_and_host_timer(device_id) {
+            Ok((device_timestamp, host_timestamp)) => {
+                println!("device_timestamp: {}", device_timestamp);
+                println!("host_timestamp: {}", host_timestamp);
+                assert!(0 < device_timestamp);
+                assert!(0 < host_timestamp);
+                println!(
+                    "device host offset: {}",
+                    device_host_timer_offset((device_timestamp, host_timestamp))
+                );
+
+                let (later_device_timestamp, later_host_timestamp) =
+                    get_device_and_host_timer(device_id).unwrap();
+                assert!(device_timestamp <= later_device_timestamp);
+                assert!(host_timestamp <= later_host_timestamp);
+
+                let host_timer = get_host_timer(device_id).unwrap();
+                println!("host timer: {}", host_timer);
+                assert!(later_host_timestamp <= host_timer);
+            }
+            Err(e) => println!("OpenCL error, clGetDeviceAndHostTimer: {}", error_text(e)),
+        }
+    }
+
+    #[test]
+    fn test_parse_opencl_version() {
+        assert_eq!(
+            Some(Version::new(3, 0, 0)),
+            parse_opencl_version("OpenCL 3.0 CUDA 11.4.1")
+        );
+        assert_eq!(
+            Some(Version::new(1, 2, 0)),
+            parse_opencl_version("OpenCL C 1.2 ")
+        );
+        assert_eq!(
+            Some(Version::new(2, 1, 3)),
+            parse_opencl_version("OpenCL 2.1.3")
+        );
+        assert_eq!(None, parse_opencl_version(""));
+        assert_eq!(None, parse_opencl_version("OpenCL"));
+        assert_eq!(None, parse_opencl_version("OpenCL C"));
+        assert_eq!(None, parse_opencl_version("OpenCL 3"));
+        assert_eq!(None, parse_opencl_version("OpenCL x.y vendor"));
+        assert_eq!(None, parse_opencl_version("Vendor OpenCL 1.2"));
+        assert_eq!(None, parse_opencl_version("OpenCL 1.2.3.4"));
+
+        let version = Version::new(2, 1, 7);
+        assert_eq!("2.1.7", version.to_string());
+        assert_eq!(version, Version::from(version.to_cl_version()));
+        assert_eq!(make_version(2, 1, 7), version.to_cl_version());
+        assert!(Version::new(1, 2, 0) < Version::new(2, 0, 0));
+        assert!(Version::new(2, 0, 1) < Version::new(2, 1, 0));
+    }
+
+    #[test]
+    fn test_get_device_subgroup_limits() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+
+        let limits = get_device_subgroup_limits(device_id, None).unwrap();
+        println!("SubgroupLimits: {:?}", limits);
+        assert_eq!(None, limits.kernel_max_num_sub_groups);
+
+        let version = device_opencl_version(device_id).unwrap();
+        let extensions = get_device_info(device_id, DeviceInfo::CL_DEVICE_EXTENSIONS)
+            .unwrap()
+            .to_string();
+        if version < Version::new(2, 1, 0) {
+            assert_eq!(SubgroupLimits::default(), limits);
+        } else if version < Version::new(3, 0, 0) || has_extension(&extensions, "cl_khr_subgroups")
+        {
+            // Sub-groups are required by OpenCL 2.1 and 2.2
+            assert!(0 < limits.max_num_sub_groups);
+        }
+    }
+
+    #[test]
+    fn test_get_nv_device_attributes() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+
+        let extensions = get_device_info(device_id, DeviceInfo::CL_DEVICE_EXTENSIONS)
+            .unwrap()
+            .to_string();
+        let attributes = get_nv_device_attributes(device_id);
+        if has_extension(&extensions, "cl_nv_device_attribute_query") {
+            let attributes = attributes.unwrap();
+            println!("NvDeviceAttributes: {:?}", attributes);
+            assert!(0 < attributes.compute_capability.0);
+            assert!(0 < attributes.warp_size);
+        } else {
+            // The driver's error is returned, rather than a panic
+            assert!(attributes.is_err());
+        }
+    }
+
+    #[test]
+    fn test_get_intel_device_info() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+
+        let extensions = get_device_info(device_id, DeviceInfo::CL_DEVICE_EXTENSIONS)
+            .unwrap()
+            .to_string();
+        let num_slices = get_device_info(device_id, DeviceInfo::CL_DEVICE_NUM_SLICES_INTEL);
+        if has_extension(&extensions, "cl_intel_device_attribute_query") {
+            assert!(0 < num_slices.unwrap().to_uint());
+            let value = get_device_info(device_id, DeviceInfo::CL_DEVICE_IP_VERSION_INTEL)
+                .unwrap()
+                .to_uint();
+            println!("CL_DEVICE_IP_VERSION_INTEL: {:#x}", value);
+            let value =
+                get_device_info(device_id, DeviceInfo::CL_DEVICE_FEATURE_CAPABILITIES_INTEL)
+                    .unwrap()
+                    .to_ulong();
+            println!("CL_DEVICE_FEATURE_CAPABILITIES_INTEL: {:#x}", value);
+        } else {
+            assert!(num_slices.is_err());
+        }
+
+        let sub_group_sizes =
+            get_device_info(device_id, DeviceInfo::CL_DEVICE_SUB_GROUP_SIZES_INTEL);
+        if has_extension(&extensions, "cl_intel_required_subgroup_size") {
+            let sub_group_sizes = sub_group_sizes.unwrap().to_vec_size();
+            println!("CL_DEVICE_SUB_GROUP_SIZES_INTEL: {:?}", sub_group_sizes);
+            assert!(!sub_group_sizes.is_empty());
+        } else {
+            assert!(sub_group_sizes.is_err());
+        }
+    }
+
+    #[test]
+    fn test_get_amd_device_attributes() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+
+        let extensions = get_device_info(device_id, DeviceInfo::CL_DEVICE_EXTENSIONS)
+            .unwrap()
+            .to_string();
+        let attributes = get_amd_device_attributes(device_id);
+        if has_extension(&extensions, "cl_amd_device_attribute_query") {
+            let attributes = attributes.unwrap();
+            println!("AmdDeviceAttributes: {:?}", attributes);
+            assert_eq!(attributes.board_name.trim(), attributes.board_name);
+            assert!(0 < attributes.wavefront_width);
+        } else {
+            // The driver's error is returned, rather than a panic
+            assert!(attributes.is_err());
+        }
+    }
+
+    #[test]
+    fn test_get_all_devices() {
+        let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+        println!("OpenCL devices: {}", devices.len());
+
+        let mut count = 0;
+        for platform_id in get_platform_ids().unwrap() {
+            let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_ALL).unwrap();
+            for device_id in device_ids.iter() {
+                assert!(devices.contains(&(platform_id, *device_id)));
+            }
+            count += device_ids.len();
+        }
+        assert_eq!(count, devices.len());
+
+        let gpus = get_all_devices(DeviceType::GPU).unwrap();
+        assert!(gpus.iter().all(|device| devices.contains(device)));
+    }
+
+    #[test]
+    fn test_get_device_platform() {
+        assert!(devices_share_platform(&[]).unwrap());
+
+        let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+        for &(platform_id, device_id) in devices.iter() {
+            assert_eq!(platform_id, get_device_platform(device_id).unwrap());
+        }
+
+        let (same, other): (Vec<_>, Vec<_>) = devices
+            .iter()
+            .partition(|&&(platform_id, _)| platform_id == devices[0].0);
+        let same: Vec<cl_device_id> = same.iter().map(|&&(_, device_id)| device_id).collect();
+        assert!(devices_share_platform(&same).unwrap());
+        if let Some(&&(_, other_device)) = other.first() {
+            assert!(!devices_share_platform(&[same[0], other_device]).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_device_host_timer_offset() {
+        assert_eq!(250, device_host_timer_offset((1250, 1000)));
+        assert_eq!(-250, device_host_timer_offset((1000, 1250)));
+        assert_eq!(0, device_host_timer_offset((7, 7)));
+    }
+
+    #[test]
+    fn test_fp_config() {
+        let config = FpConfig::from(0b1000_1001);
+        assert!(config.contains(FpConfig::DENORM));
+        assert!(!config.contains(FpConfig::INF_NAN));
+        assert!(config.contains(FpConfig::ROUND_TO_ZERO));
+        assert!(config.contains(FpConfig::CORRECTLY_ROUNDED_DIVIDE_SQRT));
+        assert!(config.contains(FpConfig::DENORM | FpConfig::ROUND_TO_ZERO));
+        assert!(!config.contains(FpConfig::DENORM | FpConfig::FMA));
+        assert_eq!(
+            "DENORM | ROUND_TO_ZERO | CORRECTLY_ROUNDED_DIVIDE_SQRT",
+            config.to_string()
+        );
+        assert_eq!(0b1000_1001, config.bits());
+        assert!(FpConfig::default().is_empty());
+        assert_eq!("NONE", FpConfig::default().to_string());
+    }
+
+    #[test]
+    fn test_device_fp_config() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+
+        // Every device reports its single precision capabilities
+        let config = fp_config(device_id, DeviceInfo::CL_DEVICE_SINGLE_FP_CONFIG).unwrap();
+        println!("CL_DEVICE_SINGLE_FP_CONFIG: {}", config);
+        assert!(!config.is_empty());
+
+        match fp_config(device_id, DeviceInfo::CL_DEVICE_DOUBLE_FP_CONFIG) {
+            Ok(config) => println!("CL_DEVICE_DOUBLE_FP_CONFIG: {}", config),
+            Err(e) => println!("CL_DEVICE_DOUBLE_FP_CONFIG: {}", ClError(e)),
+        }
+        match fp_config(device_id, DeviceInfo::CL_DEVICE_HALF_FP_CONFIG) {
+            Ok(config) => println!("CL_DEVICE_HALF_FP_CONFIG: {}", config),
+            Err(e) => println!("CL_DEVICE_HALF_FP_CONFIG: {}", ClError(e)),
+        }
+
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            fp_config(device_id, DeviceInfo::CL_DEVICE_NAME)
+        );
+    }
+
+    #[test]
+    fn test_svm_capabilities() {
+        let capabilities = SvmCapabilities::COARSE_GRAIN_BUFFER | SvmCapabilities::ATOMICS;
+        assert_eq!(
+            CL_DEVICE_SVM_COARSE_GRAIN_BUFFER | CL_DEVICE_SVM_ATOMICS,
+            cl_device_svm_capabilities::from(capabilities)
+        );
+        assert!(capabilities.contains(SvmCapabilities::ATOMICS));
+        assert!(!capabilities.contains(SvmCapabilities::FINE_GRAIN_BUFFER));
+        assert!(!capabilities.has_fine_grain());
+        assert!(!capabilities.is_empty());
+
+        let capabilities = SvmCapabilities::from(CL_DEVICE_SVM_FINE_GRAIN_SYSTEM);
+        assert!(capabilities.has_fine_grain());
+        assert!(capabilities.supports_fine_grain_system());
+        assert!(!capabilities.supports_fine_grain_buffer());
+        assert!(SvmCapabilities::default().is_empty());
+
+        let capabilities =
+            SvmCapabilities::COARSE_GRAIN_BUFFER | SvmCapabilities::FINE_GRAIN_BUFFER;
+        assert!(capabilities.supports_coarse_grain_buffer());
+        assert!(capabilities.supports_fine_grain_buffer());
+        assert!(!capabilities.supports_atomics());
+        assert_eq!(
+            "COARSE_GRAIN_BUFFER | FINE_GRAIN_BUFFER",
+            format!("{:?}", capabilities)
+        );
+        assert_eq!("NONE", SvmCapabilities::default().to_string());
+    }
+
+    #[cfg(feature = "cl_khr_terminate_context")]
+    #[test]
+    fn test_terminate_capability() {
+        assert!(TerminateCapability::default().is_empty());
+        let capability = TerminateCapability::from(CL_DEVICE_TERMINATE_CAPABILITY_CONTEXT_KHR);
+        assert!(capability.supports_context());
+        assert_eq!(CL_DEVICE_TERMINATE_CAPABILITY_CONTEXT_KHR, capability.bits());
+        assert_eq!("CONTEXT", capability.to_string());
+
+        let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+        for &(_, device) in devices.iter() {
+            match get_device_terminate_capability(device) {
+                Ok(capability) => {
+                    println!("CL_DEVICE_TERMINATE_CAPABILITY_KHR: {:?}", capability)
+                }
+                Err(e) => println!(
+                    "OpenCL error, CL_DEVICE_TERMINATE_CAPABILITY_KHR: {}",
+                    ClError(e)
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_device_summary() {
+        for platform_id in get_platform_ids().unwrap() {
+            for device in get_device_ids(platform_id, CL_DEVICE_TYPE_ALL).unwrap() {
+                let summary = get_device_summary(device).unwrap();
+                println!("{:#?}", summary);
+                assert!(!summary.name.is_empty());
+                assert!(!summary.device_type.is_empty());
+                assert!(0 < summary.max_compute_units);
+                assert!(0 < summary.max_work_group_size);
+                assert!(!summary.max_work_item_sizes.is_empty());
+                assert!(summary.local_mem_size <= summary.global_mem_size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_device_svm_capabilities() {
+        let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+        let device = devices.iter().find_map(|&(_, device)| {
+            device_opencl_version(device)
+                .ok()
+                .filter(|&version| Version::new(2, 0, 0) <= version)
+                .map(|version| (device, version))
+        });
+        match device {
+            Some((device, version)) => {
+                let capabilities = device_svm_capabilities(device).unwrap();
+                println!("CL_DEVICE_SVM_CAPABILITIES: {:?}", capabilities);
+                // Coarse-grained buffers are required by OpenCL 2.x, optional in 3.0
+                if version < Version::new(3, 0, 0) {
+                    assert!(capabilities.supports_coarse_grain_buffer());
+                }
+            }
+            None => println!("No OpenCL 2.0 device"),
+        }
+    }
+
+    #[test]
+    fn test_device_type() {
+        let device_type = DeviceType::GPU | DeviceType::ACCELERATOR;
+        assert_eq!(
+            CL_DEVICE_TYPE_GPU | CL_DEVICE_TYPE_ACCELERATOR,
+            cl_device_type::from(device_type)
+        );
+        assert!(device_type.contains(DeviceType::GPU));
+        assert!(!device_type.contains(DeviceType::GPU | DeviceType::CPU));
+        assert!(device_type.intersects(DeviceType::GPU | DeviceType::CPU));
+        assert!(!device_type.intersects(DeviceType::CPU));
+        assert!(DeviceType::ALL.contains(device_type));
+        assert_eq!("GPU | ACCELERATOR", device_type.to_string());
+        assert_eq!("ALL", DeviceType::ALL.to_string());
+        assert_eq!("NONE", DeviceType::from_bits(0).unwrap().to_string());
+
+        let mut device_type = DeviceType::CPU;
+        device_type |= DeviceType::CUSTOM;
+        assert_eq!(
+            CL_DEVICE_TYPE_CPU | CL_DEVICE_TYPE_CUSTOM,
+            device_type.bits()
+        );
+
+        assert_eq!(
+            Some(DeviceType::GPU),
+            DeviceType::from_bits(CL_DEVICE_TYPE_GPU)
+        );
+        assert_eq!(
+            Some(DeviceType::ALL),
+            DeviceType::from_bits(CL_DEVICE_TYPE_ALL)
+        );
+        assert_eq!(None, DeviceType::from_bits(1 << 20));
+
+        assert_eq!(
+            Ok(DeviceType::CPU | DeviceType::GPU),
+            DeviceType::try_from(CL_DEVICE_TYPE_CPU | CL_DEVICE_TYPE_GPU)
+        );
+        assert_eq!(
+            Err(CL_INVALID_DEVICE_TYPE),
+            DeviceType::try_from(CL_DEVICE_TYPE_GPU | 1 << 20)
+        );
+
+        let device_type = DeviceType::GPU | DeviceType::ACCELERATOR;
+        assert_eq!(DeviceType::GPU, device_type & DeviceType::GPU);
+        assert_eq!(0, (device_type & DeviceType::CPU).bits());
+        let mut masked = DeviceType::ALL;
+        masked &= device_type;
+        assert_eq!(device_type, masked);
+        assert_eq!("GPU | ACCELERATOR", format!("{:?}", device_type));
+    }
 }
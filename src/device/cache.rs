@@ -0,0 +1,247 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A cache of immutable device parameters.
+//!
+//! Hot code paths may ask for the same device parameters many times, e.g. the
+//! maximum work-group size when choosing a local work size. The parameters in
+//! the cache do not change for the lifetime of a device, so each parameter is
+//! queried once per device and never invalidated.
+//!
+//! The device helpers, e.g. `default_max_chunk`, share one process-wide cache.
+
+use super::{get_device_info, DeviceInfo};
+use crate::types::{cl_device_id, cl_int, cl_uint, cl_ulong};
+
+use libc::size_t;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+// The cached parameters of a device, each is queried on first access.
+// Only values are cached, an error may be transient, e.g. CL_OUT_OF_HOST_MEMORY.
+#[derive(Debug, Default)]
+struct DeviceEntry {
+    max_work_group_size: Mutex<Option<size_t>>,
+    max_compute_units: Mutex<Option<cl_uint>>,
+    address_bits: Mutex<Option<cl_uint>>,
+    max_mem_alloc_size: Mutex<Option<cl_ulong>>,
+    global_mem_size: Mutex<Option<cl_ulong>>,
+    host_unified_memory: Mutex<Option<bool>>,
+}
+
+// Get a cached value, or call query and cache its value. The cell is locked
+// while query is called, so threads that access the value for the first time
+// together call query once, unless it fails.
+fn get_or_query<T: Copy>(
+    cell: &Mutex<Option<T>>,
+    query: impl FnOnce() -> Result<T, cl_int>,
+) -> Result<T, cl_int> {
+    let mut cached = cell.lock().unwrap_or_else(|e| e.into_inner());
+    match *cached {
+        Some(value) => Ok(value),
+        None => {
+            let value = query()?;
+            *cached = Some(value);
+            Ok(value)
+        }
+    }
+}
+
+// The cache of the device helpers.
+static DEVICE_INFO_CACHE: OnceLock<DeviceInfoCache> = OnceLock::new();
+
+/// The process-wide DeviceInfoCache used by the device helpers, e.g.
+/// `default_max_chunk` and `select_device_by`.  
+/// It is safe to keep the entries of released sub-devices, see DeviceInfoCache,
+/// since the helpers only use the parameters that a sub-device shares with its
+/// parent device, except `select_device_by`, which only scores root devices.
+pub(crate) fn device_info_cache() -> &'static DeviceInfoCache {
+    DEVICE_INFO_CACHE.get_or_init(DeviceInfoCache::new)
+}
+
+/// A thread-safe cache of immutable device parameters, keyed by device.
+/// Each typed getter calls clGetDeviceInfo for a device until it succeeds,
+/// and then returns the cached value.
+///
+/// Note: a released sub-device's cl_device_id may be reused by OpenCL for a
+/// new sub-device, so a cache should not outlive the sub-devices in it.
+#[derive(Debug, Default)]
+pub struct DeviceInfoCache {
+    // The devices are stored as usize since raw pointers are not Send.
+    devices: Mutex<HashMap<usize, Arc<DeviceEntry>>>,
+}
+
+impl DeviceInfoCache {
+    pub fn new() -> DeviceInfoCache {
+        DeviceInfoCache::default()
+    }
+
+    // The entry of a device, created on first access. The map is only locked
+    // to find the entry, not while a parameter is queried.
+    fn entry(&self, device: cl_device_id) -> Arc<DeviceEntry> {
+        let mut devices = self.devices.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(devices.entry(device as usize).or_default())
+    }
+
+    /// CL_DEVICE_MAX_WORK_GROUP_SIZE of a device.
+    pub fn max_work_group_size(&self, device: cl_device_id) -> Result<size_t, cl_int> {
+        get_or_query(&self.entry(device).max_work_group_size, || {
+            Ok(get_device_info(device, DeviceInfo::CL_DEVICE_MAX_WORK_GROUP_SIZE)?.to_size())
+        })
+    }
+
+    /// CL_DEVICE_MAX_COMPUTE_UNITS of a device.
+    pub fn max_compute_units(&self, device: cl_device_id) -> Result<cl_uint, cl_int> {
+        get_or_query(&self.entry(device).max_compute_units, || {
+            Ok(get_device_info(device, DeviceInfo::CL_DEVICE_MAX_COMPUTE_UNITS)?.to_uint())
+        })
+    }
+
+    /// CL_DEVICE_ADDRESS_BITS of a device.
+    pub fn address_bits(&self, device: cl_device_id) -> Result<cl_uint, cl_int> {
+        get_or_query(&self.entry(device).address_bits, || {
+            Ok(get_device_info(device, DeviceInfo::CL_DEVICE_ADDRESS_BITS)?.to_uint())
+        })
+    }
+
+    /// CL_DEVICE_MAX_MEM_ALLOC_SIZE of a device.
+    pub fn max_mem_alloc_size(&self, device: cl_device_id) -> Result<cl_ulong, cl_int> {
+        get_or_query(&self.entry(device).max_mem_alloc_size, || {
+            Ok(get_device_info(device, DeviceInfo::CL_DEVICE_MAX_MEM_ALLOC_SIZE)?.to_ulong())
+        })
+    }
+
+    /// CL_DEVICE_GLOBAL_MEM_SIZE of a device.
+    pub fn global_mem_size(&self, device: cl_device_id) -> Result<cl_ulong, cl_int> {
+        get_or_query(&self.entry(device).global_mem_size, || {
+            Ok(get_device_info(device, DeviceInfo::CL_DEVICE_GLOBAL_MEM_SIZE)?.to_ulong())
+        })
+    }
+
+    /// CL_DEVICE_HOST_UNIFIED_MEMORY of a device, deprecated in OpenCL 2.0,
+    /// see `device_has_unified_memory`.
+    pub fn host_unified_memory(&self, device: cl_device_id) -> Result<bool, cl_int> {
+        get_or_query(&self.entry(device).host_unified_memory, || {
+            Ok(get_device_info(device, DeviceInfo::CL_DEVICE_HOST_UNIFIED_MEMORY)?.to_bool())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::default_max_chunk;
+    use crate::device::{get_all_devices, CL_DEVICE_TYPE_ALL};
+    use crate::error_codes::CL_INVALID_VALUE;
+    use crate::macros::call_hooks::calls;
+    use std::ptr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    #[test]
+    fn test_get_or_query() {
+        let entry = Arc::new(DeviceEntry::default());
+        let queries = Arc::new(AtomicUsize::new(0));
+
+        // Concurrent first accesses query the value once
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let entry = Arc::clone(&entry);
+                let queries = Arc::clone(&queries);
+                thread::spawn(move || {
+                    get_or_query(&entry.max_work_group_size, || {
+                        queries.fetch_add(1, Ordering::SeqCst);
+                        Ok(256)
+                    })
+                })
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(Ok(256), thread.join().unwrap());
+        }
+        for _ in 0..100 {
+            let value = get_or_query(&entry.max_work_group_size, || {
+                queries.fetch_add(1, Ordering::SeqCst);
+                Ok(0)
+            });
+            assert_eq!(Ok(256), value);
+        }
+        assert_eq!(1, queries.load(Ordering::SeqCst));
+
+        // Errors are not cached
+        let query = || Err(CL_INVALID_VALUE);
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            get_or_query(&entry.address_bits, query)
+        );
+        assert_eq!(Ok(64), get_or_query(&entry.address_bits, || Ok(64)));
+        assert_eq!(Ok(64), get_or_query(&entry.address_bits, || Ok(32)));
+    }
+
+    #[test]
+    fn test_device_info_cache_calls() {
+        let cache = DeviceInfoCache::new();
+        let (_, device) = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap()[0];
+
+        // clGetDeviceInfo is called once per parameter
+        let before = calls("clGetDeviceInfo");
+        for _ in 0..3 {
+            cache.address_bits(device).unwrap();
+            cache.max_mem_alloc_size(device).unwrap();
+        }
+        assert_eq!(2, calls("clGetDeviceInfo") - before);
+
+        // and again after an error
+        let before = calls("clGetDeviceInfo");
+        assert!(cache.address_bits(ptr::null_mut()).is_err());
+        assert!(cache.address_bits(ptr::null_mut()).is_err());
+        assert_eq!(2, calls("clGetDeviceInfo") - before);
+
+        // The helpers use the process-wide cache
+        let max_chunk = default_max_chunk(device).unwrap();
+        let before = calls("clGetDeviceInfo");
+        assert_eq!(Ok(max_chunk), default_max_chunk(device));
+        assert_eq!(0, calls("clGetDeviceInfo") - before);
+    }
+
+    #[test]
+    fn test_device_info_cache() {
+        let cache = DeviceInfoCache::new();
+        let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+        for &(_, device) in devices.iter() {
+            let value = cache.max_work_group_size(device).unwrap();
+            println!("CL_DEVICE_MAX_WORK_GROUP_SIZE: {}", value);
+            assert!(0 < value);
+            assert_eq!(Ok(value), cache.max_work_group_size(device));
+
+            let value = cache.address_bits(device).unwrap();
+            println!("CL_DEVICE_ADDRESS_BITS: {}", value);
+            assert!(32 == value || 64 == value);
+
+            let value = cache.max_mem_alloc_size(device).unwrap();
+            println!("CL_DEVICE_MAX_MEM_ALLOC_SIZE: {}", value);
+            assert!(value <= cache.global_mem_size(device).unwrap());
+
+            let value = cache.max_compute_units(device).unwrap();
+            println!("CL_DEVICE_MAX_COMPUTE_UNITS: {}", value);
+            assert!(0 < value);
+
+            println!(
+                "CL_DEVICE_HOST_UNIFIED_MEMORY: {:?}",
+                cache.host_unified_memory(device)
+            );
+        }
+        assert_eq!(devices.len(), cache.devices.lock().unwrap().len());
+    }
+}
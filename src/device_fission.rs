@@ -0,0 +1,294 @@
+// Copyright (c) 2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Device partitioning with the cl_ext_device_fission extension, for
+//! OpenCL 1.1 devices that do not support clCreateSubDevices.
+//!
+//! The ICD loader does not export the extension functions, so they are
+//! loaded from a platform with clGetExtensionFunctionAddressForPlatform,
+//! see `DeviceFission`.
+
+pub use super::error_codes::{
+    CL_DEVICE_PARTITION_FAILED_EXT, CL_INVALID_PARTITION_COUNT_EXT, CL_INVALID_PARTITION_NAME_EXT,
+};
+pub use super::ffi::cl_ext::{
+    cl_device_partition_property_ext, CL_AFFINITY_DOMAIN_L1_CACHE_EXT,
+    CL_AFFINITY_DOMAIN_L2_CACHE_EXT, CL_AFFINITY_DOMAIN_L3_CACHE_EXT,
+    CL_AFFINITY_DOMAIN_L4_CACHE_EXT, CL_AFFINITY_DOMAIN_NEXT_FISSIONABLE_EXT,
+    CL_AFFINITY_DOMAIN_NUMA_EXT, CL_DEVICE_AFFINITY_DOMAINS_EXT, CL_DEVICE_PARENT_DEVICE_EXT,
+    CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN_EXT, CL_DEVICE_PARTITION_BY_COUNTS_EXT,
+    CL_DEVICE_PARTITION_BY_NAMES_EXT, CL_DEVICE_PARTITION_EQUALLY_EXT,
+    CL_DEVICE_PARTITION_STYLE_EXT, CL_DEVICE_PARTITION_TYPES_EXT, CL_DEVICE_REFERENCE_COUNT_EXT,
+    CL_PARTITION_BY_COUNTS_LIST_END_EXT, CL_PARTITION_BY_NAMES_LIST_END_EXT,
+    CL_PROPERTIES_LIST_END_EXT,
+};
+
+use super::create_object_list;
+use super::device::get_device_platform;
+use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+use super::platform::get_extension_function;
+use super::types::{cl_device_id, cl_int, cl_platform_id, cl_uint};
+use std::ffi::CStr;
+
+/// The name of the extension in CL_PLATFORM_EXTENSIONS and CL_DEVICE_EXTENSIONS.
+pub const CL_EXT_DEVICE_FISSION: &str = "cl_ext_device_fission";
+
+#[allow(non_camel_case_types)]
+type clCreateSubDevicesEXT_fn = extern "system" fn(
+    in_device: cl_device_id,
+    properties: *const cl_device_partition_property_ext,
+    num_entries: cl_uint,
+    out_devices: *mut cl_device_id,
+    num_devices: *mut cl_uint,
+) -> cl_int;
+
+#[allow(non_camel_case_types)]
+type clRetainDeviceEXT_fn = extern "system" fn(device: cl_device_id) -> cl_int;
+
+#[allow(non_camel_case_types)]
+type clReleaseDeviceEXT_fn = extern "system" fn(device: cl_device_id) -> cl_int;
+
+// Load an extension function, name must be nul terminated.
+fn load<F: Copy>(platform: cl_platform_id, name: &[u8]) -> Result<F, cl_int> {
+    let name = CStr::from_bytes_with_nul(name).map_err(|_| CL_INVALID_VALUE)?;
+    // Safety: the function types above match the cl_ext_device_fission functions.
+    unsafe { get_extension_function::<F>(platform, name) }
+}
+
+/// The cl_ext_device_fission extension functions of an OpenCL platform.
+#[derive(Clone, Copy, Debug)]
+pub struct DeviceFission {
+    create_sub_devices: clCreateSubDevicesEXT_fn,
+    retain_device: clRetainDeviceEXT_fn,
+    release_device: clReleaseDeviceEXT_fn,
+}
+
+impl DeviceFission {
+    /// Load the extension functions of a platform.
+    /// Calls clGetExtensionFunctionAddressForPlatform.
+    ///
+    /// * `platform` - the OpenCL platform.
+    ///
+    /// returns a Result containing the extension functions
+    /// or CL_INVALID_VALUE if the platform does not provide them.
+    pub fn load(platform: cl_platform_id) -> Result<DeviceFission, cl_int> {
+        Ok(DeviceFission {
+            create_sub_devices: load(platform, b"clCreateSubDevicesEXT\0")?,
+            retain_device: load(platform, b"clRetainDeviceEXT\0")?,
+            release_device: load(platform, b"clReleaseDeviceEXT\0")?,
+        })
+    }
+
+    /// Load the extension functions of the platform of a device.
+    /// Calls clGetDeviceInfo to get CL_DEVICE_PLATFORM and
+    /// clGetExtensionFunctionAddressForPlatform.
+    ///
+    /// * `device` - the OpenCL device.
+    ///
+    /// returns a Result containing the extension functions
+    /// or CL_INVALID_VALUE if the platform does not provide them
+    /// or the error code from the OpenCL C API function.
+    pub fn load_for_device(device: cl_device_id) -> Result<DeviceFission, cl_int> {
+        DeviceFission::load(get_device_platform(device)?)
+    }
+
+    /// Create sub-devices by partitioning an OpenCL device.
+    /// Calls clCreateSubDevicesEXT to get the partitioned sub-devices.
+    ///
+    /// * `in_device` - the cl_device_id of the OpenCL device to partition.
+    /// * `properties` - the CL_PROPERTIES_LIST_END_EXT terminated slice of
+    /// cl_device_partition_property_ext, e.g. from partition_equally_ext.
+    ///
+    /// returns a Result containing a vector of sub-device ids, which must be
+    /// released with release_device
+    /// or CL_INVALID_VALUE if `properties` is not terminated
+    /// or the error code from the OpenCL C API function.
+    pub fn create_sub_devices(
+        &self,
+        in_device: cl_device_id,
+        properties: &[cl_device_partition_property_ext],
+    ) -> Result<Vec<cl_device_id>, cl_int> {
+        if Some(&CL_PROPERTIES_LIST_END_EXT) != properties.last() {
+            return Err(CL_INVALID_VALUE);
+        }
+
+        create_object_list(|num_entries, devices, num_devices| {
+            (self.create_sub_devices)(
+                in_device,
+                properties.as_ptr(),
+                num_entries,
+                devices,
+                num_devices,
+            )
+        })
+    }
+
+    /// Retain an OpenCL sub-device.
+    /// Calls clRetainDeviceEXT to increment the device reference count.
+    ///
+    /// * `device` - the cl_device_id of the sub-device.
+    ///
+    /// returns an empty Result or the error code from the OpenCL C API function.
+    pub fn retain_device(&self, device: cl_device_id) -> Result<(), cl_int> {
+        let status: cl_int = (self.retain_device)(device);
+        if CL_SUCCESS != status {
+            Err(status)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Release an OpenCL sub-device.
+    /// Calls clReleaseDeviceEXT to decrement the device reference count.
+    ///
+    /// * `device` - the cl_device_id of the sub-device.
+    ///
+    /// returns an empty Result or the error code from the OpenCL C API function.
+    pub fn release_device(&self, device: cl_device_id) -> Result<(), cl_int> {
+        let status: cl_int = (self.release_device)(device);
+        if CL_SUCCESS != status {
+            Err(status)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The properties to partition a device into as many sub-devices as possible,
+/// each with `compute_units` compute units, for create_sub_devices.
+pub fn partition_equally_ext(compute_units: cl_uint) -> Vec<cl_device_partition_property_ext> {
+    vec![
+        CL_DEVICE_PARTITION_EQUALLY_EXT,
+        compute_units as cl_device_partition_property_ext,
+        CL_PROPERTIES_LIST_END_EXT,
+    ]
+}
+
+/// The properties to partition a device into a sub-device for each count,
+/// with that number of compute units, for create_sub_devices.
+pub fn partition_by_counts_ext(counts: &[cl_uint]) -> Vec<cl_device_partition_property_ext> {
+    let mut properties: Vec<cl_device_partition_property_ext> =
+        Vec::with_capacity(counts.len() + 3);
+    properties.push(CL_DEVICE_PARTITION_BY_COUNTS_EXT);
+    properties.extend(
+        counts
+            .iter()
+            .map(|&count| count as cl_device_partition_property_ext),
+    );
+    properties.push(CL_PARTITION_BY_COUNTS_LIST_END_EXT);
+    properties.push(CL_PROPERTIES_LIST_END_EXT);
+    properties
+}
+
+/// The properties to partition a device into a sub-device for each list of
+/// compute unit names, for create_sub_devices.
+pub fn partition_by_names_ext(names: &[&[cl_uint]]) -> Vec<cl_device_partition_property_ext> {
+    let mut properties: Vec<cl_device_partition_property_ext> = Vec::new();
+    properties.push(CL_DEVICE_PARTITION_BY_NAMES_EXT);
+    for &sub_device in names {
+        properties.extend(
+            sub_device
+                .iter()
+                .map(|&name| name as cl_device_partition_property_ext),
+        );
+        properties.push(CL_PARTITION_BY_NAMES_LIST_END_EXT);
+    }
+    properties.push(CL_PROPERTIES_LIST_END_EXT);
+    properties
+}
+
+/// The properties to partition a device into sub-devices that share the
+/// given affinity domain, e.g. CL_AFFINITY_DOMAIN_L2_CACHE_EXT, for create_sub_devices.
+pub fn partition_by_affinity_domain_ext(
+    affinity_domain: cl_uint,
+) -> Vec<cl_device_partition_property_ext> {
+    vec![
+        CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN_EXT,
+        affinity_domain as cl_device_partition_property_ext,
+        CL_PROPERTIES_LIST_END_EXT,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{
+        get_all_devices, get_device_info, has_extension, DeviceInfo, CL_DEVICE_TYPE_ALL,
+    };
+    use crate::error_codes::error_text;
+
+    #[test]
+    fn test_partition_properties_ext() {
+        assert_eq!(
+            vec![CL_DEVICE_PARTITION_EQUALLY_EXT, 4, 0],
+            partition_equally_ext(4)
+        );
+        assert_eq!(
+            vec![CL_DEVICE_PARTITION_BY_COUNTS_EXT, 2, 3, 0, 0],
+            partition_by_counts_ext(&[2, 3])
+        );
+        assert_eq!(
+            vec![
+                CL_DEVICE_PARTITION_BY_NAMES_EXT,
+                0,
+                1,
+                CL_PARTITION_BY_NAMES_LIST_END_EXT,
+                2,
+                CL_PARTITION_BY_NAMES_LIST_END_EXT,
+                0
+            ],
+            partition_by_names_ext(&[&[0, 1], &[2]])
+        );
+        assert_eq!(
+            vec![
+                CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN_EXT,
+                CL_AFFINITY_DOMAIN_NUMA_EXT as cl_device_partition_property_ext,
+                0
+            ],
+            partition_by_affinity_domain_ext(CL_AFFINITY_DOMAIN_NUMA_EXT)
+        );
+    }
+
+    #[test]
+    fn test_device_fission_ext() {
+        let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+        for &(_, device) in devices.iter() {
+            let extensions = get_device_info(device, DeviceInfo::CL_DEVICE_EXTENSIONS)
+                .unwrap()
+                .to_string();
+            if !has_extension(&extensions, CL_EXT_DEVICE_FISSION) {
+                continue;
+            }
+
+            let fission = DeviceFission::load_for_device(device).unwrap();
+            assert_eq!(
+                Err(CL_INVALID_VALUE),
+                fission.create_sub_devices(device, &[CL_DEVICE_PARTITION_EQUALLY_EXT, 1])
+            );
+
+            match fission.create_sub_devices(device, &partition_equally_ext(1)) {
+                Ok(sub_devices) => {
+                    println!("CL_DEVICE_PARTITION_EQUALLY_EXT: {}", sub_devices.len());
+                    assert!(!sub_devices.is_empty());
+                    for sub_device in sub_devices {
+                        fission.retain_device(sub_device).unwrap();
+                        fission.release_device(sub_device).unwrap();
+                        fission.release_device(sub_device).unwrap();
+                    }
+                }
+                Err(e) => println!("clCreateSubDevicesEXT: {}", error_text(e)),
+            }
+        }
+    }
+}
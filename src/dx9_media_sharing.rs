@@ -22,7 +22,7 @@
 use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
 pub use super::ffi::cl_dx9_media_sharing::*;
 #[allow(unused_imports)]
-pub use cl_sys::{cl_device_id, cl_platform_id, cl_uint};
+pub use super::types::{cl_device_id, cl_platform_id, cl_uint};
 #[allow(unused_imports)]
 use libc::c_void;
 #[allow(unused_imports)]
@@ -13,15 +13,36 @@
 // limitations under the License.
 
 //! OpenCL OpenGl ES Interoperability API.
+//!
+//! The functions that take pointer and count pairs are in the [raw]
+//! submodule. Each raw function is also available at its old path in this
+//! module but is deprecated, its replacement has a `_slice` suffix.
 
+pub mod raw;
+
+#[allow(unused_imports)]
+use super::command_queue::wait_list_ptr;
 #[allow(unused_imports)]
 use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
 pub use super::ffi::cl_egl::*;
 #[allow(unused_imports)]
-pub use cl_sys::{cl_context, cl_event, cl_int, cl_mem_flags};
+pub use super::types::{cl_context, cl_event, cl_int, cl_mem_flags};
 #[allow(unused_imports)]
 use std::ptr;
 
+#[cfg(feature = "cl_khr_egl_image")]
+#[deprecated(note = "use the slice-based variant: create_from_egl_image_slice")]
+#[inline]
+pub fn create_from_egl_image(
+    context: cl_context,
+    display: CLeglDisplayKHR,
+    image: CLeglImageKHR,
+    flags: cl_mem_flags,
+    properties: *const cl_egl_image_properties_khr,
+) -> Result<cl_mem, cl_int> {
+    raw::create_from_egl_image(context, display, image, flags, properties)
+}
+
 /// Create an OpenCL image object, from the EGLImage source provided as image.  
 /// Requires the cl_khr_egl_image extension.  
 /// Calls clCreateFromEGLImageKHR to create an OpenCL memory object.  
@@ -30,28 +51,46 @@ use std::ptr;
 /// * `display` - should be of type EGLDisplay, cast into the type CLeglDisplayKHR
 /// * `image` - should be of type EGLImageKHR, cast into the type CLeglImageKHR.  
 /// * `flags` -  usage information about the memory object being created.  
-/// * `properties` - a null terminated list of property names and their
-/// corresponding values.  
+/// * `properties` - a zero terminated list of property names and their
+///   corresponding values, or an empty slice.
 ///
 /// returns a Result containing the new OpenCL image object
+/// or CL_INVALID_VALUE if `properties` is not zero terminated
 /// or the error code from the OpenCL C API function.
 #[cfg(feature = "cl_khr_egl_image")]
 #[inline]
-pub fn create_from_egl_image(
+pub fn create_from_egl_image_slice(
     context: cl_context,
     display: CLeglDisplayKHR,
     image: CLeglImageKHR,
     flags: cl_mem_flags,
-    properties: *const cl_egl_image_properties_khr,
+    properties: &[cl_egl_image_properties_khr],
 ) -> Result<cl_mem, cl_int> {
-    let mut status: cl_int = CL_INVALID_VALUE;
-    let mem =
-        unsafe { clCreateFromEGLImageKHR(context, display, image, flags, properties, &mut status) };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(mem)
-    }
+    let properties_ptr = match properties.last() {
+        None => ptr::null(),
+        Some(0) => properties.as_ptr(),
+        Some(_) => return Err(CL_INVALID_VALUE),
+    };
+    raw::create_from_egl_image(context, display, image, flags, properties_ptr)
+}
+
+#[cfg(feature = "cl_khr_egl_image")]
+#[deprecated(note = "use the slice-based variant: enqueue_acquire_egl_objects_slice")]
+#[inline]
+pub fn enqueue_acquire_egl_objects(
+    command_queue: cl_command_queue,
+    num_objects: cl_uint,
+    mem_objects: *const cl_mem,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_acquire_egl_objects(
+        command_queue,
+        num_objects,
+        mem_objects,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
 /// Acquire OpenCL memory objects that have been created from EGL resources.  
@@ -59,38 +98,48 @@ pub fn create_from_egl_image(
 /// Calls clEnqueueAcquireEGLObjectsKHR.  
 ///
 /// * `command_queue` - a valid OpenCL command_queue.
-/// * `num_objects` - the number of memory objects to acquire.
 /// * `mem_objects` - the memory objects to acquire.
-/// * `num_events_in_wait_list` - the number of events in the wait list.
-/// * `event_wait_list` - the wait list events.
+/// * `event_wait_list` - events that must complete before this command is executed.
 ///
 /// returns a Result containing the new OpenCL event
 /// or the error code from the OpenCL C API function.
 #[cfg(feature = "cl_khr_egl_image")]
 #[inline]
-pub fn enqueue_acquire_egl_objects(
+pub fn enqueue_acquire_egl_objects_slice(
+    command_queue: cl_command_queue,
+    mem_objects: &[cl_mem],
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_acquire_egl_objects(
+        command_queue,
+        mem_objects.len() as cl_uint,
+        if mem_objects.is_empty() {
+            ptr::null()
+        } else {
+            mem_objects.as_ptr()
+        },
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
+}
+
+#[cfg(feature = "cl_khr_egl_image")]
+#[deprecated(note = "use the slice-based variant: enqueue_release_egl_objects_slice")]
+#[inline]
+pub fn enqueue_release_egl_objects(
     command_queue: cl_command_queue,
     num_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueAcquireEGLObjectsKHR(
-            command_queue,
-            num_objects,
-            mem_objects,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_release_egl_objects(
+        command_queue,
+        num_objects,
+        mem_objects,
+        num_events_in_wait_list,
+        event_wait_list,
+    )
 }
 
 /// Release OpenCL memory objects that have been created from EGL resources.  
@@ -98,38 +147,29 @@ pub fn enqueue_acquire_egl_objects(
 /// Calls clEnqueueReleaseEGLObjectsKHR.  
 ///
 /// * `command_queue` - a valid OpenCL command_queue.
-/// * `num_objects` - the number of memory objects to acquire.
-/// * `mem_objects` - the memory objects to acquire.
-/// * `num_events_in_wait_list` - the number of events in the wait list.
-/// * `event_wait_list` - the wait list events.
+/// * `mem_objects` - the memory objects to release.
+/// * `event_wait_list` - events that must complete before this command is executed.
 ///
 /// returns a Result containing the new OpenCL event
 /// or the error code from the OpenCL C API function.
 #[cfg(feature = "cl_khr_egl_image")]
 #[inline]
-pub fn enqueue_release_egl_objects(
+pub fn enqueue_release_egl_objects_slice(
     command_queue: cl_command_queue,
-    num_objects: cl_uint,
-    mem_objects: *const cl_mem,
-    num_events_in_wait_list: cl_uint,
-    event_wait_list: *const cl_event,
+    mem_objects: &[cl_mem],
+    event_wait_list: &[cl_event],
 ) -> Result<cl_event, cl_int> {
-    let mut event: cl_event = ptr::null_mut();
-    let status: cl_int = unsafe {
-        clEnqueueReleaseEGLObjectsKHR(
-            command_queue,
-            num_objects,
-            mem_objects,
-            num_events_in_wait_list,
-            event_wait_list,
-            &mut event,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(event)
-    }
+    raw::enqueue_release_egl_objects(
+        command_queue,
+        mem_objects.len() as cl_uint,
+        if mem_objects.is_empty() {
+            ptr::null()
+        } else {
+            mem_objects.as_ptr()
+        },
+        event_wait_list.len() as cl_uint,
+        wait_list_ptr(event_wait_list),
+    )
 }
 
 /// Create an event object linked to an EGL fence sync object.  
@@ -158,3 +198,51 @@ pub fn create_event_from_egl_sync_khr(
         Ok(event)
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "cl_khr_egl_image")]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_raw_and_deprecated_paths() {
+        // The deprecated paths must still resolve to the raw function signatures.
+        let _create: fn(
+            cl_context,
+            CLeglDisplayKHR,
+            CLeglImageKHR,
+            cl_mem_flags,
+            *const cl_egl_image_properties_khr,
+        ) -> Result<cl_mem, cl_int> = create_from_egl_image;
+        let _acquire: fn(
+            cl_command_queue,
+            cl_uint,
+            *const cl_mem,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_acquire_egl_objects;
+        let _release: fn(
+            cl_command_queue,
+            cl_uint,
+            *const cl_mem,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = enqueue_release_egl_objects;
+        let _raw_release: fn(
+            cl_command_queue,
+            cl_uint,
+            *const cl_mem,
+            cl_uint,
+            *const cl_event,
+        ) -> Result<cl_event, cl_int> = raw::enqueue_release_egl_objects;
+    }
+
+    #[test]
+    fn test_create_from_egl_image_properties_not_terminated() {
+        // A properties list must be zero terminated
+        let result =
+            create_from_egl_image_slice(ptr::null_mut(), ptr::null_mut(), ptr::null_mut(), 0, &[1]);
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+    }
+}
@@ -40,7 +40,9 @@ pub use cl_sys::{
 // #ifdef CL_VERSION_3_0
 pub const CL_COMMAND_SVM_MIGRATE_MEM: cl_uint = 0x120E;
 
-use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+use super::error_codes::{
+    error_text, CL_INVALID_VALUE, CL_PROFILING_INFO_NOT_AVAILABLE, CL_SUCCESS,
+};
 use super::info_type::InfoType;
 use super::types::{
     cl_command_type, cl_context, cl_event, cl_event_info, cl_int, cl_profiling_info, cl_uint,
@@ -57,6 +59,10 @@ use libc::{c_void, intptr_t, size_t};
 use std::fmt;
 use std::mem;
 use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
 
 /// Wait for OpenCL events to complete.  
 /// Calls clWaitForEvents.
@@ -152,6 +158,8 @@ pub fn create_user_event(context: cl_context) -> Result<cl_event, cl_int> {
 #[inline]
 pub fn retain_event(event: cl_event) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clRetainEvent(event) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::retained(event as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -168,6 +176,8 @@ pub fn retain_event(event: cl_event) -> Result<(), cl_int> {
 #[inline]
 pub fn release_event(event: cl_event) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clReleaseEvent(event) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::released(event as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -179,11 +189,17 @@ pub fn release_event(event: cl_event) -> Result<(), cl_int> {
 /// Calls clSetUserEventStatus to set the execution status.
 ///
 /// * `event` - the OpenCL event.
-/// * `execution_status` - the OpenCL execution_status.
+/// * `execution_status` - CL_COMPLETE or a negative integer error code.
 ///
-/// returns an empty Result or the error code from the OpenCL C API function.
+/// returns an empty Result
+/// or CL_INVALID_VALUE if execution_status is not CL_COMPLETE or negative
+/// or the error code from the OpenCL C API function.
 #[inline]
 pub fn set_user_event_status(event: cl_event, execution_status: cl_int) -> Result<(), cl_int> {
+    if CL_COMPLETE != execution_status && 0 <= execution_status {
+        return Err(CL_INVALID_VALUE);
+    }
+
     let status: cl_int = unsafe { clSetUserEventStatus(event, execution_status) };
     if CL_SUCCESS != status {
         Err(status)
@@ -222,6 +238,157 @@ pub fn set_event_callback(
     }
 }
 
+/// The type of a closure registered by set_event_callback_closure.
+pub type EventCallback = Box<dyn FnMut(cl_event, cl_int) + Send>;
+
+// Trampoline for set_event_callback_closure: reconstitutes the boxed closure
+// from user_data, calls it and then frees it, since OpenCL calls it only once.
+extern "C" fn event_callback_trampoline(
+    event: cl_event,
+    event_command_status: cl_int,
+    user_data: *mut c_void,
+) {
+    let mut callback = unsafe { Box::from_raw(user_data as *mut EventCallback) };
+    // A panic must not unwind into the OpenCL implementation.
+    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        callback(event, event_command_status)
+    }));
+}
+
+/// Register a Rust closure for a specific command execution status.  
+/// Calls clSetEventCallback to register a callback function that calls the
+/// closure once and then drops it.
+///
+/// Note: OpenCL may call the closure from any thread, including an OpenCL
+/// implementation thread, so it must be `Send`. It should return quickly and
+/// must not call blocking OpenCL functions such as clFinish or clWaitForEvents.
+///
+/// * `event` - the OpenCL event.
+/// * `command_exec_callback_type` - CL_SUBMITTED, CL_RUNNING or CL_COMPLETE.
+/// * `callback` - the closure to call with the event and its execution status.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn set_event_callback_closure(
+    event: cl_event,
+    command_exec_callback_type: cl_int,
+    callback: EventCallback,
+) -> Result<(), cl_int> {
+    let user_data = Box::into_raw(Box::new(callback));
+    let status: cl_int = unsafe {
+        clSetEventCallback(
+            event,
+            command_exec_callback_type,
+            Some(event_callback_trampoline),
+            user_data as *mut c_void,
+        )
+    };
+    if CL_SUCCESS != status {
+        // The callback will never be called, so free the closure here.
+        unsafe { drop(Box::from_raw(user_data)) };
+        Err(status)
+    } else {
+        Ok(())
+    }
+}
+
+/// The command execution stages that subscribe_event_stages can report.  
+/// Error is reported instead of a stage if the command terminated abnormally,
+/// it cannot be subscribed to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventStage {
+    Submitted,
+    Running,
+    Complete,
+    Error(cl_int),
+}
+
+impl EventStage {
+    // The clSetEventCallback command_exec_callback_type for the stage.
+    fn callback_type(self) -> Option<cl_int> {
+        match self {
+            EventStage::Submitted => Some(CL_SUBMITTED),
+            EventStage::Running => Some(CL_RUNNING),
+            EventStage::Complete => Some(CL_COMPLETE),
+            EventStage::Error(_) => None,
+        }
+    }
+}
+
+impl From<cl_int> for EventStage {
+    fn from(status: cl_int) -> Self {
+        match status {
+            CL_SUBMITTED => EventStage::Submitted,
+            CL_RUNNING => EventStage::Running,
+            CL_COMPLETE => EventStage::Complete,
+            _ => EventStage::Error(status),
+        }
+    }
+}
+
+/// Register one Rust closure for several command execution stages.  
+/// Calls clSetEventCallback once for each stage; every registration shares
+/// the closure, which is dropped after the last registration has been called.
+/// OpenCL calls every registration exactly once: with its stage, or with
+/// EventStage::Error if the command terminated abnormally.
+///
+/// Note: OpenCL may call the closure from any thread and in any order.
+/// Drivers may report the Submitted and Running stages late, or only when the
+/// command completes, for fast commands; only Complete (or Error) is certain.
+///
+/// OpenCL cannot remove a callback, so if a registration fails the earlier
+/// registrations are rolled back by disabling them: they no longer call the
+/// closure, although a stage reported before the failure cannot be undone.
+///
+/// * `event` - the OpenCL event.
+/// * `stages` - the stages to subscribe to, not empty and not Error.
+/// * `f` - the closure to call with each stage.
+///
+/// returns an empty Result
+/// or CL_INVALID_VALUE if `stages` is empty or contains Error
+/// or the error code from the OpenCL C API function.
+pub fn subscribe_event_stages<F>(event: cl_event, stages: &[EventStage], f: F) -> Result<(), cl_int>
+where
+    F: Fn(EventStage) + Send + Sync + 'static,
+{
+    subscribe_stages_with(stages, f, |callback_type, callback| {
+        set_event_callback_closure(event, callback_type, callback)
+    })
+}
+
+// subscribe_event_stages with the registration function as a parameter.
+fn subscribe_stages_with<F, R>(stages: &[EventStage], f: F, mut register: R) -> Result<(), cl_int>
+where
+    F: Fn(EventStage) + Send + Sync + 'static,
+    R: FnMut(cl_int, EventCallback) -> Result<(), cl_int>,
+{
+    let callback_types = stages
+        .iter()
+        .map(|stage| stage.callback_type())
+        .collect::<Option<Vec<cl_int>>>()
+        .ok_or(CL_INVALID_VALUE)?;
+    if callback_types.is_empty() {
+        return Err(CL_INVALID_VALUE);
+    }
+
+    // The closure and whether the registrations are enabled.
+    let subscription = Arc::new((f, AtomicBool::new(true)));
+    for callback_type in callback_types {
+        let registration = Arc::clone(&subscription);
+        let callback: EventCallback = Box::new(move |_, status| {
+            let (f, enabled) = &*registration;
+            if enabled.load(Ordering::Acquire) {
+                f(EventStage::from(status));
+            }
+        });
+        if let Err(e) = register(callback_type, callback) {
+            subscription.1.store(false, Ordering::Release);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
 /// Get profiling data about an OpenCL event.
 /// Calls clGetEventProfilingInfo to get the desired profiling data about the event.
 pub fn get_event_profiling_data(
@@ -273,6 +440,59 @@ pub fn get_event_profiling_info(
     }
 }
 
+/// The elapsed time in nanoseconds between two profiling timestamps.
+fn profiling_interval(
+    event: cl_event,
+    from: ProfilingInfo,
+    to: ProfilingInfo,
+) -> Result<cl_ulong, cl_int> {
+    let start = get_event_profiling_info(event, from)?.to_ulong();
+    let end = get_event_profiling_info(event, to)?.to_ulong();
+    if start > end {
+        Err(CL_PROFILING_INFO_NOT_AVAILABLE)
+    } else {
+        Ok(end - start)
+    }
+}
+
+/// Get the execution time of the command associated with an event when
+/// profiling is enabled.  
+/// Calls clGetEventProfilingInfo to get CL_PROFILING_COMMAND_START and
+/// CL_PROFILING_COMMAND_END.
+///
+/// * `event` - the OpenCL event.
+///
+/// returns a Result containing the elapsed time in nanoseconds
+/// or CL_PROFILING_INFO_NOT_AVAILABLE if the start time is after the end time
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn profiling_command_duration(event: cl_event) -> Result<cl_ulong, cl_int> {
+    profiling_interval(
+        event,
+        ProfilingInfo::CL_PROFILING_COMMAND_START,
+        ProfilingInfo::CL_PROFILING_COMMAND_END,
+    )
+}
+
+/// Get the time from when the command associated with an event was enqueued
+/// until it finished executing, when profiling is enabled.  
+/// Calls clGetEventProfilingInfo to get CL_PROFILING_COMMAND_QUEUED and
+/// CL_PROFILING_COMMAND_END.
+///
+/// * `event` - the OpenCL event.
+///
+/// returns a Result containing the elapsed time in nanoseconds
+/// or CL_PROFILING_INFO_NOT_AVAILABLE if the queued time is after the end time
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn profiling_queued_to_end(event: cl_event) -> Result<cl_ulong, cl_int> {
+    profiling_interval(
+        event,
+        ProfilingInfo::CL_PROFILING_COMMAND_QUEUED,
+        ProfilingInfo::CL_PROFILING_COMMAND_END,
+    )
+}
+
 pub fn status_text(status: cl_int) -> &'static str {
     match status {
         CL_COMPLETE => "CL_COMPLETE",
@@ -283,24 +503,217 @@ pub fn status_text(status: cl_int) -> &'static str {
     }
 }
 
-#[derive(Debug)]
-/// CommandExecutionStatus is a newtype around the OpenCL command execution status
-pub struct CommandExecutionStatus(pub cl_int);
+/// The execution status of the command associated with an OpenCL event.  
+/// A negative status is an error code indicating that the command terminated
+/// abnormally.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandExecutionStatus {
+    Complete,
+    Running,
+    Submitted,
+    Queued,
+    /// The command terminated abnormally with the error code.
+    Error(cl_int),
+    /// A positive value that is not a valid command execution status.
+    Unknown(cl_int),
+}
+
+impl CommandExecutionStatus {
+    /// Whether the command has finished, either successfully or abnormally.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            CommandExecutionStatus::Complete | CommandExecutionStatus::Error(_)
+        )
+    }
+}
 
 /// Implement the From trait
 impl From<cl_int> for CommandExecutionStatus {
     fn from(status: cl_int) -> Self {
-        CommandExecutionStatus(status)
+        match status {
+            CL_COMPLETE => CommandExecutionStatus::Complete,
+            CL_RUNNING => CommandExecutionStatus::Running,
+            CL_SUBMITTED => CommandExecutionStatus::Submitted,
+            CL_QUEUED => CommandExecutionStatus::Queued,
+            _ if status < 0 => CommandExecutionStatus::Error(status),
+            _ => CommandExecutionStatus::Unknown(status),
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<CommandExecutionStatus> for cl_int {
+    fn from(status: CommandExecutionStatus) -> Self {
+        match status {
+            CommandExecutionStatus::Complete => CL_COMPLETE,
+            CommandExecutionStatus::Running => CL_RUNNING,
+            CommandExecutionStatus::Submitted => CL_SUBMITTED,
+            CommandExecutionStatus::Queued => CL_QUEUED,
+            CommandExecutionStatus::Error(status) | CommandExecutionStatus::Unknown(status) => {
+                status
+            }
+        }
     }
 }
 
 /// Implement the Display trait
 impl fmt::Display for CommandExecutionStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", status_text(self.0))
+        match self {
+            CommandExecutionStatus::Error(status) => write!(f, "{}", error_text(*status)),
+            _ => write!(f, "{}", status_text(cl_int::from(*self))),
+        }
     }
 }
 
+/// Get the execution status of the command associated with an OpenCL event.  
+/// Calls clGetEventInfo to get CL_EVENT_COMMAND_EXECUTION_STATUS.
+///
+/// * `event` - the OpenCL event.
+///
+/// returns a Result containing the CommandExecutionStatus
+/// or the error code from the OpenCL C API function.
+pub fn get_event_command_execution_status(
+    event: cl_event,
+) -> Result<CommandExecutionStatus, cl_int> {
+    let status = get_event_info(event, EventInfo::CL_EVENT_COMMAND_EXECUTION_STATUS)?;
+    Ok(CommandExecutionStatus::from(status.to_int()))
+}
+
+/// A receiver of the final execution status of an event, see event_notify_all.
+pub enum NotifySink {
+    /// Send the status on a channel.
+    Channel(Sender<cl_int>),
+    /// Call a closure with the status.
+    Callback(Box<dyn FnOnce(cl_int) + Send>),
+    /// Wake a task, e.g. a future waiting for the event.
+    Waker(Waker),
+}
+
+impl NotifySink {
+    /// Notify the sink of the final execution status of an event:
+    /// CL_COMPLETE or a negative error code.
+    pub fn notify(self, status: cl_int) {
+        match self {
+            // The receiver may have been dropped, which is not an error.
+            NotifySink::Channel(sender) => sender.send(status).unwrap_or(()),
+            NotifySink::Callback(f) => f(status),
+            NotifySink::Waker(waker) => waker.wake(),
+        }
+    }
+}
+
+/// Implement the Debug trait
+impl fmt::Debug for NotifySink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotifySink::Channel(_) => write!(f, "Channel"),
+            NotifySink::Callback(_) => write!(f, "Callback"),
+            NotifySink::Waker(_) => write!(f, "Waker"),
+        }
+    }
+}
+
+// The sinks of event_notify_all, shared by the event callback and the status
+// check after the callback is registered; whichever is first notifies them.
+struct NotifySinks(Mutex<Option<Vec<NotifySink>>>);
+
+impl NotifySinks {
+    fn notify(&self, status: cl_int) {
+        let sinks = self.0.lock().unwrap_or_else(|e| e.into_inner()).take();
+        for sink in sinks.into_iter().flatten() {
+            // A panicking sink must not prevent the other sinks from being notified.
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| sink.notify(status)));
+        }
+    }
+}
+
+/// Notify several sinks when an event completes.  
+/// Calls clSetEventCallback once for CL_COMPLETE, then clGetEventInfo in case
+/// the event had already completed. Each sink is notified exactly once with
+/// the final execution status: CL_COMPLETE, or a negative error code if the
+/// command terminated abnormally.
+///
+/// Note: the sinks may be notified from any thread, including an OpenCL
+/// implementation thread, so Callback closures should return quickly.
+///
+/// * `event` - the OpenCL event.
+/// * `sinks` - the sinks to notify, not empty.
+///
+/// returns an empty Result
+/// or CL_INVALID_VALUE if `sinks` is empty
+/// or the error code from the OpenCL C API function, in which case the
+/// sinks are dropped without being notified.
+pub fn event_notify_all(event: cl_event, sinks: Vec<NotifySink>) -> Result<(), cl_int> {
+    if sinks.is_empty() {
+        return Err(CL_INVALID_VALUE);
+    }
+
+    let sinks = Arc::new(NotifySinks(Mutex::new(Some(sinks))));
+    let callback_sinks = Arc::clone(&sinks);
+    set_event_callback_closure(
+        event,
+        CL_COMPLETE,
+        Box::new(move |_, status| callback_sinks.notify(status)),
+    )?;
+
+    // OpenCL calls the callback when it is registered after the event has
+    // completed, but notify the sinks now in case the implementation does not.
+    if let Ok(status) = get_event_command_execution_status(event) {
+        if status.is_finished() {
+            sinks.notify(cl_int::from(status));
+        }
+    }
+    Ok(())
+}
+
+/// The type of a closure called by fire_and_forget when a command terminates
+/// abnormally, with the negative error code.
+pub type EventErrorCallback = Box<dyn FnOnce(cl_int) + Send>;
+
+/// Enqueue a command and release its event, for commands whose event is not
+/// needed. Forgetting to release an unused event leaks it.  
+/// Calls `enqueue`, then clSetEventCallback for CL_COMPLETE if `on_error` is
+/// given, and then clReleaseEvent.
+///
+/// Note: OpenCL keeps the event until the command has completed and its
+/// callbacks have been called, so `on_error` is still called after the event
+/// is released here.
+///
+/// * `enqueue` - a closure that enqueues the command and returns its event,
+///   e.g. calling enqueue_write_buffer.
+/// * `on_error` - an optional closure to call if the command terminates
+///   abnormally, e.g. to log the error code.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+pub fn fire_and_forget<F>(enqueue: F, on_error: Option<EventErrorCallback>) -> Result<(), cl_int>
+where
+    F: FnOnce() -> Result<cl_event, cl_int>,
+{
+    let event = enqueue()?;
+    let registered = match on_error {
+        Some(on_error) => {
+            let mut on_error = Some(on_error);
+            set_event_callback_closure(
+                event,
+                CL_COMPLETE,
+                Box::new(move |_, status| {
+                    if status < 0 {
+                        if let Some(on_error) = on_error.take() {
+                            on_error(status);
+                        }
+                    }
+                }),
+            )
+        }
+        None => Ok(()),
+    };
+    // Release the event even if the callback could not be registered.
+    release_event(event)?;
+    registered
+}
+
 pub fn command_type_text(command_type: cl_command_type) -> &'static str {
     match command_type {
         CL_COMMAND_NDRANGE_KERNEL => "CL_COMMAND_NDRANGE_KERNEL",
@@ -371,6 +784,12 @@ impl fmt::Display for EventCommandType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
+    use crate::error_codes::CL_OUT_OF_RESOURCES;
+    use crate::platform::get_platform_ids;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::Wake;
 
     #[test]
     fn test_status_text() {
@@ -390,6 +809,225 @@ mod tests {
         assert_eq!("UNKNOWN_STATUS", text);
     }
 
+    #[test]
+    fn test_user_event() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let event = create_user_event(context).unwrap();
+        let value = get_event_command_execution_status(event).unwrap();
+        println!("user event status: {}", value);
+        assert_eq!(CommandExecutionStatus::Submitted, value);
+
+        set_user_event_status(event, CL_COMPLETE).unwrap();
+        let value = get_event_command_execution_status(event).unwrap();
+        println!("user event status: {}", value);
+        assert_eq!(CommandExecutionStatus::Complete, value);
+
+        release_event(event).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_subscribe_event_stages() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let event = create_user_event(context).unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let sender = std::sync::Mutex::new(sender);
+        let stages = [EventStage::Running, EventStage::Complete];
+        subscribe_event_stages(event, &stages, move |stage| {
+            sender.lock().unwrap().send(stage).unwrap();
+        })
+        .unwrap();
+
+        set_user_event_status(event, CL_COMPLETE).unwrap();
+        let timeout = std::time::Duration::from_secs(5);
+        let received: Vec<EventStage> = (0..stages.len())
+            .filter_map(|_| receiver.recv_timeout(timeout).ok())
+            .collect();
+        println!("event stages: {:?}", received);
+        assert!(received.contains(&EventStage::Complete));
+
+        release_event(event).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_event_stage() {
+        assert_eq!(EventStage::Complete, EventStage::from(CL_COMPLETE));
+        assert_eq!(EventStage::Running, EventStage::from(CL_RUNNING));
+        assert_eq!(EventStage::Submitted, EventStage::from(CL_SUBMITTED));
+        assert_eq!(
+            EventStage::Error(CL_OUT_OF_RESOURCES),
+            EventStage::from(CL_OUT_OF_RESOURCES)
+        );
+
+        // Invalid stages are rejected before calling OpenCL, dropping the closure
+        let counter = Arc::new(());
+        let captured = Arc::clone(&counter);
+        let result = subscribe_event_stages(
+            ptr::null_mut(),
+            &[EventStage::Complete, EventStage::Error(-1)],
+            move |_| assert!(0 < Arc::strong_count(&captured)),
+        );
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+        assert_eq!(1, Arc::strong_count(&counter));
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            subscribe_event_stages(ptr::null_mut(), &[], |_| ())
+        );
+    }
+
+    #[test]
+    fn test_subscribe_stages_rollback() {
+        // The second registration fails, so the first must not call the closure
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let mut registered: Vec<EventCallback> = Vec::new();
+        let stages = [
+            EventStage::Running,
+            EventStage::Complete,
+            EventStage::Submitted,
+        ];
+        let result = subscribe_stages_with(
+            &stages,
+            move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            },
+            |callback_type, callback| {
+                if CL_COMPLETE == callback_type {
+                    Err(CL_OUT_OF_RESOURCES)
+                } else {
+                    registered.push(callback);
+                    Ok(())
+                }
+            },
+        );
+        assert_eq!(Err(CL_OUT_OF_RESOURCES), result);
+        assert_eq!(1, registered.len());
+
+        // OpenCL still calls the first registration
+        for mut callback in registered {
+            callback(ptr::null_mut(), CL_RUNNING);
+        }
+        assert_eq!(0, calls.load(Ordering::SeqCst));
+        assert_eq!(1, Arc::strong_count(&calls));
+    }
+
+    // A waker that counts how many times it is woken.
+    struct CountWaker(AtomicUsize);
+
+    impl Wake for CountWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_notify_sinks() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let called = Arc::new(AtomicUsize::new(0));
+        let callback_called = Arc::clone(&called);
+        let waker = Arc::new(CountWaker(AtomicUsize::new(0)));
+        let sinks = NotifySinks(Mutex::new(Some(vec![
+            NotifySink::Channel(sender),
+            NotifySink::Callback(Box::new(|_| panic!("sink panic"))),
+            NotifySink::Callback(Box::new(move |status| {
+                assert_eq!(CL_OUT_OF_RESOURCES, status);
+                callback_called.fetch_add(1, Ordering::SeqCst);
+            })),
+            NotifySink::Waker(Waker::from(Arc::clone(&waker))),
+        ])));
+
+        // The sinks are notified once, even if one of them panics
+        sinks.notify(CL_OUT_OF_RESOURCES);
+        sinks.notify(CL_COMPLETE);
+        assert_eq!(
+            vec![CL_OUT_OF_RESOURCES],
+            receiver.iter().collect::<Vec<cl_int>>()
+        );
+        assert_eq!(1, called.load(Ordering::SeqCst));
+        assert_eq!(1, waker.0.load(Ordering::SeqCst));
+
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            event_notify_all(ptr::null_mut(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_event_notify_all() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let timeout = std::time::Duration::from_secs(5);
+
+        let event = create_user_event(context).unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let callback_sender = sender.clone();
+        let waker = Arc::new(CountWaker(AtomicUsize::new(0)));
+        let sinks = vec![
+            NotifySink::Channel(sender.clone()),
+            NotifySink::Callback(Box::new(move |status| {
+                callback_sender.send(status).unwrap()
+            })),
+            NotifySink::Waker(Waker::from(Arc::clone(&waker))),
+        ];
+
+        // Registered before the event completes
+        event_notify_all(event, sinks).unwrap();
+        set_user_event_status(event, CL_COMPLETE).unwrap();
+        assert_eq!(CL_COMPLETE, receiver.recv_timeout(timeout).unwrap());
+        assert_eq!(CL_COMPLETE, receiver.recv_timeout(timeout).unwrap());
+
+        // Registered after the event completed
+        event_notify_all(event, vec![NotifySink::Channel(sender)]).unwrap();
+        assert_eq!(CL_COMPLETE, receiver.recv_timeout(timeout).unwrap());
+
+        // Each sink is notified exactly once
+        assert!(receiver.recv_timeout(timeout / 10).is_err());
+        assert_eq!(1, waker.0.load(Ordering::SeqCst));
+
+        release_event(event).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_set_user_event_status_invalid() {
+        // Invalid statuses are rejected before calling OpenCL
+        let event = ptr::null_mut();
+        assert_eq!(Err(CL_INVALID_VALUE), set_user_event_status(event, CL_RUNNING));
+        assert_eq!(Err(CL_INVALID_VALUE), set_user_event_status(event, CL_SUBMITTED));
+        assert_eq!(Err(CL_INVALID_VALUE), set_user_event_status(event, CL_QUEUED));
+        assert_eq!(Err(CL_INVALID_VALUE), set_user_event_status(event, 42));
+    }
+
+    #[test]
+    fn test_command_execution_status() {
+        let status = CommandExecutionStatus::from(CL_COMPLETE);
+        assert_eq!(CommandExecutionStatus::Complete, status);
+        assert!(status.is_finished());
+        assert_eq!("CL_COMPLETE", status.to_string());
+
+        let status = CommandExecutionStatus::from(CL_QUEUED);
+        assert_eq!(CommandExecutionStatus::Queued, status);
+        assert!(!status.is_finished());
+        assert_eq!(CL_QUEUED, cl_int::from(status));
+
+        let status = CommandExecutionStatus::from(CL_OUT_OF_RESOURCES);
+        assert_eq!(CommandExecutionStatus::Error(CL_OUT_OF_RESOURCES), status);
+        assert!(status.is_finished());
+        assert_eq!("CL_OUT_OF_RESOURCES", status.to_string());
+
+        let status = CommandExecutionStatus::from(CL_QUEUED + 1);
+        assert_eq!(CommandExecutionStatus::Unknown(CL_QUEUED + 1), status);
+        assert_eq!("UNKNOWN_STATUS", status.to_string());
+    }
+
     #[test]
     fn test_command_type_text() {
         let text = command_type_text(CL_COMMAND_NDRANGE_KERNEL);
@@ -410,4 +1048,43 @@ mod tests {
         let text = command_type_text(CL_COMMAND_SVM_MIGRATE_MEM + 1);
         assert_eq!("UNKNOWN_COMMAND_TYPE", text);
     }
+
+    #[test]
+    fn test_fire_and_forget() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        // Discarded events do not increase the reference count
+        let event = create_user_event(context).unwrap();
+        let count = || {
+            get_event_info(event, EventInfo::CL_EVENT_REFERENCE_COUNT)
+                .unwrap()
+                .to_uint()
+        };
+        let before = count();
+        for _ in 0..100 {
+            fire_and_forget(|| retain_event(event).map(|_| event), None).unwrap();
+        }
+        assert_eq!(before, count());
+
+        // An abnormal completion is still reported after the event is released
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let on_error: EventErrorCallback = Box::new(move |status| sender.send(status).unwrap());
+        fire_and_forget(|| retain_event(event).map(|_| event), Some(on_error)).unwrap();
+        set_user_event_status(event, CL_OUT_OF_RESOURCES).unwrap();
+        let status = receiver
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(CL_OUT_OF_RESOURCES, status);
+
+        // An enqueue error is returned without an event to release
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            fire_and_forget(|| Err(CL_INVALID_VALUE), None)
+        );
+
+        release_event(event).unwrap();
+        release_context(context).unwrap();
+    }
 }
@@ -0,0 +1,417 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! C callable functions for the convenience functions of this crate.
+//! Requires the `ffi_export` feature.
+//!
+//! The functions follow the OpenCL C API conventions: they return an OpenCL
+//! error code, CL_SUCCESS on success, and return their results through
+//! pointer arguments. Null pointer arguments return CL_INVALID_VALUE.
+//!
+//! Strings returned by these functions are allocated by Rust and are owned
+//! by the caller, who must free them with `cl3_free_string`.
+//! They must not be freed with the C `free` function.
+//!
+//! A panic does not unwind into the caller: the function returns
+//! CL_OUT_OF_HOST_MEMORY instead.
+
+#![allow(non_camel_case_types)]
+
+use super::device::estimate_peak_gflops;
+use super::error_codes::{CL_INVALID_VALUE, CL_OUT_OF_HOST_MEMORY, CL_SUCCESS};
+use super::program::{build_program, get_program_build_info, ProgramBuildInfo};
+use super::snapshot::DeviceSnapshot;
+use super::types::{cl_device_id, cl_int, cl_program, cl_uint};
+
+use libc::c_char;
+use std::ffi::{CStr, CString};
+use std::fmt::Write;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+/// A device and its estimated peak GFLOPS, see `rank_devices`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct cl3_ranked_device {
+    pub device: cl_device_id,
+    pub gflops: f64,
+}
+
+// Call f, returning CL_OUT_OF_HOST_MEMORY if it panics, since a panic must
+// not unwind into C code.
+fn catch_panic<F: FnOnce() -> cl_int>(f: F) -> cl_int {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(CL_OUT_OF_HOST_MEMORY)
+}
+
+// Transfer ownership of a Rust string to the caller.
+fn into_c_string(text: String) -> *mut c_char {
+    // Interior nul characters cannot be represented in a C string.
+    let text = text.replace('\0', "");
+    CString::new(text).unwrap().into_raw()
+}
+
+// Escape a string for a JSON string value.
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                write!(escaped, "\\u{:04x}", c as u32).unwrap();
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Format a number for a JSON value, JSON has no NaN or infinity.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::from("null")
+    }
+}
+
+fn device_report_json(device: cl_device_id) -> Result<String, cl_int> {
+    let snapshot = DeviceSnapshot::capture(device)?;
+    let estimate = estimate_peak_gflops(device)?;
+    Ok(format!(
+        "{{\"name\":\"{}\",\"version\":\"{}\",\"device_type\":{},\"max_compute_units\":{},\"max_clock_frequency\":{},\"peak_gflops\":{}}}",
+        json_escape(&snapshot.name),
+        json_escape(&snapshot.version),
+        snapshot.device_type,
+        snapshot.max_compute_units,
+        estimate.max_clock_frequency,
+        json_number(estimate.gflops)
+    ))
+}
+
+/// Get a JSON report of the main attributes of an OpenCL device.
+/// Calls clGetDeviceInfo.
+///
+/// * `device` - a valid OpenCL device.
+/// * `json` - set to a nul terminated JSON object on success, which must be
+/// freed with `cl3_free_string`.
+///
+/// returns CL_SUCCESS or the error code from the OpenCL C API function.
+///
+/// # Safety
+///
+/// `json` must be null or point to writable memory for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn cl3_get_device_report_json(
+    device: cl_device_id,
+    json: *mut *mut c_char,
+) -> cl_int {
+    if json.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    *json = ptr::null_mut();
+    catch_panic(|| match device_report_json(device) {
+        Ok(text) => {
+            *json = into_c_string(text);
+            CL_SUCCESS
+        }
+        Err(e) => e,
+    })
+}
+
+/// Build a program for a device and get its build log.
+/// Calls clBuildProgram and clGetProgramBuildInfo.
+///
+/// * `program` - a valid OpenCL program.
+/// * `device` - the device to build the program for.
+/// * `options` - a nul terminated string of build options, may be null.
+/// * `log` - set to the nul terminated build log, which must be freed with
+/// `cl3_free_string`. The log is also returned when the build fails.
+///
+/// returns CL_SUCCESS or the error code from the OpenCL C API function,
+/// e.g. CL_BUILD_PROGRAM_FAILURE.
+///
+/// # Safety
+///
+/// `options` must be null or a nul terminated string and `log` must be null
+/// or point to writable memory for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn cl3_build_program_log(
+    program: cl_program,
+    device: cl_device_id,
+    options: *const c_char,
+    log: *mut *mut c_char,
+) -> cl_int {
+    if log.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    *log = ptr::null_mut();
+
+    catch_panic(|| {
+        let empty = CString::default();
+        let options = if options.is_null() {
+            empty.as_c_str()
+        } else {
+            CStr::from_ptr(options)
+        };
+        let status = match build_program(program, &[device], options, None, ptr::null_mut()) {
+            Ok(_) => CL_SUCCESS,
+            Err(e) => e,
+        };
+
+        match get_program_build_info(program, device, ProgramBuildInfo::CL_PROGRAM_BUILD_LOG) {
+            Ok(value) => {
+                *log = into_c_string(value.to_string());
+                status
+            }
+            Err(e) => {
+                if CL_SUCCESS == status {
+                    e
+                } else {
+                    status
+                }
+            }
+        }
+    })
+}
+
+// The first device with the highest estimated peak GFLOPS.
+fn best_ranked_device(devices: &[cl_device_id]) -> Result<cl3_ranked_device, cl_int> {
+    let mut best: Option<cl3_ranked_device> = None;
+    for &device in devices {
+        let gflops = estimate_peak_gflops(device)?.gflops;
+        match best {
+            Some(b) if gflops <= b.gflops => {}
+            _ => best = Some(cl3_ranked_device { device, gflops }),
+        }
+    }
+    best.ok_or(CL_INVALID_VALUE)
+}
+
+/// Select the device with the highest estimated peak GFLOPS, see `estimate_peak_gflops`.
+/// Calls clGetDeviceInfo for each device.
+///
+/// * `devices` - an array of OpenCL devices.
+/// * `num_devices` - the number of devices in the array, must be greater than zero.
+/// * `best` - set to the device with the highest estimate and its estimate.
+///
+/// returns CL_SUCCESS or CL_INVALID_VALUE if an argument is invalid
+/// or the error code from the OpenCL C API function for the first device that
+/// cannot be queried.
+///
+/// # Safety
+///
+/// `devices` must point to `num_devices` devices and `best` must be null or
+/// point to writable memory for a cl3_ranked_device.
+#[no_mangle]
+pub unsafe extern "C" fn cl3_select_best_device(
+    devices: *const cl_device_id,
+    num_devices: cl_uint,
+    best: *mut cl3_ranked_device,
+) -> cl_int {
+    if devices.is_null() || 0 == num_devices || best.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    let devices = slice::from_raw_parts(devices, num_devices as usize);
+    catch_panic(|| match best_ranked_device(devices) {
+        Ok(ranked) => {
+            *best = ranked;
+            CL_SUCCESS
+        }
+        Err(e) => e,
+    })
+}
+
+/// Free a string returned by a cl3 function.
+/// It does nothing if `text` is null.
+///
+/// # Safety
+///
+/// `text` must be null or a string returned by a cl3 function that has not
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn cl3_free_string(text: *mut c_char) {
+    if !text.is_null() {
+        let _ = catch_unwind(|| drop(CString::from_raw(text)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
+    use crate::error_codes::{error_text, CL_BUILD_PROGRAM_FAILURE};
+    use crate::platform::get_platform_ids;
+    use crate::program::{create_program_with_source, release_program};
+
+    // The exported functions as declared to C.
+    extern "C" {
+        #[link_name = "cl3_get_device_report_json"]
+        fn c_get_device_report_json(device: cl_device_id, json: *mut *mut c_char) -> cl_int;
+        #[link_name = "cl3_build_program_log"]
+        fn c_build_program_log(
+            program: cl_program,
+            device: cl_device_id,
+            options: *const c_char,
+            log: *mut *mut c_char,
+        ) -> cl_int;
+        #[link_name = "cl3_select_best_device"]
+        fn c_select_best_device(
+            devices: *const cl_device_id,
+            num_devices: cl_uint,
+            best: *mut cl3_ranked_device,
+        ) -> cl_int;
+        #[link_name = "cl3_free_string"]
+        fn c_free_string(text: *mut c_char);
+    }
+
+    #[test]
+    fn test_json_escape() {
+        assert_eq!("plain", json_escape("plain"));
+        assert_eq!("a\\\"b\\\\c", json_escape("a\"b\\c"));
+        assert_eq!("x\\ny\\u0001", json_escape("x\ny\u{1}"));
+    }
+
+    #[test]
+    fn test_json_number() {
+        assert_eq!("1.5", json_number(1.5));
+        assert_eq!("null", json_number(f64::NAN));
+        assert_eq!("null", json_number(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(CL_SUCCESS, catch_panic(|| CL_SUCCESS));
+        assert_eq!(
+            CL_OUT_OF_HOST_MEMORY,
+            catch_panic(|| panic!("panic in export"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        unsafe {
+            assert_eq!(
+                CL_INVALID_VALUE,
+                c_get_device_report_json(ptr::null_mut(), ptr::null_mut())
+            );
+
+            let mut best = cl3_ranked_device {
+                device: ptr::null_mut(),
+                gflops: 0.0,
+            };
+            assert_eq!(
+                CL_INVALID_VALUE,
+                c_build_program_log(
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null(),
+                    ptr::null_mut()
+                )
+            );
+            assert_eq!(
+                CL_INVALID_VALUE,
+                c_select_best_device(ptr::null(), 1, &mut best)
+            );
+            let devices = [ptr::null_mut()];
+            assert_eq!(
+                CL_INVALID_VALUE,
+                c_select_best_device(devices.as_ptr(), 0, &mut best)
+            );
+            assert_eq!(
+                CL_INVALID_VALUE,
+                c_select_best_device(devices.as_ptr(), 1, ptr::null_mut())
+            );
+
+            // Freeing a null string does nothing
+            c_free_string(ptr::null_mut());
+
+            // A string returned by a cl3 function is freed by cl3_free_string
+            let text = into_c_string(String::from("a\0b"));
+            assert_eq!("ab", CStr::from_ptr(text).to_str().unwrap());
+            c_free_string(text);
+        }
+    }
+
+    #[test]
+    fn test_device_report_json() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+
+        unsafe {
+            let mut best = cl3_ranked_device {
+                device: ptr::null_mut(),
+                gflops: 0.0,
+            };
+            let status =
+                c_select_best_device(device_ids.as_ptr(), device_ids.len() as cl_uint, &mut best);
+            assert_eq!(CL_SUCCESS, status);
+            assert!(device_ids.contains(&best.device));
+            println!("Best device peak GFLOPS: {}", best.gflops);
+
+            let mut json: *mut c_char = ptr::null_mut();
+            let status = c_get_device_report_json(best.device, &mut json);
+            if CL_SUCCESS == status {
+                let text = CStr::from_ptr(json).to_str().unwrap();
+                println!("Device report: {}", text);
+                assert!(text.starts_with('{') && text.ends_with('}'));
+                c_free_string(json);
+            } else {
+                assert!(json.is_null());
+                println!(
+                    "OpenCL error, cl3_get_device_report_json: {}",
+                    error_text(status)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_program_log() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device = device_ids[0];
+        let context = create_context(&[device], ptr::null(), None, ptr::null_mut()).unwrap();
+
+        unsafe {
+            // The log is returned both when the build succeeds and when it fails
+            let source = "kernel void ok(global int* x) { x[0] = 1; }";
+            let program = create_program_with_source(context, &[source]).unwrap();
+            let options = CString::new("-Werror").unwrap();
+            let mut log: *mut c_char = ptr::null_mut();
+            let status = c_build_program_log(program, device, options.as_ptr(), &mut log);
+            assert_eq!(CL_SUCCESS, status);
+            assert!(!log.is_null());
+            c_free_string(log);
+            release_program(program).unwrap();
+
+            let source = "kernel void broken(global int* x) { x[0] = ; }";
+            let program = create_program_with_source(context, &[source]).unwrap();
+            let mut log: *mut c_char = ptr::null_mut();
+            let status = c_build_program_log(program, device, ptr::null(), &mut log);
+            assert_eq!(CL_BUILD_PROGRAM_FAILURE, status);
+            assert!(!log.is_null());
+            println!("Build log: {}", CStr::from_ptr(log).to_str().unwrap());
+            c_free_string(log);
+            release_program(program).unwrap();
+        }
+
+        release_context(context).unwrap();
+    }
+}
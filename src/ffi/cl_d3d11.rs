@@ -18,7 +18,7 @@
 
 #![allow(non_camel_case_types, non_upper_case_globals)]
 
-pub use cl_sys::{
+pub use crate::types::{
     cl_command_queue, cl_command_type, cl_context, cl_context_info, cl_device_id, cl_event,
     cl_image_info, cl_int, cl_mem, cl_mem_flags, cl_mem_info, cl_platform_id, cl_uint,
 };
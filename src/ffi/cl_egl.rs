@@ -17,7 +17,7 @@
 
 #![allow(non_camel_case_types, non_upper_case_globals)]
 
-pub use cl_sys::{
+pub use crate::types::{
     cl_command_queue, cl_command_type, cl_context, cl_event, cl_int, cl_mem, cl_mem_flags, cl_uint,
 };
 use libc::{c_void, intptr_t};
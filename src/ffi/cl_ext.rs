@@ -18,7 +18,7 @@
 
 #![allow(non_camel_case_types, non_upper_case_globals)]
 
-pub use cl_sys::{
+pub use crate::types::{
     cl_bitfield, cl_bool, cl_channel_type, cl_command_queue, cl_command_queue_properties,
     cl_command_type, cl_context, cl_device_id, cl_device_info, cl_event, cl_event_info,
     cl_image_format, cl_int, cl_kernel, cl_kernel_exec_info, cl_kernel_info, cl_map_flags, cl_mem,
@@ -50,6 +50,9 @@ pub const CL_CONTEXT_TERMINATED_KHR: cl_int = -1121;
 pub const CL_DEVICE_TERMINATE_CAPABILITY_KHR: cl_uint = 0x2031;
 pub const CL_CONTEXT_TERMINATE_KHR: cl_uint = 0x2032;
 
+pub type cl_device_terminate_capability_khr = cl_bitfield;
+pub const CL_DEVICE_TERMINATE_CAPABILITY_CONTEXT_KHR: cl_device_terminate_capability_khr = 1 << 0;
+
 pub const CL_DEVICE_SPIR_VERSIONS: cl_uint = 0x40E0;
 pub const CL_PROGRAM_BINARY_TYPE_INTERMEDIATE: cl_uint = 0x40E1;
 
@@ -315,7 +318,7 @@ pub const CL_DEVICE_LUID_KHR: cl_device_info = 0x106D;
 pub const CL_DEVICE_NODE_MASK_KHR: cl_device_info = 0x106E;
 
 // cl_khr_pci_bus_info
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 #[repr(C)]
 pub struct cl_device_pci_bus_info_khr {
     pub pci_domain: cl_uint,
@@ -336,6 +339,98 @@ pub const CL_DEVICE_INTEGER_DOT_PRODUCT_INPUT_4x8BIT_KHR:
 
 pub const CL_DEVICE_INTEGER_DOT_PRODUCT_CAPABILITIES_KHR: cl_device_info = 0x1073;
 
+// cl_khr_external_memory extension
+
+#[cfg(feature = "cl_khr_external_memory")]
+pub type cl_external_memory_handle_type_khr = cl_uint;
+
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_PLATFORM_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR: cl_platform_info = 0x2044;
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_DEVICE_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR: cl_device_info = 0x204F;
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_DEVICE_HANDLE_LIST_KHR: cl_uint = 0x2051;
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_DEVICE_HANDLE_LIST_END_KHR: cl_uint = 0;
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_COMMAND_ACQUIRE_EXTERNAL_MEM_OBJECTS_KHR: cl_command_type = 0x2047;
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_COMMAND_RELEASE_EXTERNAL_MEM_OBJECTS_KHR: cl_command_type = 0x2048;
+
+// cl_khr_external_memory_opaque_fd extension
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_FD_KHR: cl_external_memory_handle_type_khr = 0x2060;
+
+// cl_khr_external_memory_win32 extension
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_WIN32_KHR: cl_external_memory_handle_type_khr = 0x2061;
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_WIN32_KMT_KHR: cl_external_memory_handle_type_khr =
+    0x2062;
+
+// cl_khr_external_memory_dx extension
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_EXTERNAL_MEMORY_HANDLE_D3D11_TEXTURE_KHR: cl_external_memory_handle_type_khr = 0x2063;
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_EXTERNAL_MEMORY_HANDLE_D3D11_TEXTURE_KMT_KHR: cl_external_memory_handle_type_khr =
+    0x2064;
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_EXTERNAL_MEMORY_HANDLE_D3D12_HEAP_KHR: cl_external_memory_handle_type_khr = 0x2065;
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_EXTERNAL_MEMORY_HANDLE_D3D12_RESOURCE_KHR: cl_external_memory_handle_type_khr = 0x2066;
+
+// cl_khr_external_memory_dma_buf extension
+#[cfg(feature = "cl_khr_external_memory")]
+pub const CL_EXTERNAL_MEMORY_HANDLE_DMA_BUF_KHR: cl_external_memory_handle_type_khr = 0x2067;
+
+// cl_khr_semaphore extension
+
+#[cfg(feature = "cl_khr_semaphore")]
+pub type cl_semaphore_type_khr = cl_uint;
+
+#[cfg(feature = "cl_khr_semaphore")]
+pub const CL_PLATFORM_SEMAPHORE_TYPES_KHR: cl_platform_info = 0x2036;
+#[cfg(feature = "cl_khr_semaphore")]
+pub const CL_DEVICE_SEMAPHORE_TYPES_KHR: cl_device_info = 0x204C;
+#[cfg(feature = "cl_khr_semaphore")]
+pub const CL_SEMAPHORE_TYPE_BINARY_KHR: cl_semaphore_type_khr = 1;
+
+// cl_khr_external_semaphore extension
+
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub type cl_external_semaphore_handle_type_khr = cl_uint;
+
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_PLATFORM_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR: cl_platform_info = 0x2037;
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_PLATFORM_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR: cl_platform_info = 0x2038;
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_DEVICE_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR: cl_device_info = 0x204D;
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_DEVICE_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR: cl_device_info = 0x204E;
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR: cl_uint = 0x203F;
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_SEMAPHORE_EXPORT_HANDLE_TYPES_LIST_END_KHR: cl_uint = 0;
+
+// cl_khr_external_semaphore_opaque_fd extension
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_SEMAPHORE_HANDLE_OPAQUE_FD_KHR: cl_external_semaphore_handle_type_khr = 0x2055;
+
+// cl_khr_external_semaphore_win32 extension
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_SEMAPHORE_HANDLE_OPAQUE_WIN32_KHR: cl_external_semaphore_handle_type_khr = 0x2056;
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_SEMAPHORE_HANDLE_OPAQUE_WIN32_KMT_KHR: cl_external_semaphore_handle_type_khr = 0x2057;
+
+// cl_khr_external_semaphore_sync_fd extension
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_SEMAPHORE_HANDLE_SYNC_FD_KHR: cl_external_semaphore_handle_type_khr = 0x2058;
+
+// cl_khr_external_semaphore_dx_fence extension
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub const CL_SEMAPHORE_HANDLE_D3D12_FENCE_KHR: cl_external_semaphore_handle_type_khr = 0x2059;
+
 // cl_arm_import_memory extension
 
 pub type cl_import_properties_arm = intptr_t;
@@ -563,6 +658,19 @@ pub const CL_DEVICE_SUB_GROUP_SIZES_INTEL: cl_uint = 0x4108;
 pub const CL_KERNEL_SPILL_MEM_SIZE_INTEL: cl_uint = 0x4109;
 pub const CL_KERNEL_COMPILE_SUB_GROUP_SIZE_INTEL: cl_uint = 0x410A;
 
+// cl_intel_device_attribute_query extension
+pub type cl_device_feature_capabilities_intel = cl_bitfield;
+pub const CL_DEVICE_FEATURE_FLAG_DP4A_INTEL: cl_device_feature_capabilities_intel = 1 << 0;
+pub const CL_DEVICE_FEATURE_FLAG_DPAS_INTEL: cl_device_feature_capabilities_intel = 1 << 1;
+
+pub const CL_DEVICE_IP_VERSION_INTEL: cl_device_info = 0x4250;
+pub const CL_DEVICE_ID_INTEL: cl_device_info = 0x4251;
+pub const CL_DEVICE_NUM_SLICES_INTEL: cl_device_info = 0x4252;
+pub const CL_DEVICE_NUM_SUB_SLICES_PER_SLICE_INTEL: cl_device_info = 0x4253;
+pub const CL_DEVICE_NUM_EUS_PER_SUB_SLICE_INTEL: cl_device_info = 0x4254;
+pub const CL_DEVICE_NUM_THREADS_PER_EU_INTEL: cl_device_info = 0x4255;
+pub const CL_DEVICE_FEATURE_CAPABILITIES_INTEL: cl_device_info = 0x4256;
+
 // cl_intel_driver_diagnostics extension
 pub const CL_CONTEXT_SHOW_DIAGNOSTICS_INTEL: cl_uint = 0x4106;
 
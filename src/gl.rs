@@ -16,13 +16,15 @@
 
 #![allow(non_camel_case_types)]
 
-pub use cl_sys::{
+pub use super::types::{
     cl_command_queue, cl_context_properties, cl_event, cl_gl_context_info, cl_gl_object_type,
-    cl_gl_platform_info, cl_gl_texture_info, CL_CGL_SHAREGROUP_KHR, CL_EGL_DISPLAY_KHR,
-    CL_GLX_DISPLAY_KHR, CL_GL_CONTEXT_KHR, CL_GL_OBJECT_BUFFER, CL_GL_OBJECT_RENDERBUFFER,
-    CL_GL_OBJECT_TEXTURE1D, CL_GL_OBJECT_TEXTURE1D_ARRAY, CL_GL_OBJECT_TEXTURE2D,
-    CL_GL_OBJECT_TEXTURE2D_ARRAY, CL_GL_OBJECT_TEXTURE3D, CL_GL_OBJECT_TEXTURE_BUFFER,
-    CL_KHR_GL_SHARING, CL_WGL_HDC_KHR,
+    cl_gl_platform_info, cl_gl_texture_info,
+};
+pub use cl_sys::{
+    CL_CGL_SHAREGROUP_KHR, CL_EGL_DISPLAY_KHR, CL_GLX_DISPLAY_KHR, CL_GL_CONTEXT_KHR,
+    CL_GL_OBJECT_BUFFER, CL_GL_OBJECT_RENDERBUFFER, CL_GL_OBJECT_TEXTURE1D,
+    CL_GL_OBJECT_TEXTURE1D_ARRAY, CL_GL_OBJECT_TEXTURE2D, CL_GL_OBJECT_TEXTURE2D_ARRAY,
+    CL_GL_OBJECT_TEXTURE3D, CL_GL_OBJECT_TEXTURE_BUFFER, CL_KHR_GL_SHARING, CL_WGL_HDC_KHR,
 };
 
 use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
@@ -12,7 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::types::{cl_image_format, cl_int, cl_name_version, cl_uchar, cl_uint, cl_ulong};
+use crate::device::DevicePartitionProperty;
+use crate::ffi::cl_ext::{CL_LUID_SIZE_KHR, CL_UUID_SIZE_KHR};
+use crate::memory::{channel_order_text, channel_type_text};
+use crate::types::{
+    cl_device_id, cl_image_format, cl_int, cl_name_version, cl_uchar, cl_uint, cl_ulong,
+};
 use libc::{intptr_t, size_t};
 use std::fmt;
 
@@ -21,18 +26,23 @@ use std::fmt;
 /// The functions will panic if they are called for the incorrect type.
 #[derive(Debug)]
 pub enum InfoType {
+    Bool(bool),
     Int(cl_int),
     Uint(cl_uint),
     Ulong(cl_ulong),
     Size(size_t),
     Ptr(intptr_t),
     VecUchar(Vec<cl_uchar>),
+    VecUint(Vec<cl_uint>),
     VecUlong(Vec<cl_ulong>),
     VecSize(Vec<size_t>),
     VecIntPtr(Vec<intptr_t>),
+    VecPartitionProperty(Vec<DevicePartitionProperty>),
     VecNameVersion(Vec<cl_name_version>),
     VecImageFormat(Vec<cl_image_format>),
     VecVecUchar(Vec<Vec<cl_uchar>>),
+    Uuid([cl_uchar; CL_UUID_SIZE_KHR]),
+    Luid([cl_uchar; CL_LUID_SIZE_KHR]),
 }
 
 impl fmt::Display for InfoType {
@@ -62,9 +72,9 @@ impl fmt::Display for InfoType {
                 for b in a.iter() {
                     s.push_str("\n");
 
-                    s.push_str(&b.image_channel_order.to_string());
+                    s.push_str(channel_order_text(b.image_channel_order));
                     s.push_str(": ");
-                    s.push_str(&b.image_channel_data_type.to_string());
+                    s.push_str(channel_type_text(b.image_channel_data_type));
                 }
 
                 write!(f, "{}", s)
@@ -82,6 +92,28 @@ impl fmt::Display for InfoType {
                 write!(f, "{}", s)
             }
 
+            // UUIDs are displayed in the canonical 8-4-4-4-12 hex form.
+            InfoType::Uuid(a) => {
+                let mut s = String::default();
+                for (i, b) in a.iter().enumerate() {
+                    if 4 == i || 6 == i || 8 == i || 10 == i {
+                        s.push('-');
+                    }
+                    s.push_str(&format!("{:02x}", b));
+                }
+
+                write!(f, "{}", s)
+            }
+
+            InfoType::Luid(a) => {
+                let mut s = String::default();
+                for b in a.iter() {
+                    s.push_str(&format!("{:02x}", b));
+                }
+
+                write!(f, "{}", s)
+            }
+
             _ => panic!("not a Displayable type, use Debug instead"),
         }
     }
@@ -105,6 +137,46 @@ impl InfoType {
         String::from_utf8_lossy(&a).into_owned()
     }
 
+    /// Get a `Vec<cl_uchar>` aka `Vec<u8>` as a borrowed str, without copying.  
+    /// Trailing nulls are excluded from the str.
+    ///
+    /// returns the str or None if the type is not a `Vec<cl_uchar>` or it is
+    /// not valid utf8.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            InfoType::VecUchar(a) => {
+                let len = a.iter().rposition(|&c| 0 != c).map_or(0, |i| i + 1);
+                std::str::from_utf8(&a[..len]).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert a `Vec<cl_uchar>` aka `Vec<u8>` into a String, without copying.  
+    /// Trailing nulls are removed from the String.
+    ///
+    /// returns the String or None if the type is not a `Vec<cl_uchar>` or it is
+    /// not valid utf8.
+    pub fn into_string(self) -> Option<String> {
+        match self {
+            InfoType::VecUchar(mut a) => {
+                // remove all trailing nulls, if any
+                while let Some(0) = a.last() {
+                    a.pop();
+                }
+                String::from_utf8(a).ok()
+            }
+            _ => None,
+        }
+    }
+
+    pub fn to_bool(self) -> bool {
+        match self {
+            InfoType::Bool(a) => a,
+            _ => panic!("not a bool"),
+        }
+    }
+
     pub fn to_int(self) -> cl_int {
         match self {
             InfoType::Int(a) => a,
@@ -112,9 +184,13 @@ impl InfoType {
         }
     }
 
+    /// Before the `Bool` variant was added, cl_bool queries returned `Uint`.  
+    /// For compatibility, a `Bool` converts to CL_TRUE or CL_FALSE, but this is
+    /// deprecated: use `to_bool` for cl_bool queries.
     pub fn to_uint(self) -> cl_uint {
         match self {
             InfoType::Uint(a) => a,
+            InfoType::Bool(a) => a as cl_uint,
             _ => panic!("not a cl_uint"),
         }
     }
@@ -147,6 +223,13 @@ impl InfoType {
         }
     }
 
+    pub fn to_vec_uint(self) -> Vec<cl_uint> {
+        match self {
+            InfoType::VecUint(a) => a,
+            _ => panic!("not a Vec<cl_uint>"),
+        }
+    }
+
     pub fn to_vec_ulong(self) -> Vec<cl_ulong> {
         match self {
             InfoType::VecUlong(a) => a,
@@ -161,13 +244,36 @@ impl InfoType {
         }
     }
 
+    /// Get a `Vec<intptr_t>`, a CL_DEVICE_PARTITION_PROPERTIES value is
+    /// returned as the raw, zero terminated, property list.
     pub fn to_vec_intptr(self) -> Vec<intptr_t> {
         match self {
             InfoType::VecIntPtr(a) => a,
+            InfoType::VecPartitionProperty(a) => a
+                .into_iter()
+                .map(intptr_t::from)
+                .chain(std::iter::once(0))
+                .collect(),
+            _ => panic!("not a Vec<intptr_t>"),
+        }
+    }
+
+    /// Get a `Vec<intptr_t>` of OpenCL device ids, e.g. from CL_CONTEXT_DEVICES
+    /// or CL_PROGRAM_DEVICES, as a `Vec<cl_device_id>`.
+    pub fn to_vec_device_id(self) -> Vec<cl_device_id> {
+        match self {
+            InfoType::VecIntPtr(a) => a.into_iter().map(|id| id as cl_device_id).collect(),
             _ => panic!("not a Vec<intptr_t>"),
         }
     }
 
+    pub fn to_vec_partition_property(self) -> Vec<DevicePartitionProperty> {
+        match self {
+            InfoType::VecPartitionProperty(a) => a,
+            _ => panic!("not a Vec<DevicePartitionProperty>"),
+        }
+    }
+
     pub fn to_vec_name_version(self) -> Vec<cl_name_version> {
         match self {
             InfoType::VecNameVersion(a) => a,
@@ -188,6 +294,26 @@ impl InfoType {
             _ => panic!("not a Vec<Vec<cl_uchar>"),
         }
     }
+
+    pub fn to_uuid(self) -> [cl_uchar; CL_UUID_SIZE_KHR] {
+        match self {
+            InfoType::Uuid(a) => a,
+            _ => panic!("not a UUID"),
+        }
+    }
+
+    pub fn to_luid(self) -> [cl_uchar; CL_LUID_SIZE_KHR] {
+        match self {
+            InfoType::Luid(a) => a,
+            _ => panic!("not a LUID"),
+        }
+    }
+}
+
+impl From<InfoType> for bool {
+    fn from(info_type: InfoType) -> Self {
+        info_type.to_bool()
+    }
 }
 
 impl From<InfoType> for cl_int {
@@ -214,11 +340,118 @@ impl From<InfoType> for size_t {
     }
 }
 
+impl From<InfoType> for Vec<cl_device_id> {
+    fn from(info_type: InfoType) -> Self {
+        info_type.to_vec_device_id()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::InfoType;
     use crate::device::*;
+    use crate::memory::{CL_FLOAT, CL_R, CL_RGBA, CL_UNORM_INT8};
+    use crate::types::{cl_device_id, cl_image_format, CL_FALSE, CL_TRUE};
     use crate::platform::*;
 
+    #[test]
+    fn test_to_bool() {
+        assert!(InfoType::Bool(true).to_bool());
+        assert!(!InfoType::Bool(false).to_bool());
+        let value: bool = InfoType::Bool(true).into();
+        assert!(value);
+    }
+
+    #[test]
+    fn test_bool_to_uint() {
+        // cl_bool queries used to return Uint
+        assert_eq!(CL_TRUE, InfoType::Bool(true).to_uint());
+        assert_eq!(CL_FALSE, InfoType::Bool(false).to_uint());
+    }
+
+    #[test]
+    fn test_to_vec_device_id() {
+        let value = InfoType::VecIntPtr(vec![0x1000, 0x2000]);
+        let devices: Vec<cl_device_id> = value.into();
+        assert_eq!(2, devices.len());
+        assert_eq!(0x1000 as cl_device_id, devices[0]);
+        assert_eq!(0x2000 as cl_device_id, devices[1]);
+    }
+
+    #[test]
+    fn test_as_str_into_string() {
+        let value = InfoType::VecUchar(b"OpenCL 3.0\0\0".to_vec());
+        assert_eq!(Some("OpenCL 3.0"), value.as_str());
+        assert_eq!(Some("OpenCL 3.0".to_string()), value.into_string());
+
+        let value = InfoType::VecUchar(vec![0]);
+        assert_eq!(Some(""), value.as_str());
+        assert_eq!(Some(String::default()), value.into_string());
+
+        // Not valid utf8
+        let value = InfoType::VecUchar(vec![b'O', 0xff, 0xfe, 0]);
+        assert_eq!(None, value.as_str());
+        assert_eq!(None, value.into_string());
+
+        let value = InfoType::Uint(1);
+        assert_eq!(None, value.as_str());
+        assert_eq!(None, value.into_string());
+    }
+
+    #[test]
+    fn test_display_uuid_luid() {
+        let uuid: [u8; 16] = [
+            0x12, 0x3e, 0x45, 0x67, 0xe8, 0x9b, 0x12, 0xd3, 0xa4, 0x56, 0x42, 0x66, 0x14, 0x17,
+            0x40, 0x00,
+        ];
+        let value = InfoType::Uuid(uuid);
+        assert_eq!("123e4567-e89b-12d3-a456-426614174000", format!("{}", value));
+
+        let luid: [u8; 8] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+        let value = InfoType::Luid(luid);
+        assert_eq!("0123456789abcdef", format!("{}", value));
+        assert_eq!(luid, value.to_luid());
+    }
+
+    #[test]
+    fn test_partition_property_to_vec_intptr() {
+        let value = InfoType::VecPartitionProperty(vec![
+            DevicePartitionProperty::Equally,
+            DevicePartitionProperty::ByCounts,
+        ]);
+        assert_eq!(
+            vec![CL_DEVICE_PARTITION_EQUALLY, CL_DEVICE_PARTITION_BY_COUNTS, 0],
+            value.to_vec_intptr()
+        );
+
+        // A device that cannot be partitioned
+        let value = InfoType::VecPartitionProperty(Vec::new());
+        assert_eq!(vec![0], value.to_vec_intptr());
+    }
+
+    #[test]
+    fn test_display_image_formats() {
+        let value = InfoType::VecImageFormat(vec![
+            cl_image_format {
+                image_channel_order: CL_RGBA,
+                image_channel_data_type: CL_UNORM_INT8,
+            },
+            cl_image_format {
+                image_channel_order: CL_R,
+                image_channel_data_type: CL_FLOAT,
+            },
+            cl_image_format {
+                image_channel_order: 0,
+                image_channel_data_type: 0,
+            },
+        ]);
+        assert_eq!(
+            "\nCL_RGBA: CL_UNORM_INT8\nCL_R: CL_FLOAT\nUNKNOWN_CHANNEL_ORDER: UNKNOWN_CHANNEL_TYPE",
+            format!("{}", value)
+        );
+        assert_eq!(3, value.to_vec_image_format().len());
+    }
+
     #[test]
     fn test_debug_display_info() {
         let platform_ids = get_platform_ids().unwrap();
@@ -16,26 +16,40 @@
 
 #![allow(non_camel_case_types)]
 
+pub mod raw;
+
 pub use cl_sys::{
     CL_KERNEL_ARG_ACCESS_NONE, CL_KERNEL_ARG_ACCESS_READ_ONLY, CL_KERNEL_ARG_ACCESS_READ_WRITE,
     CL_KERNEL_ARG_ACCESS_WRITE_ONLY, CL_KERNEL_ARG_ADDRESS_CONSTANT, CL_KERNEL_ARG_ADDRESS_GLOBAL,
     CL_KERNEL_ARG_ADDRESS_LOCAL, CL_KERNEL_ARG_ADDRESS_PRIVATE, CL_KERNEL_ARG_TYPE_CONST,
     CL_KERNEL_ARG_TYPE_NONE, CL_KERNEL_ARG_TYPE_PIPE, CL_KERNEL_ARG_TYPE_RESTRICT,
-    CL_KERNEL_ARG_TYPE_VOLATILE, CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM,
-    CL_KERNEL_EXEC_INFO_SVM_PTRS,
+    CL_KERNEL_ARG_TYPE_VOLATILE,
 };
+#[cfg(feature = "CL_VERSION_2_0")]
+pub use cl_sys::{CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM, CL_KERNEL_EXEC_INFO_SVM_PTRS};
 
 use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
 use super::info_type::InfoType;
 #[allow(unused_imports)]
+use super::macros::zero_extend_info_value;
+#[allow(unused_imports)]
 use super::types::{
-    cl_device_id, cl_int, cl_kernel, cl_kernel_arg_access_qualifier,
-    cl_kernel_arg_address_qualifier, cl_kernel_arg_info, cl_kernel_exec_info, cl_kernel_info,
-    cl_kernel_sub_group_info, cl_kernel_work_group_info, cl_program, cl_uint, cl_ulong,
+    cl_bool, cl_char, cl_device_id, cl_double, cl_float, cl_int, cl_kernel,
+    cl_kernel_arg_access_qualifier, cl_kernel_arg_address_qualifier, cl_kernel_arg_info,
+    cl_kernel_exec_info, cl_kernel_info, cl_kernel_sub_group_info, cl_kernel_work_group_info,
+    cl_long, cl_mem, cl_program, cl_short, cl_uchar, cl_uint, cl_ulong, cl_ushort, CL_FALSE,
+    CL_TRUE,
+};
+use super::types::{
+    cl_char16, cl_char2, cl_char4, cl_char8, cl_double16, cl_double2, cl_double4, cl_double8,
+    cl_float16, cl_float2, cl_float4, cl_float8, cl_int16, cl_int2, cl_int4, cl_int8, cl_long16,
+    cl_long2, cl_long4, cl_long8, cl_short16, cl_short2, cl_short4, cl_short8, cl_uchar16,
+    cl_uchar2, cl_uchar4, cl_uchar8, cl_uint16, cl_uint2, cl_uint4, cl_uint8, cl_ulong16,
+    cl_ulong2, cl_ulong4, cl_ulong8, cl_ushort16, cl_ushort2, cl_ushort4, cl_ushort8,
 };
 use super::{
     api2_info_size, api2_info_value, api2_info_vector, api_info_size, api_info_value,
-    api_info_vector,
+    api_info_vector, create_object_list,
 };
 #[allow(unused_imports)]
 use cl_sys::{
@@ -68,17 +82,6 @@ pub fn create_kernel(program: cl_program, kernel_name: &CStr) -> Result<cl_kerne
     }
 }
 
-fn count_kernels_in_program(program: cl_program) -> Result<cl_uint, cl_int> {
-    let mut count: cl_uint = 0;
-    let status: cl_int =
-        unsafe { clCreateKernelsInProgram(program, 0, ptr::null_mut(), &mut count) };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(count)
-    }
-}
-
 /// Create OpenCL kernel objects for all kernel functions in a program.  
 /// Calls clCreateKernelsInProgram to create OpenCL kernel objects.  
 ///
@@ -88,22 +91,9 @@ fn count_kernels_in_program(program: cl_program) -> Result<cl_uint, cl_int> {
 /// or the error code from the OpenCL C API function.
 #[inline]
 pub fn create_kernels_in_program(program: cl_program) -> Result<Vec<cl_kernel>, cl_int> {
-    let count: cl_uint = count_kernels_in_program(program)?;
-    let mut kernels: Vec<cl_kernel> = Vec::with_capacity(count as usize);
-    let status: cl_int = unsafe {
-        kernels.set_len(count as usize);
-        clCreateKernelsInProgram(
-            program,
-            count,
-            kernels.as_mut_ptr() as *mut cl_kernel,
-            ptr::null_mut(),
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(kernels)
-    }
+    create_object_list(|num_entries, kernels, num_kernels| unsafe {
+        clCreateKernelsInProgram(program, num_entries, kernels, num_kernels)
+    })
 }
 
 /// Clone an OpenCL kernel object.  
@@ -135,6 +125,8 @@ pub fn clone_kernel(source_kernel: cl_kernel) -> Result<cl_kernel, cl_int> {
 #[inline]
 pub fn retain_kernel(kernel: cl_kernel) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clRetainKernel(kernel) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::retained(kernel as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -151,6 +143,8 @@ pub fn retain_kernel(kernel: cl_kernel) -> Result<(), cl_int> {
 #[inline]
 pub fn release_kernel(kernel: cl_kernel) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clReleaseKernel(kernel) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::released(kernel as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -158,14 +152,7 @@ pub fn release_kernel(kernel: cl_kernel) -> Result<(), cl_int> {
     }
 }
 
-/// Set the argument value for a specific argument of a kernel.  
-/// Calls clSetKernelArg.  
-///
-/// * `kernel` - the OpenCL kernel.
-/// * `arg_index` - the kernel argument index.
-/// * `arg_ptr` - pointer to the data for the argument at arg_index.
-///
-/// returns an empty Result or the error code from the OpenCL C API function.
+#[deprecated(note = "use the typed variant: KernelArg")]
 #[inline]
 pub fn set_kernel_arg(
     kernel: cl_kernel,
@@ -173,45 +160,22 @@ pub fn set_kernel_arg(
     arg_size: size_t,
     arg_value: *const c_void,
 ) -> Result<(), cl_int> {
-    let status: cl_int = unsafe { clSetKernelArg(kernel, arg_index, arg_size, arg_value) };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(())
-    }
+    raw::set_kernel_arg(kernel, arg_index, arg_size, arg_value)
 }
 
-/// Set set a SVM pointer as the argument value for a specific argument of a kernel.  
-/// Calls clSetKernelArgSVMPointer.  
-///
-/// * `kernel` - the OpenCL kernel.
-/// * `arg_index` - the kernel argument index.
-/// * `arg_ptr` - the SVM pointer to the data for the argument at arg_index.
-///
-/// returns an empty Result or the error code from the OpenCL C API function.
+#[deprecated(note = "use the typed variant: SvmPtr")]
 #[inline]
 pub fn set_kernel_arg_svm_pointer(
     kernel: cl_kernel,
     arg_index: cl_uint,
     arg_ptr: *const c_void,
 ) -> Result<(), cl_int> {
-    let status: cl_int = unsafe { clSetKernelArgSVMPointer(kernel, arg_index, arg_ptr) };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(())
-    }
+    raw::set_kernel_arg_svm_pointer(kernel, arg_index, arg_ptr)
 }
 
-/// Pass additional information other than argument values to a kernel.  
-/// Calls clSetKernelExecInfo.  
-///
-/// * `kernel` - the OpenCL kernel.
-/// * `param_name` - the information to be passed to kernel, see:
-/// [Kernel Execution Properties](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#kernel-exec-info-table).
-/// * `param_ptr` - pointer to the data for the param_name.
-///
-/// returns an empty Result or the error code from the OpenCL C API function.
+#[deprecated(
+    note = "use the typed variants: set_kernel_exec_info_svm_ptrs or set_kernel_exec_info_svm_fine_grain_system"
+)]
 #[inline]
 pub fn set_kernel_exec_info(
     kernel: cl_kernel,
@@ -219,15 +183,104 @@ pub fn set_kernel_exec_info(
     param_value_size: size_t,
     param_value: *const c_void,
 ) -> Result<(), cl_int> {
-    let status: cl_int =
-        unsafe { clSetKernelExecInfo(kernel, param_name, param_value_size, param_value) };
-    if CL_SUCCESS != status {
-        Err(status)
+    raw::set_kernel_exec_info(kernel, param_name, param_value_size, param_value)
+}
+
+// The element size of a core clSetKernelExecInfo parameter and whether it is
+// a single value rather than an array.
+#[cfg(feature = "CL_VERSION_2_0")]
+fn exec_info_element(param_name: cl_kernel_exec_info) -> Option<(size_t, bool)> {
+    match param_name {
+        CL_KERNEL_EXEC_INFO_SVM_PTRS => Some((mem::size_of::<*const c_void>(), false)),
+        CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM => Some((mem::size_of::<cl_bool>(), true)),
+        _ => None,
+    }
+}
+
+/// The param_value_size that clSetKernelExecInfo expects for a parameter.  
+/// CL_KERNEL_EXEC_INFO_SVM_PTRS is an array of pointers, while
+/// CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM is a single cl_bool.  
+/// CL_VERSION_2_0
+///
+/// * `param_name` - the clSetKernelExecInfo parameter.
+/// * `element_count` - the number of values, e.g. of SVM pointers.
+///
+/// returns the size in bytes, or None if `param_name` is not a core
+/// parameter or it is a single value and `element_count` is not 1.
+#[cfg(feature = "CL_VERSION_2_0")]
+pub fn exec_info_expected_size(
+    param_name: cl_kernel_exec_info,
+    element_count: usize,
+) -> Option<size_t> {
+    let (size, single) = exec_info_element(param_name)?;
+    if single && 1 != element_count {
+        None
     } else {
-        Ok(())
+        Some(size * element_count)
     }
 }
 
+// Whether a clSetKernelExecInfo size is consistent with a core parameter,
+// the sizes of other parameters are unknown.
+#[cfg(feature = "CL_VERSION_2_0")]
+fn is_consistent_exec_info_size(param_name: cl_kernel_exec_info, param_value_size: size_t) -> bool {
+    match exec_info_element(param_name) {
+        Some((size, _)) => {
+            Some(param_value_size) == exec_info_expected_size(param_name, param_value_size / size)
+        }
+        None => true,
+    }
+}
+
+/// Set the SVM pointers that a kernel may access other than through its
+/// arguments, e.g. pointers stored in an SVM buffer.  
+/// Calls clSetKernelExecInfo with CL_KERNEL_EXEC_INFO_SVM_PTRS.  
+/// CL_VERSION_2_0
+///
+/// * `kernel` - the OpenCL kernel.
+/// * `svm_ptrs` - the SVM pointers.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_0")]
+pub fn set_kernel_exec_info_svm_ptrs(
+    kernel: cl_kernel,
+    svm_ptrs: &[*const c_void],
+) -> Result<(), cl_int> {
+    let size = exec_info_expected_size(CL_KERNEL_EXEC_INFO_SVM_PTRS, svm_ptrs.len())
+        .ok_or(CL_INVALID_VALUE)?;
+    raw::set_kernel_exec_info(
+        kernel,
+        CL_KERNEL_EXEC_INFO_SVM_PTRS,
+        size,
+        svm_ptrs.as_ptr() as *const c_void,
+    )
+}
+
+/// Set whether a kernel may access fine-grain system SVM allocations that
+/// are not passed as arguments or SVM pointers.  
+/// Calls clSetKernelExecInfo with CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM.  
+/// CL_VERSION_2_0
+///
+/// * `kernel` - the OpenCL kernel.
+/// * `enable` - whether the kernel may access any system allocation.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_0")]
+pub fn set_kernel_exec_info_svm_fine_grain_system(
+    kernel: cl_kernel,
+    enable: bool,
+) -> Result<(), cl_int> {
+    let value: cl_bool = if enable { CL_TRUE } else { CL_FALSE };
+    let size = exec_info_expected_size(CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM, 1)
+        .ok_or(CL_INVALID_VALUE)?;
+    raw::set_kernel_exec_info(
+        kernel,
+        CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM,
+        size,
+        &value as *const cl_bool as *const c_void,
+    )
+}
+
 /// Get data about an OpenCL kernel.
 /// Calls clGetKernelInfo to get the desired data about the kernel.
 pub fn get_kernel_data(
@@ -429,7 +482,19 @@ pub enum KernelSubGroupInfo {
     CL_KERNEL_COMPILE_NUM_SUB_GROUPS = 0x11BA,
 }
 
-/// Get specific information about sub groups of an OpenCL kernel.  
+#[cfg(feature = "CL_VERSION_2_1")]
+#[deprecated(note = "use the slice-based variant: get_kernel_sub_group_info_slice")]
+pub fn get_kernel_sub_group_info(
+    kernel: cl_kernel,
+    device: cl_device_id,
+    param_name: KernelSubGroupInfo,
+    input_value_size: size_t,
+    input_value: *const c_void,
+) -> Result<InfoType, cl_int> {
+    raw::get_kernel_sub_group_info(kernel, device, param_name, input_value_size, input_value)
+}
+
+/// Get information about the sub-groups of a kernel on a specific device.  
 /// Calls clGetKernelSubGroupInfo to get the desired information about the kernel.  
 /// CL_VERSION_2_1
 ///
@@ -437,113 +502,128 @@ pub enum KernelSubGroupInfo {
 /// * `device` - a specific device in the list of devices associated with kernel.
 /// * `param_name` - the type of kernel information being queried, see:
 /// [Kernel Object Subgroup Queries](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#kernel-subgroup-info-table).
-/// * `input_value_size` - the size in bytes of memory pointed to by input_value.
-/// * `input_value` -  pointer to memory where the appropriate parameterization
-/// of the query is passed from.
+/// * `input_value` - the parameterization of the query: the local work size
+///   for the NDRANGE queries, the sub-group count for
+///   CL_KERNEL_LOCAL_SIZE_FOR_SUB_GROUP_COUNT, or empty.
 ///
 /// returns a Result containing the desired information in an InfoType enum
 /// or the error code from the OpenCL C API function.
 #[cfg(feature = "CL_VERSION_2_1")]
-pub fn get_kernel_sub_group_info(
+#[inline]
+pub fn get_kernel_sub_group_info_slice(
     kernel: cl_kernel,
     device: cl_device_id,
     param_name: KernelSubGroupInfo,
-    input_value_size: size_t,
-    input_value: *const c_void,
+    input_value: &[size_t],
 ) -> Result<InfoType, cl_int> {
-    let mut size: size_t = mem::size_of::<size_t>();
-    let param_id = param_name as cl_kernel_sub_group_info;
-    match param_name {
-        KernelSubGroupInfo::CL_KERNEL_MAX_SUB_GROUP_SIZE_FOR_NDRANGE
-        | KernelSubGroupInfo::CL_KERNEL_SUB_GROUP_COUNT_FOR_NDRANGE
-        | KernelSubGroupInfo::CL_KERNEL_MAX_NUM_SUB_GROUPS
-        | KernelSubGroupInfo::CL_KERNEL_COMPILE_NUM_SUB_GROUPS => {
-            // get the value
-            let mut data: size_t = 0;
-            let data_ptr: *mut size_t = &mut data;
-            let status = unsafe {
-                clGetKernelSubGroupInfo(
-                    kernel,
-                    device,
-                    param_id,
-                    input_value_size,
-                    input_value,
-                    size,
-                    data_ptr as *mut c_void,
-                    ptr::null_mut(),
-                )
-            };
-            if CL_SUCCESS != status {
-                Err(status)
-            } else {
-                Ok(InfoType::Size(data))
-            }
-        }
+    raw::get_kernel_sub_group_info(
+        kernel,
+        device,
+        param_name,
+        mem::size_of_val(input_value),
+        if input_value.is_empty() {
+            ptr::null()
+        } else {
+            input_value.as_ptr() as *const c_void
+        },
+    )
+}
+
+/// A value that can be set as a kernel argument, see `kernel_args!`.
+pub trait KernelArg {
+    /// Set the value as the argument at `arg_index` of `kernel`.
+    fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int>;
+}
 
-        KernelSubGroupInfo::CL_KERNEL_LOCAL_SIZE_FOR_SUB_GROUP_COUNT => {
-            // get the size
-            let status: cl_int = unsafe {
-                clGetKernelSubGroupInfo(
-                    kernel,
-                    device,
-                    param_id,
-                    input_value_size,
-                    input_value,
-                    0,
-                    ptr::null_mut(),
-                    &mut size,
-                )
-            };
-            if CL_SUCCESS != status {
-                Err(status)
-            } else {
-                // Get the information.
-                let count = size / mem::size_of::<size_t>();
-                let mut data: Vec<size_t> = Vec::with_capacity(count);
-                let status = unsafe {
-                    data.set_len(count);
-                    clGetKernelSubGroupInfo(
+macro_rules! impl_kernel_arg {
+    ($($t:ty),*) => {
+        $(
+            impl KernelArg for $t {
+                #[inline]
+                fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+                    raw::set_kernel_arg(
                         kernel,
-                        device,
-                        param_id,
-                        input_value_size,
-                        input_value,
-                        size,
-                        data.as_mut_ptr() as *mut c_void,
-                        ptr::null_mut(),
+                        arg_index,
+                        mem::size_of::<$t>(),
+                        self as *const $t as *const c_void,
                     )
-                };
-                if CL_SUCCESS != status {
-                    Err(status)
-                } else {
-                    Ok(InfoType::VecSize(data))
                 }
             }
-        }
+        )*
+    };
+}
+
+impl_kernel_arg!(
+    cl_char, cl_uchar, cl_short, cl_ushort, cl_int, cl_uint, cl_long, cl_ulong, cl_float,
+    cl_double, cl_mem
+);
+
+// The 3-component vector types are the same as the 4-component types.
+impl_kernel_arg!(cl_char2, cl_char4, cl_char8, cl_char16);
+impl_kernel_arg!(cl_uchar2, cl_uchar4, cl_uchar8, cl_uchar16);
+impl_kernel_arg!(cl_short2, cl_short4, cl_short8, cl_short16);
+impl_kernel_arg!(cl_ushort2, cl_ushort4, cl_ushort8, cl_ushort16);
+impl_kernel_arg!(cl_int2, cl_int4, cl_int8, cl_int16);
+impl_kernel_arg!(cl_uint2, cl_uint4, cl_uint8, cl_uint16);
+impl_kernel_arg!(cl_long2, cl_long4, cl_long8, cl_long16);
+impl_kernel_arg!(cl_ulong2, cl_ulong4, cl_ulong8, cl_ulong16);
+impl_kernel_arg!(cl_float2, cl_float4, cl_float8, cl_float16);
+impl_kernel_arg!(cl_double2, cl_double4, cl_double8, cl_double16);
+
+/// The size in bytes of a local memory kernel argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalMem(pub size_t);
+
+impl KernelArg for LocalMem {
+    #[inline]
+    fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+        raw::set_kernel_arg(kernel, arg_index, self.0, ptr::null())
     }
 }
 
+/// An SVM pointer kernel argument.  
+/// Calls clSetKernelArgSVMPointer to set the argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SvmPtr(pub *const c_void);
+
+impl KernelArg for SvmPtr {
+    #[inline]
+    fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+        raw::set_kernel_arg_svm_pointer(kernel, arg_index, self.0)
+    }
+}
+
+/// A set of kernel arguments, see `kernel_args!`.
+pub trait KernelArgs {
+    /// Set all of the arguments of a kernel.
+    ///
+    /// returns an empty Result or the index of the argument that failed
+    /// and the error code from the OpenCL C API function.
+    fn bind(&self, kernel: cl_kernel) -> Result<(), (cl_uint, cl_int)>;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::command_queue::{
+        create_command_queue, enqueue_nd_range_kernel_slice, enqueue_read_buffer_slice,
+        release_command_queue,
+    };
     use crate::context::{create_context, release_context};
-    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::device::{get_device_ids, select_device, CL_DEVICE_TYPE_ALL, CL_DEVICE_TYPE_GPU};
+    use crate::event::release_event;
+    use crate::memory::{create_buffer_of_size, release_mem_object, CL_MEM_WRITE_ONLY};
     use crate::error_codes::error_text;
     use crate::platform::get_platform_ids;
     use crate::program::{build_program, create_program_with_source, release_program};
+    use crate::types::{cl_int3, CL_BLOCKING};
     use std::ffi::CString;
 
     #[test]
     fn test_kernel() {
-        let platform_ids = get_platform_ids().unwrap();
-
-        // Choose the first platform
-        let platform_id = platform_ids[0];
-
-        let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_GPU).unwrap();
-        assert!(0 < device_ids.len());
-
-        let device_id = device_ids[0];
+        // Choose a GPU if there is one, otherwise any device
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+        let device_ids = [device_id];
 
         let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut());
         let context = context.unwrap();
@@ -714,4 +794,162 @@ mod tests {
         release_program(program).unwrap();
         release_context(context).unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn test_exec_info_expected_size() {
+        let ptr_size = mem::size_of::<*const c_void>();
+        assert_eq!(
+            Some(3 * ptr_size),
+            exec_info_expected_size(CL_KERNEL_EXEC_INFO_SVM_PTRS, 3)
+        );
+        assert_eq!(
+            Some(0),
+            exec_info_expected_size(CL_KERNEL_EXEC_INFO_SVM_PTRS, 0)
+        );
+        assert_eq!(
+            Some(mem::size_of::<cl_bool>()),
+            exec_info_expected_size(CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM, 1)
+        );
+        assert_eq!(
+            None,
+            exec_info_expected_size(CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM, 2)
+        );
+        assert_eq!(None, exec_info_expected_size(0, 1));
+
+        assert!(is_consistent_exec_info_size(
+            CL_KERNEL_EXEC_INFO_SVM_PTRS,
+            2 * ptr_size
+        ));
+        assert!(!is_consistent_exec_info_size(
+            CL_KERNEL_EXEC_INFO_SVM_PTRS,
+            ptr_size + 1
+        ));
+        assert!(is_consistent_exec_info_size(
+            CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM,
+            mem::size_of::<cl_bool>()
+        ));
+        assert!(!is_consistent_exec_info_size(
+            CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM,
+            ptr_size
+        ));
+        // The sizes of other parameters are not checked
+        assert!(is_consistent_exec_info_size(0, 3));
+    }
+
+    #[test]
+    fn test_kernel_args_bind_error_index() {
+        crate::kernel_args! {
+            struct TestArgs {
+                a: cl_int,
+                #[arg(skip)]
+                name: String,
+                #[arg(index = 3)]
+                b: cl_float,
+                scratch: LocalMem,
+            }
+        }
+
+        let args = TestArgs {
+            a: 1,
+            name: String::from("test"),
+            b: 2.0,
+            scratch: LocalMem(64),
+        };
+        assert_eq!(1, args.a);
+        assert_eq!("test", args.name);
+        assert_eq!(2.0, args.b);
+        assert_eq!(LocalMem(64), args.scratch);
+
+        // Binding to an invalid kernel fails at the first argument
+        let (index, status) = args.bind(ptr::null_mut()).unwrap_err();
+        assert_eq!(0, index);
+        assert_ne!(CL_SUCCESS, status);
+    }
+
+    #[test]
+    fn test_vector_kernel_arg() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+
+        let source = "kernel void components(global float* out, float4 v, int3 w)
+            { out[0] = v.x; out[1] = v.y; out[2] = v.z; out[3] = v.w; out[4] = w.z; }";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::default();
+        build_program(program, &[device_id], &options, None, ptr::null_mut()).unwrap();
+        let name = CString::new("components").unwrap();
+        let kernel = create_kernel(program, &name).unwrap();
+
+        const COUNT: usize = 5;
+        let size = COUNT * mem::size_of::<cl_float>();
+        let buffer = create_buffer_of_size(context, CL_MEM_WRITE_ONLY, size).unwrap();
+        let v = cl_float4::from([1.0, 2.0, 3.0, 4.0]);
+        let w = cl_int3::from([7, 8, 9]);
+        assert_eq!([7, 8, 9, 0], w.s);
+        buffer.set_arg(kernel, 0).unwrap();
+        v.set_arg(kernel, 1).unwrap();
+        w.set_arg(kernel, 2).unwrap();
+
+        let event = enqueue_nd_range_kernel_slice(queue, kernel, None, &[1], None, &[]).unwrap();
+        let mut result = [0.0f32; COUNT];
+        let read_event = unsafe {
+            enqueue_read_buffer_slice(queue, buffer, CL_BLOCKING, 0, &mut result, &[event])
+        }
+        .unwrap();
+        assert_eq!([1.0, 2.0, 3.0, 4.0, 9.0], result);
+        let components: [cl_float; 4] = v.into();
+        assert_eq!(result[..4], components);
+
+        release_event(read_event).unwrap();
+        release_event(event).unwrap();
+        release_mem_object(buffer).unwrap();
+        release_kernel(kernel).unwrap();
+        release_program(program).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_raw_and_deprecated_paths() {
+        // The deprecated paths must still resolve to the raw function signatures.
+        let _set_arg: fn(cl_kernel, cl_uint, size_t, *const c_void) -> Result<(), cl_int> =
+            set_kernel_arg;
+        let _set_arg_svm: fn(cl_kernel, cl_uint, *const c_void) -> Result<(), cl_int> =
+            set_kernel_arg_svm_pointer;
+        let _set_exec_info: fn(
+            cl_kernel,
+            cl_kernel_exec_info,
+            size_t,
+            *const c_void,
+        ) -> Result<(), cl_int> = set_kernel_exec_info;
+        let _raw: fn(cl_kernel, cl_uint, size_t, *const c_void) -> Result<(), cl_int> =
+            raw::set_kernel_arg;
+
+        #[cfg(feature = "CL_VERSION_2_1")]
+        {
+            let _sub_group_info: fn(
+                cl_kernel,
+                cl_device_id,
+                KernelSubGroupInfo,
+                size_t,
+                *const c_void,
+            ) -> Result<InfoType, cl_int> = get_kernel_sub_group_info;
+        }
+
+        // The deprecated and typed paths must call the same OpenCL function.
+        let kernel: cl_kernel = ptr::null_mut();
+        let value: cl_int = 1;
+        let old = set_kernel_arg(
+            kernel,
+            0,
+            mem::size_of::<cl_int>(),
+            &value as *const cl_int as *const c_void,
+        );
+        assert!(old.is_err());
+        assert_eq!(old, value.set_arg(kernel, 0));
+    }
 }
@@ -0,0 +1,222 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Raw pointer OpenCL Kernel Object API.
+//!
+//! These functions pass pointers and sizes straight through to the OpenCL
+//! C API, so the caller must ensure that they are valid.  
+//! Prefer the typed functions in the parent module, e.g. `KernelArg`.
+
+#![allow(non_camel_case_types)]
+// The functions mirror the OpenCL C API, see the module documentation.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+#[cfg(feature = "CL_VERSION_2_0")]
+use super::is_consistent_exec_info_size;
+#[cfg(feature = "CL_VERSION_2_1")]
+use super::KernelSubGroupInfo;
+use crate::error_codes::CL_SUCCESS;
+#[allow(unused_imports)]
+use crate::info_type::InfoType;
+#[allow(unused_imports)]
+use crate::macros::zero_extend_info_value;
+#[allow(unused_imports)]
+use crate::types::{
+    cl_device_id, cl_int, cl_kernel, cl_kernel_exec_info, cl_kernel_sub_group_info, cl_uint,
+};
+#[allow(unused_imports)]
+use cl_sys::{
+    clGetKernelSubGroupInfo, clSetKernelArg, clSetKernelArgSVMPointer, clSetKernelExecInfo,
+};
+
+use libc::{c_void, size_t};
+#[allow(unused_imports)]
+use std::mem;
+#[allow(unused_imports)]
+use std::ptr;
+
+/// Set the argument value for a specific argument of a kernel.  
+/// Calls clSetKernelArg.  
+///
+/// * `kernel` - the OpenCL kernel.
+/// * `arg_index` - the kernel argument index.
+/// * `arg_ptr` - pointer to the data for the argument at arg_index.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+#[inline]
+pub fn set_kernel_arg(
+    kernel: cl_kernel,
+    arg_index: cl_uint,
+    arg_size: size_t,
+    arg_value: *const c_void,
+) -> Result<(), cl_int> {
+    let status: cl_int = unsafe { clSetKernelArg(kernel, arg_index, arg_size, arg_value) };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(())
+    }
+}
+
+/// Set set a SVM pointer as the argument value for a specific argument of a kernel.  
+/// Calls clSetKernelArgSVMPointer.  
+///
+/// * `kernel` - the OpenCL kernel.
+/// * `arg_index` - the kernel argument index.
+/// * `arg_ptr` - the SVM pointer to the data for the argument at arg_index.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+#[inline]
+pub fn set_kernel_arg_svm_pointer(
+    kernel: cl_kernel,
+    arg_index: cl_uint,
+    arg_ptr: *const c_void,
+) -> Result<(), cl_int> {
+    let status: cl_int = unsafe { clSetKernelArgSVMPointer(kernel, arg_index, arg_ptr) };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(())
+    }
+}
+
+/// Pass additional information other than argument values to a kernel.  
+/// Calls clSetKernelExecInfo.  
+///
+/// * `kernel` - the OpenCL kernel.
+/// * `param_name` - the information to be passed to kernel, see:
+/// [Kernel Execution Properties](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#kernel-exec-info-table).
+/// * `param_value_size` - the size in bytes of the data for the param_name,
+///   see exec_info_expected_size.
+/// * `param_ptr` - pointer to the data for the param_name.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+#[inline]
+pub fn set_kernel_exec_info(
+    kernel: cl_kernel,
+    param_name: cl_kernel_exec_info,
+    param_value_size: size_t,
+    param_value: *const c_void,
+) -> Result<(), cl_int> {
+    #[cfg(feature = "CL_VERSION_2_0")]
+    debug_assert!(
+        is_consistent_exec_info_size(param_name, param_value_size),
+        "invalid clSetKernelExecInfo param_value_size {} for param_name {:#x}",
+        param_value_size,
+        param_name
+    );
+    let status: cl_int =
+        unsafe { clSetKernelExecInfo(kernel, param_name, param_value_size, param_value) };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(())
+    }
+}
+
+/// Get specific information about sub groups of an OpenCL kernel.  
+/// Calls clGetKernelSubGroupInfo to get the desired information about the kernel.  
+/// CL_VERSION_2_1
+///
+/// * `kernel` - the OpenCL kernel.
+/// * `device` - a specific device in the list of devices associated with kernel.
+/// * `param_name` - the type of kernel information being queried, see:
+/// [Kernel Object Subgroup Queries](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#kernel-subgroup-info-table).
+/// * `input_value_size` - the size in bytes of memory pointed to by input_value.
+/// * `input_value` -  pointer to memory where the appropriate parameterization
+/// of the query is passed from.
+///
+/// returns a Result containing the desired information in an InfoType enum
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_1")]
+pub fn get_kernel_sub_group_info(
+    kernel: cl_kernel,
+    device: cl_device_id,
+    param_name: KernelSubGroupInfo,
+    input_value_size: size_t,
+    input_value: *const c_void,
+) -> Result<InfoType, cl_int> {
+    let mut size: size_t = mem::size_of::<size_t>();
+    let param_id = param_name as cl_kernel_sub_group_info;
+    match param_name {
+        KernelSubGroupInfo::CL_KERNEL_MAX_SUB_GROUP_SIZE_FOR_NDRANGE
+        | KernelSubGroupInfo::CL_KERNEL_SUB_GROUP_COUNT_FOR_NDRANGE
+        | KernelSubGroupInfo::CL_KERNEL_MAX_NUM_SUB_GROUPS
+        | KernelSubGroupInfo::CL_KERNEL_COMPILE_NUM_SUB_GROUPS => {
+            // get the value
+            let mut data: size_t = 0;
+            let data_ptr: *mut size_t = &mut data;
+            let mut returned: size_t = size;
+            let status = unsafe {
+                clGetKernelSubGroupInfo(
+                    kernel,
+                    device,
+                    param_id,
+                    input_value_size,
+                    input_value,
+                    size,
+                    data_ptr as *mut c_void,
+                    &mut returned,
+                )
+            };
+            if CL_SUCCESS != status {
+                Err(status)
+            } else {
+                unsafe { zero_extend_info_value(data_ptr as *mut u8, size, returned) };
+                Ok(InfoType::Size(data))
+            }
+        }
+
+        KernelSubGroupInfo::CL_KERNEL_LOCAL_SIZE_FOR_SUB_GROUP_COUNT => {
+            // get the size
+            let status: cl_int = unsafe {
+                clGetKernelSubGroupInfo(
+                    kernel,
+                    device,
+                    param_id,
+                    input_value_size,
+                    input_value,
+                    0,
+                    ptr::null_mut(),
+                    &mut size,
+                )
+            };
+            if CL_SUCCESS != status {
+                Err(status)
+            } else {
+                // Get the information.
+                let count = size / mem::size_of::<size_t>();
+                let mut data: Vec<size_t> = Vec::with_capacity(count);
+                let status = unsafe {
+                    data.set_len(count);
+                    clGetKernelSubGroupInfo(
+                        kernel,
+                        device,
+                        param_id,
+                        input_value_size,
+                        input_value,
+                        size,
+                        data.as_mut_ptr() as *mut c_void,
+                        ptr::null_mut(),
+                    )
+                };
+                if CL_SUCCESS != status {
+                    Err(status)
+                } else {
+                    Ok(InfoType::VecSize(data))
+                }
+            }
+        }
+    }
+}
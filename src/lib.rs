@@ -45,13 +45,31 @@
 //! They contain Rust adapter functions for the OpenCL API C functions defined
 //! in those sections with their associated types and constants. The exceptions are:
 //!
+//! * [compat] - contains functions with the arguments of deprecated OpenCL 1.x
+//!   functions that call their replacements where possible.
+//! * [debug] - contains functions to find the library that implements a
+//!   platform, for debugging OpenCL ICD loader issues.
+//! * [device_fission] - contains functions to partition OpenCL 1.1 devices
+//!   with the cl_ext_device_fission extension, requires the `cl_ext_device_fission` feature.
 //! * [error_codes] - contains the OpenCL API error codes from cl.h and a function
-//! (`error_text`) to convert an error code to it's enum name from cl.h.
+//!   (`error_text`) to convert an error code to it's enum name from cl.h.
+//! * [export] - contains C callable functions for some of the convenience
+//!   functions of this crate, requires the `ffi_export` feature.
 //! * [info_type] - contains a Rust enum (`InfoType`) to hold the OpenCL types
-//! that can be returned from OpenCL "Info" functions, e.g. clGetPlatformInfo,
-//! clGetDeviceInfo, clGetProgramInfo, etc.
+//!   that can be returned from OpenCL "Info" functions, e.g. clGetPlatformInfo,
+//!   clGetDeviceInfo, clGetProgramInfo, etc.
 //! * [macros] - contains Rust macros to call the OpenCL "Info" functions and
-//! return the appropriate `InfoType` in a Rust Result.
+//!   return the appropriate `InfoType` in a Rust Result.
+//! * [preflight] - contains functions to check at startup that the OpenCL
+//!   versions, extensions and functions that an application requires are available.
+//! * [quick] - contains a default context and command-queue for examples and
+//!   quick scripts, requires the `quick` feature.
+//! * [selection] - contains the parser of the `CL3_DEVICE` environment variable,
+//!   which pins the device chosen by the device selection functions.
+//! * [snapshot] - contains a snapshot of the OpenCL platforms and devices
+//!   in a system, see `SystemSnapshot`.
+//! * [svm] - contains types to describe and sub-allocate shared virtual memory
+//!   (SVM) regions.
 //!
 //! It is vital to call the correct `InfoType` method type when decoding the
 //! result of "Info" functions, since the methods will panic if called with the
@@ -71,15 +89,21 @@
 extern crate cl_sys;
 
 pub mod command_queue;
+pub mod compat;
 pub mod context;
 pub mod d3d10;
 pub mod d3d11;
+pub mod debug;
 pub mod device;
+#[cfg(all(feature = "cl_ext_device_fission", feature = "CL_VERSION_1_2"))]
+pub mod device_fission;
 pub mod dx9_media_sharing;
 pub mod egl;
 pub mod error_codes;
 pub mod event;
 pub mod ext;
+#[cfg(feature = "ffi_export")]
+pub mod export;
 pub mod ffi;
 pub mod gl;
 pub mod info_type;
@@ -87,6 +111,247 @@ pub mod kernel;
 pub mod macros;
 pub mod memory;
 pub mod platform;
+pub mod preflight;
 pub mod program;
+#[cfg(feature = "quick")]
+pub mod quick;
 pub mod sampler;
+pub mod selection;
+pub mod snapshot;
+pub mod svm;
 pub mod types;
+
+use error_codes::{CL_INVALID_VALUE, CL_OUT_OF_RESOURCES, CL_SUCCESS};
+use types::{cl_int, cl_uint};
+
+use std::cmp;
+use std::ptr;
+
+/// The maximum number of attempts to get a list in get_object_list.
+const OBJECT_LIST_ATTEMPTS: usize = 4;
+
+/// Get a list of OpenCL objects from an OpenCL C API function that is called
+/// twice: first to get the number of objects and then to get the objects,
+/// e.g. clGetPlatformIDs.  
+/// The number of objects may change between the calls, e.g. when a device is
+/// added, so the list is requested again if the second call returns
+/// CL_INVALID_VALUE or more objects than were requested, up to
+/// OBJECT_LIST_ATTEMPTS times. A CL_INVALID_VALUE is only retried while the
+/// number of objects changes, otherwise it is returned.  
+/// Functions that create the objects must use `create_object_list` instead,
+/// since the objects of a retried call would leak.
+///
+/// * `not_found` - a status that means that there are no objects,
+///   e.g. CL_DEVICE_NOT_FOUND.
+/// * `get_list` - calls the OpenCL C API function with the number of entries,
+///   a pointer to the entries and a pointer to the number of objects.
+///
+/// returns a Result containing the objects
+/// or CL_OUT_OF_RESOURCES if more objects were returned on every attempt
+/// or the error code from the OpenCL C API function.
+pub(crate) fn get_object_list<T, F>(
+    not_found: Option<cl_int>,
+    mut get_list: F,
+) -> Result<Vec<T>, cl_int>
+where
+    F: FnMut(cl_uint, *mut T, *mut cl_uint) -> cl_int,
+{
+    let mut attempt = 1;
+    // The number of objects of an attempt whose second call failed with
+    // CL_INVALID_VALUE
+    let mut invalid_count: Option<cl_uint> = None;
+    loop {
+        let mut count: cl_uint = 0;
+        let status = get_list(0, ptr::null_mut(), &mut count);
+        if Some(status) == not_found || (CL_SUCCESS == status && 0 == count) {
+            return Ok(Vec::default());
+        }
+        if CL_SUCCESS != status {
+            return Err(status);
+        }
+        // The number of objects did not change, so the error is not a race
+        if Some(count) == invalid_count {
+            return Err(CL_INVALID_VALUE);
+        }
+
+        let mut objects: Vec<T> = Vec::with_capacity(count as usize);
+        // Some implementations may not set the number of objects
+        let mut num_objects: cl_uint = count;
+        let status = get_list(count, objects.as_mut_ptr(), &mut num_objects);
+
+        let invalid = CL_INVALID_VALUE == status;
+        if invalid || (CL_SUCCESS == status && count < num_objects) {
+            if attempt < OBJECT_LIST_ATTEMPTS {
+                attempt += 1;
+                invalid_count = if invalid { Some(count) } else { None };
+                continue;
+            }
+            return Err(if invalid { status } else { CL_OUT_OF_RESOURCES });
+        }
+
+        if Some(status) == not_found {
+            return Ok(Vec::default());
+        }
+        if CL_SUCCESS != status {
+            return Err(status);
+        }
+        unsafe { objects.set_len(cmp::min(count, num_objects) as usize) };
+        return Ok(objects);
+    }
+}
+
+/// Create a list of OpenCL objects with an OpenCL C API function that is
+/// called twice: first to get the number of objects and then to create them,
+/// e.g. clCreateKernelsInProgram.  
+/// Unlike `get_object_list`, the second call is not retried, since it may
+/// have created objects.
+///
+/// * `create_list` - calls the OpenCL C API function with the number of entries,
+///   a pointer to the entries and a pointer to the number of objects.
+///
+/// returns a Result containing the new objects
+/// or the error code from the OpenCL C API function.
+pub(crate) fn create_object_list<T, F>(mut create_list: F) -> Result<Vec<T>, cl_int>
+where
+    F: FnMut(cl_uint, *mut T, *mut cl_uint) -> cl_int,
+{
+    let mut count: cl_uint = 0;
+    let status = create_list(0, ptr::null_mut(), &mut count);
+    if CL_SUCCESS != status {
+        return Err(status);
+    }
+    if 0 == count {
+        return Ok(Vec::default());
+    }
+
+    let mut objects: Vec<T> = Vec::with_capacity(count as usize);
+    let mut num_objects: cl_uint = count;
+    let status = create_list(count, objects.as_mut_ptr(), &mut num_objects);
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        // At most count objects are created
+        unsafe { objects.set_len(cmp::min(count, num_objects) as usize) };
+        Ok(objects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A mock of an OpenCL C API function that lists `counts[n]` objects on the
+    // nth call, to simulate objects being added or removed between calls.
+    fn mock_get_list(
+        counts: &[cl_uint],
+        calls: &mut usize,
+        num_entries: cl_uint,
+        objects: *mut usize,
+        num_objects: *mut cl_uint,
+    ) -> cl_int {
+        let available = counts[cmp::min(*calls, counts.len() - 1)];
+        *calls += 1;
+        if !objects.is_null() && num_entries < available && 0 == available % 2 {
+            // Some functions, e.g. clCreateKernelsInProgram, fail
+            return CL_INVALID_VALUE;
+        }
+        unsafe {
+            for i in 0..cmp::min(num_entries, available) {
+                *objects.add(i as usize) = i as usize;
+            }
+            if !num_objects.is_null() {
+                *num_objects = available;
+            }
+        }
+        CL_SUCCESS
+    }
+
+    #[test]
+    fn test_get_object_list() {
+        // No race
+        let mut calls = 0;
+        let objects = get_object_list(None, |n, o, c| mock_get_list(&[3], &mut calls, n, o, c));
+        assert_eq!(Ok(vec![0, 1, 2]), objects);
+        assert_eq!(2, calls);
+
+        // An object is added between the calls
+        let mut calls = 0;
+        let objects = get_object_list(None, |n, o, c| mock_get_list(&[2, 3], &mut calls, n, o, c));
+        assert_eq!(Ok(vec![0, 1, 2]), objects);
+        assert_eq!(4, calls);
+
+        // An object is added between the calls and the second call fails
+        let mut calls = 0;
+        let objects = get_object_list(None, |n, o, c| mock_get_list(&[3, 4], &mut calls, n, o, c));
+        assert_eq!(Ok(vec![0, 1, 2, 3]), objects);
+        assert_eq!(4, calls);
+
+        // An object is removed between the calls
+        let mut calls = 0;
+        let objects = get_object_list(None, |n, o, c| mock_get_list(&[3, 2], &mut calls, n, o, c));
+        assert_eq!(Ok(vec![0, 1]), objects);
+        assert_eq!(2, calls);
+
+        // The number of objects keeps changing, the attempts are bounded and
+        // the last error is returned
+        let mut calls = 0;
+        let counts: Vec<cl_uint> = (1..20).map(|i| 2 * i).collect();
+        let objects = get_object_list(None, |n, o, c| mock_get_list(&counts, &mut calls, n, o, c));
+        assert_eq!(Err(CL_INVALID_VALUE), objects);
+        assert_eq!(2 * OBJECT_LIST_ATTEMPTS, calls);
+
+        // A persistent CL_INVALID_VALUE is not retried when the number of
+        // objects does not change
+        let mut calls = 0;
+        let objects = get_object_list(None, |_, objects: *mut usize, count: *mut cl_uint| {
+            calls += 1;
+            if objects.is_null() {
+                unsafe { *count = 3 };
+                CL_SUCCESS
+            } else {
+                CL_INVALID_VALUE
+            }
+        });
+        assert_eq!(Err(CL_INVALID_VALUE), objects);
+        assert_eq!(3, calls);
+
+        // More objects on every attempt, the list is not truncated
+        let mut calls = 0;
+        let counts: Vec<cl_uint> = (1..20).map(|i| 2 * i + 1).collect();
+        let objects = get_object_list(None, |n, o, c| mock_get_list(&counts, &mut calls, n, o, c));
+        assert_eq!(Err(CL_OUT_OF_RESOURCES), objects);
+        assert_eq!(2 * OBJECT_LIST_ATTEMPTS, calls);
+
+        // No objects
+        let mut calls = 0;
+        let objects = get_object_list(None, |n, o, c| mock_get_list(&[0], &mut calls, n, o, c));
+        assert_eq!(Ok(Vec::default()), objects);
+        assert_eq!(1, calls);
+
+        // A not found status
+        let objects: Result<Vec<usize>, cl_int> = get_object_list(Some(-1), |_, _, _| -1);
+        assert_eq!(Ok(Vec::default()), objects);
+        let objects: Result<Vec<usize>, cl_int> = get_object_list(Some(-1), |_, _, _| -2);
+        assert_eq!(Err(-2), objects);
+    }
+
+    #[test]
+    fn test_create_object_list() {
+        let mut calls = 0;
+        let objects = create_object_list(|n, o, c| mock_get_list(&[3], &mut calls, n, o, c));
+        assert_eq!(Ok(vec![0, 1, 2]), objects);
+        assert_eq!(2, calls);
+
+        // The second call fails, it is not retried
+        let mut calls = 0;
+        let objects = create_object_list(|n, o, c| mock_get_list(&[3, 4], &mut calls, n, o, c));
+        assert_eq!(Err(CL_INVALID_VALUE), objects);
+        assert_eq!(2, calls);
+
+        // No objects
+        let mut calls = 0;
+        let objects = create_object_list(|n, o, c| mock_get_list(&[0], &mut calls, n, o, c));
+        assert_eq!(Ok(Vec::default()), objects);
+        assert_eq!(1, calls);
+    }
+}
@@ -14,6 +14,28 @@
 
 #![allow(non_camel_case_types, unused_macros)]
 
+use libc::size_t;
+use std::ptr;
+
+/// Zero extend a scalar info value of `size` bytes, where the OpenCL
+/// implementation only returned `returned` bytes of it, e.g. a one byte
+/// cl_bool. Used by the `api_info_value` and `api2_info_value` macros.
+///
+/// # Safety
+///
+/// `data` must point to `size` bytes of writable memory.
+#[doc(hidden)]
+pub unsafe fn zero_extend_info_value(data: *mut u8, size: size_t, returned: size_t) {
+    if 0 < returned && returned < size {
+        if cfg!(target_endian = "big") {
+            ptr::copy(data, data.add(size - returned), returned);
+            ptr::write_bytes(data, 0, size - returned);
+        } else {
+            ptr::write_bytes(data.add(returned), 0, size - returned);
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! api_info_size {
     ($func:ident, $api:ident) => {
@@ -38,18 +60,23 @@ macro_rules! api_info_value {
             let size: size_t = mem::size_of::<$ty>();
             let mut data: $ty = $ty::default();
             let data_ptr: *mut $ty = &mut data;
+            // The size of the value returned, which may be smaller than the type.
+            let mut returned: size_t = size;
             let status = unsafe {
                 $api(
                     object,
                     param_name,
                     size,
                     data_ptr as *mut c_void,
-                    ptr::null_mut(),
+                    &mut returned,
                 )
             };
             if CL_SUCCESS != status {
                 Err(status)
             } else {
+                unsafe {
+                    $crate::macros::zero_extend_info_value(data_ptr as *mut u8, size, returned)
+                };
                 Ok(data)
             }
         }
@@ -113,6 +140,8 @@ macro_rules! api2_info_value {
             let size: size_t = mem::size_of::<$ty>();
             let mut data: $ty = $ty::default();
             let data_ptr: *mut $ty = &mut data;
+            // The size of the value returned, which may be smaller than the type.
+            let mut returned: size_t = size;
             let status = unsafe {
                 $api(
                     object,
@@ -120,12 +149,15 @@ macro_rules! api2_info_value {
                     param_name,
                     size,
                     data_ptr as *mut c_void,
-                    ptr::null_mut(),
+                    &mut returned,
                 )
             };
             if CL_SUCCESS != status {
                 Err(status)
             } else {
+                unsafe {
+                    $crate::macros::zero_extend_info_value(data_ptr as *mut u8, size, returned)
+                };
                 Ok(data)
             }
         }
@@ -166,3 +198,306 @@ macro_rules! api2_info_vector {
         }
     };
 }
+
+/// Declare a struct of kernel arguments and implement `KernelArgs` for it.  
+/// `bind` sets each field as the kernel argument at its index in
+/// declaration order. A field attribute changes how it is bound:
+/// * `#[arg(index = 3)]` - sets the field at index 3, the following fields
+///   then continue from that index.
+/// * `#[arg(skip)]` - the field is not a kernel argument and does not take an index.
+///
+/// Each field that is not skipped must implement `KernelArg`: an OpenCL scalar
+/// type, cl_mem, LocalMem or SvmPtr.
+///
+/// ```no_run
+/// use cl3::kernel::{KernelArgs, LocalMem};
+/// use cl3::kernel_args;
+/// use cl3::types::{cl_float, cl_kernel, cl_mem};
+///
+/// kernel_args! {
+///     struct SaxpyArgs {
+///         z: cl_mem,
+///         x: cl_mem,
+///         y: cl_mem,
+///         a: cl_float,
+///         #[arg(skip)]
+///         label: String,
+///         #[arg(index = 5)]
+///         scratch: LocalMem,
+///     }
+/// }
+///
+/// fn set_args(kernel: cl_kernel, z: cl_mem, x: cl_mem, y: cl_mem) {
+///     let label = String::from("saxpy");
+///     let args = SaxpyArgs { z, x, y, a: 300.0, label, scratch: LocalMem(1024) };
+///     args.bind(kernel).unwrap();
+/// }
+/// ```
+#[macro_export]
+macro_rules! kernel_args {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($fields:tt)*
+        }
+    ) => {
+        $crate::kernel_args!(@field $name [$(#[$attr])* $vis struct $name] [] [] [] [] $($fields)*);
+    };
+
+    // All fields parsed: the state is the struct name, the struct header,
+    // its fields, the fields to bind and the pending field attributes.
+    (@field $name:ident [$($header:tt)*] [$($fields:tt)*]
+        [$({ $field:ident [$($index:expr)?] })*] [] []
+    ) => {
+        $($header)* {
+            $($fields)*
+        }
+
+        impl $crate::kernel::KernelArgs for $name {
+            #[allow(unused_assignments, unused_mut)]
+            fn bind(
+                &self,
+                kernel: $crate::types::cl_kernel,
+            ) -> Result<(), ($crate::types::cl_uint, $crate::types::cl_int)> {
+                let mut index: $crate::types::cl_uint = 0;
+                $(
+                    $(index = $index;)?
+                    $crate::kernel::KernelArg::set_arg(&self.$field, kernel, index)
+                        .map_err(|e| (index, e))?;
+                    index += 1;
+                )*
+                Ok(())
+            }
+        }
+    };
+
+    (@field $name:ident $header:tt $fields:tt $binds:tt $attrs:tt []
+        #[arg(skip)] $($rest:tt)*
+    ) => {
+        $crate::kernel_args!(@field $name $header $fields $binds $attrs [skip] $($rest)*);
+    };
+
+    (@field $name:ident $header:tt $fields:tt $binds:tt $attrs:tt []
+        #[arg(index = $index:expr)] $($rest:tt)*
+    ) => {
+        $crate::kernel_args!(@field $name $header $fields $binds $attrs [$index] $($rest)*);
+    };
+
+    (@field $name:ident $header:tt $fields:tt $binds:tt [$($attrs:tt)*] $arg:tt
+        #[$field_attr:meta] $($rest:tt)*
+    ) => {
+        $crate::kernel_args!(@field $name $header $fields $binds [$($attrs)* #[$field_attr]] $arg $($rest)*);
+    };
+
+    (@field $name:ident $header:tt [$($fields:tt)*] $binds:tt [$($attrs:tt)*] [skip]
+        $field_vis:vis $field:ident : $ty:ty $(, $($rest:tt)*)?
+    ) => {
+        $crate::kernel_args!(@field $name $header [$($fields)* $($attrs)* $field_vis $field: $ty,]
+            $binds [] [] $($($rest)*)?);
+    };
+
+    (@field $name:ident $header:tt [$($fields:tt)*] [$($binds:tt)*] [$($attrs:tt)*] [$($index:expr)?]
+        $field_vis:vis $field:ident : $ty:ty $(, $($rest:tt)*)?
+    ) => {
+        $crate::kernel_args!(@field $name $header [$($fields)* $($attrs)* $field_vis $field: $ty,]
+            [$($binds)* { $field [$($index)?] }] [] [] $($($rest)*)?);
+    };
+}
+
+/// Test utility: run a block, catching any panic, and assert that a reference
+/// count is the same afterwards as it was before.  
+/// The count expression is evaluated before and after the block, e.g. a
+/// closure that calls get_context_info for CL_CONTEXT_REFERENCE_COUNT.
+///
+/// returns the std::thread::Result of the block so that tests can check
+/// whether it panicked.
+#[cfg(test)]
+#[macro_export]
+macro_rules! refcount_invariant {
+    ($count:expr, $body:block) => {{
+        let before = $count;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| $body));
+        let after = $count;
+        assert_eq!(before, after, "reference count changed");
+        result
+    }};
+}
+
+/// Test hooks: counters of the successful calls to the retain and release
+/// functions of each OpenCL object, on the current thread.  
+/// The CL_*_REFERENCE_COUNT queries are stale as soon as they return and
+/// include the references of other threads, so tests use `balance` as the
+/// count of `refcount_invariant` instead, e.g.
+/// `refcount_invariant!(balance(context as usize), { ... })`.
+#[cfg(test)]
+pub(crate) mod refcount_hooks {
+    use crate::error_codes::CL_SUCCESS;
+    use crate::types::cl_int;
+    use std::cell::RefCell;
+
+    thread_local! {
+        // The retains less the releases of each object, by handle.
+        static COUNTS: RefCell<Vec<(usize, isize)>> = RefCell::new(Vec::new());
+    }
+
+    fn count(handle: usize, status: cl_int, delta: isize) {
+        if CL_SUCCESS == status {
+            COUNTS.with(|counts| {
+                let mut counts = counts.borrow_mut();
+                match counts.iter_mut().find(|(h, _)| *h == handle) {
+                    Some((_, count)) => *count += delta,
+                    None => counts.push((handle, delta)),
+                }
+            });
+        }
+    }
+
+    /// Count a call to a retain function that returned `status`.
+    pub fn retained(handle: usize, status: cl_int) {
+        count(handle, status, 1);
+    }
+
+    /// Count a call to a release function that returned `status`.
+    pub fn released(handle: usize, status: cl_int) {
+        count(handle, status, -1);
+    }
+
+    /// The successful retains less releases of an object on the current thread.
+    pub fn balance(handle: usize) -> isize {
+        COUNTS.with(|counts| {
+            counts
+                .borrow()
+                .iter()
+                .find(|(h, _)| *h == handle)
+                .map_or(0, |(_, count)| *count)
+        })
+    }
+}
+
+/// Test hooks: counters of the calls to the functions that query OpenCL,
+/// by OpenCL function name, on the current thread.  
+/// Only the functions that tests count call `called`, e.g. get_platform_ids
+/// counts "clGetPlatformIDs".
+#[cfg(test)]
+pub(crate) mod call_hooks {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static CALLS: RefCell<Vec<(&'static str, usize)>> = RefCell::new(Vec::new());
+    }
+
+    /// Count a call to the OpenCL function `name`.
+    pub fn called(name: &'static str) {
+        CALLS.with(|calls| {
+            let mut calls = calls.borrow_mut();
+            match calls.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, count)) => *count += 1,
+                None => calls.push((name, 1)),
+            }
+        });
+    }
+
+    /// The number of calls to the OpenCL function `name` on the current thread.
+    pub fn calls(name: &'static str) -> usize {
+        CALLS.with(|calls| {
+            calls
+                .borrow()
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map_or(0, |(_, count)| *count)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+    use crate::types::{cl_int, cl_uint, cl_ulong};
+    use libc::{c_void, size_t};
+    use std::mem;
+    use std::ptr;
+
+    // A mock info function that returns `param_name` bytes of a value,
+    // like an implementation that returns a one byte cl_bool.
+    // The rest of the buffer is filled with 0xAA, so that the test fails
+    // unless the value is zero extended.
+    unsafe fn mock_get_info(
+        _object: *mut c_void,
+        param_name: cl_uint,
+        size: size_t,
+        value: *mut c_void,
+        size_ret: *mut size_t,
+    ) -> cl_int {
+        let returned = param_name as size_t;
+        if size < returned {
+            return CL_INVALID_VALUE;
+        }
+        ptr::write_bytes(value as *mut u8, 0xAA, size);
+        match returned {
+            1 => *(value as *mut u8) = 0x81,
+            2 => *(value as *mut u16) = 0x8281,
+            4 => *(value as *mut u32) = 0x8483_8281,
+            _ => return CL_INVALID_VALUE,
+        }
+        if !size_ret.is_null() {
+            *size_ret = returned;
+        }
+        CL_SUCCESS
+    }
+
+    unsafe fn mock_get_info2(
+        object: *mut c_void,
+        _idx: cl_uint,
+        param_name: cl_uint,
+        size: size_t,
+        value: *mut c_void,
+        size_ret: *mut size_t,
+    ) -> cl_int {
+        mock_get_info(object, param_name, size, value, size_ret)
+    }
+
+    #[test]
+    fn test_info_value_zero_extend() {
+        api_info_value!(get_uint, cl_uint, mock_get_info);
+        assert_eq!(0x81, get_uint(ptr::null_mut(), 1).unwrap());
+        assert_eq!(0x8281, get_uint(ptr::null_mut(), 2).unwrap());
+        assert_eq!(0x8483_8281, get_uint(ptr::null_mut(), 4).unwrap());
+
+        api_info_value!(get_ulong, cl_ulong, mock_get_info);
+        assert_eq!(0x81, get_ulong(ptr::null_mut(), 1).unwrap());
+        assert_eq!(0x8483_8281, get_ulong(ptr::null_mut(), 4).unwrap());
+
+        api2_info_value!(get_uint2, cl_uint, cl_uint, mock_get_info2);
+        assert_eq!(0x81, get_uint2(ptr::null_mut(), 0, 1).unwrap());
+        assert_eq!(0x8281, get_uint2(ptr::null_mut(), 0, 2).unwrap());
+        assert_eq!(0x8483_8281, get_uint2(ptr::null_mut(), 0, 4).unwrap());
+    }
+
+    #[test]
+    fn test_refcount_hooks() {
+        use super::refcount_hooks::{balance, released, retained};
+        use crate::error_codes::CL_INVALID_MEM_OBJECT;
+
+        let handle = 0x1000;
+        assert_eq!(0, balance(handle));
+        refcount_invariant!(balance(handle), {
+            retained(handle, CL_SUCCESS);
+            retained(handle, CL_SUCCESS);
+            assert_eq!(2, balance(handle));
+            // Failed calls are not counted
+            released(handle, CL_INVALID_MEM_OBJECT);
+            assert_eq!(2, balance(handle));
+            released(handle, CL_SUCCESS);
+            released(handle, CL_SUCCESS);
+        })
+        .unwrap();
+
+        // The counts are per thread
+        retained(handle, CL_SUCCESS);
+        std::thread::spawn(move || assert_eq!(0, balance(handle)))
+            .join()
+            .unwrap();
+        released(handle, CL_SUCCESS);
+        assert_eq!(0, balance(handle));
+    }
+}
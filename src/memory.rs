@@ -13,11 +13,18 @@
 // limitations under the License.
 
 //! OpenCL Memory Object API.
+//!
+//! The functions that take host data or property list pointers are in the
+//! [raw] submodule. Each raw function is also available at its old path in
+//! this module but is deprecated. The replacements take slices or references,
+//! e.g. `create_buffer_from_slice` and `create_image_from_desc`.
 
 #![allow(non_camel_case_types)]
 
+pub mod raw;
+
 pub use cl_sys::{
-    CL_RGBx, CL_RGx, CL_Rx, CL_sRGB, CL_sRGBA, CL_sRGBx, CL_A, CL_ABGR, CL_ADDRESS_CLAMP,
+    CL_RGBx, CL_RGx, CL_Rx, CL_sBGRA, CL_sRGB, CL_sRGBA, CL_sRGBx, CL_A, CL_ABGR, CL_ADDRESS_CLAMP,
     CL_ADDRESS_CLAMP_TO_EDGE, CL_ADDRESS_MIRRORED_REPEAT, CL_ADDRESS_NONE, CL_ADDRESS_REPEAT,
     CL_ARGB, CL_BGRA, CL_BUFFER_CREATE_TYPE_REGION, CL_DEPTH, CL_DEPTH_STENCIL, CL_FILTER_LINEAR,
     CL_FILTER_NEAREST, CL_FLOAT, CL_HALF_FLOAT, CL_INTENSITY, CL_LUMINANCE, CL_MAP_READ,
@@ -34,24 +41,28 @@ pub use cl_sys::{
     CL_UNSIGNED_INT16, CL_UNSIGNED_INT32, CL_UNSIGNED_INT8,
 };
 
-use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+use super::device::{get_device_info, DeviceInfo, ImageLimits};
+use super::error_codes::{
+    error_text, CL_INVALID_IMAGE_SIZE, CL_INVALID_OPERATION, CL_INVALID_VALUE, CL_SUCCESS,
+};
 use super::info_type::InfoType;
 #[allow(unused_imports)]
 use super::types::{
-    cl_buffer_create_type, cl_context, cl_image_desc, cl_image_format, cl_image_info, cl_int,
-    cl_map_flags, cl_mem, cl_mem_flags, cl_mem_info, cl_mem_object_type, cl_mem_properties,
-    cl_pipe_info, cl_svm_mem_flags, cl_uint, cl_ulong,
+    cl_buffer_create_type, cl_buffer_region, cl_channel_order, cl_channel_type, cl_context,
+    cl_device_id, cl_image_desc, cl_image_format, cl_image_info, cl_int, cl_map_flags, cl_mem,
+    cl_mem_flags, cl_mem_info, cl_mem_object_type, cl_mem_properties, cl_pipe_info,
+    cl_svm_mem_flags, cl_uint, cl_ulong,
 };
 #[allow(unused_imports)]
 use cl_sys::{
-    clCreateBuffer, clCreatePipe, clCreateSubBuffer, clGetImageInfo, clGetMemObjectInfo,
-    clGetPipeInfo, clReleaseMemObject, clRetainMemObject, clSVMAlloc, clSVMFree,
-    clSetMemObjectDestructorCallback,
+    clCreatePipe, clGetImageInfo, clGetMemObjectInfo, clGetPipeInfo, clReleaseMemObject,
+    clRetainMemObject, clSetMemObjectDestructorCallback,
 };
 
 use super::{api_info_size, api_info_value, api_info_vector};
 
 use libc::{c_void, intptr_t, size_t};
+use std::fmt;
 use std::mem;
 use std::ptr;
 
@@ -101,88 +112,259 @@ extern "system" {
     // #endif
 }
 
-/// Create an OpenCL buffer object for a context.  
-/// Calls clCreateBuffer to create an OpenCL buffer object.  
+#[deprecated(
+    note = "use the slice-based variant: create_buffer_from_slice or create_buffer_of_size"
+)]
+#[inline]
+pub fn create_buffer(
+    context: cl_context,
+    flags: cl_mem_flags,
+    size: size_t,
+    host_ptr: *mut c_void,
+) -> Result<cl_mem, cl_int> {
+    raw::create_buffer(context, flags, size, host_ptr)
+}
+
+/// Create an uninitialised OpenCL buffer object for a context.  
+/// Calls clCreateBuffer to create an OpenCL buffer object without host data.
 ///
 /// * `context` - a valid OpenCL context.
 /// * `flags` - a bit-field used to specify allocation and usage information
-/// about the image memory object being created, see:
+///   about the buffer memory object being created, see:
 /// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
 /// * `size` - the size in bytes of the buffer memory object to be allocated.
-/// * `host_ptr` - a pointer to the buffer data that may already be allocated
-/// by the application.
 ///
 /// returns a Result containing the new OpenCL buffer object
 /// or the error code from the OpenCL C API function.
 #[inline]
-pub fn create_buffer(
+pub fn create_buffer_of_size(
     context: cl_context,
     flags: cl_mem_flags,
     size: size_t,
-    host_ptr: *mut c_void,
 ) -> Result<cl_mem, cl_int> {
-    let mut status: cl_int = CL_INVALID_VALUE;
-    let mem: cl_mem = unsafe { clCreateBuffer(context, flags, size, host_ptr, &mut status) };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(mem)
-    }
+    raw::create_buffer(context, flags, size, ptr::null_mut())
 }
 
-/// Create an new OpenCL buffer object from an existing buffer object.  
-/// Calls clCreateSubBuffer to create an OpenCL sub-buffer object.  
+/// Create an OpenCL buffer object containing a copy of a slice.  
+/// Calls clCreateBuffer with CL_MEM_COPY_HOST_PTR added to `flags`.
 ///
-/// * `buffer` - a valid OpenCL buffer.
+/// * `context` - a valid OpenCL context.
 /// * `flags` - a bit-field used to specify allocation and usage information
-/// about the sub-buffer memory object being created, see:
-/// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
-/// * `buffer_create_type`,`buffer_create_info` - describe the type of
-/// buffer object to be created, see:
-/// [SubBuffer Attributes](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#subbuffer-create-info-table).
+///   about the buffer memory object being created, must not contain
+///   CL_MEM_USE_HOST_PTR.
+/// * `data` - the data to copy into the buffer.
 ///
 /// returns a Result containing the new OpenCL buffer object
+/// or CL_INVALID_VALUE if `flags` contains CL_MEM_USE_HOST_PTR
 /// or the error code from the OpenCL C API function.
 #[inline]
+pub fn create_buffer_from_slice<T: Copy>(
+    context: cl_context,
+    flags: cl_mem_flags,
+    data: &[T],
+) -> Result<cl_mem, cl_int> {
+    // The buffer must not outlive the borrow of data.
+    if 0 != flags & CL_MEM_USE_HOST_PTR {
+        return Err(CL_INVALID_VALUE);
+    }
+    raw::create_buffer(
+        context,
+        flags | CL_MEM_COPY_HOST_PTR,
+        mem::size_of_val(data),
+        data.as_ptr() as *mut c_void,
+    )
+}
+
+#[deprecated(note = "use the typed variant: create_sub_buffer_region")]
+#[inline]
 pub fn create_sub_buffer(
     buffer: cl_mem,
     flags: cl_mem_flags,
     buffer_create_type: cl_buffer_create_type,
     buffer_create_info: *const c_void,
 ) -> Result<cl_mem, cl_int> {
-    let mut status: cl_int = CL_INVALID_VALUE;
-    let mem: cl_mem = unsafe {
-        clCreateSubBuffer(
-            buffer,
-            flags,
-            buffer_create_type,
-            buffer_create_info,
-            &mut status,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
+    raw::create_sub_buffer(buffer, flags, buffer_create_type, buffer_create_info)
+}
+
+// Convert CL_DEVICE_MEM_BASE_ADDR_ALIGN from bits to bytes.
+fn base_addr_align_bytes(bits: cl_uint) -> size_t {
+    (bits as size_t / 8).max(1)
+}
+
+// Round an offset up to a multiple of an alignment in bytes.
+fn align_up(offset: size_t, alignment: size_t) -> size_t {
+    offset.div_ceil(alignment) * alignment
+}
+
+/// Get the alignment in bytes of the origin of a sub-buffer on a device.  
+/// Calls clGetDeviceInfo to get CL_DEVICE_MEM_BASE_ADDR_ALIGN.
+///
+/// Note: CL_DEVICE_MEM_BASE_ADDR_ALIGN is in **bits**, not bytes, e.g. 1024
+/// for a device that requires sub-buffer origins to be 128 byte aligned.
+/// Treating it as a number of bytes is a common cause of
+/// CL_MISALIGNED_SUB_BUFFER_OFFSET.
+///
+/// * `device` - the device that will use the sub-buffer.
+///
+/// returns a Result containing the alignment in bytes
+/// or the error code from the OpenCL C API function.
+pub fn sub_buffer_alignment_bytes(device: cl_device_id) -> Result<size_t, cl_int> {
+    let bits = get_device_info(device, DeviceInfo::CL_DEVICE_MEM_BASE_ADDR_ALIGN)?.to_uint();
+    Ok(base_addr_align_bytes(bits))
+}
+
+/// Round a sub-buffer origin up to the alignment of a device.  
+/// Calls sub_buffer_alignment_bytes.
+///
+/// * `offset` - the origin of the sub-buffer in bytes.
+/// * `device` - the device that will use the sub-buffer.
+///
+/// returns a Result containing the aligned origin in bytes
+/// or the error code from the OpenCL C API function.
+pub fn align_up_for_sub_buffer(offset: size_t, device: cl_device_id) -> Result<size_t, cl_int> {
+    Ok(align_up(offset, sub_buffer_alignment_bytes(device)?))
+}
+
+/// How create_sub_buffer_region checks the origin of a sub-buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubBufferAlignment {
+    /// Pass the origin to clCreateSubBuffer unchanged.
+    Unchecked,
+    /// Return SubBufferError::Misaligned if the origin is not aligned for
+    /// the device.
+    Validate(cl_device_id),
+    /// Round the origin up to the alignment of the device.
+    /// Return SubBufferError::OutOfBounds if the aligned region does not fit
+    /// in the buffer.
+    AlignUp(cl_device_id),
+}
+
+/// Why create_sub_buffer_region could not create a sub-buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubBufferError {
+    /// The origin is not a multiple of the device alignment in bytes.
+    Misaligned { origin: size_t, alignment: size_t },
+    /// The region at the aligned origin extends past the end of the buffer.
+    OutOfBounds {
+        origin: size_t,
+        size: size_t,
+        buffer_size: size_t,
+    },
+    /// The error code from the OpenCL C API function.
+    Api(cl_int),
+}
+
+/// Implement the From trait
+impl From<cl_int> for SubBufferError {
+    fn from(status: cl_int) -> Self {
+        SubBufferError::Api(status)
+    }
+}
+
+/// Implement the Display trait
+impl fmt::Display for SubBufferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubBufferError::Misaligned { origin, alignment } => write!(
+                f,
+                "sub-buffer origin of {} bytes is not a multiple of {} bytes \
+                 (CL_DEVICE_MEM_BASE_ADDR_ALIGN is {} bits)",
+                origin,
+                alignment,
+                alignment * 8
+            ),
+            SubBufferError::OutOfBounds {
+                origin,
+                size,
+                buffer_size,
+            } => write!(
+                f,
+                "sub-buffer of {} bytes at aligned origin {} does not fit in a buffer of {} bytes",
+                size, origin, buffer_size
+            ),
+            SubBufferError::Api(status) => write!(f, "{}", error_text(*status)),
+        }
+    }
+}
+
+impl std::error::Error for SubBufferError {}
+
+// Check a sub-buffer origin against an alignment in bytes.
+#[allow(clippy::manual_is_multiple_of)]
+fn check_sub_buffer_origin(origin: size_t, alignment: size_t) -> Result<(), SubBufferError> {
+    if 0 != origin % alignment {
+        Err(SubBufferError::Misaligned { origin, alignment })
     } else {
-        Ok(mem)
+        Ok(())
     }
 }
 
-/// Create an OpenCL image object for a context.  
-/// Calls clCreateImage to create an OpenCL image object.  
+// Check that a sub-buffer region fits in a buffer of buffer_size bytes.
+fn check_sub_buffer_region(
+    origin: size_t,
+    size: size_t,
+    buffer_size: size_t,
+) -> Result<(), SubBufferError> {
+    match origin.checked_add(size) {
+        Some(end) if end <= buffer_size => Ok(()),
+        _ => Err(SubBufferError::OutOfBounds {
+            origin,
+            size,
+            buffer_size,
+        }),
+    }
+}
+
+/// Create an OpenCL sub-buffer object for a region of a buffer object,
+/// optionally checking or aligning its origin for a device.  
+/// Calls sub_buffer_alignment_bytes, unless `alignment` is Unchecked, then
+/// clCreateSubBuffer with CL_BUFFER_CREATE_TYPE_REGION.
+/// If `alignment` is AlignUp, also calls clGetMemObjectInfo to check the
+/// aligned region against CL_MEM_SIZE.
 ///
-/// * `context` - a valid OpenCL context.
+/// * `buffer` - a valid OpenCL buffer.
 /// * `flags` - a bit-field used to specify allocation and usage information
-/// about the image memory object being created, see:
-/// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
-/// * `image_format` - a pointer to a structure that describes format properties
-/// of the image to be allocated.
-/// * `image_desc` - a pointer to a structure that describes type and dimensions
-/// of the image to be allocated.
-/// * `host_ptr` - a pointer to the image data that may already be allocated
-/// by the application.
+///   about the sub-buffer memory object being created.
+/// * `origin` - the offset of the region in `buffer` in bytes.
+/// * `size` - the size of the region in bytes.
+/// * `alignment` - how to check `origin` against CL_DEVICE_MEM_BASE_ADDR_ALIGN.
 ///
-/// returns a Result containing the new OpenCL image object
+/// returns a Result containing the new OpenCL buffer object and its origin,
+/// which differs from `origin` if it was aligned up
+/// or SubBufferError::Misaligned if `origin` is not aligned for the device
+/// or SubBufferError::OutOfBounds if the aligned region does not fit in `buffer`
 /// or the error code from the OpenCL C API function.
+pub fn create_sub_buffer_region(
+    buffer: cl_mem,
+    flags: cl_mem_flags,
+    origin: size_t,
+    size: size_t,
+    alignment: SubBufferAlignment,
+) -> Result<(cl_mem, size_t), SubBufferError> {
+    let origin = match alignment {
+        SubBufferAlignment::Unchecked => origin,
+        SubBufferAlignment::Validate(device) => {
+            check_sub_buffer_origin(origin, sub_buffer_alignment_bytes(device)?)?;
+            origin
+        }
+        SubBufferAlignment::AlignUp(device) => {
+            let origin = align_up_for_sub_buffer(origin, device)?;
+            let buffer_size = get_mem_object_info(buffer, MemInfo::CL_MEM_SIZE)?.to_size();
+            check_sub_buffer_region(origin, size, buffer_size)?;
+            origin
+        }
+    };
+    let region = cl_buffer_region { origin, size };
+    let mem = raw::create_sub_buffer(
+        buffer,
+        flags,
+        CL_BUFFER_CREATE_TYPE_REGION,
+        &region as *const cl_buffer_region as *const c_void,
+    )?;
+    Ok((mem, origin))
+}
+
+#[deprecated(note = "use the typed variant: create_image_from_desc")]
 #[inline]
 pub fn create_image(
     context: cl_context,
@@ -191,24 +373,277 @@ pub fn create_image(
     image_desc: *const cl_image_desc,
     host_ptr: *mut c_void,
 ) -> Result<cl_mem, cl_int> {
-    let mut status: cl_int = CL_INVALID_VALUE;
-    let mem: cl_mem = unsafe {
-        clCreateImage(
-            context,
-            flags,
-            image_format,
-            image_desc,
-            host_ptr,
-            &mut status,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
+    raw::create_image(context, flags, image_format, image_desc, host_ptr)
+}
+
+/// Create an uninitialised OpenCL image object for a context.  
+/// Calls clCreateImage to create an OpenCL image object without host data,
+/// use enqueue_write_image_slice to initialise it.
+///
+/// * `context` - a valid OpenCL context.
+/// * `flags` - a bit-field used to specify allocation and usage information
+///   about the image memory object being created.
+/// * `image_format` - the format properties of the image to be allocated.
+/// * `image_desc` - the type and dimensions of the image to be allocated.
+///
+/// returns a Result containing the new OpenCL image object
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn create_image_from_desc(
+    context: cl_context,
+    flags: cl_mem_flags,
+    image_format: &cl_image_format,
+    image_desc: &cl_image_desc,
+) -> Result<cl_mem, cl_int> {
+    raw::create_image(context, flags, image_format, image_desc, ptr::null_mut())
+}
+
+/// Describe an image format.
+///
+/// * `image_channel_order` - the channel order, e.g. CL_RGBA.
+/// * `image_channel_data_type` - the channel data type, e.g. CL_UNORM_INT8.
+///
+/// returns a cl_image_format for create_image.
+#[inline]
+pub fn image_format(
+    image_channel_order: cl_channel_order,
+    image_channel_data_type: cl_channel_type,
+) -> cl_image_format {
+    cl_image_format {
+        image_channel_order,
+        image_channel_data_type,
+    }
+}
+
+/// Describe the common 8 bits per channel RGBA image format:
+/// CL_RGBA and CL_UNORM_INT8.  
+/// It is one of the minimum list of image formats that all devices with
+/// image support must support.
+///
+/// returns a cl_image_format for create_image.
+#[inline]
+pub fn image_format_rgba_u8() -> cl_image_format {
+    image_format(CL_RGBA, CL_UNORM_INT8)
+}
+
+/// Describe a 2D image, all other fields are zero.
+///
+/// * `width` - the width of the image in pixels.
+/// * `height` - the height of the image in pixels.
+/// * `row_pitch` - the scan-line pitch in bytes of the host_ptr data, or 0
+///   if there is no host_ptr or its rows are packed.
+///
+/// returns a cl_image_desc for create_image.
+#[inline]
+pub fn image_desc_2d(width: size_t, height: size_t, row_pitch: size_t) -> cl_image_desc {
+    cl_image_desc {
+        image_type: CL_MEM_OBJECT_IMAGE2D,
+        image_width: width,
+        image_height: height,
+        image_depth: 0,
+        image_array_size: 0,
+        image_row_pitch: row_pitch,
+        image_slice_pitch: 0,
+        num_mip_levels: 0,
+        num_samples: 0,
+        mem_object: ptr::null_mut(),
+    }
+}
+
+/// Describe a 3D image, all other fields are zero.
+///
+/// * `width` - the width of the image in pixels.
+/// * `height` - the height of the image in pixels.
+/// * `depth` - the depth of the image in pixels.
+/// * `row_pitch` - the scan-line pitch in bytes of the host_ptr data, or 0
+///   if there is no host_ptr or its rows are packed.
+/// * `slice_pitch` - the size in bytes of each 2D slice of the host_ptr data,
+///   or 0 if there is no host_ptr or its slices are packed.
+///
+/// returns a cl_image_desc for create_image.
+#[inline]
+pub fn image_desc_3d(
+    width: size_t,
+    height: size_t,
+    depth: size_t,
+    row_pitch: size_t,
+    slice_pitch: size_t,
+) -> cl_image_desc {
+    cl_image_desc {
+        image_type: CL_MEM_OBJECT_IMAGE3D,
+        image_width: width,
+        image_height: height,
+        image_depth: depth,
+        image_array_size: 0,
+        image_row_pitch: row_pitch,
+        image_slice_pitch: slice_pitch,
+        num_mip_levels: 0,
+        num_samples: 0,
+        mem_object: ptr::null_mut(),
+    }
+}
+
+/// Validate a 2D or 3D image description against the limits of a device.  
+/// A 3D image that kernels may write to, i.e. not created with
+/// CL_MEM_READ_ONLY, requires 3D image write support.  
+/// Other image types are not checked.
+///
+/// * `limits` - the image limits of the device, see device_image_limits.
+/// * `supports_3d_image_writes` - see device_supports_3d_image_writes.
+/// * `flags` - the flags that the image will be created with.
+/// * `image_desc` - the description of the image.
+///
+/// returns an empty Result
+/// or CL_INVALID_IMAGE_SIZE if the image is larger than the limits
+/// or CL_INVALID_OPERATION if the image is a writable 3D image and the
+/// device does not support 3D image writes.
+pub fn validate_image_desc(
+    limits: &ImageLimits,
+    supports_3d_image_writes: bool,
+    flags: cl_mem_flags,
+    image_desc: &cl_image_desc,
+) -> Result<(), cl_int> {
+    match image_desc.image_type {
+        CL_MEM_OBJECT_IMAGE2D
+            if image_desc.image_width > limits.image2d_max_width
+                || image_desc.image_height > limits.image2d_max_height =>
+        {
+            Err(CL_INVALID_IMAGE_SIZE)
+        }
+        CL_MEM_OBJECT_IMAGE3D
+            if image_desc.image_width > limits.image3d_max_width
+                || image_desc.image_height > limits.image3d_max_height
+                || image_desc.image_depth > limits.image3d_max_depth =>
+        {
+            Err(CL_INVALID_IMAGE_SIZE)
+        }
+        CL_MEM_OBJECT_IMAGE3D if 0 == flags & CL_MEM_READ_ONLY && !supports_3d_image_writes => {
+            Err(CL_INVALID_OPERATION)
+        }
+        _ => Ok(()),
+    }
+}
+
+#[deprecated(note = "use the typed variant: create_validated_image")]
+pub fn create_image_for_device(
+    context: cl_context,
+    device: cl_device_id,
+    flags: cl_mem_flags,
+    image_format: &cl_image_format,
+    image_desc: &cl_image_desc,
+    host_ptr: *mut c_void,
+) -> Result<cl_mem, cl_int> {
+    raw::create_image_for_device(context, device, flags, image_format, image_desc, host_ptr)
+}
+
+/// Create an uninitialised OpenCL image object after validating it for a
+/// device.  
+/// Calls device_image_limits, device_supports_3d_image_writes and
+/// validate_image_desc before clCreateImage, so that an unsupported image
+/// fails with a specific error code rather than a driver specific one.
+///
+/// * `context` - a valid OpenCL context.
+/// * `device` - the device in the context that will use the image.
+/// * `flags` - a bit-field used to specify allocation and usage information
+///   about the image memory object being created.
+/// * `image_format` - the format properties of the image to be allocated.
+/// * `image_desc` - the type and dimensions of the image to be allocated.
+///
+/// returns a Result containing the new OpenCL image object
+/// or the error code from validate_image_desc
+/// or the error code from the OpenCL C API function.
+pub fn create_validated_image(
+    context: cl_context,
+    device: cl_device_id,
+    flags: cl_mem_flags,
+    image_format: &cl_image_format,
+    image_desc: &cl_image_desc,
+) -> Result<cl_mem, cl_int> {
+    raw::create_image_for_device(
+        context,
+        device,
+        flags,
+        image_format,
+        image_desc,
+        ptr::null_mut(),
+    )
+}
+
+/// Why create_constant_buffer could not create a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstantBufferError {
+    /// The data is larger than CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE.
+    TooLarge { size: cl_ulong, max_size: cl_ulong },
+    /// The error code from the OpenCL C API function.
+    Api(cl_int),
+}
+
+/// Implement the From trait
+impl From<cl_int> for ConstantBufferError {
+    fn from(status: cl_int) -> Self {
+        ConstantBufferError::Api(status)
+    }
+}
+
+/// Implement the Display trait
+impl fmt::Display for ConstantBufferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstantBufferError::TooLarge { size, max_size } => write!(
+                f,
+                "constant buffer of {} bytes exceeds CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE of {} bytes",
+                size, max_size
+            ),
+            ConstantBufferError::Api(status) => write!(f, "{}", error_text(*status)),
+        }
+    }
+}
+
+impl std::error::Error for ConstantBufferError {}
+
+// Check the size of a constant buffer against CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE.
+fn check_constant_buffer_size(
+    size: cl_ulong,
+    max_size: cl_ulong,
+) -> Result<(), ConstantBufferError> {
+    if size > max_size {
+        Err(ConstantBufferError::TooLarge { size, max_size })
     } else {
-        Ok(mem)
+        Ok(())
     }
 }
 
+/// Create a read-only buffer containing a copy of a table for a `constant`
+/// kernel argument, e.g. a lookup table.  
+/// Calls clGetDeviceInfo to check the size of the data against
+/// CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE, then clCreateBuffer with
+/// CL_MEM_READ_ONLY | CL_MEM_COPY_HOST_PTR.
+///
+/// Set the buffer as an argument of a kernel, e.g. `constant float* table`,
+/// in the same way as a `global` buffer. The total size of the `constant`
+/// arguments of a kernel must also be within the device limit, and the number
+/// of them within CL_DEVICE_MAX_CONSTANT_ARGS.
+///
+/// * `context` - a valid OpenCL context.
+/// * `device` - the device in the context that will use the buffer.
+/// * `data` - the table to copy into the buffer, must not be empty.
+///
+/// returns a Result containing the new OpenCL buffer, which must be released
+/// or ConstantBufferError::TooLarge with the size of the data and the device
+/// limit, in bytes
+/// or the error code from the OpenCL C API function.
+pub fn create_constant_buffer<T: Copy>(
+    context: cl_context,
+    device: cl_device_id,
+    data: &[T],
+) -> Result<cl_mem, ConstantBufferError> {
+    let size = mem::size_of_val(data);
+    let max_size =
+        get_device_info(device, DeviceInfo::CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE)?.to_ulong();
+    check_constant_buffer_size(size as cl_ulong, max_size)?;
+    Ok(create_buffer_from_slice(context, CL_MEM_READ_ONLY, data)?)
+}
+
 /// Create an OpenCL pipe object for a context.  
 /// Calls clCreatePipe to create an OpenCL pipe object.  
 /// CL_VERSION_2_0
@@ -249,86 +684,114 @@ pub fn create_pipe(
     }
 }
 
-/// Create an OpenCL buffer object for a context.  
+// Get the pointer to pass for a properties slice: null if it is empty,
+// otherwise it must be zero terminated.
+#[cfg(feature = "CL_VERSION_3_0")]
+fn mem_properties_ptr(
+    properties: &[cl_mem_properties],
+) -> Result<*const cl_mem_properties, cl_int> {
+    match properties.last() {
+        None => Ok(ptr::null()),
+        Some(0) => Ok(properties.as_ptr()),
+        Some(_) => Err(CL_INVALID_VALUE),
+    }
+}
+
+#[cfg(feature = "CL_VERSION_3_0")]
+#[deprecated(note = "use the slice-based variant: create_buffer_with_properties_slice")]
+#[inline]
+pub fn create_buffer_with_properties(
+    context: cl_context,
+    properties: *const cl_mem_properties,
+    flags: cl_mem_flags,
+    size: size_t,
+    host_ptr: *mut c_void,
+) -> Result<cl_mem, cl_int> {
+    raw::create_buffer_with_properties(context, properties, flags, size, host_ptr)
+}
+
+/// Create an uninitialised OpenCL buffer object for a context.  
 /// Calls clCreateBufferWithProperties to create an OpenCL buffer object.  
 /// CL_VERSION_3_0
 ///
 /// * `context` - a valid OpenCL context.
-/// * `properties` - an optional null terminated list of properties.
+/// * `properties` - a zero terminated list of properties, or an empty slice.
 /// * `flags` - a bit-field used to specify allocation and usage information
-/// about the image memory object being created, see:
-/// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
+///   about the buffer memory object being created.
 /// * `size` - the size in bytes of the buffer memory object to be allocated.
-/// * `host_ptr` - a pointer to the buffer data that may already be allocated
-/// by the application.
 ///
 /// returns a Result containing the new OpenCL buffer object
+/// or CL_INVALID_VALUE if `properties` is not zero terminated
 /// or the error code from the OpenCL C API function.
 #[cfg(feature = "CL_VERSION_3_0")]
 #[inline]
-pub fn create_buffer_with_properties(
+pub fn create_buffer_with_properties_slice(
     context: cl_context,
-    properties: *const cl_mem_properties,
+    properties: &[cl_mem_properties],
     flags: cl_mem_flags,
     size: size_t,
+) -> Result<cl_mem, cl_int> {
+    raw::create_buffer_with_properties(
+        context,
+        mem_properties_ptr(properties)?,
+        flags,
+        size,
+        ptr::null_mut(),
+    )
+}
+
+#[cfg(feature = "CL_VERSION_3_0")]
+#[deprecated(note = "use the slice-based variant: create_image_with_properties_slice")]
+#[inline]
+pub fn create_image_with_properties(
+    context: cl_context,
+    properties: *const cl_mem_properties,
+    flags: cl_mem_flags,
+    image_format: *const cl_image_format,
+    image_desc: *const cl_image_desc,
     host_ptr: *mut c_void,
 ) -> Result<cl_mem, cl_int> {
-    let mut status: cl_int = CL_INVALID_VALUE;
-    let mem: cl_mem = unsafe {
-        clCreateBufferWithProperties(context, properties, flags, size, host_ptr, &mut status)
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(mem)
-    }
+    raw::create_image_with_properties(
+        context,
+        properties,
+        flags,
+        image_format,
+        image_desc,
+        host_ptr,
+    )
 }
 
-/// Create an OpenCL image object for a context.  
-/// Calls clCreateImage to create an OpenCL image object.  
+/// Create an uninitialised OpenCL image object for a context.  
+/// Calls clCreateImageWithProperties to create an OpenCL image object.  
 /// CL_VERSION_3_0
 ///
 /// * `context` - a valid OpenCL context.
-/// * `properties` - an optional null terminated list of properties.
+/// * `properties` - a zero terminated list of properties, or an empty slice.
 /// * `flags` - a bit-field used to specify allocation and usage information
-/// about the image memory object being created, see:
-/// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
-/// * `image_format` - a pointer to a structure that describes format properties
-/// of the image to be allocated.
-/// * `image_desc` - a pointer to a structure that describes type and dimensions
-/// of the image to be allocated.
-/// * `host_ptr` - a pointer to the image data that may already be allocated
-/// by the application.
+///   about the image memory object being created.
+/// * `image_format` - the format properties of the image to be allocated.
+/// * `image_desc` - the type and dimensions of the image to be allocated.
 ///
 /// returns a Result containing the new OpenCL image object
+/// or CL_INVALID_VALUE if `properties` is not zero terminated
 /// or the error code from the OpenCL C API function.
-#[inline]
 #[cfg(feature = "CL_VERSION_3_0")]
-pub fn create_image_with_properties(
+#[inline]
+pub fn create_image_with_properties_slice(
     context: cl_context,
-    properties: *const cl_mem_properties,
+    properties: &[cl_mem_properties],
     flags: cl_mem_flags,
-    image_format: *const cl_image_format,
-    image_desc: *const cl_image_desc,
-    host_ptr: *mut c_void,
+    image_format: &cl_image_format,
+    image_desc: &cl_image_desc,
 ) -> Result<cl_mem, cl_int> {
-    let mut status: cl_int = CL_INVALID_VALUE;
-    let mem: cl_mem = unsafe {
-        clCreateImageWithProperties(
-            context,
-            properties,
-            flags,
-            image_format,
-            image_desc,
-            host_ptr,
-            &mut status,
-        )
-    };
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        Ok(mem)
-    }
+    raw::create_image_with_properties(
+        context,
+        mem_properties_ptr(properties)?,
+        flags,
+        image_format,
+        image_desc,
+        ptr::null_mut(),
+    )
 }
 
 /// Retain an OpenCL memory object.  
@@ -340,6 +803,8 @@ pub fn create_image_with_properties(
 #[inline]
 pub fn retain_mem_object(memobj: cl_mem) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clRetainMemObject(memobj) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::retained(memobj as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -356,6 +821,8 @@ pub fn retain_mem_object(memobj: cl_mem) -> Result<(), cl_int> {
 #[inline]
 pub fn release_mem_object(memobj: cl_mem) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clReleaseMemObject(memobj) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::released(memobj as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -381,15 +848,17 @@ fn count_supported_image_formats(
 
 /// Get the list of image formats supported by an OpenCL implementation for a
 /// specified context, image type, and allocation information.  
-/// Calls clGetSupportedImageFormats to get the desired information about the program.
+/// Calls clGetSupportedImageFormats to get the number of supported image
+/// formats and then the image formats.
 ///
 /// * `context` - a valid OpenCL context on which the image object(s) will be created.
 /// * `flags` - a bit-field used to specify allocation and usage information
 /// about the image memory object being created, see:
 /// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
-/// * `image_type` - describes the image type.
+/// * `image_type` - describes the image type, e.g. CL_MEM_OBJECT_IMAGE2D.
 ///
-/// returns a Result containing the desired information in an InfoType enum
+/// returns a Result containing the supported image formats, see
+/// channel_order_text and channel_type_text to display them,
 /// or the error code from the OpenCL C API function.
 #[inline]
 pub fn get_supported_image_formats(
@@ -417,6 +886,66 @@ pub fn get_supported_image_formats(
     }
 }
 
+/// Get the name of an image channel order, e.g. "CL_RGBA".
+///
+/// * `order` - the image_channel_order of a cl_image_format.
+///
+/// returns the name of the channel order constant or "UNKNOWN_CHANNEL_ORDER".
+#[allow(non_upper_case_globals)]
+pub fn channel_order_text(order: cl_channel_order) -> &'static str {
+    match order {
+        CL_R => "CL_R",
+        CL_A => "CL_A",
+        CL_RG => "CL_RG",
+        CL_RA => "CL_RA",
+        CL_RGB => "CL_RGB",
+        CL_RGBA => "CL_RGBA",
+        CL_BGRA => "CL_BGRA",
+        CL_ARGB => "CL_ARGB",
+        CL_INTENSITY => "CL_INTENSITY",
+        CL_LUMINANCE => "CL_LUMINANCE",
+        CL_Rx => "CL_Rx",
+        CL_RGx => "CL_RGx",
+        CL_RGBx => "CL_RGBx",
+        CL_DEPTH => "CL_DEPTH",
+        CL_DEPTH_STENCIL => "CL_DEPTH_STENCIL",
+        CL_sRGB => "CL_sRGB",
+        CL_sRGBx => "CL_sRGBx",
+        CL_sRGBA => "CL_sRGBA",
+        CL_sBGRA => "CL_sBGRA",
+        CL_ABGR => "CL_ABGR",
+        _ => "UNKNOWN_CHANNEL_ORDER",
+    }
+}
+
+/// Get the name of an image channel data type, e.g. "CL_UNORM_INT8".
+///
+/// * `data_type` - the image_channel_data_type of a cl_image_format.
+///
+/// returns the name of the channel type constant or "UNKNOWN_CHANNEL_TYPE".
+pub fn channel_type_text(data_type: cl_channel_type) -> &'static str {
+    match data_type {
+        CL_SNORM_INT8 => "CL_SNORM_INT8",
+        CL_SNORM_INT16 => "CL_SNORM_INT16",
+        CL_UNORM_INT8 => "CL_UNORM_INT8",
+        CL_UNORM_INT16 => "CL_UNORM_INT16",
+        CL_UNORM_SHORT_565 => "CL_UNORM_SHORT_565",
+        CL_UNORM_SHORT_555 => "CL_UNORM_SHORT_555",
+        CL_UNORM_INT_101010 => "CL_UNORM_INT_101010",
+        CL_SIGNED_INT8 => "CL_SIGNED_INT8",
+        CL_SIGNED_INT16 => "CL_SIGNED_INT16",
+        CL_SIGNED_INT32 => "CL_SIGNED_INT32",
+        CL_UNSIGNED_INT8 => "CL_UNSIGNED_INT8",
+        CL_UNSIGNED_INT16 => "CL_UNSIGNED_INT16",
+        CL_UNSIGNED_INT32 => "CL_UNSIGNED_INT32",
+        CL_HALF_FLOAT => "CL_HALF_FLOAT",
+        CL_FLOAT => "CL_FLOAT",
+        CL_UNORM_INT24 => "CL_UNORM_INT24",
+        CL_UNORM_INT_101010_2 => "CL_UNORM_INT_101010_2",
+        _ => "UNKNOWN_CHANNEL_TYPE",
+    }
+}
+
 /// Get data about an OpenCL memory object.
 /// Calls clGetMemObjectInfo to get the desired data about the memory object.
 pub fn get_mem_object_data(
@@ -462,13 +991,17 @@ pub fn get_mem_object_info(memobj: cl_mem, param_name: MemInfo) -> Result<InfoTy
     match param_name {
         MemInfo::CL_MEM_TYPE
         | MemInfo::CL_MEM_MAP_COUNT
-        | MemInfo::CL_MEM_REFERENCE_COUNT
-        | MemInfo::CL_MEM_USES_SVM_POINTER // CL_VERSION_2_0
-        => {
+        | MemInfo::CL_MEM_REFERENCE_COUNT => {
             api_info_value!(get_value, cl_uint, clGetMemObjectInfo);
             Ok(InfoType::Uint(get_value(memobj, param_id)?))
         }
 
+        MemInfo::CL_MEM_USES_SVM_POINTER // CL_VERSION_2_0
+        => {
+            api_info_value!(get_value, cl_uint, clGetMemObjectInfo);
+            Ok(InfoType::Bool(0 != get_value(memobj, param_id)?))
+        }
+
         MemInfo::CL_MEM_FLAGS => {
             api_info_value!(get_value, cl_ulong, clGetMemObjectInfo);
             Ok(InfoType::Ulong(get_value(memobj, param_id)?))
@@ -614,44 +1147,61 @@ pub fn get_pipe_info(pipe: cl_mem, param_name: PipeInfo) -> Result<InfoType, cl_
     }
 }
 
-/// Register a callback function with an OpenCL memory object that is called when the
-/// memory object is destroyed.  
-/// Calls clSetMemObjectDestructorCallback.  
-///
-/// * `memobj` - the OpenCL memory object.
-/// * `pfn_notify` - callback function to be registered by the application.
-/// * `user_data` - passed as the user_data argument when pfn_notify is called.
-///
-/// returns an empty Result or the error code from the OpenCL C API function.
+#[deprecated(note = "use the typed variant: set_mem_object_destructor_callback_closure")]
 #[inline]
 pub fn set_mem_object_destructor_callback(
     memobj: cl_mem,
     pfn_notify: extern "C" fn(cl_mem, *mut c_void),
     user_data: *mut c_void,
 ) -> Result<(), cl_int> {
-    let status: cl_int =
-        unsafe { clSetMemObjectDestructorCallback(memobj, Some(pfn_notify), user_data) };
+    raw::set_mem_object_destructor_callback(memobj, pfn_notify, user_data)
+}
+
+/// The type of a closure registered by set_mem_object_destructor_callback_closure.
+pub type MemObjectDestructorCallback = Box<dyn FnOnce(cl_mem) + Send>;
+
+// Trampoline for set_mem_object_destructor_callback_closure: reconstitutes the
+// boxed closure from user_data and calls it, OpenCL calls it only once.
+extern "C" fn mem_object_destructor_trampoline(memobj: cl_mem, user_data: *mut c_void) {
+    let callback = unsafe { Box::from_raw(user_data as *mut MemObjectDestructorCallback) };
+    // A panic must not unwind into the OpenCL implementation.
+    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(memobj)));
+}
+
+/// Register a Rust closure with an OpenCL memory object that is called when
+/// the memory object is destroyed.  
+/// Calls clSetMemObjectDestructorCallback to register a callback function
+/// that calls the closure once and then drops it.
+///
+/// Note: OpenCL may call the closure from any thread, so it must be `Send`.
+///
+/// * `memobj` - the OpenCL memory object.
+/// * `callback` - the closure to call with the memory object.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn set_mem_object_destructor_callback_closure(
+    memobj: cl_mem,
+    callback: MemObjectDestructorCallback,
+) -> Result<(), cl_int> {
+    let user_data = Box::into_raw(Box::new(callback));
+    let status: cl_int = unsafe {
+        clSetMemObjectDestructorCallback(
+            memobj,
+            Some(mem_object_destructor_trampoline),
+            user_data as *mut c_void,
+        )
+    };
     if CL_SUCCESS != status {
+        // The callback will never be called, so free the closure here.
+        unsafe { drop(Box::from_raw(user_data)) };
         Err(status)
     } else {
         Ok(())
     }
 }
 
-/// Allocate a shared virtual memory (SVM) buffer that can be shared by the
-/// host and all devices in an OpenCL context.  
-/// Calls clSVMAlloc.  
-/// CL_VERSION_2_0
-///
-/// * `context` - a valid OpenCL context.
-/// * `flags` - a bit-field used to specify allocation and usage information, see:
-/// [SVM Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#svm-flags-table).
-/// * `size` - the size in bytes of the SVM buffer to be allocated.
-/// * `alignment` - the minimum alignment in bytes that is required for the
-/// newly created buffers memory region.
-///
-/// returns Result containing the address of the SVM buffer
-/// or the error code: CL_INVALID_VALUE if the address is NULL.
+#[deprecated(note = "use the typed variant: svm::SvmBuffer")]
 #[inline]
 pub fn svm_alloc(
     context: cl_context,
@@ -659,21 +1209,522 @@ pub fn svm_alloc(
     size: size_t,
     alignment: cl_uint,
 ) -> Result<*mut c_void, cl_int> {
-    let ptr = unsafe { clSVMAlloc(context, flags, size, alignment) };
-    if ptr::null_mut() == ptr {
-        Err(CL_INVALID_VALUE)
-    } else {
-        Ok(ptr)
-    }
+    raw::svm_alloc(context, flags, size, alignment)
 }
 
-/// Free a shared virtual memory (SVM) buffer allocated using clSVMAlloc.  
-/// Calls clSVMFree.  
-/// CL_VERSION_2_0
-///
-/// * `context` - the valid OpenCL context used to create the SVM buffer.
-/// * `svm_pointer` - the value returned by a call to clSVMAlloc.
+#[deprecated(note = "use the typed variant: svm::SvmBuffer")]
 #[inline]
 pub fn svm_free(context: cl_context, svm_pointer: *mut c_void) {
-    unsafe { clSVMFree(context, svm_pointer) };
+    raw::svm_free(context, svm_pointer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::{
+        create_command_queue, enqueue_copy_buffer_to_image_slice, enqueue_copy_image_slice,
+        enqueue_copy_image_to_buffer_slice, enqueue_nd_range_kernel_slice,
+        enqueue_read_buffer_slice, enqueue_read_image_slice, enqueue_write_image_slice,
+        release_command_queue,
+    };
+    use crate::context::{create_context, release_context};
+    use crate::device::{device_supports_3d_image_writes, get_device_ids, CL_DEVICE_TYPE_ALL};
+    use crate::event::{get_event_info, release_event, EventInfo, CL_COMPLETE};
+    use crate::kernel::{create_kernel, release_kernel, KernelArg};
+    use crate::platform::get_platform_ids;
+    use crate::program::{build_program, create_program_with_source, release_program};
+    use crate::types::{CL_BLOCKING, CL_NON_BLOCKING};
+    use std::ffi::CString;
+
+    #[test]
+    fn test_image_format_and_desc() {
+        let format = image_format_rgba_u8();
+        assert_eq!(CL_RGBA, format.image_channel_order);
+        assert_eq!(CL_UNORM_INT8, format.image_channel_data_type);
+
+        let desc = image_desc_2d(640, 480, 2560);
+        assert_eq!(CL_MEM_OBJECT_IMAGE2D, desc.image_type);
+        assert_eq!(640, desc.image_width);
+        assert_eq!(480, desc.image_height);
+        assert_eq!(2560, desc.image_row_pitch);
+        assert_eq!(0, desc.image_depth);
+        assert_eq!(0, desc.image_array_size);
+        assert_eq!(0, desc.image_slice_pitch);
+        assert_eq!(0, desc.num_mip_levels);
+        assert_eq!(0, desc.num_samples);
+        assert!(desc.mem_object.is_null());
+    }
+
+    #[test]
+    fn test_validate_image_desc() {
+        let limits = ImageLimits {
+            image2d_max_width: 1024,
+            image2d_max_height: 512,
+            image3d_max_width: 256,
+            image3d_max_height: 128,
+            image3d_max_depth: 64,
+        };
+
+        let desc = image_desc_2d(1024, 512, 0);
+        assert_eq!(
+            Ok(()),
+            validate_image_desc(&limits, false, CL_MEM_WRITE_ONLY, &desc)
+        );
+        let desc = image_desc_2d(1025, 512, 0);
+        assert_eq!(
+            Err(CL_INVALID_IMAGE_SIZE),
+            validate_image_desc(&limits, false, CL_MEM_READ_ONLY, &desc)
+        );
+
+        let desc = image_desc_3d(256, 128, 64, 0, 0);
+        assert_eq!(
+            Ok(()),
+            validate_image_desc(&limits, false, CL_MEM_READ_ONLY, &desc)
+        );
+        assert_eq!(
+            Ok(()),
+            validate_image_desc(&limits, true, CL_MEM_READ_WRITE, &desc)
+        );
+        assert_eq!(
+            Err(CL_INVALID_OPERATION),
+            validate_image_desc(&limits, false, CL_MEM_READ_WRITE, &desc)
+        );
+        assert_eq!(
+            Err(CL_INVALID_OPERATION),
+            validate_image_desc(&limits, false, CL_MEM_WRITE_ONLY, &desc)
+        );
+        // Defaults to CL_MEM_READ_WRITE
+        assert_eq!(
+            Err(CL_INVALID_OPERATION),
+            validate_image_desc(&limits, false, 0, &desc)
+        );
+        let desc = image_desc_3d(256, 128, 65, 0, 0);
+        assert_eq!(
+            Err(CL_INVALID_IMAGE_SIZE),
+            validate_image_desc(&limits, true, CL_MEM_READ_ONLY, &desc)
+        );
+    }
+
+    #[test]
+    fn test_3d_image_write_read() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let format = image_format_rgba_u8();
+        let desc = image_desc_3d(4, 4, 4, 0, 0);
+        let supported = device_supports_3d_image_writes(device_id).unwrap();
+        println!("device_supports_3d_image_writes: {}", supported);
+        let image = create_validated_image(context, device_id, CL_MEM_READ_WRITE, &format, &desc);
+        if !supported {
+            assert_eq!(Err(CL_INVALID_OPERATION), image);
+            release_context(context).unwrap();
+            return;
+        }
+        let image = image.unwrap();
+
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+        let origin: [size_t; 3] = [0, 0, 0];
+        let region: [size_t; 3] = [4, 4, 4];
+        let data: Vec<u8> = (0..(4 * 4 * 4 * 4)).map(|x| x as u8).collect();
+        let event = unsafe {
+            enqueue_write_image_slice(
+                queue,
+                image,
+                CL_BLOCKING,
+                &origin,
+                &region,
+                0,
+                0,
+                &data,
+                &[],
+            )
+        }
+        .unwrap();
+        release_event(event).unwrap();
+
+        let mut results = vec![0u8; data.len()];
+        let event = unsafe {
+            enqueue_read_image_slice(
+                queue,
+                image,
+                CL_BLOCKING,
+                &origin,
+                &region,
+                0,
+                0,
+                &mut results,
+                &[],
+            )
+        }
+        .unwrap();
+
+        // The event of a blocking read is complete, but must still be released
+        let status = get_event_info(event, EventInfo::CL_EVENT_COMMAND_EXECUTION_STATUS)
+            .unwrap()
+            .to_int();
+        assert_eq!(CL_COMPLETE, status);
+        release_event(event).unwrap();
+        assert_eq!(data, results);
+
+        // The region does not fit in a shorter slice
+        let mut short = vec![0u8; data.len() - 1];
+        let result = unsafe {
+            enqueue_read_image_slice(
+                queue,
+                image,
+                CL_BLOCKING,
+                &origin,
+                &region,
+                0,
+                0,
+                &mut short,
+                &[],
+            )
+        };
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+
+        release_command_queue(queue).unwrap();
+        release_mem_object(image).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_get_supported_image_formats() {
+        let platform_ids = get_platform_ids().unwrap();
+
+        // Choose the first platform and device
+        let platform_id = platform_ids[0];
+        let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_ALL).unwrap();
+        assert!(0 < device_ids.len());
+        let device_ids = [device_ids[0]];
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let formats =
+            get_supported_image_formats(context, CL_MEM_READ_WRITE, CL_MEM_OBJECT_IMAGE2D).unwrap();
+        println!("CL_MEM_OBJECT_IMAGE2D formats: {}", formats.len());
+        for format in formats.iter() {
+            println!(
+                "{}: {}",
+                channel_order_text(format.image_channel_order),
+                channel_type_text(format.image_channel_data_type)
+            );
+        }
+        assert!(formats
+            .iter()
+            .any(|format| CL_RGBA == format.image_channel_order));
+
+        let format = image_format_rgba_u8();
+        let desc = image_desc_2d(16, 8, 0);
+        let image = create_image_from_desc(context, CL_MEM_READ_WRITE, &format, &desc).unwrap();
+        let value = get_image_info(image, ImageInfo::CL_IMAGE_WIDTH).unwrap();
+        assert_eq!(16, value.to_size());
+        release_mem_object(image).unwrap();
+
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_copy_images() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+
+        const WIDTH: usize = 16;
+        const HEIGHT: usize = 8;
+        const SIZE: usize = WIDTH * HEIGHT * 4;
+        let format = image_format_rgba_u8();
+        let desc = image_desc_2d(WIDTH, HEIGHT, 0);
+        let images: Vec<cl_mem> = (0..3)
+            .map(|_| create_image_from_desc(context, CL_MEM_READ_WRITE, &format, &desc).unwrap())
+            .collect();
+        let buffer = create_buffer_of_size(context, CL_MEM_READ_WRITE, SIZE).unwrap();
+
+        let origin: [size_t; 3] = [0, 0, 0];
+        let region: [size_t; 3] = [WIDTH, HEIGHT, 1];
+        let data: Vec<u8> = (0..SIZE).map(|x| x as u8).collect();
+        let write_event = unsafe {
+            enqueue_write_image_slice(
+                queue,
+                images[0],
+                CL_NON_BLOCKING,
+                &origin,
+                &region,
+                0,
+                0,
+                &data,
+                &[],
+            )
+        }
+        .unwrap();
+
+        // image -> buffer -> image -> image, without going through host memory
+        let to_buffer = enqueue_copy_image_to_buffer_slice(
+            queue,
+            images[0],
+            buffer,
+            &origin,
+            &region,
+            0,
+            &[write_event],
+        )
+        .unwrap();
+        let to_image = enqueue_copy_buffer_to_image_slice(
+            queue,
+            buffer,
+            images[1],
+            0,
+            &origin,
+            &region,
+            &[to_buffer],
+        )
+        .unwrap();
+        let copy_event = enqueue_copy_image_slice(
+            queue,
+            images[1],
+            images[2],
+            &origin,
+            &origin,
+            &region,
+            &[to_image],
+        )
+        .unwrap();
+
+        let mut results = vec![0u8; SIZE];
+        let read_event = unsafe {
+            enqueue_read_image_slice(
+                queue,
+                images[2],
+                CL_BLOCKING,
+                &origin,
+                &region,
+                0,
+                0,
+                &mut results,
+                &[copy_event],
+            )
+        }
+        .unwrap();
+        assert_eq!(data, results);
+
+        for event in [write_event, to_buffer, to_image, copy_event, read_event].iter() {
+            release_event(*event).unwrap();
+        }
+        release_mem_object(buffer).unwrap();
+        for image in images {
+            release_mem_object(image).unwrap();
+        }
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_check_constant_buffer_size() {
+        assert_eq!(Ok(()), check_constant_buffer_size(64, 64));
+        let error = check_constant_buffer_size(65, 64).unwrap_err();
+        assert_eq!(
+            ConstantBufferError::TooLarge {
+                size: 65,
+                max_size: 64
+            },
+            error
+        );
+        println!("{}", error);
+        assert!(error.to_string().contains("65"));
+        let boxed: Box<dyn std::error::Error> = Box::new(error);
+        assert!(boxed.to_string().contains("65"));
+
+        let error = ConstantBufferError::from(CL_INVALID_VALUE);
+        assert_eq!("CL_INVALID_VALUE", error.to_string());
+    }
+
+    #[test]
+    fn test_sub_buffer_alignment() {
+        // CL_DEVICE_MEM_BASE_ADDR_ALIGN is in bits
+        assert_eq!(128, base_addr_align_bytes(1024));
+        assert_eq!(4, base_addr_align_bytes(32));
+        assert_eq!(1, base_addr_align_bytes(0));
+
+        assert_eq!(0, align_up(0, 128));
+        assert_eq!(128, align_up(1, 128));
+        assert_eq!(128, align_up(128, 128));
+        assert_eq!(256, align_up(129, 128));
+
+        assert_eq!(Ok(()), check_sub_buffer_origin(256, 128));
+        let error = check_sub_buffer_origin(100, 128).unwrap_err();
+        assert_eq!(
+            SubBufferError::Misaligned {
+                origin: 100,
+                alignment: 128
+            },
+            error
+        );
+        println!("{}", error);
+        assert!(error.to_string().contains("1024 bits"));
+
+        assert_eq!(Ok(()), check_sub_buffer_region(128, 128, 256));
+        let error = check_sub_buffer_region(128, 129, 256).unwrap_err();
+        assert_eq!(
+            SubBufferError::OutOfBounds {
+                origin: 128,
+                size: 129,
+                buffer_size: 256
+            },
+            error
+        );
+        println!("{}", error);
+        assert!(check_sub_buffer_region(size_t::MAX, 1, 256).is_err());
+
+        let boxed: Box<dyn std::error::Error> = Box::new(error);
+        assert!(boxed.to_string().contains("256 bytes"));
+        assert_eq!(
+            "CL_INVALID_VALUE",
+            SubBufferError::from(CL_INVALID_VALUE).to_string()
+        );
+    }
+
+    #[test]
+    fn test_create_constant_buffer() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+
+        let source = "kernel void lookup(constant int* table, global int* out)
+            { size_t i = get_global_id(0); out[i] = 2 * table[i]; }";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::default();
+        build_program(program, &[device_id], &options, None, ptr::null_mut()).unwrap();
+        let kernel = create_kernel(program, &CString::new("lookup").unwrap()).unwrap();
+
+        const COUNT: usize = 256;
+        let table: Vec<cl_int> = (0..COUNT as cl_int).collect();
+        let constant = create_constant_buffer(context, device_id, &table).unwrap();
+        let out = create_buffer_of_size(context, CL_MEM_WRITE_ONLY, COUNT * 4).unwrap();
+        constant.set_arg(kernel, 0).unwrap();
+        out.set_arg(kernel, 1).unwrap();
+
+        let event =
+            enqueue_nd_range_kernel_slice(queue, kernel, None, &[COUNT], None, &[]).unwrap();
+        let mut results = vec![0 as cl_int; COUNT];
+        let read_event = unsafe {
+            enqueue_read_buffer_slice(queue, out, CL_BLOCKING, 0, &mut results, &[event])
+        }
+        .unwrap();
+        assert!(table.iter().zip(results.iter()).all(|(t, r)| 2 * t == *r));
+
+        // A table larger than the device limit is rejected before clCreateBuffer
+        let max_size = get_device_info(device_id, DeviceInfo::CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE)
+            .unwrap()
+            .to_ulong();
+        let large = vec![0u8; max_size as usize + 1];
+        assert_eq!(
+            Err(ConstantBufferError::TooLarge {
+                size: max_size + 1,
+                max_size
+            }),
+            create_constant_buffer(context, device_id, &large)
+        );
+
+        release_event(read_event).unwrap();
+        release_event(event).unwrap();
+        release_mem_object(out).unwrap();
+        release_mem_object(constant).unwrap();
+        release_kernel(kernel).unwrap();
+        release_program(program).unwrap();
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_raw_and_deprecated_paths() {
+        // The deprecated paths must still resolve to the raw function signatures.
+        let _buffer: fn(cl_context, cl_mem_flags, size_t, *mut c_void) -> Result<cl_mem, cl_int> =
+            create_buffer;
+        let _sub_buffer: fn(
+            cl_mem,
+            cl_mem_flags,
+            cl_buffer_create_type,
+            *const c_void,
+        ) -> Result<cl_mem, cl_int> = create_sub_buffer;
+        let _image: fn(
+            cl_context,
+            cl_mem_flags,
+            *const cl_image_format,
+            *const cl_image_desc,
+            *mut c_void,
+        ) -> Result<cl_mem, cl_int> = create_image;
+        let _image_for_device: fn(
+            cl_context,
+            cl_device_id,
+            cl_mem_flags,
+            &cl_image_format,
+            &cl_image_desc,
+            *mut c_void,
+        ) -> Result<cl_mem, cl_int> = create_image_for_device;
+        let _destructor: fn(
+            cl_mem,
+            extern "C" fn(cl_mem, *mut c_void),
+            *mut c_void,
+        ) -> Result<(), cl_int> = set_mem_object_destructor_callback;
+        let _raw: fn(cl_context, cl_mem_flags, size_t, *mut c_void) -> Result<cl_mem, cl_int> =
+            raw::create_buffer;
+
+        #[cfg(feature = "CL_VERSION_3_0")]
+        {
+            let _buffer_with_properties: fn(
+                cl_context,
+                *const cl_mem_properties,
+                cl_mem_flags,
+                size_t,
+                *mut c_void,
+            ) -> Result<cl_mem, cl_int> = create_buffer_with_properties;
+            let _image_with_properties: fn(
+                cl_context,
+                *const cl_mem_properties,
+                cl_mem_flags,
+                *const cl_image_format,
+                *const cl_image_desc,
+                *mut c_void,
+            ) -> Result<cl_mem, cl_int> = create_image_with_properties;
+        }
+
+        // The deprecated and slice-based paths must call the same OpenCL function.
+        let context: cl_context = ptr::null_mut();
+        let old = create_buffer(context, CL_MEM_READ_WRITE, 16, ptr::null_mut());
+        assert!(old.is_err());
+        assert_eq!(old, create_buffer_of_size(context, CL_MEM_READ_WRITE, 16));
+    }
+
+    #[test]
+    fn test_slice_checks() {
+        let context: cl_context = ptr::null_mut();
+        let data = [0u32; 4];
+        // The buffer could outlive the borrow of data
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            create_buffer_from_slice(context, CL_MEM_USE_HOST_PTR, &data)
+        );
+
+        #[cfg(feature = "CL_VERSION_3_0")]
+        {
+            // A properties list must be zero terminated
+            assert_eq!(
+                Err(CL_INVALID_VALUE),
+                create_buffer_with_properties_slice(context, &[1], CL_MEM_READ_WRITE, 16)
+            );
+            let format = image_format_rgba_u8();
+            let desc = image_desc_2d(4, 4, 0);
+            assert_eq!(
+                Err(CL_INVALID_VALUE),
+                create_image_with_properties_slice(context, &[1], 0, &format, &desc)
+            );
+        }
+    }
 }
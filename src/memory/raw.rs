@@ -0,0 +1,328 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Raw pointer OpenCL Memory Object API.
+//!
+//! These functions pass pointers and sizes straight through to the OpenCL
+//! C API, so the caller must ensure that they are valid.  
+//! Prefer the slice-based and typed functions in the parent module.
+
+#![allow(non_camel_case_types)]
+// The functions mirror the OpenCL C API, see the module documentation.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+#[allow(unused_imports)]
+use super::{
+    clCreateBufferWithProperties, clCreateImage, clCreateImageWithProperties, validate_image_desc,
+};
+use crate::device::{device_image_limits, device_supports_3d_image_writes};
+use crate::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+#[allow(unused_imports)]
+use crate::types::{
+    cl_buffer_create_type, cl_context, cl_device_id, cl_image_desc, cl_image_format, cl_int,
+    cl_mem, cl_mem_flags, cl_mem_properties, cl_svm_mem_flags, cl_uint,
+};
+use cl_sys::{
+    clCreateBuffer, clCreateSubBuffer, clSVMAlloc, clSVMFree, clSetMemObjectDestructorCallback,
+    CL_MEM_OBJECT_IMAGE3D,
+};
+
+use libc::{c_void, size_t};
+use std::ptr;
+
+/// Create an OpenCL buffer object for a context.  
+/// Calls clCreateBuffer to create an OpenCL buffer object.  
+///
+/// * `context` - a valid OpenCL context.
+/// * `flags` - a bit-field used to specify allocation and usage information
+/// about the image memory object being created, see:
+/// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
+/// * `size` - the size in bytes of the buffer memory object to be allocated.
+/// * `host_ptr` - a pointer to the buffer data that may already be allocated
+/// by the application.
+///
+/// returns a Result containing the new OpenCL buffer object
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn create_buffer(
+    context: cl_context,
+    flags: cl_mem_flags,
+    size: size_t,
+    host_ptr: *mut c_void,
+) -> Result<cl_mem, cl_int> {
+    let mut status: cl_int = CL_INVALID_VALUE;
+    let mem: cl_mem = unsafe { clCreateBuffer(context, flags, size, host_ptr, &mut status) };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(mem)
+    }
+}
+
+/// Create an new OpenCL buffer object from an existing buffer object.  
+/// Calls clCreateSubBuffer to create an OpenCL sub-buffer object.  
+///
+/// * `buffer` - a valid OpenCL buffer.
+/// * `flags` - a bit-field used to specify allocation and usage information
+/// about the sub-buffer memory object being created, see:
+/// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
+/// * `buffer_create_type`,`buffer_create_info` - describe the type of
+/// buffer object to be created, see:
+/// [SubBuffer Attributes](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#subbuffer-create-info-table).
+///
+/// returns a Result containing the new OpenCL buffer object
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn create_sub_buffer(
+    buffer: cl_mem,
+    flags: cl_mem_flags,
+    buffer_create_type: cl_buffer_create_type,
+    buffer_create_info: *const c_void,
+) -> Result<cl_mem, cl_int> {
+    let mut status: cl_int = CL_INVALID_VALUE;
+    let mem: cl_mem = unsafe {
+        clCreateSubBuffer(
+            buffer,
+            flags,
+            buffer_create_type,
+            buffer_create_info,
+            &mut status,
+        )
+    };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(mem)
+    }
+}
+
+/// Create an OpenCL image object for a context.  
+/// Calls clCreateImage to create an OpenCL image object.  
+///
+/// * `context` - a valid OpenCL context.
+/// * `flags` - a bit-field used to specify allocation and usage information
+/// about the image memory object being created, see:
+/// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
+/// * `image_format` - a pointer to a structure that describes format properties
+/// of the image to be allocated.
+/// * `image_desc` - a pointer to a structure that describes type and dimensions
+/// of the image to be allocated.
+/// * `host_ptr` - a pointer to the image data that may already be allocated
+/// by the application.
+///
+/// returns a Result containing the new OpenCL image object
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn create_image(
+    context: cl_context,
+    flags: cl_mem_flags,
+    image_format: *const cl_image_format,
+    image_desc: *const cl_image_desc,
+    host_ptr: *mut c_void,
+) -> Result<cl_mem, cl_int> {
+    let mut status: cl_int = CL_INVALID_VALUE;
+    let mem: cl_mem = unsafe {
+        clCreateImage(
+            context,
+            flags,
+            image_format,
+            image_desc,
+            host_ptr,
+            &mut status,
+        )
+    };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(mem)
+    }
+}
+
+/// Create an OpenCL image object after validating it for a device.  
+/// Calls device_image_limits, device_supports_3d_image_writes and
+/// validate_image_desc before clCreateImage, so that an unsupported image
+/// fails with a specific error code rather than a driver specific one.
+///
+/// * `context` - a valid OpenCL context.
+/// * `device` - the device in the context that will use the image.
+/// * `flags` - a bit-field used to specify allocation and usage information
+///   about the image memory object being created.
+/// * `image_format` - the format properties of the image to be allocated.
+/// * `image_desc` - the type and dimensions of the image to be allocated.
+/// * `host_ptr` - a pointer to the image data that may already be allocated
+///   by the application.
+///
+/// returns a Result containing the new OpenCL image object
+/// or the error code from validate_image_desc
+/// or the error code from the OpenCL C API function.
+pub fn create_image_for_device(
+    context: cl_context,
+    device: cl_device_id,
+    flags: cl_mem_flags,
+    image_format: &cl_image_format,
+    image_desc: &cl_image_desc,
+    host_ptr: *mut c_void,
+) -> Result<cl_mem, cl_int> {
+    let limits = device_image_limits(device)?;
+    let supports_3d_image_writes =
+        CL_MEM_OBJECT_IMAGE3D == image_desc.image_type && device_supports_3d_image_writes(device)?;
+    validate_image_desc(&limits, supports_3d_image_writes, flags, image_desc)?;
+    create_image(context, flags, image_format, image_desc, host_ptr)
+}
+
+/// Create an OpenCL buffer object for a context.  
+/// Calls clCreateBufferWithProperties to create an OpenCL buffer object.  
+/// CL_VERSION_3_0
+///
+/// * `context` - a valid OpenCL context.
+/// * `properties` - an optional null terminated list of properties.
+/// * `flags` - a bit-field used to specify allocation and usage information
+/// about the image memory object being created, see:
+/// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
+/// * `size` - the size in bytes of the buffer memory object to be allocated.
+/// * `host_ptr` - a pointer to the buffer data that may already be allocated
+/// by the application.
+///
+/// returns a Result containing the new OpenCL buffer object
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_3_0")]
+#[inline]
+pub fn create_buffer_with_properties(
+    context: cl_context,
+    properties: *const cl_mem_properties,
+    flags: cl_mem_flags,
+    size: size_t,
+    host_ptr: *mut c_void,
+) -> Result<cl_mem, cl_int> {
+    let mut status: cl_int = CL_INVALID_VALUE;
+    let mem: cl_mem = unsafe {
+        clCreateBufferWithProperties(context, properties, flags, size, host_ptr, &mut status)
+    };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(mem)
+    }
+}
+
+/// Create an OpenCL image object for a context.  
+/// Calls clCreateImage to create an OpenCL image object.  
+/// CL_VERSION_3_0
+///
+/// * `context` - a valid OpenCL context.
+/// * `properties` - an optional null terminated list of properties.
+/// * `flags` - a bit-field used to specify allocation and usage information
+/// about the image memory object being created, see:
+/// [Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#memory-flags-table).
+/// * `image_format` - a pointer to a structure that describes format properties
+/// of the image to be allocated.
+/// * `image_desc` - a pointer to a structure that describes type and dimensions
+/// of the image to be allocated.
+/// * `host_ptr` - a pointer to the image data that may already be allocated
+/// by the application.
+///
+/// returns a Result containing the new OpenCL image object
+/// or the error code from the OpenCL C API function.
+#[inline]
+#[cfg(feature = "CL_VERSION_3_0")]
+pub fn create_image_with_properties(
+    context: cl_context,
+    properties: *const cl_mem_properties,
+    flags: cl_mem_flags,
+    image_format: *const cl_image_format,
+    image_desc: *const cl_image_desc,
+    host_ptr: *mut c_void,
+) -> Result<cl_mem, cl_int> {
+    let mut status: cl_int = CL_INVALID_VALUE;
+    let mem: cl_mem = unsafe {
+        clCreateImageWithProperties(
+            context,
+            properties,
+            flags,
+            image_format,
+            image_desc,
+            host_ptr,
+            &mut status,
+        )
+    };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(mem)
+    }
+}
+
+/// Register a callback function with an OpenCL memory object that is called when the
+/// memory object is destroyed.  
+/// Calls clSetMemObjectDestructorCallback.  
+///
+/// * `memobj` - the OpenCL memory object.
+/// * `pfn_notify` - callback function to be registered by the application.
+/// * `user_data` - passed as the user_data argument when pfn_notify is called.
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+#[inline]
+pub fn set_mem_object_destructor_callback(
+    memobj: cl_mem,
+    pfn_notify: extern "C" fn(cl_mem, *mut c_void),
+    user_data: *mut c_void,
+) -> Result<(), cl_int> {
+    let status: cl_int =
+        unsafe { clSetMemObjectDestructorCallback(memobj, Some(pfn_notify), user_data) };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(())
+    }
+}
+
+/// Allocate a shared virtual memory (SVM) buffer that can be shared by the
+/// host and all devices in an OpenCL context.  
+/// Calls clSVMAlloc.  
+/// CL_VERSION_2_0
+///
+/// * `context` - a valid OpenCL context.
+/// * `flags` - a bit-field used to specify allocation and usage information, see:
+/// [SVM Memory Flags](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#svm-flags-table).
+/// * `size` - the size in bytes of the SVM buffer to be allocated.
+/// * `alignment` - the minimum alignment in bytes that is required for the
+/// newly created buffers memory region.
+///
+/// returns Result containing the address of the SVM buffer
+/// or the error code: CL_INVALID_VALUE if the address is NULL.
+#[inline]
+pub fn svm_alloc(
+    context: cl_context,
+    flags: cl_svm_mem_flags,
+    size: size_t,
+    alignment: cl_uint,
+) -> Result<*mut c_void, cl_int> {
+    let ptr = unsafe { clSVMAlloc(context, flags, size, alignment) };
+    if ptr::null_mut() == ptr {
+        Err(CL_INVALID_VALUE)
+    } else {
+        Ok(ptr)
+    }
+}
+
+/// Free a shared virtual memory (SVM) buffer allocated using clSVMAlloc.  
+/// Calls clSVMFree.  
+/// CL_VERSION_2_0
+///
+/// * `context` - the valid OpenCL context used to create the SVM buffer.
+/// * `svm_pointer` - the value returned by a call to clSVMAlloc.
+#[inline]
+pub fn svm_free(context: cl_context, svm_pointer: *mut c_void) {
+    unsafe { clSVMFree(context, svm_pointer) };
+}
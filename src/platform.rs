@@ -16,18 +16,37 @@
 
 #![allow(non_camel_case_types)]
 
-use super::error_codes::CL_SUCCESS;
+use super::device::{has_extension, name_version_str, parse_opencl_version, Version};
+use super::error_codes::{CL_INVALID_VALUE, CL_PLATFORM_NOT_FOUND_KHR, CL_SUCCESS};
+#[cfg(feature = "cl_khr_semaphore")]
+pub use super::ffi::cl_ext::{cl_semaphore_type_khr, CL_SEMAPHORE_TYPE_BINARY_KHR};
+
+#[cfg(feature = "cl_khr_external_memory")]
+use super::ffi::cl_ext::CL_PLATFORM_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR;
+use super::ffi::cl_ext::CL_PLATFORM_ICD_SUFFIX_KHR;
+#[cfg(feature = "cl_khr_semaphore")]
+use super::ffi::cl_ext::CL_PLATFORM_SEMAPHORE_TYPES_KHR;
+#[cfg(feature = "cl_khr_external_semaphore")]
+use super::ffi::cl_ext::{
+    CL_PLATFORM_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR, CL_PLATFORM_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR,
+};
 use super::info_type::InfoType;
 use super::types::{cl_int, cl_name_version, cl_platform_id, cl_platform_info, cl_uint, cl_ulong};
-use super::{api_info_size, api_info_value, api_info_vector};
-use cl_sys::{clGetPlatformIDs, clGetPlatformInfo};
+use super::{api_info_size, api_info_value, api_info_vector, get_object_list};
+#[allow(unused_imports)]
+use cl_sys::{clGetExtensionFunctionAddressForPlatform, clGetPlatformIDs, clGetPlatformInfo};
 
 use libc::{c_void, size_t};
+#[allow(unused_imports)]
+use std::ffi::CStr;
 use std::mem;
 use std::ptr;
 
 /// Get the available platforms.  
-/// Calls clGetPlatformIDs to get the available platform ids.
+/// Calls clGetPlatformIDs to get the available platform ids.  
+/// Returns an empty vector if clGetPlatformIDs returns CL_PLATFORM_NOT_FOUND_KHR,
+/// i.e. an ICD loader is installed but no OpenCL platforms are registered,
+/// see get_platform_ids_strict.
 ///  # Examples
 /// ```
 /// use cl3::platform::get_platform_ids;
@@ -39,33 +58,33 @@ use std::ptr;
 /// returns a Result containing a vector of available platform ids
 /// or the error code from the OpenCL C API function.
 pub fn get_platform_ids() -> Result<Vec<cl_platform_id>, cl_int> {
-    // Get the number of platforms
-    let mut count: cl_uint = 0;
-    let mut status = unsafe { clGetPlatformIDs(0, ptr::null_mut(), &mut count) };
-
-    if CL_SUCCESS != status {
-        Err(status)
-    } else {
-        if 0 < count {
-            // Get the platform ids.
-            let len = count as usize;
-            let mut ids: Vec<cl_platform_id> = Vec::with_capacity(len);
-            unsafe {
-                ids.set_len(len);
-                status = clGetPlatformIDs(count, ids.as_mut_ptr(), ptr::null_mut());
-            };
+    platform_ids_or_empty(get_platform_ids_strict())
+}
 
-            if CL_SUCCESS != status {
-                Err(status)
-            } else {
-                Ok(ids)
-            }
-        } else {
-            Ok(Vec::default())
-        }
+/// Treat CL_PLATFORM_NOT_FOUND_KHR as no platforms.
+fn platform_ids_or_empty(
+    result: Result<Vec<cl_platform_id>, cl_int>,
+) -> Result<Vec<cl_platform_id>, cl_int> {
+    match result {
+        Err(CL_PLATFORM_NOT_FOUND_KHR) => Ok(Vec::default()),
+        result => result,
     }
 }
 
+/// Get the available platforms.  
+/// Calls clGetPlatformIDs to get the available platform ids.  
+/// Unlike get_platform_ids, it returns CL_PLATFORM_NOT_FOUND_KHR as an error.
+///
+/// returns a Result containing a vector of available platform ids
+/// or the error code from the OpenCL C API function.
+pub fn get_platform_ids_strict() -> Result<Vec<cl_platform_id>, cl_int> {
+    #[cfg(test)]
+    crate::macros::call_hooks::called("clGetPlatformIDs");
+    get_object_list(None, |num_entries, platforms, num_platforms| unsafe {
+        clGetPlatformIDs(num_entries, platforms, num_platforms)
+    })
+}
+
 /// Get data about an OpenCL platform.
 /// Calls clGetPlatformInfo to get the desired data about the platform.
 pub fn get_platform_data(
@@ -92,6 +111,22 @@ pub enum PlatformInfo {
     CL_PLATFORM_NUMERIC_VERSION = 0x0906,
     // CL_VERSION_3_0
     CL_PLATFORM_EXTENSIONS_WITH_VERSION = 0x0907,
+    // cl_khr_icd
+    CL_PLATFORM_ICD_SUFFIX_KHR = CL_PLATFORM_ICD_SUFFIX_KHR as isize,
+    // cl_khr_semaphore
+    #[cfg(feature = "cl_khr_semaphore")]
+    CL_PLATFORM_SEMAPHORE_TYPES_KHR = CL_PLATFORM_SEMAPHORE_TYPES_KHR as isize,
+    // cl_khr_external_semaphore
+    #[cfg(feature = "cl_khr_external_semaphore")]
+    CL_PLATFORM_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR =
+        CL_PLATFORM_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR as isize,
+    #[cfg(feature = "cl_khr_external_semaphore")]
+    CL_PLATFORM_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR =
+        CL_PLATFORM_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR as isize,
+    // cl_khr_external_memory
+    #[cfg(feature = "cl_khr_external_memory")]
+    CL_PLATFORM_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR =
+        CL_PLATFORM_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR as isize,
 }
 
 /// Get specific information about an OpenCL platform.
@@ -133,7 +168,8 @@ pub fn get_platform_info(
         | PlatformInfo::CL_PLATFORM_VERSION
         | PlatformInfo::CL_PLATFORM_NAME
         | PlatformInfo::CL_PLATFORM_VENDOR
-        | PlatformInfo::CL_PLATFORM_EXTENSIONS => {
+        | PlatformInfo::CL_PLATFORM_EXTENSIONS
+        | PlatformInfo::CL_PLATFORM_ICD_SUFFIX_KHR => {
             Ok(InfoType::VecUchar(get_platform_data(platform, param_id)?))
         }
 
@@ -156,14 +192,263 @@ pub fn get_platform_info(
             api_info_vector!(get_vec, cl_name_version, clGetPlatformInfo);
             Ok(InfoType::VecNameVersion(get_vec(platform, param_id, size)?))
         }
+
+        // cl_khr_semaphore
+        #[cfg(feature = "cl_khr_semaphore")]
+        PlatformInfo::CL_PLATFORM_SEMAPHORE_TYPES_KHR => get_platform_uints(platform, param_id),
+
+        // cl_khr_external_semaphore
+        #[cfg(feature = "cl_khr_external_semaphore")]
+        PlatformInfo::CL_PLATFORM_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR
+        | PlatformInfo::CL_PLATFORM_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR => {
+            get_platform_uints(platform, param_id)
+        }
+
+        // cl_khr_external_memory
+        #[cfg(feature = "cl_khr_external_memory")]
+        PlatformInfo::CL_PLATFORM_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR => {
+            get_platform_uints(platform, param_id)
+        }
+    }
+}
+
+// Get a list of cl_uint platform information, which may be empty.
+#[cfg(any(feature = "cl_khr_semaphore", feature = "cl_khr_external_memory"))]
+fn get_platform_uints(
+    platform: cl_platform_id,
+    param_id: cl_platform_info,
+) -> Result<InfoType, cl_int> {
+    api_info_size!(get_size, clGetPlatformInfo);
+    let size = get_size(platform, param_id)?;
+    api_info_vector!(get_vec, cl_uint, clGetPlatformInfo);
+    Ok(InfoType::VecUint(get_vec(platform, param_id, size)?))
+}
+
+/// Find the first platform whose name contains a string, ignoring case.  
+/// Calls clGetPlatformIDs and clGetPlatformInfo for CL_PLATFORM_NAME.
+///
+/// * `name` - the string to find in the platform name, e.g. "intel".
+///
+/// returns a Result containing the id of the first matching platform, if any,
+/// or the error code from the OpenCL C API function.
+pub fn find_platform_by_name(name: &str) -> Result<Option<cl_platform_id>, cl_int> {
+    let name = name.to_lowercase();
+    for platform in get_platform_ids()? {
+        let platform_name = get_platform_info(platform, PlatformInfo::CL_PLATFORM_NAME)?;
+        if platform_name.to_string().to_lowercase().contains(&name) {
+            return Ok(Some(platform));
+        }
+    }
+    Ok(None)
+}
+
+/// Find the platforms that support an extension.  
+/// Calls clGetPlatformIDs and clGetPlatformInfo for CL_PLATFORM_EXTENSIONS.
+///
+/// * `extension` - the exact extension name, e.g. "cl_khr_fp16" does not
+///   match "cl_khr_fp16_extended".
+///
+/// returns a Result containing the ids of the matching platforms
+/// or the error code from the OpenCL C API function.
+pub fn platforms_with_extension(extension: &str) -> Result<Vec<cl_platform_id>, cl_int> {
+    let mut platforms = Vec::new();
+    for platform in get_platform_ids()? {
+        let extensions = get_platform_info(platform, PlatformInfo::CL_PLATFORM_EXTENSIONS)?;
+        if has_extension(&extensions.to_string(), extension) {
+            platforms.push(platform);
+        }
+    }
+    Ok(platforms)
+}
+
+/// Get the semaphore types supported by a platform.  
+/// Calls clGetPlatformInfo for CL_PLATFORM_SEMAPHORE_TYPES_KHR.  
+/// cl_khr_semaphore
+///
+/// * `platform` - the cl_platform_id of the OpenCL platform.
+///
+/// returns a Result containing the semaphore types, e.g.
+/// CL_SEMAPHORE_TYPE_BINARY_KHR, which may be empty
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "cl_khr_semaphore")]
+pub fn get_platform_semaphore_types(
+    platform: cl_platform_id,
+) -> Result<Vec<cl_semaphore_type_khr>, cl_int> {
+    Ok(get_platform_info(platform, PlatformInfo::CL_PLATFORM_SEMAPHORE_TYPES_KHR)?.to_vec_uint())
+}
+
+/// Get the OpenCL version of a platform.  
+/// Calls clGetPlatformInfo for CL_PLATFORM_NUMERIC_VERSION and, if it is not
+/// supported, for CL_PLATFORM_VERSION.
+///
+/// * `platform` - the cl_platform_id of the OpenCL platform.
+///
+/// returns a Result containing the OpenCL version of the platform
+/// or CL_INVALID_VALUE if the version string cannot be parsed
+/// or the error code from the OpenCL C API function.
+pub fn platform_opencl_version(platform: cl_platform_id) -> Result<Version, cl_int> {
+    // CL_PLATFORM_NUMERIC_VERSION is not supported before OpenCL 3.0
+    if let Ok(value) = get_platform_info(platform, PlatformInfo::CL_PLATFORM_NUMERIC_VERSION) {
+        let version = value.to_uint();
+        if 0 != version {
+            return Ok(Version::from(version));
+        }
+    }
+    let text = get_platform_info(platform, PlatformInfo::CL_PLATFORM_VERSION)?.to_string();
+    parse_opencl_version(&text).ok_or(CL_INVALID_VALUE)
+}
+
+/// The information about an OpenCL platform that is commonly needed for
+/// diagnostics, see `get_platform_summary`.  
+/// The optional fields are None if the platform does not support the query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlatformSummary {
+    pub profile: String,
+    pub version_string: String,
+    pub version: Version,
+    pub name: String,
+    pub vendor: String,
+    pub extensions: Vec<String>,
+    /// CL_VERSION_2_1
+    pub host_timer_resolution: Option<cl_ulong>,
+    /// CL_VERSION_3_0
+    pub numeric_version: Option<cl_uint>,
+    /// CL_VERSION_3_0, the name and numeric version of each extension.
+    pub extensions_with_version: Option<Vec<(String, cl_uint)>>,
+}
+
+/// Get a summary of the information about an OpenCL platform.  
+/// Calls clGetPlatformInfo for each field of PlatformSummary.
+///
+/// * `platform` - the cl_platform_id of the OpenCL platform.
+///
+/// returns a Result containing the PlatformSummary
+/// or CL_INVALID_VALUE if the platform version cannot be parsed
+/// or the error code from the OpenCL C API function.
+pub fn get_platform_summary(platform: cl_platform_id) -> Result<PlatformSummary, cl_int> {
+    let version_string =
+        get_platform_info(platform, PlatformInfo::CL_PLATFORM_VERSION)?.to_string();
+    let numeric_version = get_platform_info(platform, PlatformInfo::CL_PLATFORM_NUMERIC_VERSION)
+        .ok()
+        .map(|value| value.to_uint());
+    let version = match numeric_version {
+        Some(version) if 0 != version => Version::from(version),
+        _ => parse_opencl_version(&version_string).ok_or(CL_INVALID_VALUE)?,
+    };
+    let extensions = get_platform_info(platform, PlatformInfo::CL_PLATFORM_EXTENSIONS)?
+        .to_string()
+        .split_whitespace()
+        .map(String::from)
+        .collect();
+
+    Ok(PlatformSummary {
+        profile: get_platform_info(platform, PlatformInfo::CL_PLATFORM_PROFILE)?.to_string(),
+        version_string,
+        version,
+        name: get_platform_info(platform, PlatformInfo::CL_PLATFORM_NAME)?.to_string(),
+        vendor: get_platform_info(platform, PlatformInfo::CL_PLATFORM_VENDOR)?.to_string(),
+        extensions,
+        host_timer_resolution: get_platform_info(
+            platform,
+            PlatformInfo::CL_PLATFORM_HOST_TIMER_RESOLUTION,
+        )
+        .ok()
+        .map(|value| value.to_ulong()),
+        numeric_version,
+        extensions_with_version: get_platform_info(
+            platform,
+            PlatformInfo::CL_PLATFORM_EXTENSIONS_WITH_VERSION,
+        )
+        .ok()
+        .map(|value| {
+            value
+                .to_vec_name_version()
+                .iter()
+                .map(|ext| (name_version_str(ext).into_owned(), ext.version))
+                .collect()
+        }),
+    })
+}
+
+/// Get the address of an extension function for a platform.  
+/// Calls clGetExtensionFunctionAddressForPlatform.  
+/// CL_VERSION_1_2
+///
+/// * `platform` - the OpenCL platform.
+/// * `func_name` - the name of the extension function, e.g. "clIcdGetPlatformIDsKHR".
+///
+/// returns a Result containing the address of the extension function
+/// or CL_INVALID_VALUE if the platform does not provide the function.
+#[cfg(feature = "CL_VERSION_1_2")]
+#[inline]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn get_extension_function_address_for_platform(
+    platform: cl_platform_id,
+    func_name: &CStr,
+) -> Result<*mut c_void, cl_int> {
+    let address = unsafe { clGetExtensionFunctionAddressForPlatform(platform, func_name.as_ptr()) };
+    if address.is_null() {
+        Err(CL_INVALID_VALUE)
+    } else {
+        Ok(address)
     }
 }
 
+/// Get an extension function for a platform as a function pointer type.  
+/// Calls clGetExtensionFunctionAddressForPlatform.  
+/// CL_VERSION_1_2
+///
+/// * `platform` - the OpenCL platform.
+/// * `func_name` - the name of the extension function.
+///
+/// returns a Result containing the extension function
+/// or CL_INVALID_VALUE if the platform does not provide the function.
+///
+/// # Safety
+///
+/// `F` must be an `extern "system"` function pointer type with the signature of
+/// the extension function named by `func_name`.
+///
+/// # Panics
+///
+/// If `F` is not the size of a pointer.
+#[cfg(feature = "CL_VERSION_1_2")]
+pub unsafe fn get_extension_function<F: Copy>(
+    platform: cl_platform_id,
+    func_name: &CStr,
+) -> Result<F, cl_int> {
+    assert_eq!(
+        mem::size_of::<*mut c_void>(),
+        mem::size_of::<F>(),
+        "get_extension_function: F must be a function pointer type"
+    );
+    let address = get_extension_function_address_for_platform(platform, func_name)?;
+    Ok(mem::transmute_copy::<*mut c_void, F>(&address))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::error_codes::error_text;
 
+    #[test]
+    fn test_platform_ids_or_empty() {
+        assert_eq!(
+            Ok(Vec::default()),
+            platform_ids_or_empty(Err(CL_PLATFORM_NOT_FOUND_KHR))
+        );
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            platform_ids_or_empty(Err(CL_INVALID_VALUE))
+        );
+        let platform_ids = vec![ptr::null_mut()];
+        assert_eq!(
+            Ok(platform_ids.clone()),
+            platform_ids_or_empty(Ok(platform_ids))
+        );
+    }
+
     #[test]
     fn test_get_platform_info() {
         let platform_ids = get_platform_ids().unwrap();
@@ -211,6 +496,105 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_get_platform_icd_suffix() {
+        let platform_ids = get_platform_ids().unwrap();
+        for platform_id in platform_ids {
+            // cl_khr_icd value, may not be supported
+            match get_platform_info(platform_id, PlatformInfo::CL_PLATFORM_ICD_SUFFIX_KHR) {
+                Ok(value) => {
+                    let value = value.to_string();
+                    println!("CL_PLATFORM_ICD_SUFFIX_KHR: {}", value)
+                }
+                Err(e) => println!(
+                    "OpenCL error, CL_PLATFORM_ICD_SUFFIX_KHR: {}",
+                    error_text(e)
+                ),
+            };
+        }
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "cl_khr_external_semaphore",
+        feature = "cl_khr_external_memory"
+    ))]
+    fn test_get_platform_external_handle_types() {
+        let mut param_names = Vec::new();
+        #[cfg(feature = "cl_khr_external_semaphore")]
+        param_names.extend_from_slice(&[
+            PlatformInfo::CL_PLATFORM_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR,
+            PlatformInfo::CL_PLATFORM_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR,
+        ]);
+        #[cfg(feature = "cl_khr_external_memory")]
+        param_names.push(PlatformInfo::CL_PLATFORM_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR);
+
+        let platform_ids = get_platform_ids().unwrap();
+        for platform_id in platform_ids {
+            // Extension values, may not be supported
+            for param_name in param_names.iter() {
+                match get_platform_info(platform_id, *param_name) {
+                    Ok(value) => {
+                        let value = value.to_vec_uint();
+                        println!("{:?}: {:X?}", param_name, value)
+                    }
+                    Err(e) => println!("OpenCL error, {:?}: {}", param_name, error_text(e)),
+                };
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn test_get_platform_semaphore_types() {
+        let platform_ids = get_platform_ids().unwrap();
+        for platform_id in platform_ids {
+            // An extension value, may not be supported
+            match get_platform_semaphore_types(platform_id) {
+                Ok(value) => println!(
+                    "semaphore types: {:X?}, binary: {}",
+                    value,
+                    value.contains(&CL_SEMAPHORE_TYPE_BINARY_KHR)
+                ),
+                Err(e) => println!("OpenCL error, semaphore types: {}", error_text(e)),
+            };
+        }
+    }
+
+    #[test]
+    fn test_find_platforms() {
+        let platform_ids = get_platform_ids().unwrap();
+        let platform_id = platform_ids[0];
+        let name = get_platform_info(platform_id, PlatformInfo::CL_PLATFORM_NAME)
+            .unwrap()
+            .to_string();
+        println!("CL_PLATFORM_NAME: {}", name);
+
+        // The first platform is found by any part of its name, in any case
+        let part: String = name.chars().take(4).collect();
+        assert_eq!(
+            Some(platform_id),
+            find_platform_by_name(&part.to_uppercase()).unwrap()
+        );
+        assert_eq!(
+            None,
+            find_platform_by_name("no such OpenCL platform").unwrap()
+        );
+
+        let extensions = get_platform_info(platform_id, PlatformInfo::CL_PLATFORM_EXTENSIONS)
+            .unwrap()
+            .to_string();
+        if let Some(extension) = extensions.split_whitespace().next() {
+            let platforms = platforms_with_extension(extension).unwrap();
+            assert!(platforms.contains(&platform_id));
+
+            // Only exact extension names match
+            let prefix: String = extension.chars().take(extension.len() - 1).collect();
+            let platforms = platforms_with_extension(&prefix).unwrap();
+            assert!(!platforms.contains(&platform_id) || has_extension(&extensions, &prefix));
+        }
+    }
+
     #[test]
     fn test_get_platform_info_3_0() {
         let platform_ids = get_platform_ids().unwrap();
@@ -223,8 +607,9 @@ mod tests {
         println!("CL_PLATFORM_VERSION: {}", value);
         assert!(!value.is_empty());
 
-        let opencl_3: String = "OpenCL 3".to_string();
-        let is_opencl_3: bool = value.contains(&opencl_3);
+        let version = platform_opencl_version(platform_id).unwrap();
+        println!("OpenCL version: {}", version);
+        let is_opencl_3: bool = Version::new(3, 0, 0) <= version;
 
         if is_opencl_3 {
             let value =
@@ -244,4 +629,39 @@ mod tests {
             assert!(0 < value.len());
         }
     }
+
+    #[test]
+    fn test_get_extension_function() {
+        type clIcdGetPlatformIDsKHR_fn =
+            extern "system" fn(cl_uint, *mut cl_platform_id, *mut cl_uint) -> cl_int;
+
+        let platform_ids = get_platform_ids().unwrap();
+        let platform_id = platform_ids[0];
+
+        let name = CStr::from_bytes_with_nul(b"clIcdGetPlatformIDsKHR\0").unwrap();
+        match unsafe { get_extension_function::<clIcdGetPlatformIDsKHR_fn>(platform_id, name) } {
+            Ok(get_ids) => {
+                let mut count: cl_uint = 0;
+                let status = get_ids(0, ptr::null_mut(), &mut count);
+                println!("clIcdGetPlatformIDsKHR: {}, count: {}", status, count);
+            }
+            Err(e) => println!("OpenCL error, clIcdGetPlatformIDsKHR: {}", error_text(e)),
+        };
+
+        let name = CStr::from_bytes_with_nul(b"clNotAnExtensionFunctionXYZ\0").unwrap();
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            get_extension_function_address_for_platform(platform_id, name)
+        );
+    }
+
+    #[test]
+    fn test_get_platform_summary() {
+        for platform_id in get_platform_ids().unwrap() {
+            let summary = get_platform_summary(platform_id).unwrap();
+            println!("{:#?}", summary);
+            assert!(!summary.name.is_empty());
+            assert!(Version::new(1, 0, 0) <= summary.version);
+        }
+    }
 }
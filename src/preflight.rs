@@ -0,0 +1,618 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Check the OpenCL capabilities that an application requires at startup.
+//!
+//! Applications that use OpenCL 2.x or 3.0 functions or extension functions
+//! may otherwise fail part way through a run, e.g. with a missing symbol or an
+//! CL_INVALID_OPERATION error. `check` reports everything that is missing in
+//! one list, with remediation text for each entry.
+//!
+//! A capability is available if:
+//! * the cl3 cargo feature for it is enabled,
+//! * the platform and device support its OpenCL version or extension,
+//! * its functions can be found: core functions in the OpenCL library and
+//!   extension functions with clGetExtensionFunctionAddressForPlatform.
+
+use super::device::{
+    device_opencl_version, get_all_devices, get_device_info, has_extension, DeviceInfo,
+    SelectDeviceError, Version, CL_DEVICE_TYPE_ALL,
+};
+use super::error_codes::{error_text, CL_DEVICE_NOT_FOUND};
+use super::platform::{get_platform_info, platform_opencl_version, PlatformInfo};
+use super::selection::{env_device, SelectionError, CL3_DEVICE};
+use super::types::{cl_device_id, cl_int, cl_platform_id};
+
+#[allow(unused_imports)]
+use std::ffi::CString;
+use std::fmt;
+
+/// An OpenCL capability that an application may require.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Capability {
+    Cl1_2,
+    Cl2_0,
+    Cl2_1,
+    Cl2_2,
+    Cl3_0,
+    KhrIcd,
+    KhrIlProgram,
+    KhrSubgroups,
+    KhrTerminateContext,
+    KhrCreateCommandQueue,
+    KhrSuggestedLocalWorkSize,
+    KhrGlSharing,
+    ExtDeviceFission,
+    IntelUsm,
+    IntelAccelerator,
+    ArmImportMemory,
+    ArmSharedVirtualMemory,
+}
+
+/// How to find whether a capability is available.
+struct Probe {
+    capability: Capability,
+    /// The cl3 cargo feature and whether it is enabled.
+    feature: (&'static str, bool),
+    /// The minimum OpenCL version, for core capabilities.
+    version: Option<(u32, u32)>,
+    /// The extension name, for extension capabilities.
+    extension: Option<&'static str>,
+    /// Functions of the capability that must be found.
+    symbols: &'static [&'static str],
+}
+
+const PROBES: &[Probe] = &[
+    Probe {
+        capability: Capability::Cl1_2,
+        feature: ("CL_VERSION_1_2", cfg!(feature = "CL_VERSION_1_2")),
+        version: Some((1, 2)),
+        extension: None,
+        symbols: &["clCompileProgram", "clLinkProgram", "clEnqueueFillBuffer"],
+    },
+    Probe {
+        capability: Capability::Cl2_0,
+        feature: ("CL_VERSION_2_0", cfg!(feature = "CL_VERSION_2_0")),
+        version: Some((2, 0)),
+        extension: None,
+        symbols: &["clCreateCommandQueueWithProperties", "clSVMAlloc"],
+    },
+    Probe {
+        capability: Capability::Cl2_1,
+        feature: ("CL_VERSION_2_1", cfg!(feature = "CL_VERSION_2_1")),
+        version: Some((2, 1)),
+        extension: None,
+        symbols: &[
+            "clCreateProgramWithIL",
+            "clCloneKernel",
+            "clGetKernelSubGroupInfo",
+        ],
+    },
+    Probe {
+        capability: Capability::Cl2_2,
+        feature: ("CL_VERSION_2_2", cfg!(feature = "CL_VERSION_2_2")),
+        version: Some((2, 2)),
+        extension: None,
+        symbols: &[
+            "clSetProgramReleaseCallback",
+            "clSetProgramSpecializationConstant",
+        ],
+    },
+    Probe {
+        capability: Capability::Cl3_0,
+        feature: ("CL_VERSION_3_0", cfg!(feature = "CL_VERSION_3_0")),
+        version: Some((3, 0)),
+        extension: None,
+        symbols: &[
+            "clCreateBufferWithProperties",
+            "clSetContextDestructorCallback",
+        ],
+    },
+    Probe {
+        capability: Capability::KhrIcd,
+        feature: ("cl_khr_icd", cfg!(feature = "cl_khr_icd")),
+        version: None,
+        extension: Some("cl_khr_icd"),
+        symbols: &["clIcdGetPlatformIDsKHR"],
+    },
+    Probe {
+        capability: Capability::KhrIlProgram,
+        feature: ("cl_khr_il_program", cfg!(feature = "cl_khr_il_program")),
+        version: None,
+        extension: Some("cl_khr_il_program"),
+        symbols: &["clCreateProgramWithILKHR"],
+    },
+    Probe {
+        capability: Capability::KhrSubgroups,
+        feature: ("cl_khr_subgroups", cfg!(feature = "cl_khr_subgroups")),
+        version: None,
+        extension: Some("cl_khr_subgroups"),
+        symbols: &["clGetKernelSubGroupInfoKHR"],
+    },
+    Probe {
+        capability: Capability::KhrTerminateContext,
+        feature: (
+            "cl_khr_terminate_context",
+            cfg!(feature = "cl_khr_terminate_context"),
+        ),
+        version: None,
+        extension: Some("cl_khr_terminate_context"),
+        symbols: &["clTerminateContextKHR"],
+    },
+    Probe {
+        capability: Capability::KhrCreateCommandQueue,
+        feature: (
+            "cl_khr_create_command_queue",
+            cfg!(feature = "cl_khr_create_command_queue"),
+        ),
+        version: None,
+        extension: Some("cl_khr_create_command_queue"),
+        symbols: &["clCreateCommandQueueWithPropertiesKHR"],
+    },
+    Probe {
+        capability: Capability::KhrSuggestedLocalWorkSize,
+        feature: (
+            "cl_khr_suggested_local_work_size",
+            cfg!(feature = "cl_khr_suggested_local_work_size"),
+        ),
+        version: None,
+        extension: Some("cl_khr_suggested_local_work_size"),
+        symbols: &["clGetKernelSuggestedLocalWorkSizeKHR"],
+    },
+    Probe {
+        capability: Capability::KhrGlSharing,
+        feature: ("cl_khr_gl_sharing", cfg!(feature = "cl_khr_gl_sharing")),
+        version: None,
+        extension: Some("cl_khr_gl_sharing"),
+        symbols: &["clGetGLContextInfoKHR"],
+    },
+    Probe {
+        capability: Capability::ExtDeviceFission,
+        feature: (
+            "cl_ext_device_fission",
+            cfg!(feature = "cl_ext_device_fission"),
+        ),
+        version: None,
+        extension: Some("cl_ext_device_fission"),
+        symbols: &[
+            "clCreateSubDevicesEXT",
+            "clRetainDeviceEXT",
+            "clReleaseDeviceEXT",
+        ],
+    },
+    Probe {
+        capability: Capability::IntelUsm,
+        feature: (
+            "cl_intel_unified_shared_memory",
+            cfg!(feature = "cl_intel_unified_shared_memory"),
+        ),
+        version: None,
+        extension: Some("cl_intel_unified_shared_memory"),
+        symbols: &[
+            "clHostMemAllocINTEL",
+            "clDeviceMemAllocINTEL",
+            "clSharedMemAllocINTEL",
+            "clMemBlockingFreeINTEL",
+            "clEnqueueMemcpyINTEL",
+        ],
+    },
+    Probe {
+        capability: Capability::IntelAccelerator,
+        feature: (
+            "cl_intel_accelerator",
+            cfg!(feature = "cl_intel_accelerator"),
+        ),
+        version: None,
+        extension: Some("cl_intel_accelerator"),
+        symbols: &["clCreateAcceleratorINTEL", "clReleaseAcceleratorINTEL"],
+    },
+    Probe {
+        capability: Capability::ArmImportMemory,
+        feature: (
+            "cl_arm_import_memory",
+            cfg!(feature = "cl_arm_import_memory"),
+        ),
+        version: None,
+        extension: Some("cl_arm_import_memory"),
+        symbols: &["clImportMemoryARM"],
+    },
+    Probe {
+        capability: Capability::ArmSharedVirtualMemory,
+        feature: (
+            "cl_arm_shared_virtual_memory",
+            cfg!(feature = "cl_arm_shared_virtual_memory"),
+        ),
+        version: None,
+        extension: Some("cl_arm_shared_virtual_memory"),
+        symbols: &["clSVMAllocARM", "clSVMFreeARM"],
+    },
+];
+
+fn probe(capability: Capability) -> &'static Probe {
+    PROBES
+        .iter()
+        .find(|probe| probe.capability == capability)
+        .expect("every Capability has a probe")
+}
+
+/// A required capability that is not available, see `check`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CapabilityError {
+    /// The cl3 cargo feature for the capability is not enabled.
+    FeatureDisabled {
+        capability: Capability,
+        feature: &'static str,
+    },
+    /// The OpenCL version of the platform or device is too low.
+    Version {
+        capability: Capability,
+        required: Version,
+        found: Version,
+    },
+    /// Neither the platform nor the device supports the extension.
+    Extension {
+        capability: Capability,
+        extension: &'static str,
+    },
+    /// A function of the capability cannot be found.
+    Symbol {
+        capability: Capability,
+        symbol: &'static str,
+    },
+    /// There is no OpenCL device to check, or OpenCL returned an error.
+    NoDevice(cl_int),
+    /// The CL3_DEVICE environment variable is invalid.
+    Selection(SelectionError),
+}
+
+impl CapabilityError {
+    /// Text describing how to make the capability available.
+    pub fn remediation(&self) -> String {
+        match self {
+            CapabilityError::FeatureDisabled { feature, .. } => {
+                format!("enable the `{}` feature of the cl3 crate", feature)
+            }
+            CapabilityError::Version { required, .. } => format!(
+                "use a platform and device that support OpenCL {}.{}, or update the OpenCL driver",
+                required.major, required.minor
+            ),
+            CapabilityError::Extension { extension, .. } => format!(
+                "use a platform or device that supports {}, or update the OpenCL driver",
+                extension
+            ),
+            CapabilityError::Symbol { symbol, .. } => format!(
+                "{} is missing from the OpenCL library or driver, update the OpenCL ICD loader and driver",
+                symbol
+            ),
+            CapabilityError::NoDevice(_) => {
+                "install an OpenCL driver and check that the ICD loader can find it".to_string()
+            }
+            CapabilityError::Selection(_) => format!("correct or unset {}", CL3_DEVICE),
+        }
+    }
+}
+
+impl fmt::Display for CapabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CapabilityError::FeatureDisabled {
+                capability,
+                feature,
+            } => write!(f, "{:?}: the `{}` feature is disabled", capability, feature)?,
+            CapabilityError::Version {
+                capability,
+                required,
+                found,
+            } => write!(
+                f,
+                "{:?}: OpenCL {}.{} is required, found {}.{}",
+                capability, required.major, required.minor, found.major, found.minor
+            )?,
+            CapabilityError::Extension {
+                capability,
+                extension,
+            } => write!(f, "{:?}: {} is not supported", capability, extension)?,
+            CapabilityError::Symbol { capability, symbol } => {
+                write!(f, "{:?}: {} cannot be found", capability, symbol)?
+            }
+            CapabilityError::NoDevice(status) => {
+                write!(f, "no OpenCL device: {}", error_text(*status))?
+            }
+            CapabilityError::Selection(error) => write!(f, "{}", error)?,
+        }
+        write!(f, ", {}", self.remediation())
+    }
+}
+
+// The cargo features that are not enabled for the required capabilities.
+fn disabled_features(required: &[Capability]) -> Vec<CapabilityError> {
+    required
+        .iter()
+        .map(|&capability| probe(capability))
+        .filter(|probe| !probe.feature.1)
+        .map(|probe| CapabilityError::FeatureDisabled {
+            capability: probe.capability,
+            feature: probe.feature.0,
+        })
+        .collect()
+}
+
+// The required capabilities that a platform and device do not provide.
+// `resolve` returns whether a function of a capability can be found.
+fn missing_capabilities(
+    required: &[Capability],
+    version: Version,
+    extensions: &str,
+    resolve: &dyn Fn(&Probe, &str) -> bool,
+) -> Vec<CapabilityError> {
+    let mut errors = Vec::new();
+    for probe in required.iter().map(|&capability| probe(capability)) {
+        if let Some((major, minor)) = probe.version {
+            let required = Version::new(major, minor, 0);
+            if version < required {
+                errors.push(CapabilityError::Version {
+                    capability: probe.capability,
+                    required,
+                    found: version,
+                });
+                continue;
+            }
+        }
+        if let Some(extension) = probe.extension {
+            if !has_extension(extensions, extension) {
+                errors.push(CapabilityError::Extension {
+                    capability: probe.capability,
+                    extension,
+                });
+                continue;
+            }
+        }
+        errors.extend(
+            probe
+                .symbols
+                .iter()
+                .filter(|symbol| !resolve(probe, symbol))
+                .map(|&symbol| CapabilityError::Symbol {
+                    capability: probe.capability,
+                    symbol,
+                }),
+        );
+    }
+    errors
+}
+
+// Whether a core function is in the OpenCL library that this process loaded.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn has_core_symbol(symbol: &str) -> bool {
+    match CString::new(symbol) {
+        Ok(name) => !unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr()) }.is_null(),
+        Err(_) => false,
+    }
+}
+
+// Core functions are linked when the process starts on other systems.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn has_core_symbol(_symbol: &str) -> bool {
+    true
+}
+
+// Whether a platform provides an extension function.
+#[cfg(feature = "CL_VERSION_1_2")]
+fn has_extension_symbol(platform: cl_platform_id, symbol: &str) -> bool {
+    use super::platform::get_extension_function_address_for_platform;
+    match CString::new(symbol) {
+        Ok(name) => get_extension_function_address_for_platform(platform, &name).is_ok(),
+        Err(_) => false,
+    }
+}
+
+// Extension functions cannot be found without clGetExtensionFunctionAddressForPlatform.
+#[cfg(not(feature = "CL_VERSION_1_2"))]
+fn has_extension_symbol(_platform: cl_platform_id, _symbol: &str) -> bool {
+    true
+}
+
+/// Check that a device provides the required capabilities.
+/// Calls clGetPlatformInfo, clGetDeviceInfo and, for extension functions,
+/// clGetExtensionFunctionAddressForPlatform.
+///
+/// * `platform` - the platform of the device.
+/// * `device` - the cl_device_id of the OpenCL device.
+/// * `required` - the capabilities that the application requires.
+///
+/// returns an empty Result or a list of every required capability that is
+/// not available, see CapabilityError.
+pub fn check_device(
+    platform: cl_platform_id,
+    device: cl_device_id,
+    required: &[Capability],
+) -> Result<(), Vec<CapabilityError>> {
+    let mut errors = disabled_features(required);
+    errors.extend(device_errors(platform, device, required));
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// The capabilities that a device does not provide, ignoring cargo features.
+fn device_errors(
+    platform: cl_platform_id,
+    device: cl_device_id,
+    required: &[Capability],
+) -> Vec<CapabilityError> {
+    let query = || -> Result<(Version, String), cl_int> {
+        let version = platform_opencl_version(platform)?.min(device_opencl_version(device)?);
+        let extensions = get_platform_info(platform, PlatformInfo::CL_PLATFORM_EXTENSIONS)?
+            .to_string()
+            + " "
+            + &get_device_info(device, DeviceInfo::CL_DEVICE_EXTENSIONS)?.to_string();
+        Ok((version, extensions))
+    };
+    match query() {
+        Ok((version, extensions)) => {
+            missing_capabilities(required, version, &extensions, &|probe, symbol| {
+                if probe.extension.is_some() {
+                    has_extension_symbol(platform, symbol)
+                } else {
+                    has_core_symbol(symbol)
+                }
+            })
+        }
+        Err(status) => vec![CapabilityError::NoDevice(status)],
+    }
+}
+
+/// Check that an OpenCL device provides the required capabilities.
+/// Checks every device on every platform, see `check_device`, or only the
+/// device selected by the CL3_DEVICE environment variable if it is set,
+/// see the selection module.
+///
+/// * `required` - the capabilities that the application requires.
+///
+/// returns an empty Result if any device provides all of the capabilities,
+/// otherwise a list of the capabilities that are not available on the device
+/// that provides the most of them, see CapabilityError.
+pub fn check(required: &[Capability]) -> Result<(), Vec<CapabilityError>> {
+    if required.is_empty() {
+        return Ok(());
+    }
+
+    let mut errors = disabled_features(required);
+    let devices = match env_device() {
+        Ok(Some(device)) => Ok(vec![device]),
+        Ok(None) => get_all_devices(CL_DEVICE_TYPE_ALL),
+        Err(SelectDeviceError::Selection(error)) => {
+            errors.push(CapabilityError::Selection(error));
+            return Err(errors);
+        }
+        Err(error) => Err(error.status()),
+    };
+    let devices = match devices {
+        Ok(devices) => devices,
+        Err(status) => {
+            errors.push(CapabilityError::NoDevice(status));
+            return Err(errors);
+        }
+    };
+    let best = devices
+        .iter()
+        .map(|&(platform, device)| device_errors(platform, device, required))
+        .min_by_key(|device_errors| device_errors.len())
+        .unwrap_or_else(|| vec![CapabilityError::NoDevice(CL_DEVICE_NOT_FOUND)]);
+    errors.extend(best);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probes() {
+        // Every capability has exactly one probe
+        for probe in PROBES.iter() {
+            let count = PROBES
+                .iter()
+                .filter(|other| other.capability == probe.capability)
+                .count();
+            assert_eq!(1, count);
+            assert!(probe.version.is_some() != probe.extension.is_some());
+            assert!(!probe.symbols.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_missing_capabilities() {
+        // A fabricated OpenCL 2.0 device without clSVMAlloc
+        let version = Version::new(2, 0, 0);
+        let extensions = "cl_khr_icd cl_khr_il_program";
+        let resolve = |_: &Probe, symbol: &str| "clSVMAlloc" != symbol;
+
+        let required = [
+            Capability::Cl1_2,
+            Capability::Cl2_0,
+            Capability::Cl2_1,
+            Capability::KhrIlProgram,
+            Capability::IntelUsm,
+        ];
+        let errors = missing_capabilities(&required, version, extensions, &resolve);
+        assert_eq!(
+            vec![
+                CapabilityError::Symbol {
+                    capability: Capability::Cl2_0,
+                    symbol: "clSVMAlloc"
+                },
+                CapabilityError::Version {
+                    capability: Capability::Cl2_1,
+                    required: Version::new(2, 1, 0),
+                    found: version
+                },
+                CapabilityError::Extension {
+                    capability: Capability::IntelUsm,
+                    extension: "cl_intel_unified_shared_memory"
+                },
+            ],
+            errors
+        );
+        for error in errors.iter() {
+            println!("{}", error);
+            assert!(!error.remediation().is_empty());
+        }
+
+        assert!(missing_capabilities(&[], version, "", &resolve).is_empty());
+
+        let error = CapabilityError::Selection(SelectionError::EmptyName);
+        assert!(error.to_string().contains(CL3_DEVICE));
+        assert!(error.remediation().contains(CL3_DEVICE));
+
+        // Disabled cargo features are reported for each capability
+        let disabled = disabled_features(&required);
+        assert_eq!(
+            !cfg!(feature = "cl_intel_unified_shared_memory"),
+            disabled.contains(&CapabilityError::FeatureDisabled {
+                capability: Capability::IntelUsm,
+                feature: "cl_intel_unified_shared_memory"
+            })
+        );
+        assert_eq!(
+            !cfg!(feature = "CL_VERSION_2_1"),
+            disabled.contains(&CapabilityError::FeatureDisabled {
+                capability: Capability::Cl2_1,
+                feature: "CL_VERSION_2_1"
+            })
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        // An empty requirement always passes, even without OpenCL devices
+        assert_eq!(Ok(()), check(&[]));
+
+        match check(&[Capability::Cl1_2, Capability::KhrIcd]) {
+            Ok(()) => println!("OpenCL 1.2 and cl_khr_icd are available"),
+            Err(errors) => {
+                for error in errors.iter() {
+                    println!("{}", error);
+                }
+            }
+        }
+    }
+}
@@ -22,7 +22,7 @@ pub use cl_sys::{
     CL_PROGRAM_BINARY_TYPE_LIBRARY, CL_PROGRAM_BINARY_TYPE_EXECUTABLE,
 };
 
-use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+use super::error_codes::{CL_INVALID_VALUE, CL_LINK_PROGRAM_FAILURE, CL_SUCCESS};
 #[allow(unused_imports)]
 use cl_sys::{
     clCreateProgramWithSource, clCreateProgramWithBinary, 
@@ -43,6 +43,8 @@ use libc::{c_void, intptr_t, size_t, c_char, c_uchar};
 use std::mem;
 use std::ptr;
 use std::ffi::CStr;
+use std::panic;
+use std::sync::{Arc, Mutex};
 
 // clUnloadPlatformCompiler disabled in cl_sys due to platform incompatibility.
 // clCreateProgramWithBuiltInKernels kernel_names mutability incorrect in cl_sys
@@ -110,19 +112,26 @@ pub fn create_program_with_source(
 ///
 /// * `context` - a valid OpenCL context.
 /// * `devices` - a slice of devices that are in context.
-/// * `binaries` - a slice of program binaries slices.
+/// * `binaries` - a slice of program binaries slices, one for each device.
 /// 
-/// returns a Result containing the new OpenCL program object
-/// or the error code from the OpenCL C API function.
-pub fn create_program_with_binary(
+/// returns a Result containing the new OpenCL program object and the
+/// binary status of each device
+/// or CL_INVALID_VALUE and no binary status if the numbers of devices and
+/// binaries differ
+/// or the error code from the OpenCL C API function and the binary status
+/// of each device, e.g. CL_INVALID_BINARY for the devices whose binary failed to load.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn create_program_with_binary_status(
     context: cl_context,
     devices: &[cl_device_id],
     binaries: &[&[u8]],
-) -> Result<cl_program, cl_int> {
-    let binaries_length = binaries.len();
+) -> Result<(cl_program, Vec<cl_int>), (cl_int, Vec<cl_int>)> {
+    if devices.len() != binaries.len() {
+        return Err((CL_INVALID_VALUE, Vec::default()));
+    }
     let lengths: Vec<size_t> = binaries.iter().map(|bin| bin.len()).collect();
-    let mut binary_status: Vec<cl_int> = Vec::with_capacity(binaries_length);
-    unsafe { binary_status.set_len(binaries_length) };
+    let binary_ptrs: Vec<*const c_uchar> = binaries.iter().map(|bin| bin.as_ptr()).collect();
+    let mut binary_status: Vec<cl_int> = vec![CL_INVALID_VALUE; binaries.len()];
     let mut status: cl_int = CL_INVALID_VALUE;
     let program: cl_program = unsafe { 
         clCreateProgramWithBinary(
@@ -130,18 +139,38 @@ pub fn create_program_with_binary(
             devices.len() as cl_uint,
             devices.as_ptr(),
             lengths.as_ptr(),
-            binaries.as_ptr() as *const *const c_uchar,
+            binary_ptrs.as_ptr(),
             binary_status.as_mut_ptr(),
             &mut status
         ) 
     };
     if CL_SUCCESS != status {
-        Err(status)
+        Err((status, binary_status))
     } else {
-        Ok(program)
+        Ok((program, binary_status))
     }
 }
 
+/// Create an OpenCL program object for a context and load binary bits into that object.  
+/// Calls clCreateProgramWithBinary to create an OpenCL program object.  
+///
+/// * `context` - a valid OpenCL context.
+/// * `devices` - a slice of devices that are in context.
+/// * `binaries` - a slice of program binaries slices, one for each device.
+/// 
+/// returns a Result containing the new OpenCL program object
+/// or the error code from the OpenCL C API function.
+#[inline]
+pub fn create_program_with_binary(
+    context: cl_context,
+    devices: &[cl_device_id],
+    binaries: &[&[u8]],
+) -> Result<cl_program, cl_int> {
+    create_program_with_binary_status(context, devices, binaries)
+        .map(|(program, _)| program)
+        .map_err(|(status, _)| status)
+}
+
 /// Create an OpenCL program object for a context and  loads the information
 /// related to the built-in kernels into that object.  
 /// Calls clCreateProgramWithBuiltInKernels to create an OpenCL program object.  
@@ -216,6 +245,8 @@ pub fn create_program_with_il(
 #[inline]
 pub fn retain_program(program: cl_program) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clRetainProgram(program) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::retained(program as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -232,6 +263,8 @@ pub fn retain_program(program: cl_program) -> Result<(), cl_int> {
 #[inline]
 pub fn release_program(program: cl_program) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clReleaseProgram(program) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::released(program as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -275,12 +308,23 @@ pub fn build_program(
     }
 }
 
+// A pointer to the first element of a slice, or null if it is empty, since
+// OpenCL rejects a non-null list with a zero count.
+fn slice_ptr<T>(slice: &[T]) -> *const T {
+    if slice.is_empty() {
+        ptr::null()
+    } else {
+        slice.as_ptr()
+    }
+}
+
 /// Compile a program’s source for the devices the OpenCL context associated
 /// with the program.  
 /// Calls clCompileProgram to compile an OpenCL program object.  
 ///
 /// * `program` - a valid OpenCL program.
-/// * `devices` - a slice of devices that are in context.
+/// * `devices` - a slice of devices that are in context, or empty for all of
+///   the devices associated with the program.
 /// * `options` - the compilation options in a null-terminated string. 
 /// * `input_headers` - a slice of programs that describe headers in the input_headers.
 /// * `header_include_names` - an array that has a one to one correspondence with
@@ -288,7 +332,8 @@ pub fn build_program(
 /// * `pfn_notify` - an optional function pointer to a notification routine.
 /// * `user_data` - passed as an argument when pfn_notify is called, or ptr::null_mut().
 /// 
-/// returns a Result containing the new OpenCL program object
+/// returns an empty Result
+/// or CL_INVALID_VALUE if the numbers of headers and include names differ
 /// or the error code from the OpenCL C API function.
 #[inline]
 pub fn compile_program(
@@ -300,15 +345,23 @@ pub fn compile_program(
     pfn_notify: Option<extern "C" fn(program: cl_program, user_data: *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<(), cl_int> {
+    if input_headers.len() != header_include_names.len() {
+        return Err(CL_INVALID_VALUE);
+    }
+    // The include names are passed as an array of C string pointers.
+    let include_names: Vec<*const c_char> = header_include_names
+        .iter()
+        .map(|name| name.as_ptr())
+        .collect();
     let status: cl_int = unsafe {
         clCompileProgram(
             program,
             devices.len() as cl_uint,
-            devices.as_ptr(),
+            slice_ptr(devices),
             options.as_ptr(),
             input_headers.len() as cl_uint,
-            input_headers.as_ptr(),
-            header_include_names.as_ptr() as *const *const c_char,
+            slice_ptr(input_headers),
+            slice_ptr(&include_names),
             pfn_notify,
             user_data
         )
@@ -320,18 +373,55 @@ pub fn compile_program(
     }
 }
 
+/// Compile a program’s source with embedded headers, see `compile_program`.  
+/// Calls clCompileProgram to compile an OpenCL program object.  
+///
+/// * `program` - a valid OpenCL program.
+/// * `devices` - a slice of devices that are in context, or empty for all of
+///   the devices associated with the program.
+/// * `options` - the compilation options in a null-terminated string.
+/// * `headers` - a slice of header programs and the names that the program
+///   source includes them by.
+/// * `pfn_notify` - an optional function pointer to a notification routine.
+/// * `user_data` - passed as an argument when pfn_notify is called, or ptr::null_mut().
+///
+/// returns an empty Result or the error code from the OpenCL C API function.
+pub fn compile_program_with_headers(
+    program: cl_program,
+    devices: &[cl_device_id],
+    options: &CStr,
+    headers: &[(cl_program, &CStr)],
+    pfn_notify: Option<extern "C" fn(program: cl_program, user_data: *mut c_void)>,
+    user_data: *mut c_void,
+) -> Result<(), cl_int> {
+    let (input_headers, header_include_names): (Vec<cl_program>, Vec<&CStr>) =
+        headers.iter().cloned().unzip();
+    compile_program(
+        program,
+        devices,
+        options,
+        &input_headers,
+        &header_include_names,
+        pfn_notify,
+        user_data,
+    )
+}
+
 /// Link a set of compiled program objects and libraries for the devices in the
 /// OpenCL context associated with the program.  
 /// Calls clLinkProgram to link an OpenCL program object.  
 ///
 /// * `context` - a valid OpenCL context.
-/// * `devices` - a slice of devices that are in context.
+/// * `devices` - a slice of devices that are in context, or empty for all of
+///   the devices associated with the context.
 /// * `options` - the link options in a null-terminated string. 
 /// * `input_programs` - a slice of programs that describe headers in the input_headers.
 /// * `pfn_notify` - an optional function pointer to a notification routine.
 /// * `user_data` - passed as an argument when pfn_notify is called, or ptr::null_mut().
 /// 
 /// returns a Result containing the new OpenCL program object
+/// or CL_INVALID_VALUE if input_programs is empty
+/// or CL_LINK_PROGRAM_FAILURE if clLinkProgram returned a null program
 /// or the error code from the OpenCL C API function.
 #[inline]
 pub fn link_program(
@@ -342,12 +432,15 @@ pub fn link_program(
     pfn_notify: Option<extern "C" fn(program: cl_program, user_data: *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<cl_program, cl_int> {
+    if input_programs.is_empty() {
+        return Err(CL_INVALID_VALUE);
+    }
     let mut status: cl_int = CL_INVALID_VALUE;
     let programme: cl_program = unsafe { 
         clLinkProgram(
             context,
             devices.len() as cl_uint,
-            devices.as_ptr(),
+            slice_ptr(devices),
             options.as_ptr(),
             input_programs.len() as cl_uint,
             input_programs.as_ptr(),
@@ -356,13 +449,150 @@ pub fn link_program(
             &mut status
         ) 
     };
+    linked_program(status, programme)
+}
+
+// Some implementations return a null program with CL_SUCCESS when a link
+// fails, e.g. when a pfn_notify callback is given.
+fn linked_program(status: cl_int, program: cl_program) -> Result<cl_program, cl_int> {
     if CL_SUCCESS != status {
         Err(status)
+    } else if program.is_null() {
+        Err(CL_LINK_PROGRAM_FAILURE)
     } else {
-        Ok(programme)
+        Ok(program)
+    }
+}
+
+/// The type of a closure registered by the `_with_callback` functions.
+pub type ProgramCallback = Box<dyn FnOnce(cl_program) + Send>;
+
+// The closure shared by a `_with_callback` function and the trampoline.
+type ProgramCallbackSlot = Mutex<Option<ProgramCallback>>;
+
+// Trampoline for the `_with_callback` functions: takes the closure from the
+// slot in user_data and calls it, then releases the trampoline's reference.
+extern "C" fn program_callback_trampoline(program: cl_program, user_data: *mut c_void) {
+    let slot = unsafe { Arc::from_raw(user_data as *const ProgramCallbackSlot) };
+    let callback = slot.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(callback) = callback {
+        // A panic must not unwind into the OpenCL implementation.
+        let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| callback(program)));
     }
 }
 
+// Call an OpenCL function with the trampoline and a slot for the closure.
+// Some implementations call the callback before returning an error and some
+// do not, so after an error the closure is dropped here but the trampoline's
+// reference is only released by the trampoline. If the callback is never
+// called, the empty slot is leaked rather than risking a use after free.
+fn with_program_callback<T, F>(callback: ProgramCallback, f: F) -> Result<T, cl_int>
+where
+    F: FnOnce(Option<extern "C" fn(cl_program, *mut c_void)>, *mut c_void) -> Result<T, cl_int>,
+{
+    let slot: Arc<ProgramCallbackSlot> = Arc::new(Mutex::new(Some(callback)));
+    let user_data = Arc::into_raw(Arc::clone(&slot)) as *mut c_void;
+    let result = f(Some(program_callback_trampoline), user_data);
+    if result.is_err() {
+        let callback = slot.lock().unwrap_or_else(|e| e.into_inner()).take();
+        drop(callback);
+    }
+    result
+}
+
+/// Build (compile & link) a program executable, calling a Rust closure when
+/// the build is complete, see `build_program`.  
+/// Calls clBuildProgram with a callback function that calls the closure once
+/// and then drops it.
+///
+/// Note: OpenCL may call the closure from any thread, so it must be `Send`.
+/// clBuildProgram may return before the build is complete.
+///
+/// * `program` - a valid OpenCL program.
+/// * `devices` - a slice of devices that are in context.
+/// * `options` - the build options in a null-terminated string.
+/// * `callback` - the closure to call with the program when the build is complete.
+///
+/// returns an empty Result or the error code from the OpenCL C API function,
+/// in which case the closure may not be called.
+pub fn build_program_with_callback(
+    program: cl_program,
+    devices: &[cl_device_id],
+    options: &CStr,
+    callback: ProgramCallback,
+) -> Result<(), cl_int> {
+    with_program_callback(callback, |pfn_notify, user_data| {
+        build_program(program, devices, options, pfn_notify, user_data)
+    })
+}
+
+/// Compile a program’s source with embedded headers, calling a Rust closure
+/// when the compilation is complete, see `compile_program_with_headers`.  
+/// Calls clCompileProgram with a callback function that calls the closure
+/// once and then drops it.
+///
+/// Note: OpenCL may call the closure from any thread, so it must be `Send`.
+///
+/// * `program` - a valid OpenCL program.
+/// * `devices` - a slice of devices that are in context, or empty for all of
+///   the devices associated with the program.
+/// * `options` - the compilation options in a null-terminated string.
+/// * `headers` - a slice of header programs and the names that the program
+///   source includes them by.
+/// * `callback` - the closure to call with the program when the compilation
+///   is complete.
+///
+/// returns an empty Result or the error code from the OpenCL C API function,
+/// in which case the closure may not be called.
+pub fn compile_program_with_callback(
+    program: cl_program,
+    devices: &[cl_device_id],
+    options: &CStr,
+    headers: &[(cl_program, &CStr)],
+    callback: ProgramCallback,
+) -> Result<(), cl_int> {
+    with_program_callback(callback, |pfn_notify, user_data| {
+        compile_program_with_headers(program, devices, options, headers, pfn_notify, user_data)
+    })
+}
+
+/// Link a set of compiled program objects and libraries, calling a Rust
+/// closure when the link is complete, see `link_program`.  
+/// Calls clLinkProgram with a callback function that calls the closure once
+/// and then drops it.
+///
+/// Note: OpenCL may call the closure from any thread, so it must be `Send`.
+///
+/// * `context` - a valid OpenCL context.
+/// * `devices` - a slice of devices that are in context, or empty for all of
+///   the devices associated with the context.
+/// * `options` - the link options in a null-terminated string.
+/// * `input_programs` - a slice of compiled programs and libraries to link.
+/// * `callback` - the closure to call with the linked program when the link
+///   is complete.
+///
+/// returns a Result containing the new OpenCL program object
+/// or the error code from the OpenCL C API function,
+/// in which case the closure may not be called.
+pub fn link_program_with_callback(
+    context: cl_context,
+    devices: &[cl_device_id],
+    options: &CStr,
+    input_programs: &[cl_program],
+    callback: ProgramCallback,
+) -> Result<cl_program, cl_int> {
+    with_program_callback(callback, |pfn_notify, user_data| {
+        link_program(
+            context,
+            devices,
+            options,
+            input_programs,
+            pfn_notify,
+            user_data,
+        )
+    })
+}
+
 /// Register a callback function with a program object that is called when the
 /// program object is destroyed.  
 /// Calls clSetProgramReleaseCallback to register a callback function.  
@@ -446,6 +676,57 @@ pub fn get_program_data(
     Ok(get_vector(program, param_name, size)?)
 }
 
+/// Get the binaries of an OpenCL program for all the devices associated
+/// with the program, e.g. to cache them for create_program_with_binary.  
+/// Calls clGetProgramInfo with CL_PROGRAM_BINARY_SIZES and CL_PROGRAM_BINARIES.
+///
+/// * `program` - the OpenCL program.
+///
+/// returns a Result containing the binary of each device, empty if there
+/// is no binary for the device
+/// or the error code from the OpenCL C API function.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn get_program_binaries(program: cl_program) -> Result<Vec<Vec<u8>>, cl_int> {
+    api_info_size!(get_size, clGetProgramInfo);
+    api_info_vector!(get_size_vec, size_t, clGetProgramInfo);
+    let param_id = ProgramInfo::CL_PROGRAM_BINARY_SIZES as cl_program_info;
+    let size = get_size(program, param_id)?;
+    let binary_sizes = get_size_vec(program, param_id, size)?;
+
+    // A vector to hold the binary of each device
+    let mut binaries: Vec<Vec<u8>> = binary_sizes
+        .into_iter()
+        .map(|size| vec![0u8; size])
+        .collect();
+
+    // A pointer to each binary, null where there is no binary to copy
+    let mut binary_ptrs: Vec<*mut u8> = binaries
+        .iter_mut()
+        .map(|binary| {
+            if binary.is_empty() {
+                ptr::null_mut()
+            } else {
+                binary.as_mut_ptr()
+            }
+        })
+        .collect();
+
+    let status = unsafe {
+        clGetProgramInfo(
+            program,
+            ProgramInfo::CL_PROGRAM_BINARIES as cl_program_info,
+            binary_ptrs.len() * mem::size_of::<*mut u8>(),
+            binary_ptrs.as_mut_ptr() as *mut c_void,
+            ptr::null_mut(),
+        )
+    };
+    if CL_SUCCESS != status {
+        Err(status)
+    } else {
+        Ok(binaries)
+    }
+}
+
 // cl_program_info
 #[derive(Clone, Copy, Debug)]
 pub enum ProgramInfo {
@@ -484,12 +765,16 @@ pub fn get_program_info(
     let param_id = param_name as cl_program_info;
     match param_name {
         ProgramInfo::CL_PROGRAM_REFERENCE_COUNT 
-        | ProgramInfo::CL_PROGRAM_NUM_DEVICES
-        | ProgramInfo::CL_PROGRAM_SCOPE_GLOBAL_CTORS_PRESENT // CL_VERSION_2_2 only
+        | ProgramInfo::CL_PROGRAM_NUM_DEVICES => {
+            api_info_value!(get_value, cl_uint, clGetProgramInfo);
+            Ok(InfoType::Uint(get_value(program, param_id)?))
+        }
+
+        ProgramInfo::CL_PROGRAM_SCOPE_GLOBAL_CTORS_PRESENT // CL_VERSION_2_2 only
         | ProgramInfo::CL_PROGRAM_SCOPE_GLOBAL_DTORS_PRESENT // CL_VERSION_2_2 only
         => {
             api_info_value!(get_value, cl_uint, clGetProgramInfo);
-            Ok(InfoType::Uint(get_value(program, param_id)?))
+            Ok(InfoType::Bool(0 != get_value(program, param_id)?))
         }
 
         ProgramInfo::CL_PROGRAM_CONTEXT => {
@@ -514,37 +799,7 @@ pub fn get_program_info(
         }
 
         ProgramInfo::CL_PROGRAM_BINARIES => {
-            // Gets the binaries for all the devices in the context
-
-            // get the binary sizes, as the case above
-            api_info_vector!(get_size_vec, size_t, clGetProgramInfo);
-            let size = get_size(program, ProgramInfo::CL_PROGRAM_BINARY_SIZES as cl_program_info)?;
-            let binary_sizes = get_size_vec(program, ProgramInfo::CL_PROGRAM_BINARY_SIZES as cl_program_info, size)?;
-
-            // A vector of vectors to hold the binaries of each device
-            let binaries = binary_sizes.into_iter().map(|size| {
-                vec![0u8; size]
-            }).collect::<Vec<Vec<u8>>>();
-
-            // Create a vector of pointers to the vectors in binaries
-            let mut binary_ptrs = binaries.iter().map(|vec| {
-                vec.as_ptr()
-            }).collect::<Vec<_>>();
-
-            let status = unsafe {
-                clGetProgramInfo(
-                    program,
-                    param_id,
-                    binary_ptrs.len() * mem::size_of::<*mut c_void>(),
-                    binary_ptrs.as_mut_ptr() as *mut _ as *mut c_void,
-                    ptr::null_mut(),
-                )
-            };
-            if CL_SUCCESS != status {
-                Err(status)
-            } else {
-                Ok(InfoType::VecVecUchar(binaries))
-            }
+            Ok(InfoType::VecVecUchar(get_program_binaries(program)?))
         }
 
         ProgramInfo::CL_PROGRAM_NUM_KERNELS => {
@@ -618,14 +873,220 @@ pub fn get_program_build_info(
     }
 }
 
+/// Get the build log of a program for a device, e.g. after a build fails.  
+/// Calls clGetProgramBuildInfo to get CL_PROGRAM_BUILD_LOG.
+///
+/// * `program` - the OpenCL program.
+/// * `device` - the device that the program was built for.
+///
+/// returns a Result containing the build log
+/// or the error code from the OpenCL C API function.
+pub fn get_program_build_log(program: cl_program, device: cl_device_id) -> Result<String, cl_int> {
+    let log = get_program_build_info(program, device, ProgramBuildInfo::CL_PROGRAM_BUILD_LOG)?;
+    Ok(log.to_string())
+}
+
+/// The build status of a program for a device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildStatus {
+    None,
+    Error,
+    Success,
+    InProgress,
+    /// A value that is not a valid build status.
+    Unknown(cl_int),
+}
+
+/// Implement the From trait
+impl From<cl_int> for BuildStatus {
+    fn from(status: cl_int) -> Self {
+        match status {
+            CL_BUILD_NONE => BuildStatus::None,
+            CL_BUILD_ERROR => BuildStatus::Error,
+            CL_BUILD_SUCCESS => BuildStatus::Success,
+            CL_BUILD_IN_PROGRESS => BuildStatus::InProgress,
+            _ => BuildStatus::Unknown(status),
+        }
+    }
+}
+
+/// Get the build status of a program for a device.  
+/// Calls clGetProgramBuildInfo to get CL_PROGRAM_BUILD_STATUS.
+///
+/// * `program` - the OpenCL program.
+/// * `device` - the device that the program was built for.
+///
+/// returns a Result containing the BuildStatus
+/// or the error code from the OpenCL C API function.
+pub fn get_program_build_status(
+    program: cl_program,
+    device: cl_device_id,
+) -> Result<BuildStatus, cl_int> {
+    let status =
+        get_program_build_info(program, device, ProgramBuildInfo::CL_PROGRAM_BUILD_STATUS)?;
+    Ok(BuildStatus::from(status.to_int()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::context::{create_context, release_context};
     use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
     use crate::platform::get_platform_ids;
-    use crate::error_codes::error_text;
+    use crate::error_codes::{error_text, CL_BUILD_PROGRAM_FAILURE};
     use std::ffi::CString;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_linked_program() {
+        let program = 1 as cl_program;
+        assert_eq!(Ok(program), linked_program(CL_SUCCESS, program));
+        assert_eq!(
+            Err(CL_LINK_PROGRAM_FAILURE),
+            linked_program(CL_SUCCESS, ptr::null_mut())
+        );
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            linked_program(CL_INVALID_VALUE, ptr::null_mut())
+        );
+
+        let options = CString::default();
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            link_program(ptr::null_mut(), &[], &options, &[], None, ptr::null_mut())
+        );
+    }
+
+    #[test]
+    fn test_build_status() {
+        assert_eq!(BuildStatus::None, BuildStatus::from(CL_BUILD_NONE));
+        assert_eq!(BuildStatus::Error, BuildStatus::from(CL_BUILD_ERROR));
+        assert_eq!(BuildStatus::Success, BuildStatus::from(CL_BUILD_SUCCESS));
+        assert_eq!(
+            BuildStatus::InProgress,
+            BuildStatus::from(CL_BUILD_IN_PROGRESS)
+        );
+        assert_eq!(BuildStatus::Unknown(1), BuildStatus::from(1));
+    }
+
+    #[test]
+    fn test_build_log() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+
+        // A kernel that does not compile
+        let program = create_program_with_source(context, &["kernel void f() { x = 1; }"]).unwrap();
+        let options = CString::default();
+        let result = build_program(program, &[device_id], &options, None, ptr::null_mut());
+        assert_eq!(Err(CL_BUILD_PROGRAM_FAILURE), result);
+
+        let status = get_program_build_status(program, device_id).unwrap();
+        assert_eq!(BuildStatus::Error, status);
+        let log = get_program_build_log(program, device_id).unwrap();
+        println!("CL_PROGRAM_BUILD_LOG: {}", log);
+        assert!(!log.is_empty());
+
+        release_program(program).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_build_program_with_callback() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let source = "kernel void f(global int* x) { x[0] = 1; }";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::default();
+        let (sender, receiver) = mpsc::channel();
+        let device = device_id as usize;
+        build_program_with_callback(
+            program,
+            &[device_id],
+            &options,
+            Box::new(move |program| {
+                let status = get_program_build_status(program, device as cl_device_id);
+                sender.send((program as usize, status)).unwrap();
+            }),
+        )
+        .unwrap();
+
+        let (built, status) = receiver.recv_timeout(Duration::from_secs(60)).unwrap();
+        assert_eq!(program as usize, built);
+        assert_eq!(Ok(BuildStatus::Success), status);
+
+        // The closure is dropped, without being called, after an error
+        let (sender, receiver) = mpsc::channel::<()>();
+        let result = build_program_with_callback(
+            ptr::null_mut(),
+            &[device_id],
+            &options,
+            Box::new(move |_| drop(sender)),
+        );
+        assert!(result.is_err());
+        assert!(receiver.recv().is_err());
+
+        release_program(program).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    fn test_compile_and_link_program() {
+        let options = CString::default();
+        let name = CString::new("scale.h").unwrap();
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            compile_program(
+                ptr::null_mut(),
+                &[],
+                &options,
+                &[],
+                &[&name],
+                None,
+                ptr::null_mut()
+            )
+        );
+
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let header = create_program_with_source(context, &["#define SCALE 2.0f\n"]).unwrap();
+        let source = r#"
+            #include "scale.h"
+            kernel void scale(global float* x)
+            {
+                x[get_global_id(0)] *= SCALE;
+            }
+        "#;
+        let program = create_program_with_source(context, &[source]).unwrap();
+
+        // Compile with the header for all the devices of the program
+        compile_program_with_headers(
+            program,
+            &[],
+            &options,
+            &[(header, &name)],
+            None,
+            ptr::null_mut(),
+        )
+        .unwrap();
+
+        let linked =
+            link_program(context, &[], &options, &[program], None, ptr::null_mut()).unwrap();
+        let value = get_program_info(linked, ProgramInfo::CL_PROGRAM_KERNEL_NAMES).unwrap();
+        assert_eq!("scale", value.to_string());
+
+        release_program(linked).unwrap();
+        release_program(program).unwrap();
+        release_program(header).unwrap();
+        release_context(context).unwrap();
+    }
 
     #[test]
     fn test_program() {
@@ -683,7 +1144,7 @@ mod tests {
         assert!(0 < value);
 
         let value = get_program_info(program, ProgramInfo::CL_PROGRAM_DEVICES).unwrap();
-        let value = value.to_vec_intptr();
+        let value = value.to_vec_device_id();
         println!("CL_PROGRAM_DEVICES: {}", value.len());
         assert!(0 < value.len());
 
@@ -735,6 +1196,31 @@ mod tests {
         println!("CL_PROGRAM_BINARIES length[0]: {}", value[0].len());
         assert!(0 < value.len());
 
+        // Create a program from the binaries of the built program
+        let binaries = get_program_binaries(program).unwrap();
+        let binary_slices: Vec<&[u8]> = binaries.iter().map(|binary| binary.as_slice()).collect();
+        let (binary_program, binary_status) =
+            create_program_with_binary_status(context, &device_ids, &binary_slices).unwrap();
+        assert_eq!(device_ids.len(), binary_status.len());
+        assert!(binary_status.iter().all(|&status| CL_SUCCESS == status));
+        build_program(binary_program, &device_ids, &options, None, ptr::null_mut()).unwrap();
+        release_program(binary_program).unwrap();
+
+        assert_eq!(
+            Err((CL_INVALID_VALUE, Vec::default())),
+            create_program_with_binary_status(context, &device_ids, &[])
+        );
+
+        // The binary status of each device is returned with the error
+        let invalid_binary = [0u8; 16];
+        let invalid_slices: Vec<&[u8]> = device_ids.iter().map(|_| &invalid_binary[..]).collect();
+        let (status, binary_status) =
+            create_program_with_binary_status(context, &device_ids, &invalid_slices).unwrap_err();
+        println!("Invalid binary status: {}, {:?}", status, binary_status);
+        assert_ne!(CL_SUCCESS, status);
+        assert_eq!(device_ids.len(), binary_status.len());
+        assert!(binary_status.iter().all(|&status| CL_SUCCESS != status));
+
         let value = get_program_info(program, ProgramInfo::CL_PROGRAM_NUM_KERNELS).unwrap();
         let value = value.to_size();
         println!("CL_PROGRAM_NUM_KERNELS: {}", value);
@@ -757,7 +1243,7 @@ mod tests {
          // CL_VERSION_2_2 value
         match get_program_info(program, ProgramInfo::CL_PROGRAM_SCOPE_GLOBAL_CTORS_PRESENT) {
             Ok(value) => {
-                let value = value.to_uint();
+                let value = value.to_bool();
                 println!("CL_PROGRAM_SCOPE_GLOBAL_CTORS_PRESENT: {}", value)
             }
             Err(e) => println!("OpenCL error, CL_PROGRAM_SCOPE_GLOBAL_CTORS_PRESENT: {}", error_text(e))
@@ -766,7 +1252,7 @@ mod tests {
         // CL_VERSION_2_2 value
         match get_program_info(program, ProgramInfo::CL_PROGRAM_SCOPE_GLOBAL_CTORS_PRESENT) {
             Ok(value) => {
-                let value = value.to_uint();
+                let value = value.to_bool();
                 println!("CL_PROGRAM_SCOPE_GLOBAL_DTORS_PRESENT: {}", value)
             }
             Err(e) => println!("OpenCL error, CL_PROGRAM_SCOPE_GLOBAL_DTORS_PRESENT: {}", error_text(e))
@@ -780,4 +1266,31 @@ mod tests {
 
         release_context(context).unwrap();
     }
+
+    #[cfg(feature = "CL_VERSION_2_1")]
+    #[test]
+    fn test_create_program_with_il() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        // An empty intermediate language is invalid
+        assert!(create_program_with_il(context, &[]).is_err());
+
+        // The magic number and header of a SPIR-V module without instructions
+        let words: [u32; 5] = [0x0723_0203, 0x0001_0000, 0, 1, 0];
+        let il: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes().to_vec()).collect();
+        match create_program_with_il(context, &il) {
+            Ok(program) => {
+                let value = get_program_info(program, ProgramInfo::CL_PROGRAM_IL).unwrap();
+                let value = value.to_vec_uchar();
+                println!("CL_PROGRAM_IL length: {}", value.len());
+                assert!(value.is_empty() || il == value);
+                release_program(program).unwrap();
+            }
+            Err(e) => println!("OpenCL error, clCreateProgramWithIL: {}", error_text(e)),
+        }
+
+        release_context(context).unwrap();
+    }
 }
@@ -0,0 +1,286 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A default OpenCL session for examples and quick scripts.
+//! Requires the `quick` feature.
+//!
+//! `default_session` chooses a device, creates a context and an in-order
+//! command-queue for it once, and returns a Session of the same context and
+//! command-queue on every call until `Session::shutdown` is called.
+//!
+//! Note: libraries should not use this module, since the session is shared by
+//! the whole process and the application cannot choose the device.
+
+use super::command_queue::{create_command_queue, release_command_queue, retain_command_queue};
+use super::context::{create_context, release_context, retain_context};
+use super::device::{get_all_devices, rank_devices, CL_DEVICE_TYPE_ALL, CL_DEVICE_TYPE_GPU};
+use super::error_codes::{CL_DEVICE_NOT_FOUND, CL_INVALID_VALUE};
+use super::kernel::create_kernel;
+use super::memory::{create_buffer_from_slice, CL_MEM_READ_WRITE};
+use super::program::{build_program, create_program_with_source, release_program};
+use super::selection::env_device;
+use super::types::{
+    cl_command_queue, cl_context, cl_device_id, cl_int, cl_kernel, cl_mem, cl_platform_id,
+};
+
+use std::ffi::CString;
+use std::mem;
+use std::ptr;
+use std::sync::Mutex;
+
+/// The platform, device, context and in-order command-queue of the default
+/// session, see `default_session`.  
+/// A Session holds a reference to the context and the command-queue, which
+/// it releases when it is dropped, so they remain valid after `shutdown`.
+// The handles are stored as usize since raw pointers are not Send.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Session {
+    platform: usize,
+    device: usize,
+    context: usize,
+    queue: usize,
+}
+
+// The default session, created by default_session and released by shutdown.
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+// Choose the device selected by CL3_DEVICE, else the GPU with the highest
+// estimated peak GFLOPS, else the first device.
+fn choose_device() -> Result<(cl_platform_id, cl_device_id), cl_int> {
+    if let Some(device) = env_device().map_err(|e| e.status())? {
+        return Ok(device);
+    }
+    let gpus = get_all_devices(CL_DEVICE_TYPE_GPU)?;
+    if !gpus.is_empty() {
+        let devices: Vec<cl_device_id> = gpus.iter().map(|&(_, device)| device).collect();
+        let best = rank_devices(&devices)[0].0;
+        if let Some(&gpu) = gpus.iter().find(|&&(_, device)| device == best) {
+            return Ok(gpu);
+        }
+    }
+    get_all_devices(CL_DEVICE_TYPE_ALL)?
+        .first()
+        .copied()
+        .ok_or(CL_DEVICE_NOT_FOUND)
+}
+
+impl Session {
+    fn create() -> Result<Self, cl_int> {
+        let (platform, device) = choose_device()?;
+        let context = create_context(&[device], ptr::null(), None, ptr::null_mut())?;
+        let queue = match create_command_queue(context, device, 0) {
+            Ok(queue) => queue,
+            Err(e) => {
+                release_context(context)?;
+                return Err(e);
+            }
+        };
+        Ok(Session {
+            platform: platform as usize,
+            device: device as usize,
+            context: context as usize,
+            queue: queue as usize,
+        })
+    }
+
+    // Another Session of the same context and command-queue, which it retains.
+    fn share(&self) -> Result<Self, cl_int> {
+        retain_context(self.context())?;
+        if let Err(e) = retain_command_queue(self.queue()) {
+            release_context(self.context())?;
+            return Err(e);
+        }
+        Ok(Session { ..*self })
+    }
+
+    // Release the command-queue and context, without dropping the Session.
+    fn release(self) -> Result<(), cl_int> {
+        let queue_result = release_command_queue(self.queue());
+        let context_result = release_context(self.context());
+        mem::forget(self);
+        queue_result.and(context_result)
+    }
+
+    /// The platform of the session device.
+    pub fn platform(&self) -> cl_platform_id {
+        self.platform as cl_platform_id
+    }
+
+    /// The session device, see `default_session`.
+    pub fn device(&self) -> cl_device_id {
+        self.device as cl_device_id
+    }
+
+    /// The session context, containing only the session device.
+    pub fn context(&self) -> cl_context {
+        self.context as cl_context
+    }
+
+    /// The in-order command-queue of the session device.
+    pub fn queue(&self) -> cl_command_queue {
+        self.queue as cl_command_queue
+    }
+
+    /// Build a program from OpenCL C source and create a kernel from it.
+    /// Calls clCreateProgramWithSource, clBuildProgram and clCreateKernel.
+    ///
+    /// * `source` - the OpenCL C source of the program.
+    /// * `name` - the name of a kernel function in the program.
+    ///
+    /// returns a Result containing the new OpenCL kernel, which must be released
+    /// or CL_INVALID_VALUE if `name` contains a nul character
+    /// or the error code from the OpenCL C API function.
+    pub fn kernel_from_source(&self, source: &str, name: &str) -> Result<cl_kernel, cl_int> {
+        let name = CString::new(name).map_err(|_| CL_INVALID_VALUE)?;
+        let program = create_program_with_source(self.context(), &[source])?;
+        let options = CString::default();
+        // The kernel retains the program, so the program is released here.
+        let kernel = build_program(program, &[self.device()], &options, None, ptr::null_mut())
+            .and_then(|_| create_kernel(program, &name));
+        release_program(program)?;
+        kernel
+    }
+
+    /// Create a read-write buffer containing a copy of a slice.
+    /// Calls clCreateBuffer with CL_MEM_COPY_HOST_PTR.
+    ///
+    /// * `data` - the data to copy into the buffer.
+    ///
+    /// returns a Result containing the new OpenCL buffer, which must be released
+    /// or the error code from the OpenCL C API function.
+    pub fn buffer_from_slice<T: Copy>(&self, data: &[T]) -> Result<cl_mem, cl_int> {
+        create_buffer_from_slice(self.context(), CL_MEM_READ_WRITE, data)
+    }
+
+    /// Release the default session's references to its command-queue and
+    /// context, if any. Sessions returned by `default_session` remain valid
+    /// until they are dropped.  
+    /// The next call to `default_session` creates a new session.
+    ///
+    /// returns an empty Result or the first error code from the OpenCL C API function.
+    pub fn shutdown() -> Result<(), cl_int> {
+        let session = SESSION.lock().unwrap_or_else(|e| e.into_inner()).take();
+        session.map_or(Ok(()), Session::release)
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        release_command_queue(self.queue()).unwrap_or(());
+        release_context(self.context()).unwrap_or(());
+    }
+}
+
+/// Get the default session, creating it on the first call.
+/// The session device is the device selected by the CL3_DEVICE environment
+/// variable, see the selection module, or the GPU with the highest estimated
+/// peak GFLOPS, see `rank_devices`, or the first device if there are no GPUs.
+///
+/// returns a Result containing a Session, which retains the context and
+/// command-queue of the default session
+/// or CL_DEVICE_NOT_FOUND if there are no devices
+/// or CL_INVALID_VALUE if CL3_DEVICE is invalid
+/// or the error code from the OpenCL C API function.
+pub fn default_session() -> Result<Session, cl_int> {
+    let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    if session.is_none() {
+        *session = Some(Session::create()?);
+    }
+    session.as_ref().unwrap().share()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::{enqueue_nd_range_kernel_slice, enqueue_read_buffer_slice};
+    use crate::context::{get_context_info, retain_context, ContextInfo};
+    use crate::event::release_event;
+    use crate::kernel::{release_kernel, KernelArgs};
+    use crate::kernel_args;
+    use crate::memory::release_mem_object;
+    use crate::types::{cl_float, CL_BLOCKING};
+
+    kernel_args! {
+        struct SaxpyArgs {
+            z: cl_mem,
+            x: cl_mem,
+            y: cl_mem,
+            a: cl_float,
+        }
+    }
+
+    #[test]
+    fn test_default_session() {
+        let session = default_session().unwrap();
+        assert_eq!(session, default_session().unwrap());
+
+        let source = "kernel void saxpy(global float* z, global float const* x,
+            global float const* y, float a)
+            { size_t i = get_global_id(0); z[i] = a * x[i] + y[i]; }";
+        let kernel = session.kernel_from_source(source, "saxpy").unwrap();
+        let x = session.buffer_from_slice(&[1.0f32; 64]).unwrap();
+        let y = session.buffer_from_slice(&[2.0f32; 64]).unwrap();
+        let z = session.buffer_from_slice(&[0.0f32; 64]).unwrap();
+        SaxpyArgs { z, x, y, a: 3.0 }.bind(kernel).unwrap();
+
+        let queue = session.queue();
+        let event = enqueue_nd_range_kernel_slice(queue, kernel, None, &[64], None, &[]).unwrap();
+        let mut result = [0.0f32; 64];
+        let read_event =
+            unsafe { enqueue_read_buffer_slice(queue, z, CL_BLOCKING, 0, &mut result, &[event]) }
+                .unwrap();
+        assert!(result.iter().all(|&value| 5.0 == value));
+
+        release_event(read_event).unwrap();
+        release_event(event).unwrap();
+
+        for buffer in [x, y, z].iter() {
+            release_mem_object(*buffer).unwrap();
+        }
+        release_kernel(kernel).unwrap();
+
+        // Shutdown releases the session's reference to the context
+        let context = session.context();
+        retain_context(context).unwrap();
+        let count = |context| {
+            get_context_info(context, ContextInfo::CL_CONTEXT_REFERENCE_COUNT)
+                .unwrap()
+                .to_uint()
+        };
+        let before = count(context);
+        Session::shutdown().unwrap();
+        assert_eq!(before - 1, count(context));
+        release_context(context).unwrap();
+
+        // A new session is created after shutdown, and shutdown may be repeated
+        let session = default_session().unwrap();
+        assert!(!session.context().is_null());
+        Session::shutdown().unwrap();
+        Session::shutdown().unwrap();
+
+        // The session remains valid after shutdown, until it is dropped
+        assert_eq!(1, count(session.context()));
+    }
+
+    #[test]
+    fn test_session_release_error() {
+        let session = Session {
+            platform: 0,
+            device: 0,
+            context: 0,
+            queue: 0,
+        };
+        assert!(session.release().is_err());
+    }
+}
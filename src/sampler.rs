@@ -16,11 +16,19 @@
 
 #![allow(non_camel_case_types)]
 
-use super::error_codes::{CL_INVALID_VALUE, CL_SUCCESS};
+#[allow(unused_imports)]
+use super::context::{get_context_info, ContextInfo};
+#[allow(unused_imports)]
+use super::device::{get_device_info, DeviceInfo};
+#[allow(unused_imports)]
+use super::error_codes::{CL_INVALID_VALUE, CL_OUT_OF_RESOURCES, CL_SUCCESS};
+use super::ffi::cl_ext::{
+    CL_SAMPLER_LOD_MAX_KHR, CL_SAMPLER_LOD_MIN_KHR, CL_SAMPLER_MIP_FILTER_MODE_KHR,
+};
 use super::info_type::InfoType;
 use super::types::{
-    cl_addressing_mode, cl_bool, cl_context, cl_filter_mode, cl_int, cl_sampler, cl_sampler_info,
-    cl_sampler_properties, cl_uint, cl_ulong,
+    cl_addressing_mode, cl_bool, cl_context, cl_filter_mode, cl_float, cl_int, cl_sampler,
+    cl_sampler_info, cl_sampler_properties, cl_uint, cl_ulong,
 };
 use super::{api_info_size, api_info_value, api_info_vector};
 #[allow(unused_imports)]
@@ -28,9 +36,19 @@ use cl_sys::{
     clCreateSampler, clCreateSamplerWithProperties, clGetSamplerInfo, clReleaseSampler,
     clRetainSampler,
 };
+use cl_sys::{
+    CL_ADDRESS_CLAMP, CL_ADDRESS_CLAMP_TO_EDGE, CL_ADDRESS_MIRRORED_REPEAT, CL_ADDRESS_NONE,
+    CL_ADDRESS_REPEAT, CL_FILTER_LINEAR, CL_FILTER_NEAREST,
+};
 use libc::{c_void, intptr_t, size_t};
+#[allow(unused_imports)]
+use std::collections::HashMap;
 use std::mem;
 use std::ptr;
+#[allow(unused_imports)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[allow(unused_imports)]
+use std::sync::Mutex;
 
 /// Create an OpenCL buffer sampler for a context.  
 /// Calls clCreateSampler to create an OpenCL sampler object.  
@@ -75,20 +93,28 @@ pub fn create_sampler(
 /// CL_VERSION_2_0
 ///
 /// * `context` - a valid OpenCL context.
-/// * `sampler_properties` - an optional null terminated list of properties, see:
+/// * `properties` - a zero terminated list of properties, e.g. from
+///   SamplerProperties, or an empty slice for the default properties, see:
 /// [Sampler Properties](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#sampler-properties-table).
 ///
 /// returns a Result containing the new OpenCL sampler object
+/// or CL_INVALID_VALUE if `properties` is not zero terminated
 /// or the error code from the OpenCL C API function.
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn create_sampler_with_properties(
     context: cl_context,
-    properties: *const cl_sampler_properties,
+    properties: &[cl_sampler_properties],
 ) -> Result<cl_sampler, cl_int> {
+    let properties_ptr = match properties.last() {
+        None => ptr::null(),
+        Some(0) => properties.as_ptr(),
+        Some(_) => return Err(CL_INVALID_VALUE),
+    };
     let mut status: cl_int = CL_INVALID_VALUE;
     let sampler: cl_sampler =
-        unsafe { clCreateSamplerWithProperties(context, properties, &mut status) };
+        unsafe { clCreateSamplerWithProperties(context, properties_ptr, &mut status) };
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -96,6 +122,215 @@ pub fn create_sampler_with_properties(
     }
 }
 
+/// The addressing mode of a sampler, see CL_SAMPLER_ADDRESSING_MODE.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AddressingMode {
+    None = CL_ADDRESS_NONE as isize,
+    ClampToEdge = CL_ADDRESS_CLAMP_TO_EDGE as isize,
+    Clamp = CL_ADDRESS_CLAMP as isize,
+    Repeat = CL_ADDRESS_REPEAT as isize,
+    MirroredRepeat = CL_ADDRESS_MIRRORED_REPEAT as isize,
+}
+
+/// The filter mode of a sampler, see CL_SAMPLER_FILTER_MODE.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FilterMode {
+    Nearest = CL_FILTER_NEAREST as isize,
+    Linear = CL_FILTER_LINEAR as isize,
+}
+
+/// A builder for the properties list of create_sampler_with_properties.  
+/// Properties that are not set take their OpenCL default values:
+/// normalized coordinates, CL_ADDRESS_CLAMP and CL_FILTER_NEAREST.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SamplerProperties {
+    normalized_coords: Option<bool>,
+    addressing_mode: Option<AddressingMode>,
+    filter_mode: Option<FilterMode>,
+    mip_filter_mode: Option<FilterMode>,
+    // The bits of the minimum and maximum level of detail.
+    lod: Option<(u32, u32)>,
+}
+
+impl SamplerProperties {
+    pub fn new() -> SamplerProperties {
+        SamplerProperties::default()
+    }
+
+    /// Set CL_SAMPLER_NORMALIZED_COORDS.
+    pub fn normalized_coords(mut self, normalized_coords: bool) -> SamplerProperties {
+        self.normalized_coords = Some(normalized_coords);
+        self
+    }
+
+    /// Set CL_SAMPLER_ADDRESSING_MODE.
+    pub fn addressing_mode(mut self, addressing_mode: AddressingMode) -> SamplerProperties {
+        self.addressing_mode = Some(addressing_mode);
+        self
+    }
+
+    /// Set CL_SAMPLER_FILTER_MODE.
+    pub fn filter_mode(mut self, filter_mode: FilterMode) -> SamplerProperties {
+        self.filter_mode = Some(filter_mode);
+        self
+    }
+
+    /// Set CL_SAMPLER_MIP_FILTER_MODE_KHR, requires cl_khr_mipmap_image.
+    pub fn mip_filter_mode(mut self, mip_filter_mode: FilterMode) -> SamplerProperties {
+        self.mip_filter_mode = Some(mip_filter_mode);
+        self
+    }
+
+    /// Set CL_SAMPLER_LOD_MIN_KHR and CL_SAMPLER_LOD_MAX_KHR, requires cl_khr_mipmap_image.
+    pub fn lod(mut self, lod_min: cl_float, lod_max: cl_float) -> SamplerProperties {
+        self.lod = Some((lod_min.to_bits(), lod_max.to_bits()));
+        self
+    }
+
+    /// The zero terminated properties list.
+    pub fn to_vec(&self) -> Vec<cl_sampler_properties> {
+        let mut properties: Vec<cl_sampler_properties> = Vec::with_capacity(13);
+        if let Some(normalized_coords) = self.normalized_coords {
+            properties.push(SamplerInfo::CL_SAMPLER_NORMALIZED_COORDS as cl_sampler_properties);
+            properties.push(normalized_coords as cl_sampler_properties);
+        }
+        if let Some(addressing_mode) = self.addressing_mode {
+            properties.push(SamplerInfo::CL_SAMPLER_ADDRESSING_MODE as cl_sampler_properties);
+            properties.push(addressing_mode as cl_sampler_properties);
+        }
+        if let Some(filter_mode) = self.filter_mode {
+            properties.push(SamplerInfo::CL_SAMPLER_FILTER_MODE as cl_sampler_properties);
+            properties.push(filter_mode as cl_sampler_properties);
+        }
+        if let Some(mip_filter_mode) = self.mip_filter_mode {
+            properties.push(CL_SAMPLER_MIP_FILTER_MODE_KHR);
+            properties.push(mip_filter_mode as cl_sampler_properties);
+        }
+        if let Some((lod_min, lod_max)) = self.lod {
+            // The cl_float values are passed in the low bits of the properties.
+            properties.push(CL_SAMPLER_LOD_MIN_KHR);
+            properties.push(lod_min as cl_sampler_properties);
+            properties.push(CL_SAMPLER_LOD_MAX_KHR);
+            properties.push(lod_max as cl_sampler_properties);
+        }
+        properties.push(0);
+        properties
+    }
+}
+
+/// A cache of the samplers of a context, keyed by their SamplerProperties.  
+/// Samplers are context-scoped: a cached sampler may only be used with the
+/// context of the cache. A sampler is created on the first request for its
+/// properties and then retained for each later request. Cached samplers are
+/// never evicted: the number of samplers is bounded by the largest
+/// CL_DEVICE_MAX_SAMPLERS of the context devices. The cache holds a reference
+/// to each sampler until `clear` is called or the cache is dropped.  
+/// CL_VERSION_2_0
+#[cfg(feature = "CL_VERSION_2_0")]
+#[derive(Debug)]
+pub struct SamplerCache {
+    // The handles are stored as usize since raw pointers are not Send.
+    context: usize,
+    max_samplers: usize,
+    samplers: Mutex<HashMap<SamplerProperties, usize>>,
+    // The number of samplers created, to verify that samplers are cached.
+    created: AtomicUsize,
+}
+
+#[cfg(feature = "CL_VERSION_2_0")]
+impl SamplerCache {
+    /// Create a sampler cache for a context.  
+    /// Calls clGetContextInfo and clGetDeviceInfo to find CL_DEVICE_MAX_SAMPLERS.
+    ///
+    /// * `context` - a valid OpenCL context.
+    ///
+    /// returns a Result containing the new SamplerCache
+    /// or the error code from the OpenCL C API function.
+    pub fn new(context: cl_context) -> Result<SamplerCache, cl_int> {
+        let devices =
+            get_context_info(context, ContextInfo::CL_CONTEXT_DEVICES)?.to_vec_device_id();
+        let mut max_samplers = 0;
+        for device in devices {
+            let value = get_device_info(device, DeviceInfo::CL_DEVICE_MAX_SAMPLERS)?.to_uint();
+            max_samplers = max_samplers.max(value as usize);
+        }
+        Ok(SamplerCache {
+            context: context as usize,
+            max_samplers,
+            samplers: Mutex::new(HashMap::new()),
+            created: AtomicUsize::new(0),
+        })
+    }
+
+    /// Get a sampler with the given properties.  
+    /// Calls clCreateSamplerWithProperties for properties that are not in the
+    /// cache, otherwise clRetainSampler.
+    ///
+    /// * `properties` - the sampler properties.
+    ///
+    /// returns a Result containing a retained sampler, which the caller must
+    /// release with release_sampler
+    /// or CL_OUT_OF_RESOURCES if the cache holds CL_DEVICE_MAX_SAMPLERS samplers
+    /// or the error code from the OpenCL C API function.
+    pub fn get(&self, properties: &SamplerProperties) -> Result<cl_sampler, cl_int> {
+        let mut samplers = self.samplers.lock().unwrap();
+        let sampler = match samplers.get(properties) {
+            Some(&sampler) => sampler as cl_sampler,
+            None => {
+                if self.max_samplers <= samplers.len() {
+                    return Err(CL_OUT_OF_RESOURCES);
+                }
+                let sampler = create_sampler_with_properties(
+                    self.context as cl_context,
+                    &properties.to_vec(),
+                )?;
+                self.created.fetch_add(1, Ordering::SeqCst);
+                samplers.insert(properties.clone(), sampler as usize);
+                sampler
+            }
+        };
+        retain_sampler(sampler)?;
+        Ok(sampler)
+    }
+
+    /// The context of the cached samplers.
+    pub fn context(&self) -> cl_context {
+        self.context as cl_context
+    }
+
+    /// The number of cached samplers.
+    pub fn len(&self) -> usize {
+        self.samplers.lock().unwrap().len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        0 == self.len()
+    }
+
+    /// Release the references of the cache to all of its samplers.  
+    /// Samplers returned by `get` remain valid until the caller releases them.
+    /// All of the samplers are removed from the cache, even if one fails to release.
+    ///
+    /// returns an empty Result or the first error code from the OpenCL C API function.
+    pub fn clear(&self) -> Result<(), cl_int> {
+        let mut samplers = self.samplers.lock().unwrap();
+        // Release every sampler before looking for the first error
+        let results: Vec<Result<(), cl_int>> = samplers
+            .drain()
+            .map(|(_, sampler)| release_sampler(sampler as cl_sampler))
+            .collect();
+        results.into_iter().try_fold((), |(), released| released)
+    }
+}
+
+#[cfg(feature = "CL_VERSION_2_0")]
+impl Drop for SamplerCache {
+    fn drop(&mut self) {
+        self.clear().unwrap_or(());
+    }
+}
+
 /// Retain an OpenCL sampler.  
 /// Calls clRetainSampler to increment the sampler reference count.
 ///
@@ -105,6 +340,8 @@ pub fn create_sampler_with_properties(
 #[inline]
 pub fn retain_sampler(sampler: cl_sampler) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clRetainSampler(sampler) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::retained(sampler as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -121,6 +358,8 @@ pub fn retain_sampler(sampler: cl_sampler) -> Result<(), cl_int> {
 #[inline]
 pub fn release_sampler(sampler: cl_sampler) -> Result<(), cl_int> {
     let status: cl_int = unsafe { clReleaseSampler(sampler) };
+    #[cfg(test)]
+    crate::macros::refcount_hooks::released(sampler as usize, status);
     if CL_SUCCESS != status {
         Err(status)
     } else {
@@ -170,13 +409,17 @@ pub fn get_sampler_info(sampler: cl_sampler, param_name: SamplerInfo) -> Result<
     let param_id = param_name as cl_sampler_info;
     match param_name {
         SamplerInfo::CL_SAMPLER_REFERENCE_COUNT
-        | SamplerInfo::CL_SAMPLER_NORMALIZED_COORDS
         | SamplerInfo::CL_SAMPLER_ADDRESSING_MODE
         | SamplerInfo::CL_SAMPLER_FILTER_MODE => {
             api_info_value!(get_value, cl_uint, clGetSamplerInfo);
             Ok(InfoType::Uint(get_value(sampler, param_id)?))
         }
 
+        SamplerInfo::CL_SAMPLER_NORMALIZED_COORDS => {
+            api_info_value!(get_value, cl_uint, clGetSamplerInfo);
+            Ok(InfoType::Bool(0 != get_value(sampler, param_id)?))
+        }
+
         SamplerInfo::CL_SAMPLER_CONTEXT => {
             api_info_value!(get_value, intptr_t, clGetSamplerInfo);
             Ok(InfoType::Ptr(get_value(sampler, param_id)?))
@@ -191,3 +434,199 @@ pub fn get_sampler_info(sampler: cl_sampler, param_name: SamplerInfo) -> Result<
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // The tests that create samplers need clCreateSamplerWithProperties
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use crate::{
+        context::{create_context, release_context},
+        device::{get_device_ids, select_device, CL_DEVICE_TYPE_ALL},
+        macros::refcount_hooks::balance,
+        platform::get_platform_ids,
+        refcount_invariant,
+    };
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use std::thread;
+
+    #[test]
+    fn test_sampler_properties() {
+        assert_eq!(vec![0], SamplerProperties::new().to_vec());
+
+        let properties = SamplerProperties::new()
+            .normalized_coords(false)
+            .addressing_mode(AddressingMode::ClampToEdge)
+            .filter_mode(FilterMode::Linear)
+            .to_vec();
+        assert_eq!(
+            vec![
+                SamplerInfo::CL_SAMPLER_NORMALIZED_COORDS as cl_sampler_properties,
+                0,
+                SamplerInfo::CL_SAMPLER_ADDRESSING_MODE as cl_sampler_properties,
+                CL_ADDRESS_CLAMP_TO_EDGE as cl_sampler_properties,
+                SamplerInfo::CL_SAMPLER_FILTER_MODE as cl_sampler_properties,
+                CL_FILTER_LINEAR as cl_sampler_properties,
+                0
+            ],
+            properties
+        );
+
+        let properties = SamplerProperties::new()
+            .mip_filter_mode(FilterMode::Linear)
+            .lod(0.5, 4.0)
+            .to_vec();
+        assert_eq!(
+            vec![
+                CL_SAMPLER_MIP_FILTER_MODE_KHR,
+                CL_FILTER_LINEAR as cl_sampler_properties,
+                CL_SAMPLER_LOD_MIN_KHR,
+                0.5f32.to_bits() as cl_sampler_properties,
+                CL_SAMPLER_LOD_MAX_KHR,
+                4.0f32.to_bits() as cl_sampler_properties,
+                0
+            ],
+            properties
+        );
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    #[test]
+    fn test_sampler_properties_not_terminated() {
+        // A properties list must be zero terminated
+        let properties = [SamplerInfo::CL_SAMPLER_NORMALIZED_COORDS as cl_sampler_properties];
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            create_sampler_with_properties(ptr::null_mut(), &properties)
+        );
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    #[test]
+    fn test_sampler() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids[..1], ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let properties = SamplerProperties::new()
+            .normalized_coords(false)
+            .addressing_mode(AddressingMode::Repeat)
+            .filter_mode(FilterMode::Linear)
+            .to_vec();
+        let sampler = create_sampler_with_properties(context, &properties).unwrap();
+
+        let value = get_sampler_info(sampler, SamplerInfo::CL_SAMPLER_NORMALIZED_COORDS).unwrap();
+        assert!(!value.to_bool());
+        let value = get_sampler_info(sampler, SamplerInfo::CL_SAMPLER_ADDRESSING_MODE).unwrap();
+        assert_eq!(CL_ADDRESS_REPEAT, value.to_uint());
+        let value = get_sampler_info(sampler, SamplerInfo::CL_SAMPLER_FILTER_MODE).unwrap();
+        assert_eq!(CL_FILTER_LINEAR, value.to_uint());
+
+        retain_sampler(sampler).unwrap();
+        let value = get_sampler_info(sampler, SamplerInfo::CL_SAMPLER_REFERENCE_COUNT).unwrap();
+        assert_eq!(2, value.to_uint());
+        release_sampler(sampler).unwrap();
+        release_sampler(sampler).unwrap();
+
+        release_context(context).unwrap();
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    #[test]
+    fn test_sampler_cache() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let context = create_context(&device_ids[..1], ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let cache = SamplerCache::new(context).unwrap();
+        assert!(cache.is_empty());
+        fn is_send_sync<T: Send + Sync>(_: &T) {}
+        is_send_sync(&cache);
+
+        let properties = SamplerProperties::new()
+            .normalized_coords(false)
+            .filter_mode(FilterMode::Linear);
+        let sampler = cache.get(&properties).unwrap();
+        let same_sampler = cache.get(&properties.clone()).unwrap();
+        assert_eq!(sampler, same_sampler);
+        assert_eq!(1, cache.created.load(Ordering::SeqCst));
+        assert_eq!(1, cache.len());
+
+        // The cache and each get hold a reference
+        let value = get_sampler_info(sampler, SamplerInfo::CL_SAMPLER_REFERENCE_COUNT).unwrap();
+        assert_eq!(3, value.to_uint());
+        release_sampler(same_sampler).unwrap();
+
+        let other_sampler = cache
+            .get(&SamplerProperties::new().addressing_mode(AddressingMode::Repeat))
+            .unwrap();
+        assert_ne!(sampler, other_sampler);
+        assert_eq!(2, cache.created.load(Ordering::SeqCst));
+        release_sampler(other_sampler).unwrap();
+
+        cache.clear().unwrap();
+        assert!(cache.is_empty());
+        let value = get_sampler_info(sampler, SamplerInfo::CL_SAMPLER_REFERENCE_COUNT).unwrap();
+        assert_eq!(1, value.to_uint());
+        release_sampler(sampler).unwrap();
+
+        drop(cache);
+        release_context(context).unwrap();
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    #[test]
+    fn test_sampler_cache_clear_error() {
+        let cache = SamplerCache {
+            context: 0,
+            max_samplers: 2,
+            samplers: Mutex::new(HashMap::new()),
+            created: AtomicUsize::new(0),
+        };
+        {
+            let mut samplers = cache.samplers.lock().unwrap();
+            samplers.insert(SamplerProperties::new(), 0);
+            samplers.insert(SamplerProperties::new().filter_mode(FilterMode::Linear), 0);
+        }
+
+        // Every sampler is removed, although the invalid samplers fail to release
+        assert!(cache.clear().is_err());
+        assert!(cache.is_empty());
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    #[test]
+    fn test_sampler_cache_stress() {
+        let (_, device_id) = select_device(&[CL_DEVICE_TYPE_ALL]).unwrap();
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let cache = SamplerCache::new(context).unwrap();
+        let properties = SamplerProperties::new().filter_mode(FilterMode::Linear);
+        let sampler = cache.get(&properties).unwrap();
+
+        // Each thread must release every sampler that it gets from the cache
+        let handle = sampler as usize;
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        refcount_invariant!(balance(handle), {
+                            let same_sampler = cache.get(&properties).unwrap();
+                            assert_eq!(handle, same_sampler as usize);
+                            release_sampler(same_sampler).unwrap();
+                        })
+                        .unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(1, cache.created.load(Ordering::SeqCst));
+
+        // The cache and the first get hold a reference
+        let value = get_sampler_info(sampler, SamplerInfo::CL_SAMPLER_REFERENCE_COUNT).unwrap();
+        assert_eq!(2, value.to_uint());
+        release_sampler(sampler).unwrap();
+
+        drop(cache);
+        release_context(context).unwrap();
+    }
+}
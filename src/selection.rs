@@ -0,0 +1,324 @@
+// Copyright (c) 2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Device selection by the `CL3_DEVICE` environment variable.
+//!
+//! A deployment can pin the device that `select_device`, `select_device_by`
+//! and `quick::default_session` choose, without code changes, by setting
+//! `CL3_DEVICE` to one of:
+//!
+//! * `platform_index:device_index` - e.g. `0:1`, the second device of the
+//!   first platform, in the order of clGetPlatformIDs and clGetDeviceIDs.
+//! * `name~substring` - e.g. `name~RTX`, the first device whose
+//!   CL_DEVICE_NAME contains the substring, ignoring case.
+//! * `uuid:HEX` - the device whose CL_DEVICE_UUID_KHR is the 32 hex digits,
+//!   optionally separated by `-`, with the `cl_khr_device_uuid` feature.
+//!
+//! `select_device` and `select_device_by` return an error, rather than the
+//! pinned device, if it is not of any of the caller's preferred types.
+
+use super::device::{
+    get_all_devices, get_device_ids, get_device_info, DeviceInfo, SelectDeviceError,
+    CL_DEVICE_TYPE_ALL,
+};
+use super::error_codes::CL_DEVICE_NOT_FOUND;
+#[cfg(feature = "cl_khr_device_uuid")]
+use super::ffi::cl_ext::CL_UUID_SIZE_KHR;
+use super::platform::get_platform_ids;
+#[cfg(feature = "cl_khr_device_uuid")]
+use super::types::cl_uchar;
+use super::types::{cl_device_id, cl_int, cl_platform_id};
+
+use std::env;
+use std::fmt;
+
+/// The name of the environment variable that selects a device.
+pub const CL3_DEVICE: &str = "CL3_DEVICE";
+
+// The accepted forms of a selection, for error messages.
+#[cfg(feature = "cl_khr_device_uuid")]
+const ACCEPTED_FORMS: &str = "platform_index:device_index, name~substring or uuid:HEX";
+#[cfg(not(feature = "cl_khr_device_uuid"))]
+const ACCEPTED_FORMS: &str = "platform_index:device_index or name~substring";
+
+/// A device selection, see the module documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Selection {
+    /// The device at an index of the devices of the platform at an index.
+    Index { platform: usize, device: usize },
+    /// The first device whose name contains a substring, ignoring case.
+    Name(String),
+    /// The device with a CL_DEVICE_UUID_KHR.
+    #[cfg(feature = "cl_khr_device_uuid")]
+    Uuid([cl_uchar; CL_UUID_SIZE_KHR]),
+}
+
+/// An invalid CL3_DEVICE value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SelectionError {
+    /// The value is not valid unicode.
+    NotUnicode,
+    /// The value does not match any of the accepted forms.
+    InvalidSyntax(String),
+    /// A platform or device index is not a number.
+    InvalidIndex(String),
+    /// The substring of a `name~` selection is empty.
+    EmptyName,
+    /// The UUID of a `uuid:` selection is not 32 hex digits.
+    #[cfg(feature = "cl_khr_device_uuid")]
+    InvalidUuid(String),
+}
+
+impl fmt::Display for SelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SelectionError::NotUnicode => write!(f, "{} is not valid unicode", CL3_DEVICE),
+            SelectionError::InvalidSyntax(value) => {
+                write!(f, "invalid {} \"{}\"", CL3_DEVICE, value)
+            }
+            SelectionError::InvalidIndex(value) => {
+                write!(f, "invalid {} index in \"{}\"", CL3_DEVICE, value)
+            }
+            SelectionError::EmptyName => write!(f, "empty {} name~ substring", CL3_DEVICE),
+            #[cfg(feature = "cl_khr_device_uuid")]
+            SelectionError::InvalidUuid(value) => write!(
+                f,
+                "invalid {} uuid in \"{}\", expected 32 hex digits",
+                CL3_DEVICE, value
+            ),
+        }?;
+        write!(f, ", the accepted forms are: {}", ACCEPTED_FORMS)
+    }
+}
+
+impl std::error::Error for SelectionError {}
+
+// Parse the 32 hex digits of a UUID, ignoring `-` separators.
+#[cfg(feature = "cl_khr_device_uuid")]
+fn parse_uuid(text: &str) -> Option<[cl_uchar; CL_UUID_SIZE_KHR]> {
+    let digits: Vec<u8> = text
+        .bytes()
+        .filter(|&c| b'-' != c)
+        .map(|c| (c as char).to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<Vec<u8>>>()?;
+    if 2 * CL_UUID_SIZE_KHR != digits.len() {
+        return None;
+    }
+    let mut uuid = [0; CL_UUID_SIZE_KHR];
+    for (byte, pair) in uuid.iter_mut().zip(digits.chunks(2)) {
+        *byte = (pair[0] << 4) | pair[1];
+    }
+    Some(uuid)
+}
+
+impl Selection {
+    /// Parse a selection, see the module documentation.
+    ///
+    /// * `text` - the selection, surrounding whitespace is ignored.
+    ///
+    /// returns a Result containing the Selection
+    /// or the SelectionError describing why `text` is invalid.
+    pub fn parse(text: &str) -> Result<Selection, SelectionError> {
+        let text = text.trim();
+        #[cfg(feature = "cl_khr_device_uuid")]
+        {
+            if let Some(uuid) = text.strip_prefix("uuid:") {
+                return parse_uuid(uuid)
+                    .map(Selection::Uuid)
+                    .ok_or_else(|| SelectionError::InvalidUuid(text.to_string()));
+            }
+        }
+        #[cfg(not(feature = "cl_khr_device_uuid"))]
+        {
+            if text.starts_with("uuid:") {
+                return Err(SelectionError::InvalidSyntax(text.to_string()));
+            }
+        }
+        if let Some(name) = text.strip_prefix("name~") {
+            if name.is_empty() {
+                Err(SelectionError::EmptyName)
+            } else {
+                Ok(Selection::Name(name.to_string()))
+            }
+        } else if let Some((platform, device)) = text.split_once(':') {
+            match (platform.parse(), device.parse()) {
+                (Ok(platform), Ok(device)) => Ok(Selection::Index { platform, device }),
+                _ => Err(SelectionError::InvalidIndex(text.to_string())),
+            }
+        } else {
+            Err(SelectionError::InvalidSyntax(text.to_string()))
+        }
+    }
+
+    /// Find the selected device.
+    /// Calls clGetPlatformIDs, clGetDeviceIDs and, for name and uuid
+    /// selections, clGetDeviceInfo.
+    ///
+    /// returns a Result containing the platform and device
+    /// or CL_DEVICE_NOT_FOUND if no device matches the selection
+    /// or the error code from the OpenCL C API function.
+    pub fn find(&self) -> Result<(cl_platform_id, cl_device_id), cl_int> {
+        match self {
+            Selection::Index { platform, device } => {
+                let platform = *get_platform_ids()?
+                    .get(*platform)
+                    .ok_or(CL_DEVICE_NOT_FOUND)?;
+                let device = *get_device_ids(platform, CL_DEVICE_TYPE_ALL)?
+                    .get(*device)
+                    .ok_or(CL_DEVICE_NOT_FOUND)?;
+                Ok((platform, device))
+            }
+            Selection::Name(name) => {
+                let name = name.to_lowercase();
+                get_all_devices(CL_DEVICE_TYPE_ALL)?
+                    .into_iter()
+                    .find(|&(_, device)| {
+                        get_device_info(device, DeviceInfo::CL_DEVICE_NAME)
+                            .map(|value| value.to_string().to_lowercase().contains(&name))
+                            .unwrap_or(false)
+                    })
+                    .ok_or(CL_DEVICE_NOT_FOUND)
+            }
+            #[cfg(feature = "cl_khr_device_uuid")]
+            Selection::Uuid(uuid) => get_all_devices(CL_DEVICE_TYPE_ALL)?
+                .into_iter()
+                .find(|&(_, device)| {
+                    get_device_info(device, DeviceInfo::CL_DEVICE_UUID_KHR)
+                        .map(|value| value.to_uuid() == *uuid)
+                        .unwrap_or(false)
+                })
+                .ok_or(CL_DEVICE_NOT_FOUND),
+        }
+    }
+}
+
+/// Get the device selection of the CL3_DEVICE environment variable.
+///
+/// returns a Result containing the Selection, or None if CL3_DEVICE is not
+/// set or is empty
+/// or the SelectionError describing why the value is invalid.
+pub fn from_env() -> Result<Option<Selection>, SelectionError> {
+    match env::var(CL3_DEVICE) {
+        Ok(value) if value.trim().is_empty() => Ok(None),
+        Ok(value) => Selection::parse(&value).map(Some),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(_)) => Err(SelectionError::NotUnicode),
+    }
+}
+
+/// Find the device selected by the CL3_DEVICE environment variable, for the
+/// device selection functions.
+///
+/// returns a Result containing the platform and device, or None if CL3_DEVICE
+/// is not set
+/// or SelectDeviceError::Selection if CL3_DEVICE is invalid
+/// or the error code from the OpenCL C API function, CL_DEVICE_NOT_FOUND if
+/// no device matches CL3_DEVICE.
+pub fn env_device() -> Result<Option<(cl_platform_id, cl_device_id)>, SelectDeviceError> {
+    match from_env()? {
+        Some(selection) => Ok(Some(selection.find()?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_index() {
+        assert_eq!(
+            Ok(Selection::Index {
+                platform: 0,
+                device: 1
+            }),
+            Selection::parse("0:1")
+        );
+        assert_eq!(
+            Ok(Selection::Index {
+                platform: 2,
+                device: 0
+            }),
+            Selection::parse(" 2:0\n")
+        );
+        assert_eq!(
+            Err(SelectionError::InvalidIndex("0:x".to_string())),
+            Selection::parse("0:x")
+        );
+        assert_eq!(
+            Err(SelectionError::InvalidIndex("-1:0".to_string())),
+            Selection::parse("-1:0")
+        );
+        assert_eq!(
+            Err(SelectionError::InvalidIndex("0:".to_string())),
+            Selection::parse("0:")
+        );
+    }
+
+    #[test]
+    fn test_parse_name() {
+        assert_eq!(
+            Ok(Selection::Name("RTX 3080".to_string())),
+            Selection::parse("name~RTX 3080")
+        );
+        assert_eq!(Err(SelectionError::EmptyName), Selection::parse("name~"));
+    }
+
+    #[cfg(feature = "cl_khr_device_uuid")]
+    #[test]
+    fn test_parse_uuid() {
+        let mut expected = [0; CL_UUID_SIZE_KHR];
+        expected[0] = 0x01;
+        expected[15] = 0xEF;
+        assert_eq!(
+            Ok(Selection::Uuid(expected)),
+            Selection::parse("uuid:010000000000000000000000000000ef")
+        );
+        assert_eq!(
+            Ok(Selection::Uuid(expected)),
+            Selection::parse("uuid:01000000-0000-0000-0000-0000000000EF")
+        );
+        assert_eq!(
+            Err(SelectionError::InvalidUuid("uuid:0123".to_string())),
+            Selection::parse("uuid:0123")
+        );
+        assert_eq!(
+            Err(SelectionError::InvalidUuid(
+                "uuid:g10000000000000000000000000000ef".to_string()
+            )),
+            Selection::parse("uuid:g10000000000000000000000000000ef")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_syntax() {
+        let error = Selection::parse("gpu").unwrap_err();
+        assert_eq!(SelectionError::InvalidSyntax("gpu".to_string()), error);
+        let text = error.to_string();
+        assert!(text.contains("platform_index:device_index"));
+        assert!(text.contains("name~substring"));
+        assert_eq!(
+            cfg!(feature = "cl_khr_device_uuid"),
+            text.contains("uuid:HEX")
+        );
+
+        #[cfg(not(feature = "cl_khr_device_uuid"))]
+        assert_eq!(
+            Err(SelectionError::InvalidSyntax(
+                "uuid:010000000000000000000000000000ef".to_string()
+            )),
+            Selection::parse("uuid:010000000000000000000000000000ef")
+        );
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A snapshot of the OpenCL platforms and devices in a system.
+//!
+//! Enumerating platforms and devices can be slow, since some ICD loaders
+//! re-read their ICD files on every call to clGetPlatformIDs.
+//! A SystemSnapshot enumerates them once, together with commonly needed
+//! device attributes, so that they can be read repeatedly without calling
+//! OpenCL. The snapshot is not updated automatically, call `refresh` to
+//! enumerate them again.
+//!
+//! The platform and device ids in a snapshot remain valid as long as the
+//! OpenCL ICDs are not unloaded.
+
+use super::device::{get_device_ids, get_device_info, DeviceInfo, CL_DEVICE_TYPE_ALL};
+use super::platform::{get_platform_ids, get_platform_info, PlatformInfo};
+use super::types::{cl_device_id, cl_device_type, cl_int, cl_platform_id, cl_uint};
+
+/// The commonly needed attributes of an OpenCL device.
+#[derive(Clone, Debug)]
+pub struct DeviceSnapshot {
+    pub id: cl_device_id,
+    pub name: String,
+    pub device_type: cl_device_type,
+    pub version: String,
+    pub max_compute_units: cl_uint,
+}
+
+impl DeviceSnapshot {
+    /// Query the attributes of an OpenCL device.
+    pub fn capture(device: cl_device_id) -> Result<DeviceSnapshot, cl_int> {
+        Ok(DeviceSnapshot {
+            id: device,
+            name: get_device_info(device, DeviceInfo::CL_DEVICE_NAME)?.to_string(),
+            device_type: get_device_info(device, DeviceInfo::CL_DEVICE_TYPE)?.to_ulong(),
+            version: get_device_info(device, DeviceInfo::CL_DEVICE_VERSION)?.to_string(),
+            max_compute_units: get_device_info(device, DeviceInfo::CL_DEVICE_MAX_COMPUTE_UNITS)?
+                .to_uint(),
+        })
+    }
+}
+
+/// An OpenCL platform and its devices.
+#[derive(Clone, Debug)]
+pub struct PlatformSnapshot {
+    pub id: cl_platform_id,
+    pub name: String,
+    pub version: String,
+    pub devices: Vec<DeviceSnapshot>,
+}
+
+impl PlatformSnapshot {
+    /// Query the attributes of an OpenCL platform and its devices.
+    pub fn capture(platform: cl_platform_id) -> Result<PlatformSnapshot, cl_int> {
+        let devices = get_device_ids(platform, CL_DEVICE_TYPE_ALL)?
+            .into_iter()
+            .map(DeviceSnapshot::capture)
+            .collect::<Result<Vec<DeviceSnapshot>, cl_int>>()?;
+        Ok(PlatformSnapshot {
+            id: platform,
+            name: get_platform_info(platform, PlatformInfo::CL_PLATFORM_NAME)?.to_string(),
+            version: get_platform_info(platform, PlatformInfo::CL_PLATFORM_VERSION)?.to_string(),
+            devices,
+        })
+    }
+}
+
+/// The OpenCL platforms and devices in a system.
+#[derive(Clone, Debug, Default)]
+pub struct SystemSnapshot {
+    platforms: Vec<PlatformSnapshot>,
+}
+
+impl SystemSnapshot {
+    /// Enumerate the OpenCL platforms and devices.
+    /// Calls clGetPlatformIDs, clGetDeviceIDs and the Info functions for
+    /// every platform and device.
+    ///
+    /// returns a Result containing the SystemSnapshot
+    /// or the error code from the OpenCL C API function.
+    pub fn capture() -> Result<SystemSnapshot, cl_int> {
+        let platforms = get_platform_ids()?
+            .into_iter()
+            .map(PlatformSnapshot::capture)
+            .collect::<Result<Vec<PlatformSnapshot>, cl_int>>()?;
+        Ok(SystemSnapshot { platforms })
+    }
+
+    /// Enumerate the OpenCL platforms and devices again.
+    /// The snapshot is unchanged if enumeration fails.
+    ///
+    /// returns an empty Result or the error code from the OpenCL C API function.
+    pub fn refresh(&mut self) -> Result<(), cl_int> {
+        *self = SystemSnapshot::capture()?;
+        Ok(())
+    }
+
+    /// The platforms in the snapshot.
+    pub fn platforms(&self) -> &[PlatformSnapshot] {
+        &self.platforms
+    }
+
+    /// The devices of every platform in the snapshot.
+    pub fn devices(&self) -> impl Iterator<Item = &DeviceSnapshot> {
+        self.platforms.iter().flat_map(|p| p.devices.iter())
+    }
+
+    /// The devices of every platform that match a device type, e.g.
+    /// CL_DEVICE_TYPE_GPU or CL_DEVICE_TYPE_CPU | CL_DEVICE_TYPE_ACCELERATOR.
+    pub fn devices_of_type(
+        &self,
+        device_type: cl_device_type,
+    ) -> impl Iterator<Item = &DeviceSnapshot> {
+        self.devices()
+            .filter(move |d| 0 != d.device_type & device_type)
+    }
+
+    /// Find a device in the snapshot.
+    pub fn device(&self, device: cl_device_id) -> Option<&DeviceSnapshot> {
+        self.devices().find(|d| d.id == device)
+    }
+
+    /// Find the platform of a device in the snapshot.
+    pub fn device_platform(&self, device: cl_device_id) -> Option<&PlatformSnapshot> {
+        self.platforms
+            .iter()
+            .find(|p| p.devices.iter().any(|d| d.id == device))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{get_all_devices, CL_DEVICE_TYPE_GPU};
+    use crate::macros::call_hooks::calls;
+
+    #[test]
+    fn test_system_snapshot() {
+        let mut snapshot = SystemSnapshot::capture().unwrap();
+        println!("Number of platforms: {}", snapshot.platforms().len());
+        assert!(0 < snapshot.platforms().len());
+
+        for platform in snapshot.platforms() {
+            println!("{}: {}", platform.name, platform.version);
+            for device in platform.devices.iter() {
+                println!(
+                    "    {}: {}, type: {:X}, compute units: {}",
+                    device.name, device.version, device.device_type, device.max_compute_units
+                );
+            }
+        }
+
+        let device = snapshot.devices().next().unwrap();
+        let id = device.id;
+        assert_eq!(id, snapshot.device(id).unwrap().id);
+        assert!(snapshot.device_platform(id).is_some());
+
+        println!(
+            "Number of GPU devices: {}",
+            snapshot.devices_of_type(CL_DEVICE_TYPE_GPU).count()
+        );
+
+        // Repeated reads do not call OpenCL, unlike repeated enumerations
+        let platform_calls = calls("clGetPlatformIDs");
+        let device_calls = calls("clGetDeviceIDs");
+        let count = snapshot.devices().count();
+        for _ in 0..100 {
+            assert_eq!(count, snapshot.devices().count());
+            assert!(snapshot.device(id).is_some());
+        }
+        assert_eq!(platform_calls, calls("clGetPlatformIDs"));
+        assert_eq!(device_calls, calls("clGetDeviceIDs"));
+
+        for _ in 0..100 {
+            assert_eq!(count, get_all_devices(CL_DEVICE_TYPE_ALL).unwrap().len());
+        }
+        assert_eq!(platform_calls + 100, calls("clGetPlatformIDs"));
+        assert!(device_calls + 100 <= calls("clGetDeviceIDs"));
+
+        snapshot.refresh().unwrap();
+        assert_eq!(count, snapshot.devices().count());
+    }
+}
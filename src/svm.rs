@@ -0,0 +1,605 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OpenCL Shared Virtual Memory (SVM) helpers.
+//! CL_VERSION_2_0
+//!
+//! An SvmBuffer allocates an SVM buffer and frees it when it is dropped. The
+//! other types in this module describe regions of SVM and sub-allocate them
+//! without pointer arithmetic at call sites.
+//!
+//! The functions that take SVM pointers, including `svm_alloc` and `svm_free`,
+//! are in the [raw] submodule. They are also available at their old paths in
+//! this module but are deprecated.
+
+#![allow(non_camel_case_types)]
+
+pub mod raw;
+
+#[allow(deprecated)]
+pub use super::memory::{svm_alloc, svm_free};
+
+#[cfg(feature = "CL_VERSION_2_0")]
+use super::command_queue::enqueue_svm_mem_fill_slice;
+use super::context::{release_context, retain_context};
+#[cfg(feature = "CL_VERSION_2_0")]
+use super::error_codes::CL_INVALID_VALUE;
+#[cfg(feature = "CL_VERSION_2_0")]
+use super::types::{cl_command_queue, cl_event};
+use super::types::{cl_context, cl_int, cl_svm_mem_flags, cl_uint};
+use libc::{c_void, size_t};
+use std::cell::Cell;
+use std::fmt;
+#[cfg(feature = "CL_VERSION_2_0")]
+use std::mem;
+
+/// A region of shared virtual memory: an SVM pointer and its size in bytes.
+/// An SvmRegion does not own the memory, it must not outlive the SVM
+/// allocation that it describes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SvmRegion {
+    ptr: *mut c_void,
+    size: size_t,
+}
+
+impl SvmRegion {
+    /// Describe a region of SVM.
+    ///
+    /// * `ptr` - an SVM pointer, e.g. returned by `raw::svm_alloc`.
+    /// * `size` - the size of the region in bytes.
+    pub fn new(ptr: *mut c_void, size: size_t) -> SvmRegion {
+        SvmRegion { ptr, size }
+    }
+
+    /// The SVM pointer to the start of the region.
+    pub fn as_mut_ptr(&self) -> *mut c_void {
+        self.ptr
+    }
+
+    /// The size of the region in bytes.
+    pub fn size(&self) -> size_t {
+        self.size
+    }
+}
+
+/// An SVM buffer that is freed when it is dropped.
+/// The SvmBuffer retains its OpenCL context, so the context remains valid
+/// until the SVM buffer has been freed.
+#[derive(Debug)]
+pub struct SvmBuffer {
+    context: cl_context,
+    ptr: *mut c_void,
+    size: size_t,
+}
+
+impl SvmBuffer {
+    /// Allocate an SVM buffer, see `raw::svm_alloc`.  
+    /// CL_VERSION_2_0
+    ///
+    /// * `context` - a valid OpenCL context.
+    /// * `flags` - a bit-field used to specify allocation and usage information.
+    /// * `size` - the size in bytes of the SVM buffer to be allocated.
+    /// * `alignment` - the minimum alignment in bytes of the SVM buffer.
+    ///
+    /// returns a Result containing the new SvmBuffer
+    /// or the error code from the OpenCL C API function.
+    pub fn new(
+        context: cl_context,
+        flags: cl_svm_mem_flags,
+        size: size_t,
+        alignment: cl_uint,
+    ) -> Result<SvmBuffer, cl_int> {
+        let ptr = raw::svm_alloc(context, flags, size, alignment)?;
+        if let Err(e) = retain_context(context) {
+            raw::svm_free(context, ptr);
+            return Err(e);
+        }
+        Ok(SvmBuffer { context, ptr, size })
+    }
+
+    /// The SVM pointer, e.g. for a `kernel::SvmPtr` argument.
+    pub fn as_mut_ptr(&self) -> *mut c_void {
+        self.ptr
+    }
+
+    /// The size of the SVM buffer in bytes.
+    pub fn size(&self) -> size_t {
+        self.size
+    }
+
+    /// The OpenCL context of the SVM buffer.
+    pub fn context(&self) -> cl_context {
+        self.context
+    }
+
+    /// The SVM region of the whole buffer, e.g. to create an SvmArena.
+    pub fn region(&self) -> SvmRegion {
+        SvmRegion::new(self.ptr, self.size)
+    }
+}
+
+impl Drop for SvmBuffer {
+    fn drop(&mut self) {
+        raw::svm_free(self.context, self.ptr);
+        release_context(self.context).unwrap_or(());
+    }
+}
+
+/// The errors returned by SvmArena.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArenaError {
+    /// There is not enough space left in the arena for the requested slice.
+    Exhausted {
+        requested: size_t,
+        available: size_t,
+    },
+    /// The alignment is not a power of two.
+    InvalidAlignment(size_t),
+    /// reset was called while slices are still outstanding.
+    SlicesOutstanding(usize),
+}
+
+impl fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArenaError::Exhausted {
+                requested,
+                available,
+            } => write!(
+                f,
+                "SVM arena exhausted: requested {} bytes, {} available",
+                requested, available
+            ),
+            ArenaError::InvalidAlignment(a) => {
+                write!(f, "SVM arena alignment {} is not a power of two", a)
+            }
+            ArenaError::SlicesOutstanding(n) => {
+                write!(f, "SVM arena reset with {} slices outstanding", n)
+            }
+        }
+    }
+}
+
+/// A bump allocator that sub-allocates slices from a single SVM region.
+/// Slices borrow the arena, so it cannot be dropped while they are in use and
+/// `reset` fails until every slice has been dropped.
+#[derive(Debug)]
+pub struct SvmArena {
+    region: SvmRegion,
+    offset: Cell<size_t>,
+    outstanding: Cell<usize>,
+}
+
+impl SvmArena {
+    /// Create an arena over an SVM region.
+    pub fn new(region: SvmRegion) -> SvmArena {
+        SvmArena {
+            region,
+            offset: Cell::new(0),
+            outstanding: Cell::new(0),
+        }
+    }
+
+    /// The SVM region that the arena allocates from.
+    pub fn region(&self) -> SvmRegion {
+        self.region
+    }
+
+    /// The number of bytes that have been allocated, including alignment padding.
+    pub fn used(&self) -> size_t {
+        self.offset.get()
+    }
+
+    /// The number of slices that have not been dropped.
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.get()
+    }
+
+    /// Allocate a slice from the arena.
+    ///
+    /// * `size` - the size of the slice in bytes.
+    /// * `align` - the alignment of the slice address in bytes, a power of two.
+    ///
+    /// returns a Result containing the new SvmSlice
+    /// or an ArenaError if the alignment is invalid or the arena is exhausted.
+    pub fn alloc(&self, size: size_t, align: size_t) -> Result<SvmSlice<'_>, ArenaError> {
+        if !align.is_power_of_two() {
+            return Err(ArenaError::InvalidAlignment(align));
+        }
+
+        // Align the address, not the offset, since the region may be less aligned.
+        let base = self.region.ptr as usize;
+        let current = self.offset.get();
+        let available = self.region.size - current;
+        let padding = (align - (base + current) % align) % align;
+        if padding > available || size > available - padding {
+            return Err(ArenaError::Exhausted {
+                requested: size,
+                available: available.saturating_sub(padding),
+            });
+        }
+
+        let offset = current + padding;
+        self.offset.set(offset + size);
+        self.outstanding.set(self.outstanding.get() + 1);
+        Ok(SvmSlice {
+            arena: self,
+            offset,
+            len: size,
+        })
+    }
+
+    /// Make the whole region available for allocation again.
+    ///
+    /// returns an empty Result
+    /// or ArenaError::SlicesOutstanding if any slices have not been dropped.
+    pub fn reset(&self) -> Result<(), ArenaError> {
+        let outstanding = self.outstanding.get();
+        if 0 < outstanding {
+            Err(ArenaError::SlicesOutstanding(outstanding))
+        } else {
+            self.offset.set(0);
+            Ok(())
+        }
+    }
+}
+
+/// A slice of an SvmArena.
+/// Use `as_mut_ptr` with `kernel::SvmPtr` to pass the slice to a
+/// kernel, and `offset` and `len` to access it through the mapped region.
+#[derive(Debug)]
+pub struct SvmSlice<'a> {
+    arena: &'a SvmArena,
+    offset: size_t,
+    len: size_t,
+}
+
+impl<'a> SvmSlice<'a> {
+    /// The SVM pointer to the start of the slice.
+    pub fn as_mut_ptr(&self) -> *mut c_void {
+        (self.arena.region.ptr as usize + self.offset) as *mut c_void
+    }
+
+    /// The offset of the slice in bytes from the start of the arena region.
+    pub fn offset(&self) -> size_t {
+        self.offset
+    }
+
+    /// The size of the slice in bytes.
+    pub fn len(&self) -> size_t {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        0 == self.len
+    }
+
+    /// The SVM region of the slice.
+    pub fn region(&self) -> SvmRegion {
+        SvmRegion::new(self.as_mut_ptr(), self.len)
+    }
+}
+
+impl<'a> Drop for SvmSlice<'a> {
+    fn drop(&mut self) {
+        self.arena.outstanding.set(self.arena.outstanding.get() - 1);
+    }
+}
+
+#[cfg(feature = "CL_VERSION_2_0")]
+#[deprecated(note = "use the typed variant: enqueue_svm_fill_region")]
+#[inline]
+pub unsafe fn enqueue_svm_fill<T: Copy>(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    value: &T,
+    count: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    raw::enqueue_svm_fill(command_queue, svm_ptr, value, count, event_wait_list)
+}
+
+/// Enqueue a command to fill an SVM region with copies of a value.  
+/// Calls clEnqueueSVMMemFill with the bytes of `value` as the pattern.  
+/// CL_VERSION_2_0
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `region` - the SVM region to fill, e.g. of an SvmBuffer or SvmSlice,
+///   aligned to the size of `T`.
+/// * `value` - the value to fill with, the size of `T` must be 1, 2, 4, 8,
+///   16, 32, 64 or 128 bytes.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if the size of `T` is invalid, or does not divide the
+/// address and size of the region
+/// or the error code from the OpenCL C API function.
+///
+/// # Safety
+///
+/// `T` must be plain old data without padding bytes, since the bytes of
+/// `value` are read as the fill pattern, e.g. a primitive or an array of them.
+#[cfg(feature = "CL_VERSION_2_0")]
+pub unsafe fn enqueue_svm_fill_region<T: Copy>(
+    command_queue: cl_command_queue,
+    region: &SvmRegion,
+    value: &T,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let size = mem::size_of::<T>();
+    if 0 == size || 0 != region.size % size {
+        return Err(CL_INVALID_VALUE);
+    }
+    raw::enqueue_svm_fill(
+        command_queue,
+        region.ptr,
+        value,
+        region.size / size,
+        event_wait_list,
+    )
+}
+
+/// The largest fill pattern size, up to 128 bytes, that divides both the
+/// address and the size of a region.
+#[cfg(feature = "CL_VERSION_2_0")]
+fn zero_pattern_size(region: &SvmRegion) -> size_t {
+    1 << (region.ptr as usize | region.size | 128).trailing_zeros()
+}
+
+/// Enqueue a command to fill an SVM region with zeros.  
+/// Calls clEnqueueSVMMemFill with the largest zero pattern that the address
+/// and size of the region allow.  
+/// CL_VERSION_2_0
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `region` - the SVM region to fill, e.g. of an SvmBuffer or SvmSlice.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or the error code from the OpenCL C API function.
+#[cfg(feature = "CL_VERSION_2_0")]
+pub fn enqueue_svm_zero(
+    command_queue: cl_command_queue,
+    region: &SvmRegion,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    const ZEROS: [u8; 128] = [0; 128];
+    let pattern = &ZEROS[..zero_pattern_size(region)];
+    enqueue_svm_mem_fill_slice(
+        command_queue,
+        region.ptr,
+        pattern,
+        region.size,
+        event_wait_list,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use crate::command_queue::{
+        create_command_queue, enqueue_svm_map_slice, enqueue_svm_unmap_slice, finish,
+        release_command_queue,
+    };
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use crate::context::create_context;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use crate::event::release_event;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use crate::memory::{CL_MAP_READ, CL_MEM_READ_WRITE};
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use crate::platform::get_platform_ids;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use crate::types::{cl_float, CL_BLOCKING};
+    #[cfg(feature = "CL_VERSION_2_0")]
+    use std::{ptr, slice};
+
+    #[test]
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn test_zero_pattern_size() {
+        let pattern_size = |ptr: usize, size| zero_pattern_size(&SvmRegion::new(ptr as _, size));
+        assert_eq!(128, pattern_size(0x1000, 1024));
+        assert_eq!(4, pattern_size(0x1004, 1024));
+        assert_eq!(2, pattern_size(0x1000, 6));
+        assert_eq!(1, pattern_size(0x1001, 1024));
+        assert_eq!(128, pattern_size(0x1000, 0));
+    }
+
+    #[test]
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn test_enqueue_svm_fill() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = create_command_queue(context, device_id, 0).unwrap();
+
+        const COUNT: usize = 1000;
+        let size = COUNT * mem::size_of::<cl_float>();
+        let buffer = SvmBuffer::new(context, CL_MEM_READ_WRITE, size, 0).unwrap();
+        let arena = SvmArena::new(buffer.region());
+        let head = arena.alloc(size / 2, 4).unwrap();
+        let tail = arena.alloc(size / 2, 4).unwrap();
+
+        let fill_event =
+            unsafe { enqueue_svm_fill_region(queue, &buffer.region(), &1.5f32, &[]) }.unwrap();
+        let zero_event = enqueue_svm_zero(queue, &tail.region(), &[fill_event]).unwrap();
+        let map_event = enqueue_svm_map_slice(
+            queue,
+            CL_BLOCKING,
+            CL_MAP_READ,
+            buffer.as_mut_ptr(),
+            size,
+            &[zero_event],
+        )
+        .unwrap();
+
+        let values = unsafe { slice::from_raw_parts(buffer.as_mut_ptr() as *const f32, COUNT) };
+        let half = head.len() / mem::size_of::<cl_float>();
+        assert_eq!(1.5, values[0]);
+        assert_eq!(1.5, values[half - 1]);
+        assert_eq!(0.0, values[half]);
+        assert_eq!(0.0, values[COUNT - 1]);
+
+        // The pattern size must divide the address
+        let unaligned = (buffer.as_mut_ptr() as usize + 2) as *mut c_void;
+        let region = SvmRegion::new(unaligned, mem::size_of::<cl_float>());
+        let result = unsafe { enqueue_svm_fill_region(queue, &region, &1.5f32, &[]) };
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+
+        let unmap_event = enqueue_svm_unmap_slice(queue, buffer.as_mut_ptr(), &[]).unwrap();
+        finish(queue).unwrap();
+        for event in [fill_event, zero_event, map_event, unmap_event].iter() {
+            release_event(*event).unwrap();
+        }
+        drop(head);
+        drop(tail);
+        drop(buffer);
+        release_command_queue(queue).unwrap();
+        release_context(context).unwrap();
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_raw_and_deprecated_paths() {
+        // The deprecated paths must still resolve to the raw function signatures.
+        let _alloc: fn(
+            cl_context,
+            cl_svm_mem_flags,
+            size_t,
+            cl_uint,
+        ) -> Result<*mut c_void, cl_int> = svm_alloc;
+        let _free: fn(cl_context, *mut c_void) = svm_free;
+        let _memory_alloc: fn(
+            cl_context,
+            cl_svm_mem_flags,
+            size_t,
+            cl_uint,
+        ) -> Result<*mut c_void, cl_int> = crate::memory::svm_alloc;
+        let _memory_free: fn(cl_context, *mut c_void) = crate::memory::svm_free;
+        let _raw_free: fn(cl_context, *mut c_void) = raw::svm_free;
+
+        #[cfg(feature = "CL_VERSION_2_0")]
+        {
+            let _fill: unsafe fn(
+                cl_command_queue,
+                *mut c_void,
+                &u32,
+                size_t,
+                &[cl_event],
+            ) -> Result<cl_event, cl_int> = enqueue_svm_fill::<u32>;
+            let _raw_fill: unsafe fn(
+                cl_command_queue,
+                *mut c_void,
+                &u32,
+                size_t,
+                &[cl_event],
+            ) -> Result<cl_event, cl_int> = raw::enqueue_svm_fill::<u32>;
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn test_enqueue_svm_fill_region_size() {
+        // The region must hold a whole number of values
+        let region = SvmRegion::new(0x1000 as *mut c_void, 6);
+        let result = unsafe { enqueue_svm_fill_region(ptr::null_mut(), &region, &1u32, &[]) };
+        assert_eq!(Err(CL_INVALID_VALUE), result);
+    }
+
+    #[test]
+    fn test_svm_arena_alignment() {
+        // A fake SVM address, the arena never dereferences it
+        let region = SvmRegion::new(0x1004 as *mut c_void, 256);
+        let arena = SvmArena::new(region);
+
+        let a = arena.alloc(10, 1).unwrap();
+        assert_eq!(0, a.offset());
+        assert_eq!(10, a.len());
+        assert_eq!(0x1004, a.as_mut_ptr() as usize);
+
+        // 0x100e aligned up to 0x1010
+        let b = arena.alloc(16, 16).unwrap();
+        assert_eq!(12, b.offset());
+        assert_eq!(0x1010, b.as_mut_ptr() as usize);
+        assert_eq!(28, arena.used());
+
+        assert_eq!(
+            Err(ArenaError::InvalidAlignment(3)),
+            arena.alloc(1, 3).map(|_| ())
+        );
+        assert_eq!(
+            Err(ArenaError::InvalidAlignment(0)),
+            arena.alloc(1, 0).map(|_| ())
+        );
+        assert_eq!(2, arena.outstanding());
+    }
+
+    #[test]
+    fn test_svm_arena_exhaustion_and_reset() {
+        let region = SvmRegion::new(0x1000 as *mut c_void, 64);
+        let arena = SvmArena::new(region);
+
+        let a = arena.alloc(60, 4).unwrap();
+        assert_eq!(
+            Err(ArenaError::Exhausted {
+                requested: 8,
+                available: 4
+            }),
+            arena.alloc(8, 4).map(|_| ())
+        );
+
+        // Alignment padding larger than the remaining space
+        assert_eq!(
+            Err(ArenaError::Exhausted {
+                requested: 1,
+                available: 0
+            }),
+            arena.alloc(1, 64).map(|_| ())
+        );
+
+        let b = arena.alloc(4, 4).unwrap();
+        assert_eq!(64, arena.used());
+
+        assert_eq!(Err(ArenaError::SlicesOutstanding(2)), arena.reset());
+        drop(a);
+        assert_eq!(Err(ArenaError::SlicesOutstanding(1)), arena.reset());
+        drop(b);
+        assert_eq!(Ok(()), arena.reset());
+        assert_eq!(0, arena.used());
+
+        let c = arena.alloc(64, 1).unwrap();
+        assert_eq!(64, c.len());
+    }
+
+    #[test]
+    fn test_svm_arena_panic_in_scope() {
+        let region = SvmRegion::new(0x1000 as *mut c_void, 64);
+        let arena = SvmArena::new(region);
+
+        // Unwinding drops the slices, so the arena can be reset afterwards
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _a = arena.alloc(16, 4).unwrap();
+            let _b = arena.alloc(16, 4).unwrap();
+            assert_eq!(2, arena.outstanding());
+            panic!("panic in scope");
+        }));
+        assert!(result.is_err());
+        assert_eq!(0, arena.outstanding());
+        assert_eq!(Ok(()), arena.reset());
+    }
+}
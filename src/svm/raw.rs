@@ -0,0 +1,67 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Raw pointer OpenCL Shared Virtual Memory (SVM) helpers.
+//! CL_VERSION_2_0
+//!
+//! These functions pass SVM pointers straight through to the OpenCL C API,
+//! so the caller must ensure that they are valid.  
+//! Prefer SvmBuffer and the SvmRegion functions in the parent module.
+
+#![allow(non_camel_case_types)]
+
+pub use crate::memory::raw::{svm_alloc, svm_free};
+
+#[cfg(feature = "CL_VERSION_2_0")]
+use crate::command_queue::enqueue_svm_mem_fill_slice;
+#[cfg(feature = "CL_VERSION_2_0")]
+use crate::error_codes::CL_INVALID_VALUE;
+#[cfg(feature = "CL_VERSION_2_0")]
+use crate::types::{cl_command_queue, cl_event, cl_int};
+#[cfg(feature = "CL_VERSION_2_0")]
+use libc::{c_void, size_t};
+#[cfg(feature = "CL_VERSION_2_0")]
+use std::{mem, slice};
+
+/// Enqueue a command to fill SVM with copies of a value.  
+/// Calls clEnqueueSVMMemFill with the bytes of `value` as the pattern.  
+/// CL_VERSION_2_0
+///
+/// * `command_queue` - a valid OpenCL command-queue.
+/// * `svm_ptr` - a pointer to the SVM to fill, aligned to the size of `T`.
+/// * `value` - the value to fill with, the size of `T` must be 1, 2, 4, 8,
+///   16, 32, 64 or 128 bytes.
+/// * `count` - the number of values to fill.
+/// * `event_wait_list` - events that must complete before this command is executed.
+///
+/// returns a Result containing the new OpenCL event
+/// or CL_INVALID_VALUE if the size of `T`, the pointer or the count are invalid
+/// or the error code from the OpenCL C API function.
+///
+/// # Safety
+///
+/// `T` must be plain old data without padding bytes, since the bytes of
+/// `value` are read as the fill pattern, e.g. a primitive or an array of them.
+#[cfg(feature = "CL_VERSION_2_0")]
+pub unsafe fn enqueue_svm_fill<T: Copy>(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    value: &T,
+    count: size_t,
+    event_wait_list: &[cl_event],
+) -> Result<cl_event, cl_int> {
+    let pattern = slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>());
+    let size = count.checked_mul(pattern.len()).ok_or(CL_INVALID_VALUE)?;
+    enqueue_svm_mem_fill_slice(command_queue, svm_ptr, pattern, size, event_wait_list)
+}
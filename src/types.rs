@@ -20,12 +20,13 @@
 // WARNING!  Unlike cl_ types in cl_platform.h, cl_bool is not guaranteed to be the same size as the bool in kernels.
 pub use cl_sys::{
     cl_addressing_mode, cl_bitfield, cl_bool, cl_buffer_create_type, cl_build_status,
-    cl_channel_order, cl_channel_type, cl_command_queue, cl_command_queue_info,
+    cl_channel_order, cl_channel_type, cl_char, cl_command_queue, cl_command_queue_info,
     cl_command_queue_properties, cl_command_type, cl_context, cl_context_info,
     cl_context_properties, cl_device_affinity_domain, cl_device_exec_capabilities,
     cl_device_fp_config, cl_device_id, cl_device_info, cl_device_local_mem_type,
     cl_device_mem_cache_type, cl_device_partition_property, cl_device_svm_capabilities,
-    cl_device_type, cl_double, cl_event, cl_event_info, cl_filter_mode, cl_float, cl_half,
+    cl_device_type, cl_double, cl_event, cl_event_info, cl_filter_mode, cl_float,
+    cl_gl_context_info, cl_gl_object_type, cl_gl_platform_info, cl_gl_texture_info, cl_half,
     cl_image_info, cl_int, cl_kernel, cl_kernel_arg_access_qualifier,
     cl_kernel_arg_address_qualifier, cl_kernel_arg_info, cl_kernel_arg_type_qualifier,
     cl_kernel_exec_info, cl_kernel_info, cl_kernel_sub_group_info, cl_kernel_work_group_info,
@@ -79,6 +80,21 @@ pub struct cl_buffer_region {
     pub size: size_t,
 }
 
+// The structures above must have the layout of the cl_sys structures, since
+// they are passed to OpenCL functions declared in cl_sys.
+macro_rules! assert_cl_sys_layout {
+    ($($name:ident),*) => {
+        $(
+            const _: () = assert!(
+                std::mem::size_of::<$name>() == std::mem::size_of::<cl_sys::$name>()
+                    && std::mem::align_of::<$name>() == std::mem::align_of::<cl_sys::$name>()
+            );
+        )*
+    };
+}
+
+assert_cl_sys_layout!(cl_image_format, cl_image_desc, cl_buffer_region);
+
 // CL_VERSION_3_0
 pub const CL_NAME_VERSION_MAX_NAME_SIZE: usize = 64;
 #[derive(Debug)]
@@ -87,3 +103,131 @@ pub struct cl_name_version {
     pub version: cl_version,
     pub name: [cl_uchar; CL_NAME_VERSION_MAX_NAME_SIZE],
 }
+
+// The structures that are not defined in cl_sys must have the size and
+// alignment of the structures in the OpenCL C headers.
+macro_rules! assert_c_layout {
+    ($($name:ty: size($size:expr), align($align:expr);)*) => {
+        $(
+            const _: () = assert!(
+                $size == std::mem::size_of::<$name>() && $align == std::mem::align_of::<$name>()
+            );
+        )*
+    };
+}
+
+assert_c_layout! {
+    cl_name_version: size(4 + CL_NAME_VERSION_MAX_NAME_SIZE), align(4);
+    crate::ffi::cl_ext::cl_name_version_khr: size(4 + CL_NAME_VERSION_MAX_NAME_SIZE), align(4);
+    crate::ffi::cl_ext::cl_device_pci_bus_info_khr: size(16), align(4);
+    cl_image_format: size(8), align(4);
+    cl_buffer_region: size(2 * std::mem::size_of::<size_t>()), align(std::mem::align_of::<size_t>());
+}
+
+// OpenCL vector types, see cl_platform.h.
+// A vector of n components is aligned to its size, and a 3-component vector
+// has the size and alignment of a 4-component vector, so cl_<type>3 is the
+// same type as cl_<type>4, as in cl_platform.h.
+macro_rules! cl_vector {
+    ($($name:ident: [$t:ty; $n:literal], align($align:literal);)*) => {
+        $(
+            #[derive(Clone, Copy, Debug, Default, PartialEq)]
+            #[repr(C, align($align))]
+            pub struct $name {
+                pub s: [$t; $n],
+            }
+
+            impl From<[$t; $n]> for $name {
+                #[inline]
+                fn from(s: [$t; $n]) -> Self {
+                    $name { s }
+                }
+            }
+
+            impl From<$name> for [$t; $n] {
+                #[inline]
+                fn from(v: $name) -> Self {
+                    v.s
+                }
+            }
+
+            const _: () = assert!(
+                $align == std::mem::size_of::<$name>() && $align == std::mem::align_of::<$name>()
+            );
+        )*
+    };
+}
+
+// The 3-component vectors, with the fourth component zero.
+macro_rules! cl_vector3 {
+    ($($name:ident = $name4:ident: $t:ty;)*) => {
+        $(
+            pub type $name = $name4;
+
+            impl From<[$t; 3]> for $name4 {
+                #[inline]
+                fn from(s: [$t; 3]) -> Self {
+                    $name4 {
+                        s: [s[0], s[1], s[2], <$t>::default()],
+                    }
+                }
+            }
+        )*
+    };
+}
+
+cl_vector! {
+    cl_char2: [cl_char; 2], align(2);
+    cl_char4: [cl_char; 4], align(4);
+    cl_char8: [cl_char; 8], align(8);
+    cl_char16: [cl_char; 16], align(16);
+    cl_uchar2: [cl_uchar; 2], align(2);
+    cl_uchar4: [cl_uchar; 4], align(4);
+    cl_uchar8: [cl_uchar; 8], align(8);
+    cl_uchar16: [cl_uchar; 16], align(16);
+    cl_short2: [cl_short; 2], align(4);
+    cl_short4: [cl_short; 4], align(8);
+    cl_short8: [cl_short; 8], align(16);
+    cl_short16: [cl_short; 16], align(32);
+    cl_ushort2: [cl_ushort; 2], align(4);
+    cl_ushort4: [cl_ushort; 4], align(8);
+    cl_ushort8: [cl_ushort; 8], align(16);
+    cl_ushort16: [cl_ushort; 16], align(32);
+    cl_int2: [cl_int; 2], align(8);
+    cl_int4: [cl_int; 4], align(16);
+    cl_int8: [cl_int; 8], align(32);
+    cl_int16: [cl_int; 16], align(64);
+    cl_uint2: [cl_uint; 2], align(8);
+    cl_uint4: [cl_uint; 4], align(16);
+    cl_uint8: [cl_uint; 8], align(32);
+    cl_uint16: [cl_uint; 16], align(64);
+    cl_long2: [cl_long; 2], align(16);
+    cl_long4: [cl_long; 4], align(32);
+    cl_long8: [cl_long; 8], align(64);
+    cl_long16: [cl_long; 16], align(128);
+    cl_ulong2: [cl_ulong; 2], align(16);
+    cl_ulong4: [cl_ulong; 4], align(32);
+    cl_ulong8: [cl_ulong; 8], align(64);
+    cl_ulong16: [cl_ulong; 16], align(128);
+    cl_float2: [cl_float; 2], align(8);
+    cl_float4: [cl_float; 4], align(16);
+    cl_float8: [cl_float; 8], align(32);
+    cl_float16: [cl_float; 16], align(64);
+    cl_double2: [cl_double; 2], align(16);
+    cl_double4: [cl_double; 4], align(32);
+    cl_double8: [cl_double; 8], align(64);
+    cl_double16: [cl_double; 16], align(128);
+}
+
+cl_vector3! {
+    cl_char3 = cl_char4: cl_char;
+    cl_uchar3 = cl_uchar4: cl_uchar;
+    cl_short3 = cl_short4: cl_short;
+    cl_ushort3 = cl_ushort4: cl_ushort;
+    cl_int3 = cl_int4: cl_int;
+    cl_uint3 = cl_uint4: cl_uint;
+    cl_long3 = cl_long4: cl_long;
+    cl_ulong3 = cl_ulong4: cl_ulong;
+    cl_float3 = cl_float4: cl_float;
+    cl_double3 = cl_double4: cl_double;
+}
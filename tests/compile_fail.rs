@@ -0,0 +1,26 @@
+// Copyright (c) 2020-2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Compile-fail tests of the macros and of the OpenCL version features, the
+// expected errors are in tests/ui.
+
+#[test]
+fn test_compile_fail() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+
+    // The OpenCL 2.0 kernel exec info API is not available in OpenCL 1.2 builds
+    #[cfg(not(feature = "CL_VERSION_2_0"))]
+    cases.compile_fail("tests/ui/cl_version_1_2/*.rs");
+}
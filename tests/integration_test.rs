@@ -15,21 +15,46 @@
 extern crate cl3;
 
 use cl3::command_queue::{
-    create_command_queue, enqueue_nd_range_kernel, enqueue_read_buffer, enqueue_write_buffer,
-    finish, release_command_queue, CL_QUEUE_PROFILING_ENABLE,
+    create_command_queue, enqueue_nd_range_kernel_slice, enqueue_read_buffer_slice,
+    enqueue_write_buffer_slice, finish, release_command_queue, CL_QUEUE_PROFILING_ENABLE,
 };
+#[cfg(feature = "CL_VERSION_2_0")]
+use cl3::command_queue::{enqueue_svm_map_slice, enqueue_svm_unmap_slice};
 use cl3::context::{create_context, release_context};
-use cl3::device::{get_device_ids, get_device_info, DeviceInfo, CL_DEVICE_TYPE_GPU};
-use cl3::event::{get_event_profiling_info, release_event, wait_for_events, ProfilingInfo};
-use cl3::kernel::{create_kernel, release_kernel, set_kernel_arg};
-use cl3::memory::{create_buffer, release_mem_object, CL_MEM_READ_ONLY, CL_MEM_WRITE_ONLY};
+use cl3::device::{
+    built_in_kernel_names, get_all_devices, get_device_ids, get_device_info, select_device,
+    DeviceInfo, CL_DEVICE_TYPE_ALL, CL_DEVICE_TYPE_GPU,
+};
+use cl3::event::{
+    get_event_profiling_info, profiling_command_duration, profiling_queued_to_end, release_event,
+    set_event_callback_closure, wait_for_events, ProfilingInfo, CL_COMPLETE,
+};
+use cl3::kernel::{create_kernel, release_kernel, KernelArgs};
+#[cfg(feature = "CL_VERSION_2_0")]
+use cl3::kernel::{KernelArg, SvmPtr};
+use cl3::kernel_args;
+#[cfg(feature = "CL_VERSION_2_0")]
+use cl3::memory::CL_MAP_READ;
+use cl3::memory::{
+    align_up_for_sub_buffer, create_buffer_of_size, create_sub_buffer_region, release_mem_object,
+    sub_buffer_alignment_bytes, SubBufferAlignment, SubBufferError, CL_MEM_READ_ONLY,
+    CL_MEM_READ_WRITE, CL_MEM_WRITE_ONLY,
+};
 use cl3::platform::{get_platform_ids, get_platform_info, PlatformInfo};
-use cl3::program::{build_program, create_program_with_source, release_program};
-use cl3::types::{cl_event, cl_float, cl_mem, CL_NON_BLOCKING, CL_BLOCKING};
-use libc::{c_void, size_t};
+use cl3::program::{
+    build_program, create_program_with_builtin_kernels, create_program_with_source, release_program,
+};
+#[cfg(feature = "CL_VERSION_2_0")]
+use cl3::svm::{SvmArena, SvmBuffer};
+#[cfg(feature = "CL_VERSION_2_0")]
+use cl3::types::cl_int;
+use cl3::types::{cl_event, cl_float, cl_mem, CL_BLOCKING, CL_NON_BLOCKING};
+use libc::size_t;
 use std::ffi::CString;
 use std::mem;
 use std::ptr;
+use std::sync::mpsc;
+use std::time::Duration;
 
 const PROGRAM_SOURCE: &str = r#"
 kernel void saxpy_float (global float* z,
@@ -43,6 +68,15 @@ z[i] = a*x[i] + y[i];
 
 const KERNEL_NAME: &str = "saxpy_float";
 
+kernel_args! {
+    struct SaxpyArgs {
+        z: cl_mem,
+        x: cl_mem,
+        y: cl_mem,
+        a: cl_float,
+    }
+}
+
 #[test]
 #[ignore]
 fn test_opencl_1_2_example() {
@@ -98,52 +132,32 @@ fn test_opencl_1_2_example() {
     }
 
     // Create OpenCL device buffers for input and output data
-    let x = create_buffer(
+    let x = create_buffer_of_size(
         context,
         CL_MEM_WRITE_ONLY,
         ARRAY_SIZE * mem::size_of::<cl_float>(),
-        ptr::null_mut(),
     )
     .unwrap();
-    let y = create_buffer(
+    let y = create_buffer_of_size(
         context,
         CL_MEM_WRITE_ONLY,
         ARRAY_SIZE * mem::size_of::<cl_float>(),
-        ptr::null_mut(),
     )
     .unwrap();
-    let z = create_buffer(
+    let z = create_buffer_of_size(
         context,
         CL_MEM_READ_ONLY,
         ARRAY_SIZE * mem::size_of::<cl_float>(),
-        ptr::null_mut(),
     )
     .unwrap();
 
     // Blocking write to OpenCL device buffer
-    let x_write_event = enqueue_write_buffer(
-        queue,
-        x,
-        CL_BLOCKING,
-        0,
-        ones.len() * mem::size_of::<cl_float>(),
-        ones.as_ptr() as cl_mem,
-        0,
-        ptr::null(),
-    )
-    .unwrap();
-    // Non-blocking write to OpenCL device buffer
-    let y_write_event = enqueue_write_buffer(
-        queue,
-        y,
-        CL_NON_BLOCKING,
-        0,
-        sums.len() * mem::size_of::<cl_float>(),
-        sums.as_ptr() as cl_mem,
-        0,
-        ptr::null(),
-    )
-    .unwrap();
+    let x_write_event =
+        unsafe { enqueue_write_buffer_slice(queue, x, CL_BLOCKING, 0, &ones, &[]) }.unwrap();
+    // Non-blocking write to OpenCL device buffer, sums is not modified before
+    // the write completes
+    let y_write_event =
+        unsafe { enqueue_write_buffer_slice(queue, y, CL_NON_BLOCKING, 0, &sums, &[]) }.unwrap();
 
     // wait for y_write_event
     let mut events: Vec<cl_event> = Vec::default();
@@ -155,46 +169,20 @@ fn test_opencl_1_2_example() {
 
     // Set up the arguments to call the OpenCL kernel function
     // i.e. the x, y & z buffers and the constant value, a
-    set_kernel_arg(
-        kernel,
-        0,
-        mem::size_of::<cl_mem>(),
-        &z as *const _ as *const c_void,
-    )
-    .unwrap();
-    set_kernel_arg(
-        kernel,
-        1,
-        mem::size_of::<cl_mem>(),
-        &x as *const _ as *const c_void,
-    )
-    .unwrap();
-    set_kernel_arg(
-        kernel,
-        2,
-        mem::size_of::<cl_mem>(),
-        &y as *const _ as *const c_void,
-    )
-    .unwrap();
-    set_kernel_arg(
-        kernel,
-        3,
-        mem::size_of::<cl_float>(),
-        &a as *const _ as *const c_void,
-    )
-    .unwrap();
+    let args = SaxpyArgs { z, x, y, a };
+    args.bind(kernel).unwrap();
 
     // Enqueue the OpenCL kernel for execution
     let global_work_sizes: [size_t; 1] = [ARRAY_SIZE];
-    let kernel_event = enqueue_nd_range_kernel(
-        queue,
-        kernel,
-        1,
-        ptr::null(),
-        global_work_sizes.as_ptr(),
-        ptr::null(),
-        0,
-        ptr::null(),
+    let kernel_event =
+        enqueue_nd_range_kernel_slice(queue, kernel, None, &global_work_sizes, None, &[]).unwrap();
+
+    // Register a closure to be called when the kernel has completed
+    let (sender, receiver) = mpsc::channel();
+    set_event_callback_closure(
+        kernel_event,
+        CL_COMPLETE,
+        Box::new(move |_event, status| sender.send(status).unwrap()),
     )
     .unwrap();
 
@@ -206,18 +194,11 @@ fn test_opencl_1_2_example() {
     // Create a results array to hold the results from the OpenCL device z buffer
     // and enqueue a read command to read the device buffer into the array
     // after the kernel event completes.
-    let results: [cl_float; ARRAY_SIZE] = [0.0; ARRAY_SIZE];
-    let read_event = enqueue_read_buffer(
-        queue,
-        z,
-        CL_NON_BLOCKING,
-        0,
-        results.len() * mem::size_of::<cl_float>(),
-        results.as_ptr() as cl_mem,
-        1,
-        events.as_ptr(),
-    )
-    .unwrap();
+    // results is not accessed until the read completes.
+    let mut results: [cl_float; ARRAY_SIZE] = [0.0; ARRAY_SIZE];
+    let read_event =
+        unsafe { enqueue_read_buffer_slice(queue, z, CL_NON_BLOCKING, 0, &mut results, &events) }
+            .unwrap();
     events.clear();
 
     // Block until all commands on the queue (i.e. the read_event) have completed
@@ -233,6 +214,14 @@ fn test_opencl_1_2_example() {
         get_event_profiling_info(kernel_event, ProfilingInfo::CL_PROFILING_COMMAND_END).unwrap();
     let duration = end_time.to_ulong() - start_time.to_ulong();
     println!("kernel execution duration (ns): {}", duration);
+    assert_eq!(duration, profiling_command_duration(kernel_event).unwrap());
+
+    let status = receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(CL_COMPLETE, status);
+
+    let lifetime = profiling_queued_to_end(kernel_event).unwrap();
+    println!("kernel queued to end duration (ns): {}", lifetime);
+    assert!(duration <= lifetime);
 
     /////////////////////////////////////////////////////////////////////
     // Release OpenCL objects
@@ -251,3 +240,172 @@ fn test_opencl_1_2_example() {
     release_command_queue(queue).unwrap();
     release_context(context).unwrap();
 }
+
+#[cfg(feature = "CL_VERSION_2_0")]
+const FILL_SOURCE: &str = r#"
+kernel void fill_int (global int* buffer, int value)
+{
+size_t i = get_global_id(0);
+buffer[i] = value;
+}"#;
+
+#[cfg(feature = "CL_VERSION_2_0")]
+#[test]
+#[ignore]
+fn test_svm_arena() {
+    let platform_ids = get_platform_ids().unwrap();
+    assert!(0 < platform_ids.len());
+
+    // Choose the first GPU device of the first platform
+    let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+    assert!(0 < device_ids.len());
+    let device_id = device_ids[0];
+
+    let svm_capabilities = get_device_info(device_id, DeviceInfo::CL_DEVICE_SVM_CAPABILITIES);
+    if svm_capabilities.map_or(true, |value| 0 == value.to_ulong()) {
+        println!("OpenCL device does not support SVM");
+        return;
+    }
+
+    let device_ids = [device_id];
+    let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+    let sources = [FILL_SOURCE];
+    let program = create_program_with_source(context, &sources).unwrap();
+    let build_options = CString::new("-cl-std=CL2.0").unwrap();
+    build_program(program, &device_ids, &build_options, None, ptr::null_mut()).unwrap();
+    let kernel_name = CString::new("fill_int").unwrap();
+    let kernel = create_kernel(program, &kernel_name).unwrap();
+    let queue = create_command_queue(context, device_id, 0).unwrap();
+
+    // Allocate one coarse grained SVM buffer and divide it between two slices
+    const COUNT: usize = 256;
+    const SIZE: usize = 2 * COUNT * mem::size_of::<cl_int>();
+    let buffer = SvmBuffer::new(context, CL_MEM_READ_WRITE, SIZE, 64).unwrap();
+    let svm = buffer.as_mut_ptr();
+    let arena = SvmArena::new(buffer.region());
+    let first = arena.alloc(COUNT * mem::size_of::<cl_int>(), 64).unwrap();
+    let second = arena.alloc(COUNT * mem::size_of::<cl_int>(), 64).unwrap();
+
+    let global_work_sizes: [size_t; 1] = [COUNT];
+    for (slice, value) in [(&first, 1 as cl_int), (&second, 2 as cl_int)].iter() {
+        SvmPtr(slice.as_mut_ptr()).set_arg(kernel, 0).unwrap();
+        value.set_arg(kernel, 1).unwrap();
+        let event =
+            enqueue_nd_range_kernel_slice(queue, kernel, None, &global_work_sizes, None, &[])
+                .unwrap();
+        release_event(event).unwrap();
+    }
+    finish(queue).unwrap();
+
+    // Map the whole SVM region to read both slices
+    let event = enqueue_svm_map_slice(queue, CL_BLOCKING, CL_MAP_READ, svm, SIZE, &[]).unwrap();
+    release_event(event).unwrap();
+    let results = unsafe { std::slice::from_raw_parts(svm as *const u8, arena.used()) };
+    for (slice, value) in [(&first, 1 as cl_int), (&second, 2 as cl_int)].iter() {
+        let ints = unsafe {
+            std::slice::from_raw_parts(
+                results[slice.offset()..].as_ptr() as *const cl_int,
+                slice.len() / mem::size_of::<cl_int>(),
+            )
+        };
+        assert!(ints.iter().all(|x| x == value));
+    }
+    let event = enqueue_svm_unmap_slice(queue, svm, &[]).unwrap();
+    finish(queue).unwrap();
+    release_event(event).unwrap();
+
+    drop(first);
+    drop(second);
+    arena.reset().unwrap();
+    drop(arena);
+    drop(buffer);
+
+    release_kernel(kernel).unwrap();
+    release_program(program).unwrap();
+    release_command_queue(queue).unwrap();
+    release_context(context).unwrap();
+}
+
+#[test]
+#[ignore]
+fn test_sub_buffer_alignment() {
+    let (_, device_id) = select_device(&[CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]).unwrap();
+    let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+
+    let alignment = sub_buffer_alignment_bytes(device_id).unwrap();
+    println!("sub-buffer alignment: {} bytes", alignment);
+    assert!(alignment.is_power_of_two());
+
+    let size = 4 * alignment;
+    let buffer = create_buffer_of_size(context, CL_MEM_READ_WRITE, size).unwrap();
+
+    // An aligned origin
+    let (sub_buffer, origin) = create_sub_buffer_region(
+        buffer,
+        CL_MEM_READ_WRITE,
+        alignment,
+        alignment,
+        SubBufferAlignment::Validate(device_id),
+    )
+    .unwrap();
+    assert_eq!(alignment, origin);
+    release_mem_object(sub_buffer).unwrap();
+
+    // A misaligned origin fails before calling clCreateSubBuffer
+    if 1 < alignment {
+        let error = create_sub_buffer_region(
+            buffer,
+            CL_MEM_READ_WRITE,
+            1,
+            alignment,
+            SubBufferAlignment::Validate(device_id),
+        )
+        .unwrap_err();
+        assert_eq!(
+            SubBufferError::Misaligned {
+                origin: 1,
+                alignment
+            },
+            error
+        );
+        println!("{}", error);
+        assert!(error.to_string().contains("CL_DEVICE_MEM_BASE_ADDR_ALIGN"));
+    }
+
+    // A misaligned origin is aligned up
+    assert_eq!(alignment, align_up_for_sub_buffer(1, device_id).unwrap());
+    let (sub_buffer, origin) = create_sub_buffer_region(
+        buffer,
+        CL_MEM_READ_WRITE,
+        1,
+        alignment,
+        SubBufferAlignment::AlignUp(device_id),
+    )
+    .unwrap();
+    assert_eq!(alignment, origin);
+    release_mem_object(sub_buffer).unwrap();
+
+    release_mem_object(buffer).unwrap();
+    release_context(context).unwrap();
+}
+
+#[test]
+#[ignore]
+fn test_built_in_kernel_names() {
+    let devices = get_all_devices(CL_DEVICE_TYPE_ALL).unwrap();
+    for &(_, device_id) in devices.iter() {
+        let names = built_in_kernel_names(device_id).unwrap();
+        println!("built-in kernels: {:?}", names);
+        if names.is_empty() {
+            continue;
+        }
+
+        // The names must be accepted by clCreateProgramWithBuiltInKernels
+        let context = create_context(&[device_id], ptr::null(), None, ptr::null_mut()).unwrap();
+        let kernel_names = CString::new(names.join(";")).unwrap();
+        let program =
+            create_program_with_builtin_kernels(context, &[device_id], &kernel_names).unwrap();
+        release_program(program).unwrap();
+        release_context(context).unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) 2021 Via Technology Ltd. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The environment is shared by the whole test process, so the tests that set
+// CL3_DEVICE are in this file, in a single test.
+
+extern crate cl3;
+
+use cl3::device::{
+    get_device_ids, get_device_info, select_device, DeviceInfo, SelectDeviceError,
+    CL_DEVICE_TYPE_ACCELERATOR, CL_DEVICE_TYPE_ALL, CL_DEVICE_TYPE_CPU, CL_DEVICE_TYPE_CUSTOM,
+    CL_DEVICE_TYPE_GPU,
+};
+use cl3::error_codes::{CL_DEVICE_NOT_FOUND, CL_INVALID_VALUE};
+use cl3::platform::get_platform_ids;
+use cl3::selection::{from_env, Selection, SelectionError, CL3_DEVICE};
+use std::env;
+
+#[test]
+fn test_cl3_device_selection() {
+    let platform = get_platform_ids().unwrap()[0];
+    let device = get_device_ids(platform, CL_DEVICE_TYPE_ALL).unwrap()[0];
+
+    // The first enumerated device is chosen if it is of a preferred type
+    env::set_var(CL3_DEVICE, "0:0");
+    assert_eq!(
+        Ok(Some(Selection::Index {
+            platform: 0,
+            device: 0
+        })),
+        from_env()
+    );
+    assert_eq!(Ok((platform, device)), select_device(&[CL_DEVICE_TYPE_ALL]));
+
+    let device_type = get_device_info(device, DeviceInfo::CL_DEVICE_TYPE)
+        .unwrap()
+        .to_ulong();
+    assert_eq!(
+        Ok((platform, device)),
+        select_device(&[CL_DEVICE_TYPE_CUSTOM, device_type])
+    );
+    let other_types = [
+        CL_DEVICE_TYPE_CPU,
+        CL_DEVICE_TYPE_GPU,
+        CL_DEVICE_TYPE_ACCELERATOR,
+        CL_DEVICE_TYPE_CUSTOM,
+    ];
+    if let Some(&other_type) = other_types.iter().find(|&&t| 0 == t & device_type) {
+        assert_eq!(
+            Err(SelectDeviceError::NotPreferred(device_type)),
+            select_device(&[other_type])
+        );
+    }
+
+    let name = get_device_info(device, DeviceInfo::CL_DEVICE_NAME)
+        .unwrap()
+        .to_string();
+    env::set_var(CL3_DEVICE, format!("name~{}", name.to_uppercase()));
+    let (_, selected) = select_device(&[CL_DEVICE_TYPE_ALL]).unwrap();
+    let selected_name = get_device_info(selected, DeviceInfo::CL_DEVICE_NAME)
+        .unwrap()
+        .to_string();
+    assert_eq!(name, selected_name);
+
+    env::set_var(CL3_DEVICE, "999:0");
+    assert_eq!(
+        Err(SelectDeviceError::Api(CL_DEVICE_NOT_FOUND)),
+        select_device(&[CL_DEVICE_TYPE_ALL])
+    );
+
+    env::set_var(CL3_DEVICE, "first gpu");
+    assert!(from_env().is_err());
+    let result = select_device(&[CL_DEVICE_TYPE_ALL]);
+    assert_eq!(
+        Err(SelectDeviceError::Selection(SelectionError::InvalidSyntax(
+            "first gpu".to_string()
+        ))),
+        result
+    );
+    assert_eq!(CL_INVALID_VALUE, result.unwrap_err().status());
+
+    env::remove_var(CL3_DEVICE);
+    assert_eq!(Ok(None), from_env());
+}
@@ -0,0 +1,6 @@
+use cl3::kernel::{
+    set_kernel_exec_info_svm_ptrs, CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM,
+    CL_KERNEL_EXEC_INFO_SVM_PTRS,
+};
+
+fn main() {}
@@ -0,0 +1,12 @@
+use cl3::kernel_args;
+use cl3::types::cl_int;
+
+kernel_args! {
+    struct Args {
+        #[arg(skip)]
+        #[arg(index = 1)]
+        count: cl_int,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use cl3::kernel_args;
+use cl3::types::cl_mem;
+
+kernel_args! {
+    struct Args {
+        buffer: cl_mem,
+        name: String,
+    }
+}
+
+fn main() {}